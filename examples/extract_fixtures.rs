@@ -0,0 +1,51 @@
+//! Extracts every distinct SCTE-35 section from a Transport Stream file, writing each into the
+//! given directory as a `.hex` file holding the encoded section, alongside a `.json` file holding
+//! its decoded form.
+//!
+//! The resulting files are suitable for use as test fixtures, e.g.
+//!
+//! ```plain
+//! cargo run --example extract_fixtures -- capture.ts fixtures/
+//! ```
+
+use hex_slice::AsHex;
+use std::collections::HashSet;
+use std::path::Path;
+use std::{env, fs, process};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("Usage: {} <input.ts> <output-dir>", args[0]);
+        process::exit(1);
+    }
+    let input = fs::read(&args[1]).unwrap_or_else(|e| {
+        eprintln!("Unable to read {}: {}", args[1], e);
+        process::exit(1);
+    });
+    let out_dir = Path::new(&args[2]);
+    fs::create_dir_all(out_dir).expect("create output directory");
+
+    let mut seen = HashSet::new();
+    for cue in scte35_reader::cue::cues(input.chunks_exact(188)) {
+        let cue = match cue {
+            Ok(cue) => cue,
+            Err(e) => {
+                eprintln!("Skipping section: {:?}", e);
+                continue;
+            }
+        };
+        if !seen.insert(cue.bytes.clone()) {
+            continue;
+        }
+        let name = format!("{:04}", seen.len());
+        fs::write(
+            out_dir.join(format!("{}.hex", name)),
+            format!("{:02x}\n", cue.bytes.plain_hex(false)),
+        )
+        .expect("write hex file");
+        let json = serde_json::to_string_pretty(&cue.section).expect("serialize section");
+        fs::write(out_dir.join(format!("{}.json", name)), json + "\n").expect("write json file");
+    }
+    println!("Wrote {} sections to {}", seen.len(), out_dir.display());
+}
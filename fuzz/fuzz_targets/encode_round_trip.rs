@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use scte35_reader::SpliceInfoSection;
+
+// every generated section should encode, and parse back to a section encoding identically
+fuzz_target!(|section: SpliceInfoSection| {
+    let data = section.encode().expect("encode generated section");
+    let parsed = SpliceInfoSection::parse(&data).expect("parse encoded section");
+    assert_eq!(parsed.encode().unwrap(), data);
+});
@@ -0,0 +1,526 @@
+//! Implementations of `arbitrary::Arbitrary`, available when this crate's `arbitrary` feature is
+//! enabled.
+//!
+//! Rather than deriving the trait (which would produce values the syntax can not carry, such as
+//! 40-bit `pts_time` values or UPIDs of the wrong length), each value generated is one that
+//! `SpliceInfoSection::encode()` accepts and that parses back to an equivalent value.  Lengths
+//! are bounded so that a generated section always fits within `MAX_SECTION_LENGTH`.
+
+use crate::{
+    time, upid, AudioComponent, ComponentSplice, DeliveryRestrictionFlags, DeviceRestrictions,
+    EncryptionAlgorithm, NetworkIndicator, ReturnMode, SapType, ScheduledComponentSplice,
+    ScheduledSplice, ScheduledSpliceMode, SegmentationDescriptor, SegmentationMode,
+    SegmentationModeComponent, SegmentationTypeId, SegmentationUpid, SegmentationUpidType,
+    SpliceCommand, SpliceDescriptor, SpliceDuration, SpliceInfoSection, SpliceInsert, SpliceMode,
+    SpliceScheduleEvent, SpliceTime, SubSegments, Tier, UtcSpliceTime,
+};
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// The most bytes generated for any variable-length field
+const MAX_BYTES: usize = 32;
+/// The most entries generated for any loop (of events, components or descriptors)
+const MAX_ENTRIES: usize = 8;
+
+impl<'a> Arbitrary<'a> for Tier {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Tier(u.int_in_range(0..=0xfff)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for EncryptionAlgorithm {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // a 6-bit field
+        Ok(EncryptionAlgorithm::from_id(u.int_in_range(0..=0x3f)?))
+    }
+}
+
+/// Generates an un-encrypted section, since encrypted sections can not be encoded directly
+impl<'a> Arbitrary<'a> for SpliceInfoSection {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(SpliceInfoSection {
+            sap_type: SapType::from_id(u.arbitrary()?),
+            protocol_version: 0,
+            encrypted_packet: false,
+            encryption_algorithm: EncryptionAlgorithm::None,
+            pts_adjustment: bits33(u)?,
+            cw_index: u.arbitrary()?,
+            tier: u.arbitrary()?,
+            splice_command: u.arbitrary()?,
+            splice_descriptors: entries(u, |u| u.arbitrary())?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for SpliceCommand {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=6)? {
+            0 => SpliceCommand::SpliceNull {},
+            1 => SpliceCommand::SpliceSchedule {
+                events: entries(u, |u| {
+                    Ok(SpliceScheduleEvent {
+                        splice_event_id: u.arbitrary()?,
+                        splice_detail: scheduled_splice(u)?,
+                    })
+                })?,
+            },
+            2 => SpliceCommand::SpliceInsert {
+                splice_event_id: u.arbitrary()?,
+                reserved: u.int_in_range(0..=0x7f)?,
+                splice_detail: splice_insert(u)?,
+            },
+            3 => SpliceCommand::TimeSignal {
+                splice_time: SpliceTime::Timed(optional(u, bits33)?),
+            },
+            4 => SpliceCommand::BandwidthReservation {},
+            5 => SpliceCommand::PrivateCommand {
+                identifier: u.arbitrary()?,
+                private_bytes: bytes(u, 0)?,
+            },
+            _ => SpliceCommand::Reserved {
+                command_type: *u.choose(&[0x01, 0x02, 0x03, 0x08, 0x80, 0xfe])?,
+                payload: bytes(u, 0)?,
+            },
+        })
+    }
+}
+
+fn scheduled_splice(u: &mut Unstructured<'_>) -> Result<ScheduledSplice> {
+    if u.ratio(1, 8)? {
+        return Ok(ScheduledSplice::Cancel);
+    }
+    let splice_mode = if u.arbitrary()? {
+        ScheduledSpliceMode::Program(UtcSpliceTime(u.arbitrary()?))
+    } else {
+        ScheduledSpliceMode::Components(entries(u, |u| {
+            Ok(ScheduledComponentSplice {
+                component_tag: u.arbitrary()?,
+                utc_splice_time: UtcSpliceTime(u.arbitrary()?),
+            })
+        })?)
+    };
+    Ok(ScheduledSplice::Insert {
+        network_indicator: network_indicator(u)?,
+        splice_mode,
+        duration: optional(u, splice_duration)?,
+        unique_program_id: u.arbitrary()?,
+        avail_num: u.arbitrary()?,
+        avails_expected: u.arbitrary()?,
+    })
+}
+
+fn splice_insert(u: &mut Unstructured<'_>) -> Result<SpliceInsert> {
+    if u.ratio(1, 8)? {
+        return Ok(SpliceInsert::Cancel);
+    }
+    // splice_immediate_flag applies to every component together
+    let immediate: bool = u.arbitrary()?;
+    let splice_time = |u: &mut Unstructured<'_>| {
+        if immediate {
+            Ok(SpliceTime::Immediate)
+        } else {
+            Ok(SpliceTime::Timed(optional(u, bits33)?))
+        }
+    };
+    let splice_mode = if u.arbitrary()? {
+        SpliceMode::Program(splice_time(u)?)
+    } else {
+        SpliceMode::Components(entries(u, |u| {
+            Ok(ComponentSplice {
+                component_tag: u.arbitrary()?,
+                splice_time: splice_time(u)?,
+            })
+        })?)
+    };
+    Ok(SpliceInsert::Insert {
+        network_indicator: network_indicator(u)?,
+        splice_mode,
+        duration: optional(u, splice_duration)?,
+        unique_program_id: u.arbitrary()?,
+        avail_num: u.arbitrary()?,
+        avails_expected: u.arbitrary()?,
+    })
+}
+
+fn network_indicator(u: &mut Unstructured<'_>) -> Result<NetworkIndicator> {
+    Ok(if u.arbitrary()? {
+        NetworkIndicator::Out
+    } else {
+        NetworkIndicator::In
+    })
+}
+
+fn splice_duration(u: &mut Unstructured<'_>) -> Result<SpliceDuration> {
+    Ok(SpliceDuration {
+        return_mode: if u.arbitrary()? {
+            ReturnMode::Automatic
+        } else {
+            ReturnMode::Manual
+        },
+        duration: bits33(u)?,
+    })
+}
+
+impl<'a> Arbitrary<'a> for SpliceDescriptor {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=5)? {
+            0 => SpliceDescriptor::AvailDescriptor {
+                provider_avail_id: u.arbitrary()?,
+            },
+            1 => {
+                let count = u.int_in_range(0..=7)?;
+                SpliceDescriptor::DTMFDescriptor {
+                    preroll: u.arbitrary()?,
+                    dtmf_chars: (0..count)
+                        .map(|_| u.choose(b"0123456789*#").copied())
+                        .collect::<Result<_>>()?,
+                }
+            }
+            2 => SpliceDescriptor::SegmentationDescriptor {
+                segmentation_event_id: u.arbitrary()?,
+                descriptor_detail: segmentation_descriptor(u)?,
+            },
+            3 => SpliceDescriptor::TimeDescriptor {
+                tai_seconds: u.int_in_range(0..=(1 << 48) - 1)?,
+                tai_nanoseconds: u.int_in_range(0..=999_999_999)?,
+                utc_offset: u.arbitrary()?,
+            },
+            4 => {
+                let count = u.int_in_range(0..=15)?;
+                SpliceDescriptor::AudioDescriptor {
+                    components: (0..count)
+                        .map(|_| {
+                            Ok(AudioComponent {
+                                component_tag: u.arbitrary()?,
+                                iso_code: [
+                                    u.int_in_range(b'a'..=b'z')?,
+                                    u.int_in_range(b'a'..=b'z')?,
+                                    u.int_in_range(b'a'..=b'z')?,
+                                ],
+                                bit_stream_mode: u.int_in_range(0..=7)?,
+                                num_channels: u.int_in_range(0..=15)?,
+                                full_srvc_audio: u.arbitrary()?,
+                            })
+                        })
+                        .collect::<Result<_>>()?,
+                }
+            }
+            _ => {
+                // CUEI descriptors with tags above 0x04 are not defined, and any other
+                // identifier is for a private descriptor
+                let identifier: [u8; 4] = u.arbitrary()?;
+                let tag = if &identifier == b"CUEI" {
+                    u.int_in_range(0x05..=0xff)?
+                } else {
+                    u.arbitrary()?
+                };
+                SpliceDescriptor::Reserved {
+                    tag,
+                    identifier,
+                    private_bytes: bytes(u, 0)?,
+                }
+            }
+        })
+    }
+}
+
+fn segmentation_descriptor(u: &mut Unstructured<'_>) -> Result<SegmentationDescriptor> {
+    if u.ratio(1, 8)? {
+        return Ok(SegmentationDescriptor::Cancel);
+    }
+    let delivery_restrictions = if u.arbitrary()? {
+        DeliveryRestrictionFlags::None
+    } else {
+        DeliveryRestrictionFlags::DeliveryRestrictions {
+            web_delivery_allowed_flag: u.arbitrary()?,
+            no_regional_blackout_flag: u.arbitrary()?,
+            archive_allowed_flag: u.arbitrary()?,
+            device_restrictions: match u.int_in_range(0..=3)? {
+                0 => DeviceRestrictions::RestrictGroup0,
+                1 => DeviceRestrictions::RestrictGroup1,
+                2 => DeviceRestrictions::RestrictGroup2,
+                _ => DeviceRestrictions::None,
+            },
+        }
+    };
+    let segmentation_mode = if u.arbitrary()? {
+        SegmentationMode::Program
+    } else {
+        SegmentationMode::Component {
+            components: entries(u, |u| {
+                Ok(SegmentationModeComponent {
+                    component_tag: u.arbitrary()?,
+                    pts_offset: bits33(u)?,
+                })
+            })?,
+        }
+    };
+    let segmentation_duration = optional(u, |u| u.int_in_range(0..=time::TickField::Bits40.max()))?;
+    let segmentation_type_id: SegmentationTypeId = u.arbitrary()?;
+    let sub_segments = if segmentation_type_id.has_sub_segments() {
+        optional(u, |u| {
+            Ok(SubSegments {
+                sub_segment_num: u.arbitrary()?,
+                sub_segments_expected: u.arbitrary()?,
+            })
+        })?
+    } else {
+        None
+    };
+    Ok(SegmentationDescriptor::Insert {
+        program_segmentation_flag: matches!(segmentation_mode, SegmentationMode::Program),
+        segmentation_duration_flag: segmentation_duration.is_some(),
+        delivery_not_restricted_flag: matches!(
+            delivery_restrictions,
+            DeliveryRestrictionFlags::None
+        ),
+        delivery_restrictions,
+        segmentation_mode,
+        segmentation_duration,
+        segmentation_upid: u.arbitrary()?,
+        segmentation_type_id,
+        segment_num: u.arbitrary()?,
+        segments_expected: u.arbitrary()?,
+        sub_segments,
+    })
+}
+
+impl<'a> Arbitrary<'a> for SegmentationTypeId {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(SegmentationTypeId::from_id(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for SegmentationUpid {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        if u.ratio(1, 8)? {
+            Ok(SegmentationUpid::None)
+        } else if u.ratio(1, 8)? {
+            // the UPIDs within a MID can not themselves be empty, and are not nested further;
+            // a handful keeps the whole within the 8-bit segmentation_upid_length
+            let count = u.int_in_range(1..=4)?;
+            Ok(SegmentationUpid::MID(
+                (0..count)
+                    .map(|_| {
+                        let n = u.int_in_range(1..=17)?;
+                        single_upid(u, n)
+                    })
+                    .collect::<Result<_>>()?,
+            ))
+        } else {
+            let n = u.int_in_range(1..=17)?;
+            single_upid(u, n)
+        }
+    }
+}
+
+/// Any single UPID other than `None` and `MID`, chosen by number
+fn single_upid(u: &mut Unstructured<'_>, n: u8) -> Result<SegmentationUpid> {
+    Ok(match n {
+        1 => SegmentationUpid::UserDefined(u.arbitrary()?),
+        2 => SegmentationUpid::Isci(u.arbitrary()?),
+        3 => SegmentationUpid::AdID(u.arbitrary()?),
+        4 => SegmentationUpid::Umid(u.arbitrary()?),
+        5 => SegmentationUpid::IsanDeprecated(u.arbitrary()?),
+        6 => SegmentationUpid::Isan(u.arbitrary()?),
+        7 => SegmentationUpid::TID(u.arbitrary()?),
+        8 => SegmentationUpid::TI(u.arbitrary()?),
+        9 => SegmentationUpid::ADI(u.arbitrary()?),
+        10 => SegmentationUpid::EIDR(u.arbitrary()?),
+        11 => SegmentationUpid::ATSC(u.arbitrary()?),
+        12 => SegmentationUpid::MPU(u.arbitrary()?),
+        13 => SegmentationUpid::ADS(u.arbitrary()?),
+        14 => SegmentationUpid::URI(u.arbitrary()?),
+        15 => SegmentationUpid::UUID(u.arbitrary()?),
+        16 => SegmentationUpid::SCR(u.arbitrary()?),
+        _ => SegmentationUpid::Reserved(
+            SegmentationUpidType::Reserved(u.int_in_range(18..=0xff)?),
+            bytes(u, 1)?,
+        ),
+    })
+}
+
+impl<'a> Arbitrary<'a> for upid::UserDefinedDeprecated {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::UserDefinedDeprecated(bytes(u, 1)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::IsciDeprecated {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::IsciDeprecated(patterned(u, "AAAA9999")?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::AdID {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::AdID(patterned(u, "AAAAXXXXXXXX")?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::IsanDeprecated {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::IsanDeprecated(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::Isan {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::Isan(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::Umid {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::Umid(u.arbitrary::<[u8; 32]>()?.to_vec()))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::TID {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::TID(patterned(u, "AA9999999999")?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::TI {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::TI(u.arbitrary::<[u8; 8]>()?.to_vec()))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::ADI {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = u.int_in_range(1..=MAX_BYTES)?;
+        Ok(upid::ADI(alphanumeric(u, len)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::EIDR {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::EIDR(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::ATSC {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::ATSC {
+            tsid: u.arbitrary()?,
+            end_of_day: u.int_in_range(0..=23)?,
+            unique_for: u.int_in_range(1..=511)?,
+            content_id: bytes(u, 0)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::MPU {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // a format_identifier followed by the private_data
+        Ok(upid::MPU(bytes(u, 4)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::ADSInformation {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::ADSInformation(bytes(u, 1)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::Url {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = u.int_in_range(1..=MAX_BYTES)?;
+        let url = format!("urn:example:{}", alphanumeric(u, len)?);
+        Ok(upid::Url(url::Url::parse(&url).expect("valid URN")))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::Uuid {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::Uuid(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for upid::SCR {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(upid::SCR(bytes(u, 1)?))
+    }
+}
+
+fn bits33(u: &mut Unstructured<'_>) -> Result<u64> {
+    u.int_in_range(0..=time::TickField::Bits33.max())
+}
+
+fn optional<'a, T>(
+    u: &mut Unstructured<'a>,
+    f: impl FnOnce(&mut Unstructured<'a>) -> Result<T>,
+) -> Result<Option<T>> {
+    Ok(if u.arbitrary()? { Some(f(u)?) } else { None })
+}
+
+/// Between zero and `MAX_ENTRIES` values produced by the given function
+fn entries<'a, T>(
+    u: &mut Unstructured<'a>,
+    mut f: impl FnMut(&mut Unstructured<'a>) -> Result<T>,
+) -> Result<Vec<T>> {
+    let count = u.int_in_range(0..=MAX_ENTRIES)?;
+    (0..count).map(|_| f(u)).collect()
+}
+
+/// Between `min` and `MAX_BYTES` bytes
+fn bytes(u: &mut Unstructured<'_>, min: usize) -> Result<Vec<u8>> {
+    let len = u.int_in_range(min..=MAX_BYTES)?;
+    (0..len).map(|_| u.arbitrary()).collect()
+}
+
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+fn alphanumeric(u: &mut Unstructured<'_>, len: usize) -> Result<String> {
+    (0..len)
+        .map(|_| u.choose(ALPHANUMERIC).map(|c| char::from(*c)))
+        .collect()
+}
+
+/// A string following the given pattern, where `A` stands for a letter, `9` for a digit and `X`
+/// for either (as in the formats the spec gives for ISCI, Ad-ID and TID)
+fn patterned(u: &mut Unstructured<'_>, pattern: &str) -> Result<String> {
+    pattern
+        .chars()
+        .map(|p| {
+            let chars = match p {
+                'A' => &ALPHANUMERIC[10..],
+                '9' => &ALPHANUMERIC[..10],
+                _ => ALPHANUMERIC,
+            };
+            u.choose(chars).map(|c| char::from(*c))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        // a deterministic stream of 'random' input for the generator
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let data: Vec<u8> = (0..64 * 1024)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..200 {
+            let section: SpliceInfoSection = u.arbitrary().unwrap();
+            let encoded = section
+                .encode()
+                .unwrap_or_else(|e| panic!("{:?} encoding {:?}", e, section));
+            let parsed = SpliceInfoSection::parse(&encoded)
+                .unwrap_or_else(|e| panic!("{:?} parsing {:?}", e, section));
+            assert_eq!(parsed.encode().unwrap(), encoded, "{:?}", section);
+        }
+        // otherwise the later sections would all be trivial
+        assert!(!u.is_empty());
+    }
+}
@@ -0,0 +1,1005 @@
+//! Construction of complete `splice_info_section()` byte sequences, ready to be
+//! [packetized](../packetize/index.html) and inserted into a Transport Stream (for instance via
+//! the [`inject`](../inject/index.html) module), and of validated commands and descriptors via
+//! [`SpliceInsertBuilder`](struct.SpliceInsertBuilder.html),
+//! [`TimeSignalBuilder`](struct.TimeSignalBuilder.html) and
+//! [`SegmentationDescriptorBuilder`](struct.SegmentationDescriptorBuilder.html).
+
+use crate::upid::UpidFormatError;
+use crate::{
+    encode, time, ComponentSplice, DeliveryRestrictionFlags, DeviceRestrictions,
+    EncryptionAlgorithm, NetworkIndicator, ReturnMode, SapType, SegmentationDescriptor,
+    SegmentationMode, SegmentationModeComponent, SegmentationTypeId, SegmentationUpid,
+    SpliceCommand, SpliceDescriptor, SpliceDuration, SpliceInfoSection, SpliceInsert, SpliceMode,
+    SpliceTime, SubSegments, Tier,
+};
+
+const SPLICE_INSERT: u8 = 0x05;
+const TIME_SIGNAL: u8 = 0x06;
+const SEGMENTATION_DESCRIPTOR: u8 = 0x02;
+
+/// Produces a `splice_insert()` with `splice_event_cancel_indicator` set, retracting the
+/// previously signalled splice event with the given `splice_event_id`.
+pub fn splice_insert_cancel(splice_event_id: u32) -> Vec<u8> {
+    let mut command = splice_event_id.to_be_bytes().to_vec();
+    // splice_event_cancel_indicator=1, reserved bits all set
+    command.push(0xff);
+    section(SPLICE_INSERT, &command, &[])
+}
+
+/// Produces an immediate `time_signal()` carrying a `segmentation_descriptor()` with
+/// `segmentation_event_cancel_indicator` set, retracting the previously signalled segmentation
+/// event with the given `segmentation_event_id`.
+pub fn segmentation_cancel(segmentation_event_id: u32) -> Vec<u8> {
+    let mut descriptor = vec![SEGMENTATION_DESCRIPTOR, 0];
+    descriptor.extend_from_slice(b"CUEI");
+    descriptor.extend_from_slice(&segmentation_event_id.to_be_bytes());
+    // segmentation_event_cancel_indicator=1, reserved bits all set
+    descriptor.push(0xff);
+    descriptor[1] = (descriptor.len() - 2) as u8;
+    // splice_time() with time_specified_flag=0
+    section(TIME_SIGNAL, &[0x7f], &descriptor)
+}
+
+/// The timing of the splice being built by a `SpliceInsertBuilder`
+#[derive(Debug, Clone)]
+enum InsertTiming {
+    Immediate,
+    Program(u64),
+    /// Component tags, each with its PTS, or `None` for an immediate splice
+    Components(Vec<(u8, Option<u64>)>),
+}
+
+/// Builds a `splice_insert()` command, validating that each value fits the field that carries
+/// it.
+///
+/// By default the command signals an immediate, program-mode splice out of the network, with
+/// no `break_duration`, and zero `unique_program_id`, `avail_num` and `avails_expected`.
+///
+/// ## Example
+///
+/// ```
+/// # use hex_literal::*;
+/// # use scte35_reader::builder::SpliceInsertBuilder;
+/// # use scte35_reader::ReturnMode;
+/// let section = SpliceInsertBuilder::new(1)
+///     .at(0x2d142b00)
+///     .break_duration(ReturnMode::Automatic, 0x0123d308)
+///     .unique_program_id(1)
+///     .avail(1, 1)
+///     .build_section()
+///     .unwrap();
+/// assert_eq!(
+///     section.encode().unwrap(),
+///     hex!("fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49")
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpliceInsertBuilder {
+    splice_event_id: u32,
+    out_of_network: bool,
+    timing: InsertTiming,
+    duration: Option<(ReturnMode, u64)>,
+    unique_program_id: u16,
+    avail_num: u8,
+    avails_expected: u8,
+}
+impl SpliceInsertBuilder {
+    pub fn new(splice_event_id: u32) -> SpliceInsertBuilder {
+        SpliceInsertBuilder {
+            splice_event_id,
+            out_of_network: true,
+            timing: InsertTiming::Immediate,
+            duration: None,
+            unique_program_id: 0,
+            avail_num: 0,
+            avails_expected: 0,
+        }
+    }
+
+    /// Signals a splice out of the network, to the start of a break (the default)
+    pub fn out_of_network(mut self) -> Self {
+        self.out_of_network = true;
+        self
+    }
+
+    /// Signals a splice back into the network, at the end of a break
+    pub fn return_to_network(mut self) -> Self {
+        self.out_of_network = false;
+        self
+    }
+
+    /// Signals a program-mode splice taking effect immediately (the default), discarding any
+    /// components previously given
+    pub fn immediate(mut self) -> Self {
+        self.timing = InsertTiming::Immediate;
+        self
+    }
+
+    /// Signals a program-mode splice at the given 33-bit PTS, discarding any components
+    /// previously given
+    pub fn at(mut self, pts: u64) -> Self {
+        self.timing = InsertTiming::Program(pts);
+        self
+    }
+
+    /// Adds a component to a component-mode splice (switching to component mode if a
+    /// program-mode time had been given), splicing at the given PTS, or immediately if `None`.
+    ///
+    /// Either all components or none must be immediate, since `splice_immediate_flag` applies
+    /// to the command as a whole.
+    pub fn component(mut self, component_tag: u8, pts: Option<u64>) -> Self {
+        match self.timing {
+            InsertTiming::Components(ref mut components) => components.push((component_tag, pts)),
+            _ => self.timing = InsertTiming::Components(vec![(component_tag, pts)]),
+        }
+        self
+    }
+
+    /// The `break_duration()`, in 90kHz units
+    pub fn break_duration(mut self, return_mode: ReturnMode, duration: u64) -> Self {
+        self.duration = Some((return_mode, duration));
+        self
+    }
+
+    pub fn unique_program_id(mut self, unique_program_id: u16) -> Self {
+        self.unique_program_id = unique_program_id;
+        self
+    }
+
+    /// The `avail_num` and `avails_expected` values
+    pub fn avail(mut self, avail_num: u8, avails_expected: u8) -> Self {
+        self.avail_num = avail_num;
+        self.avails_expected = avails_expected;
+        self
+    }
+
+    /// Produces the command, failing if any value is out of range
+    pub fn build(self) -> Result<SpliceCommand, BuildError> {
+        let timed = |pts: u64, field_name| Ok(SpliceTime::Timed(Some(check_33(pts, field_name)?)));
+        let splice_mode = match self.timing {
+            InsertTiming::Immediate => SpliceMode::Program(SpliceTime::Immediate),
+            InsertTiming::Program(pts) => SpliceMode::Program(timed(pts, "splice_time.pts_time")?),
+            InsertTiming::Components(components) => {
+                if components.len() > 0xff {
+                    return Err(BuildError::FieldOutOfRange {
+                        field_name: "splice_insert.component_count",
+                        value: components.len() as u64,
+                    });
+                }
+                let immediate = components[0].1.is_none();
+                let mut splices: Vec<ComponentSplice> = Vec::with_capacity(components.len());
+                for (component_tag, pts) in components {
+                    if splices.iter().any(|c| c.component_tag == component_tag) {
+                        return Err(BuildError::DuplicateComponent(component_tag));
+                    }
+                    let splice_time = match pts {
+                        None if immediate => SpliceTime::Immediate,
+                        Some(pts) if !immediate => timed(pts, "component.splice_time.pts_time")?,
+                        _ => return Err(BuildError::MixedComponentTiming),
+                    };
+                    splices.push(ComponentSplice {
+                        component_tag,
+                        splice_time,
+                    });
+                }
+                SpliceMode::Components(splices)
+            }
+        };
+        let duration = match self.duration {
+            Some((return_mode, duration)) => Some(SpliceDuration {
+                return_mode,
+                duration: check_33(duration, "break_duration.duration")?,
+            }),
+            None => None,
+        };
+        Ok(SpliceCommand::SpliceInsert {
+            splice_event_id: self.splice_event_id,
+            reserved: 0x7f,
+            splice_detail: SpliceInsert::Insert {
+                network_indicator: if self.out_of_network {
+                    NetworkIndicator::Out
+                } else {
+                    NetworkIndicator::In
+                },
+                splice_mode,
+                duration,
+                unique_program_id: self.unique_program_id,
+                avail_num: self.avail_num,
+                avails_expected: self.avails_expected,
+            },
+        })
+    }
+
+    /// Produces a section carrying the command, with no PTS adjustment, no encryption, the
+    /// 'all tiers' tier value and no descriptors, ready for descriptors to be added and for
+    /// `SpliceInfoSection::encode()`
+    pub fn build_section(self) -> Result<SpliceInfoSection, BuildError> {
+        Ok(owned_section(self.build()?, vec![]))
+    }
+}
+
+/// Builds a section carrying a `time_signal()` command and its descriptors, typically
+/// segmentation descriptors built with `SegmentationDescriptorBuilder`.
+///
+/// By default the `time_signal()` is immediate (having no `pts_time`).
+///
+/// ## Example
+///
+/// ```
+/// # use scte35_reader::builder::{SegmentationDescriptorBuilder, TimeSignalBuilder};
+/// # use scte35_reader::{upid, SegmentationTypeId, SegmentationUpid};
+/// let section = TimeSignalBuilder::new()
+///     .at(0x1_592d_03c0)
+///     .segmentation(
+///         SegmentationDescriptorBuilder::new(1, SegmentationTypeId::ProviderAdvertisementStart)
+///             .upid(SegmentationUpid::AdID(upid::AdID("ABCD01234567".to_string())))
+///             .duration(30 * 90_000)
+///             .segment(1, 2),
+///     )
+///     .build_section()
+///     .unwrap();
+/// let data = section.encode().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TimeSignalBuilder {
+    pts: Option<u64>,
+    descriptors: Vec<PendingDescriptor>,
+}
+#[derive(Debug)]
+enum PendingDescriptor {
+    Built(SpliceDescriptor),
+    Segmentation(SegmentationDescriptorBuilder),
+}
+impl TimeSignalBuilder {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> TimeSignalBuilder {
+        TimeSignalBuilder {
+            pts: None,
+            descriptors: vec![],
+        }
+    }
+
+    /// Signals the given 33-bit PTS
+    pub fn at(mut self, pts: u64) -> Self {
+        self.pts = Some(pts);
+        self
+    }
+
+    /// Signals no `pts_time`, so that the `time_signal()` takes effect immediately (the default)
+    pub fn immediate(mut self) -> Self {
+        self.pts = None;
+        self
+    }
+
+    /// Appends the given segmentation descriptor to the descriptor loop
+    pub fn segmentation(mut self, descriptor: SegmentationDescriptorBuilder) -> Self {
+        self.descriptors
+            .push(PendingDescriptor::Segmentation(descriptor));
+        self
+    }
+
+    /// Appends an already constructed descriptor to the descriptor loop
+    pub fn descriptor(mut self, descriptor: SpliceDescriptor) -> Self {
+        self.descriptors.push(PendingDescriptor::Built(descriptor));
+        self
+    }
+
+    /// Produces a section as for `SpliceInsertBuilder::build_section()`, carrying the
+    /// `time_signal()` and descriptors, failing if any value is out of range
+    pub fn build_section(self) -> Result<SpliceInfoSection, BuildError> {
+        let splice_time = match self.pts {
+            Some(pts) => SpliceTime::Timed(Some(check_33(pts, "splice_time.pts_time")?)),
+            None => SpliceTime::Timed(None),
+        };
+        let descriptors = self
+            .descriptors
+            .into_iter()
+            .map(|d| match d {
+                PendingDescriptor::Built(d) => Ok(d),
+                PendingDescriptor::Segmentation(b) => b.build(),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(owned_section(
+            SpliceCommand::TimeSignal { splice_time },
+            descriptors,
+        ))
+    }
+}
+
+/// Builds a `segmentation_descriptor()`, validating that each value fits the field that carries
+/// it.
+///
+/// By default the descriptor is program-mode, without delivery restrictions, and has no
+/// `segmentation_duration` and no UPID, with zero `segment_num` and `segments_expected`.
+#[derive(Debug)]
+pub struct SegmentationDescriptorBuilder {
+    segmentation_event_id: u32,
+    segmentation_type_id: SegmentationTypeId,
+    segmentation_upid: SegmentationUpid,
+    segmentation_duration: Option<u64>,
+    delivery_restrictions: Option<(bool, bool, bool, DeviceRestrictions)>,
+    components: Option<Vec<(u8, u64)>>,
+    segment_num: u8,
+    segments_expected: u8,
+    sub_segments: Option<(u8, u8)>,
+}
+impl SegmentationDescriptorBuilder {
+    pub fn new(
+        segmentation_event_id: u32,
+        segmentation_type_id: SegmentationTypeId,
+    ) -> SegmentationDescriptorBuilder {
+        SegmentationDescriptorBuilder {
+            segmentation_event_id,
+            segmentation_type_id,
+            segmentation_upid: SegmentationUpid::None,
+            segmentation_duration: None,
+            delivery_restrictions: None,
+            components: None,
+            segment_num: 0,
+            segments_expected: 0,
+            sub_segments: None,
+        }
+    }
+
+    pub fn upid(mut self, segmentation_upid: SegmentationUpid) -> Self {
+        self.segmentation_upid = segmentation_upid;
+        self
+    }
+
+    /// The 40-bit `segmentation_duration`, in 90kHz units
+    pub fn duration(mut self, segmentation_duration: u64) -> Self {
+        self.segmentation_duration = Some(segmentation_duration);
+        self
+    }
+
+    /// Restricts delivery of the segment, clearing `delivery_not_restricted_flag`
+    pub fn delivery_restrictions(
+        mut self,
+        web_delivery_allowed: bool,
+        no_regional_blackout: bool,
+        archive_allowed: bool,
+        device_restrictions: DeviceRestrictions,
+    ) -> Self {
+        self.delivery_restrictions = Some((
+            web_delivery_allowed,
+            no_regional_blackout,
+            archive_allowed,
+            device_restrictions,
+        ));
+        self
+    }
+
+    /// Adds a component, switching the descriptor to component mode, with the given 33-bit
+    /// `pts_offset` from the time of the `time_signal()`
+    pub fn component(mut self, component_tag: u8, pts_offset: u64) -> Self {
+        self.components
+            .get_or_insert_with(Vec::new)
+            .push((component_tag, pts_offset));
+        self
+    }
+
+    /// The `segment_num` and `segments_expected` values
+    pub fn segment(mut self, segment_num: u8, segments_expected: u8) -> Self {
+        self.segment_num = segment_num;
+        self.segments_expected = segments_expected;
+        self
+    }
+
+    /// The `sub_segment_num` and `sub_segments_expected` values, which only placement
+    /// opportunity and ad block start types may carry
+    pub fn sub_segment(mut self, sub_segment_num: u8, sub_segments_expected: u8) -> Self {
+        self.sub_segments = Some((sub_segment_num, sub_segments_expected));
+        self
+    }
+
+    /// Produces the descriptor, failing if any value is out of range or not permitted for the
+    /// `segmentation_type_id`, or if an ISCI, Ad-ID or TID `segmentation_upid` is not of the
+    /// form its scheme requires
+    pub fn build(self) -> Result<SpliceDescriptor, BuildError> {
+        if self.sub_segments.is_some() && !self.segmentation_type_id.has_sub_segments() {
+            return Err(BuildError::SubSegmentsNotAllowed(
+                self.segmentation_type_id.id(),
+            ));
+        }
+        if let Some(duration) = self.segmentation_duration {
+            if duration > time::TickField::Bits40.max() {
+                return Err(BuildError::FieldOutOfRange {
+                    field_name: "segmentation_descriptor.segmentation_duration",
+                    value: duration,
+                });
+            }
+        }
+        match &self.segmentation_upid {
+            SegmentationUpid::Isci(id) => id.validate(),
+            SegmentationUpid::AdID(id) => id.validate(),
+            SegmentationUpid::TID(id) => id.validate(),
+            _ => Ok(()),
+        }
+        .map_err(BuildError::InvalidUpid)?;
+        let upid_length = self.segmentation_upid.segmentation_upid_length();
+        if upid_length > 0xff {
+            return Err(BuildError::FieldOutOfRange {
+                field_name: "segmentation_descriptor.segmentation_upid_length",
+                value: upid_length as u64,
+            });
+        }
+        let segmentation_mode = match self.components {
+            None => SegmentationMode::Program,
+            Some(components) => {
+                if components.len() > 0xff {
+                    return Err(BuildError::FieldOutOfRange {
+                        field_name: "segmentation_descriptor.component_count",
+                        value: components.len() as u64,
+                    });
+                }
+                let mut result: Vec<SegmentationModeComponent> = vec![];
+                for (component_tag, pts_offset) in components {
+                    if result.iter().any(|c| c.component_tag == component_tag) {
+                        return Err(BuildError::DuplicateComponent(component_tag));
+                    }
+                    result.push(SegmentationModeComponent {
+                        component_tag,
+                        pts_offset: check_33(
+                            pts_offset,
+                            "segmentation_descriptor.component.pts_offset",
+                        )?,
+                    });
+                }
+                SegmentationMode::Component { components: result }
+            }
+        };
+        let delivery_restrictions = match self.delivery_restrictions {
+            None => DeliveryRestrictionFlags::None,
+            Some((web, blackout, archive, device_restrictions)) => {
+                DeliveryRestrictionFlags::DeliveryRestrictions {
+                    web_delivery_allowed_flag: web,
+                    no_regional_blackout_flag: blackout,
+                    archive_allowed_flag: archive,
+                    device_restrictions,
+                }
+            }
+        };
+        Ok(SpliceDescriptor::SegmentationDescriptor {
+            segmentation_event_id: self.segmentation_event_id,
+            descriptor_detail: SegmentationDescriptor::Insert {
+                program_segmentation_flag: matches!(segmentation_mode, SegmentationMode::Program),
+                segmentation_duration_flag: self.segmentation_duration.is_some(),
+                delivery_not_restricted_flag: matches!(
+                    delivery_restrictions,
+                    DeliveryRestrictionFlags::None
+                ),
+                delivery_restrictions,
+                segmentation_mode,
+                segmentation_duration: self.segmentation_duration,
+                segmentation_upid: self.segmentation_upid,
+                segmentation_type_id: self.segmentation_type_id,
+                segment_num: self.segment_num,
+                segments_expected: self.segments_expected,
+                sub_segments: self.sub_segments.map(|(num, expected)| SubSegments {
+                    sub_segment_num: num,
+                    sub_segments_expected: expected,
+                }),
+            },
+        })
+    }
+}
+
+fn check_33(value: u64, field_name: &'static str) -> Result<u64, BuildError> {
+    if value > time::TickField::Bits33.max() {
+        Err(BuildError::FieldOutOfRange { field_name, value })
+    } else {
+        Ok(value)
+    }
+}
+
+/// A section with no PTS adjustment, no encryption and the 'all tiers' tier value
+fn owned_section(
+    splice_command: SpliceCommand,
+    splice_descriptors: Vec<SpliceDescriptor>,
+) -> SpliceInfoSection {
+    SpliceInfoSection {
+        sap_type: SapType::Unspecified,
+        protocol_version: 0,
+        encrypted_packet: false,
+        encryption_algorithm: EncryptionAlgorithm::None,
+        pts_adjustment: 0,
+        cw_index: 0,
+        tier: Tier::ALL,
+        splice_command,
+        splice_descriptors,
+    }
+}
+
+/// Problems which prevent the builders of this module producing a command or descriptor
+#[derive(Debug, PartialEq, Eq)]
+pub enum BuildError {
+    /// A value is too large to be represented in the number of bits the syntax allows for the
+    /// named field
+    FieldOutOfRange {
+        field_name: &'static str,
+        value: u64,
+    },
+    /// Some components were given a PTS while others were immediate
+    MixedComponentTiming,
+    /// The same `component_tag` was given more than once
+    DuplicateComponent(u8),
+    /// Sub-segment values were given for a segmentation descriptor of the given
+    /// `segmentation_type_id`, which does not carry them
+    SubSegmentsNotAllowed(u8),
+    /// The character-based `segmentation_upid` is not of the form its scheme requires
+    InvalidUpid(UpidFormatError),
+}
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::FieldOutOfRange { field_name, value } => {
+                write!(f, "value {} is out of range for {}", value, field_name)
+            }
+            BuildError::MixedComponentTiming => {
+                f.write_str("some components have a PTS, while others are immediate")
+            }
+            BuildError::DuplicateComponent(tag) => {
+                write!(f, "component_tag {} given more than once", tag)
+            }
+            BuildError::SubSegmentsNotAllowed(type_id) => write!(
+                f,
+                "segmentation_type_id {:#04x} does not carry sub-segments",
+                type_id
+            ),
+            BuildError::InvalidUpid(e) => write!(f, "invalid segmentation_upid: {}", e),
+        }
+    }
+}
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::InvalidUpid(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Wraps the given command and descriptor loop in a `splice_info_section()`, with no PTS
+/// adjustment, no encryption and the 'all tiers' tier value, appending the `CRC_32`.
+fn section(splice_command_type: u8, command: &[u8], descriptors: &[u8]) -> Vec<u8> {
+    let header = encode::SectionHeader {
+        sap_type: SapType::Unspecified.id(),
+        protocol_version: 0,
+        encryption_algorithm: EncryptionAlgorithm::None.id(),
+        pts_adjustment: 0,
+        cw_index: 0,
+        tier: Tier::ALL.value(),
+    };
+    // the messages this module builds are far smaller than the maximum section size, and the
+    // header values are all in range
+    encode::section(&header, splice_command_type, command, descriptors)
+        .expect("section within limits")
+}
+
+/// A block cipher with which `encrypt()` can protect the _splice_command_ and descriptors of a
+/// section.
+///
+/// Implementations of the DES based algorithms defined by _SCTE-35_ are available when the `des`
+/// feature of this crate is enabled.
+pub trait SectionCipher {
+    /// The algorithm to be signalled in the section's `encryption_algorithm` field
+    fn algorithm(&self) -> EncryptionAlgorithm;
+
+    /// The cipher's block size in bytes.  The encrypted portion of the section will be padded to
+    /// a multiple of this size.
+    fn block_size(&self) -> usize {
+        8
+    }
+
+    /// Encrypt the given data in-place.  The length of `data` will be a multiple of
+    /// `block_size()`.
+    fn encrypt(&self, data: &mut [u8]);
+}
+
+/// Problems which prevent `encrypt()` from processing a section
+#[derive(Debug, PartialEq)]
+pub enum EncryptError {
+    /// The section is shorter than the minimum `splice_info_section()`, or its `section_length`
+    /// does not match the length of the given data
+    InvalidSection,
+    /// The section's `encrypted_packet` flag was already set
+    AlreadyEncrypted,
+    /// After padding, the section would exceed the maximum `section_length` of `4093`
+    SectionTooLong(usize),
+}
+impl std::fmt::Display for EncryptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncryptError::InvalidSection => f.write_str("invalid splice_info_section"),
+            EncryptError::AlreadyEncrypted => f.write_str("section is already encrypted"),
+            EncryptError::SectionTooLong(len) => write!(
+                f,
+                "encrypted section_length {} would exceed the maximum of 4093",
+                len
+            ),
+        }
+    }
+}
+impl std::error::Error for EncryptError {}
+
+/// Produces an encrypted version of the given complete, un-encrypted `splice_info_section()`.
+///
+/// The `encrypted_packet`, `encryption_algorithm` and `cw_index` fields are set, and an
+/// `E_CRC_32` is appended to the _splice_command_ and descriptor loop (after any
+/// `alignment_stuffing` the cipher's block size requires), before that portion of the section
+/// is encrypted and the section's `CRC_32` recalculated.
+pub fn encrypt<C: SectionCipher + ?Sized>(
+    section: &[u8],
+    cipher: &C,
+    cw_index: u8,
+) -> Result<Vec<u8>, EncryptError> {
+    // table_id through splice_command_length
+    const HEADER_LEN: usize = 13;
+    if section.len() < HEADER_LEN + 1 + 2 + 4
+        || usize::from(section[1] & 0x0f) << 8 | usize::from(section[2]) != section.len() - 3
+    {
+        return Err(EncryptError::InvalidSection);
+    }
+    if section[4] & 0b1000_0000 != 0 {
+        return Err(EncryptError::AlreadyEncrypted);
+    }
+    let block_size = cipher.block_size();
+    // splice_command_type through to the end of the descriptor loop
+    let mut payload = section[HEADER_LEN..section.len() - 4].to_vec();
+    let stuffing = (block_size - (payload.len() + 4) % block_size) % block_size;
+    payload.resize(payload.len() + stuffing, 0xff);
+    let e_crc = mpeg2ts_reader::mpegts_crc::sum32(&payload);
+    payload.extend_from_slice(&e_crc.to_be_bytes());
+    cipher.encrypt(&mut payload[..]);
+
+    let section_length = HEADER_LEN - 3 + payload.len() + 4;
+    if section_length > 4093 {
+        return Err(EncryptError::SectionTooLong(section_length));
+    }
+    let mut data = Vec::with_capacity(3 + section_length);
+    data.extend_from_slice(&section[..HEADER_LEN]);
+    data[1] = (data[1] & 0xf0) | (section_length >> 8) as u8;
+    data[2] = section_length as u8;
+    data[4] = 0b1000_0000 | (cipher.algorithm().id() & 0b11_1111) << 1 | (data[4] & 1);
+    data[9] = cw_index;
+    data.extend_from_slice(&payload);
+    let crc = mpeg2ts_reader::mpegts_crc::sum32(&data);
+    data.extend_from_slice(&crc.to_be_bytes());
+    Ok(data)
+}
+
+/// DES in Electronic Codebook mode (`encryption_algorithm` 1)
+#[cfg(feature = "des")]
+pub struct DesEcb(des::Des);
+#[cfg(feature = "des")]
+impl DesEcb {
+    pub fn new(key: &[u8; 8]) -> DesEcb {
+        DesEcb(des::cipher::KeyInit::new(key.into()))
+    }
+}
+#[cfg(feature = "des")]
+impl SectionCipher for DesEcb {
+    fn algorithm(&self) -> EncryptionAlgorithm {
+        EncryptionAlgorithm::DesEcb
+    }
+    fn encrypt(&self, data: &mut [u8]) {
+        use des::cipher::BlockEncrypt;
+        for block in data.chunks_exact_mut(8) {
+            self.0.encrypt_block(block.into());
+        }
+    }
+}
+
+/// DES in Cipher Block Chaining mode (`encryption_algorithm` 2)
+#[cfg(feature = "des")]
+pub struct DesCbc {
+    cipher: des::Des,
+    iv: [u8; 8],
+}
+#[cfg(feature = "des")]
+impl DesCbc {
+    pub fn new(key: &[u8; 8], iv: [u8; 8]) -> DesCbc {
+        DesCbc {
+            cipher: des::cipher::KeyInit::new(key.into()),
+            iv,
+        }
+    }
+}
+#[cfg(feature = "des")]
+impl SectionCipher for DesCbc {
+    fn algorithm(&self) -> EncryptionAlgorithm {
+        EncryptionAlgorithm::DesCbc
+    }
+    fn encrypt(&self, data: &mut [u8]) {
+        use des::cipher::BlockEncrypt;
+        let mut prev = self.iv;
+        for block in data.chunks_exact_mut(8) {
+            for (b, p) in block.iter_mut().zip(prev.iter()) {
+                *b ^= p;
+            }
+            self.cipher.encrypt_block(block.into());
+            prev.copy_from_slice(block);
+        }
+    }
+}
+
+/// Triple DES EDE3 in Electronic Codebook mode (`encryption_algorithm` 3)
+#[cfg(feature = "des")]
+pub struct TripleDesEde3Ecb(des::TdesEde3);
+#[cfg(feature = "des")]
+impl TripleDesEde3Ecb {
+    pub fn new(key: &[u8; 24]) -> TripleDesEde3Ecb {
+        TripleDesEde3Ecb(des::cipher::KeyInit::new(key.into()))
+    }
+}
+#[cfg(feature = "des")]
+impl SectionCipher for TripleDesEde3Ecb {
+    fn algorithm(&self) -> EncryptionAlgorithm {
+        EncryptionAlgorithm::TripleDesEde3Ecb
+    }
+    fn encrypt(&self, data: &mut [u8]) {
+        use des::cipher::BlockEncrypt;
+        for block in data.chunks_exact_mut(8) {
+            self.0.encrypt_block(block.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util;
+    use crate::{LogDiagnostics, SpliceDescriptors, SpliceInfoHeader};
+    use matches::*;
+
+    #[test]
+    fn splice_insert_cancel_bytes() {
+        let data = splice_insert_cancel(0x1234_5678);
+        assert_eq!(mpeg2ts_reader::mpegts_crc::sum32(&data), 0);
+        assert_eq!(usize::from(data[2]) + 3, data.len());
+        let (header, rest) = SpliceInfoHeader::try_new(&data[3..]).unwrap();
+        assert_eq!(header.splice_command_length(), 5);
+        assert_eq!(&rest[..5], &[0x12, 0x34, 0x56, 0x78, 0xff]);
+    }
+
+    #[test]
+    fn segmentation_cancel_bytes() {
+        let data = segmentation_cancel(42);
+        assert_eq!(mpeg2ts_reader::mpegts_crc::sum32(&data), 0);
+        let (header, rest) = SpliceInfoHeader::try_new(&data[3..]).unwrap();
+        assert_eq!(header.splice_command_length(), 1);
+        let descriptors = SpliceDescriptors {
+            buf: &rest[3..rest.len() - 4],
+            diagnostics: &LogDiagnostics,
+        };
+        let descriptors: Vec<_> = descriptors.into_iter().map(|d| d.unwrap()).collect();
+        assert_eq!(descriptors.len(), 1);
+        assert_matches!(
+            descriptors[0],
+            SpliceDescriptor::SegmentationDescriptor {
+                segmentation_event_id: 42,
+                descriptor_detail: SegmentationDescriptor::Cancel,
+                ..
+            }
+        );
+    }
+
+    #[test]
+    fn splice_insert_builder() {
+        let section = SpliceInsertBuilder::new(2)
+            .return_to_network()
+            .component(1, Some(100))
+            .component(2, Some(time::PTS_MODULUS - 1))
+            .build_section()
+            .unwrap();
+        let parsed = SpliceInfoSection::parse(&section.encode().unwrap()).unwrap();
+        assert_matches!(
+            parsed.splice_command,
+            SpliceCommand::SpliceInsert {
+                splice_event_id: 2,
+                splice_detail: SpliceInsert::Insert {
+                    network_indicator: NetworkIndicator::In,
+                    splice_mode: SpliceMode::Components(ref components),
+                    duration: None,
+                    ..
+                },
+                ..
+            } if components.len() == 2
+        );
+
+        assert_eq!(
+            SpliceInsertBuilder::new(1)
+                .at(time::PTS_MODULUS)
+                .build()
+                .err(),
+            Some(BuildError::FieldOutOfRange {
+                field_name: "splice_time.pts_time",
+                value: time::PTS_MODULUS
+            })
+        );
+        assert_eq!(
+            SpliceInsertBuilder::new(1)
+                .break_duration(ReturnMode::Manual, 1 << 33)
+                .build()
+                .err(),
+            Some(BuildError::FieldOutOfRange {
+                field_name: "break_duration.duration",
+                value: 1 << 33
+            })
+        );
+        assert_eq!(
+            SpliceInsertBuilder::new(1)
+                .component(1, None)
+                .component(2, Some(0))
+                .build()
+                .err(),
+            Some(BuildError::MixedComponentTiming)
+        );
+        assert_eq!(
+            SpliceInsertBuilder::new(1)
+                .component(1, None)
+                .component(1, None)
+                .build()
+                .err(),
+            Some(BuildError::DuplicateComponent(1))
+        );
+    }
+
+    #[test]
+    fn time_signal_builder() {
+        // the time_signal with a Program Start descriptor from the tests of the parser
+        let section = TimeSignalBuilder::new()
+            .at(0x1_592d_03c0)
+            .segmentation(
+                SegmentationDescriptorBuilder::new(1, SegmentationTypeId::ProgramStart)
+                    .segment(1, 1),
+            )
+            .build_section()
+            .unwrap();
+        assert_eq!(section.encode().unwrap(), test_util::TIME_SIGNAL);
+
+        let section = TimeSignalBuilder::new()
+            .segmentation(
+                SegmentationDescriptorBuilder::new(
+                    7,
+                    SegmentationTypeId::ProviderAdvertisementStart,
+                )
+                .upid(SegmentationUpid::AdID(crate::upid::AdID(
+                    "ABCD01234567".to_string(),
+                )))
+                .delivery_restrictions(false, true, true, DeviceRestrictions::RestrictGroup1)
+                .duration(30 * 90_000)
+                .segment(1, 2),
+            )
+            .build_section()
+            .unwrap();
+        let data = section.encode().unwrap();
+        let parsed = SpliceInfoSection::parse(&data).unwrap();
+        assert_matches!(
+            &parsed.splice_descriptors[..],
+            [SpliceDescriptor::SegmentationDescriptor {
+                segmentation_event_id: 7,
+                descriptor_detail: SegmentationDescriptor::Insert {
+                    delivery_not_restricted_flag: false,
+                    delivery_restrictions: DeliveryRestrictionFlags::DeliveryRestrictions {
+                        web_delivery_allowed_flag: false,
+                        device_restrictions: DeviceRestrictions::RestrictGroup1,
+                        ..
+                    },
+                    segmentation_duration: Some(2_700_000),
+                    segmentation_upid: SegmentationUpid::AdID(_),
+                    segment_num: 1,
+                    segments_expected: 2,
+                    ..
+                },
+            }]
+        );
+
+        assert_eq!(
+            SegmentationDescriptorBuilder::new(1, SegmentationTypeId::ProgramStart)
+                .sub_segment(1, 2)
+                .build()
+                .err(),
+            Some(BuildError::SubSegmentsNotAllowed(0x10))
+        );
+        assert_eq!(
+            SegmentationDescriptorBuilder::new(1, SegmentationTypeId::ProgramStart)
+                .upid(SegmentationUpid::TID(crate::upid::TID(
+                    "MV000414640".to_string()
+                )))
+                .build()
+                .err(),
+            Some(BuildError::InvalidUpid(UpidFormatError::Length {
+                expected: 12,
+                actual: 11
+            }))
+        );
+        assert!(SegmentationDescriptorBuilder::new(
+            1,
+            SegmentationTypeId::ProviderPlacementOpportunityStart
+        )
+        .sub_segment(1, 2)
+        .component(1, 0)
+        .build()
+        .is_ok());
+        assert_eq!(
+            TimeSignalBuilder::new()
+                .segmentation(
+                    SegmentationDescriptorBuilder::new(1, SegmentationTypeId::BreakStart)
+                        .duration(1 << 40)
+                )
+                .build_section()
+                .err(),
+            Some(BuildError::FieldOutOfRange {
+                field_name: "segmentation_descriptor.segmentation_duration",
+                value: 1 << 40
+            })
+        );
+    }
+
+    /// trivially reversible 'cipher' so that the result can be checked
+    struct XorCipher;
+    impl SectionCipher for XorCipher {
+        fn algorithm(&self) -> EncryptionAlgorithm {
+            EncryptionAlgorithm::Private(0x20)
+        }
+        fn encrypt(&self, data: &mut [u8]) {
+            assert_eq!(data.len() % 8, 0);
+            for b in data {
+                *b ^= 0x55;
+            }
+        }
+    }
+
+    #[test]
+    fn encrypted_section() {
+        let plain = splice_insert_cancel(7);
+        let data = encrypt(&plain, &XorCipher, 3).unwrap();
+        assert_eq!(mpeg2ts_reader::mpegts_crc::sum32(&data), 0);
+        assert_eq!(usize::from(data[2]) + 3, data.len());
+        let (header, _) = SpliceInfoHeader::try_new(&data[3..]).unwrap();
+        assert!(header.encrypted_packet());
+        assert_eq!(
+            header.encryption_algorithm(),
+            EncryptionAlgorithm::Private(0x20)
+        );
+        assert_eq!(header.cw_index(), 3);
+        // the unencrypted header fields are unchanged (apart from the command length)
+        assert_eq!(header.tier(), Tier::ALL);
+        assert_eq!(header.splice_command_length(), 5);
+
+        let mut payload = data[13..data.len() - 4].to_vec();
+        assert_eq!(payload.len() % 8, 0);
+        XorCipher.encrypt(&mut payload);
+        // E_CRC_32 covers the command, descriptor loop and stuffing
+        assert_eq!(mpeg2ts_reader::mpegts_crc::sum32(&payload), 0);
+        assert_eq!(&payload[..8], &plain[13..plain.len() - 4]);
+
+        assert_eq!(
+            encrypt(&data, &XorCipher, 0),
+            Err(EncryptError::AlreadyEncrypted)
+        );
+    }
+
+    #[cfg(feature = "des")]
+    #[test]
+    fn des_ecb() {
+        use des::cipher::{BlockDecrypt, KeyInit};
+        let key = [1, 2, 3, 4, 5, 6, 7, 8];
+        let data = encrypt(&segmentation_cancel(1), &DesEcb::new(&key), 0).unwrap();
+        assert_eq!(
+            SpliceInfoHeader::try_new(&data[3..])
+                .unwrap()
+                .0
+                .encryption_algorithm(),
+            EncryptionAlgorithm::DesEcb
+        );
+        let des = des::Des::new((&key).into());
+        let mut payload = data[13..data.len() - 4].to_vec();
+        for block in payload.chunks_exact_mut(8) {
+            des.decrypt_block(block.into());
+        }
+        assert_eq!(mpeg2ts_reader::mpegts_crc::sum32(&payload), 0);
+    }
+}
@@ -0,0 +1,641 @@
+//! Checks of sections against the normative rules of _SCTE-35_, for QC tools which need to
+//! report every problem with a cue rather than just whether it could be parsed.
+//!
+//! [`check()`](fn.check.html) examines the bytes of a section, reporting both the problems
+//! that the parser notices in the syntax (such as reserved bits which are not set) and those
+//! found by [`check_section()`](fn.check_section.html) in the values of an already-parsed
+//! `SpliceInfoSection` (such as field combinations the spec prohibits).  Each problem is
+//! reported as a [`Finding`](struct.Finding.html) of one of the [`RULES`](constant.RULES.html),
+//! each of which has a stable identifier and a [`Severity`](enum.Severity.html).
+//!
+//! ## Example
+//!
+//! ```
+//! # use hex_literal::*;
+//! # use scte35_reader::conformance::{self, Severity};
+//! let data = hex!("fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49");
+//! let report = conformance::check(&data);
+//! assert!(report.is_conformant());
+//! for finding in &report.findings {
+//!     println!("{}", finding);
+//! }
+//! ```
+
+use crate::{
+    NetworkIndicator, ScheduledSplice, ScheduledSpliceMode, Scte35Diagnostics, Scte35Error,
+    Scte35Warning, SegmentationDescriptor, SegmentationMode, SegmentationTypeId, SpliceCommand,
+    SpliceDescriptor, SpliceInfoSection, SpliceInsert, SpliceMode, UpidLengthMode,
+};
+use std::cell::RefCell;
+use std::fmt;
+
+/// How serious a breach of a rule is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Not a breach of the spec, but something which limits the checks that can be made, or
+    /// which downstream equipment may handle unexpectedly
+    Info,
+    /// A breach which equipment can usually tolerate, such as reserved bits not being set
+    Warning,
+    /// A breach which is likely to cause the cue to be rejected or misinterpreted
+    Error,
+}
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A rule which sections are checked against
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    /// Identifies the rule in reports, and will not change between releases
+    pub id: &'static str,
+    pub severity: Severity,
+    /// A short statement of what the rule requires
+    pub summary: &'static str,
+}
+
+macro_rules! rules {
+    ($($name:ident = ($id:literal, $severity:ident, $summary:literal),)*) => {
+        $(
+            #[doc = $summary]
+            pub const $name: Rule = Rule {
+                id: $id,
+                severity: Severity::$severity,
+                summary: $summary,
+            };
+        )*
+        /// Every rule that sections are checked against
+        pub const RULES: &[Rule] = &[$($name),*];
+    };
+}
+
+rules! {
+    SECTION_INVALID = ("section.invalid", Error, "The section must be parseable"),
+    SECTION_CRC = ("section.crc", Error, "The CRC_32 must match the content of the section"),
+    PRIVATE_INDICATOR = ("section.private-indicator", Warning, "The private_indicator must be 0"),
+    PROTOCOL_VERSION = ("section.protocol-version", Error, "The protocol_version must be 0"),
+    ENCRYPTED = ("section.encrypted", Info, "The content of encrypted sections can not be checked"),
+    LEGACY_COMMAND_LENGTH = (
+        "section.legacy-command-length",
+        Warning,
+        "The splice_command_length must be given, rather than 0xfff"
+    ),
+    RESERVED_BITS = (
+        "syntax.reserved-bits",
+        Warning,
+        "Reserved fields must have all bits set to 1"
+    ),
+    TRAILING_DATA = (
+        "syntax.trailing-data",
+        Warning,
+        "Commands and descriptors must not have trailing bytes"
+    ),
+    DESCRIPTOR_TAG = (
+        "descriptor.tag",
+        Warning,
+        "CUEI descriptors must use a defined splice_descriptor_tag"
+    ),
+    DUPLICATE_COMPONENT = (
+        "component.duplicate",
+        Error,
+        "A component_tag must not appear more than once in a component loop"
+    ),
+    BREAK_DURATION_IN = (
+        "splice-insert.duration-in",
+        Warning,
+        "A break_duration should only be given with out_of_network_indicator set"
+    ),
+    DTMF_CHAR = ("dtmf.char", Error, "DTMF_char values must be 0-9, * or #"),
+    TAI_NANOSECONDS = ("time.nanoseconds", Error, "TAI_ns must be less than 1,000,000,000"),
+    SEGMENTATION_TYPE = (
+        "segmentation.type",
+        Warning,
+        "The segmentation_type_id must be a defined value"
+    ),
+    UPID_LENGTH = (
+        "segmentation.upid-length",
+        Error,
+        "A UPID must have the length that its segmentation_upid_type requires"
+    ),
+    UPID_URI = (
+        "segmentation.upid-uri",
+        Error,
+        "A URI UPID must hold a valid URI"
+    ),
+    SEGMENT_NUM = (
+        "segmentation.segment-num",
+        Warning,
+        "A segment number must not exceed the number of segments expected"
+    ),
+}
+
+/// A breach of a rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: &'static Rule,
+    /// Describes the particular breach, including where in the section it was found
+    pub message: String,
+}
+impl Finding {
+    fn new(rule: &'static Rule, message: impl Into<String>) -> Finding {
+        Finding {
+            rule,
+            message: message.into(),
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.rule.severity
+    }
+}
+/// Formats as, e.g. `warning [syntax.reserved-bits] splice_time.reserved was 0x00`
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}] {}",
+            self.rule.severity, self.rule.id, self.message
+        )
+    }
+}
+
+/// The breaches of rules found in a section, in the order in which they were found
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConformanceReport {
+    pub findings: Vec<Finding>,
+}
+impl ConformanceReport {
+    /// `true` if there are no findings of `Severity::Error`
+    pub fn is_conformant(&self) -> bool {
+        self.worst() < Some(Severity::Error)
+    }
+
+    /// The severity of the most serious finding, or `None` if there are no findings
+    pub fn worst(&self) -> Option<Severity> {
+        self.findings.iter().map(Finding::severity).max()
+    }
+
+    fn add(&mut self, rule: &'static Rule, message: impl Into<String>) {
+        self.findings.push(Finding::new(rule, message));
+    }
+}
+/// One finding per line
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for finding in &self.findings {
+            writeln!(f, "{}", finding)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the bytes of a section, through to the end given by its `section_length`.
+///
+/// If the section can not be parsed, the report consists of a single `SECTION_INVALID` (or
+/// `SECTION_CRC`) finding.
+pub fn check(data: &[u8]) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+    let collector = Collector::default();
+    let section = match SpliceInfoSection::parse_with(data, &collector) {
+        Ok(section) => {
+            for warning in collector.0.into_inner() {
+                add_warning(&mut report, warning);
+            }
+            section
+        }
+        Err(Scte35Error::Crc(crc)) => {
+            report.add(
+                &SECTION_CRC,
+                format!("CRC_32 check failed (residue {:#010x})", crc),
+            );
+            return report;
+        }
+        Err(e) => {
+            report.add(&SECTION_INVALID, e.to_string());
+            return report;
+        }
+    };
+    report.findings.extend(check_section(&section).findings);
+    report
+}
+
+/// Collects warnings, accepting UPIDs of the wrong length so that they can be reported along
+/// with any other problems in the section
+#[derive(Default)]
+struct Collector(RefCell<Vec<Scte35Warning>>);
+impl Scte35Diagnostics for Collector {
+    fn warning(&self, warning: &Scte35Warning) {
+        self.0.borrow_mut().push(warning.clone());
+    }
+
+    fn upid_length_mode(&self) -> UpidLengthMode {
+        UpidLengthMode::Warn
+    }
+}
+
+fn add_warning(report: &mut ConformanceReport, warning: Scte35Warning) {
+    match warning {
+        Scte35Warning::TrailingData {
+            syntax,
+            consumed,
+            length,
+        } => report.add(
+            &TRAILING_DATA,
+            format!(
+                "{}() was {} bytes long, but its content ended after {} bytes",
+                syntax, length, consumed
+            ),
+        ),
+        Scte35Warning::UnexpectedDescriptorTag(tag) => report.add(
+            &DESCRIPTOR_TAG,
+            format!(
+                "CUEI descriptor with undefined splice_descriptor_tag {:#04x}",
+                tag
+            ),
+        ),
+        Scte35Warning::Crc(crc) => report.add(
+            &SECTION_CRC,
+            format!("CRC_32 check failed (residue {:#010x})", crc),
+        ),
+        Scte35Warning::LegacySpliceCommandLength => {
+            report.add(&LEGACY_COMMAND_LENGTH, "splice_command_length was 0xfff")
+        }
+        Scte35Warning::PrivateIndicator => {
+            report.add(&PRIVATE_INDICATOR, "private_indicator was 1")
+        }
+        Scte35Warning::ReservedBits { field_name, value } => {
+            report.add(&RESERVED_BITS, format!("{} was {:#04x}", field_name, value))
+        }
+        Scte35Warning::UpidLength {
+            upid_type,
+            expected,
+            actual,
+        } => report.add(
+            &UPID_LENGTH,
+            format!(
+                "{} UPID was {} bytes long, rather than {}",
+                upid_type, actual, expected
+            ),
+        ),
+        Scte35Warning::MalformedUri(bytes) => report.add(
+            &UPID_URI,
+            format!(
+                "URI UPID {:?} is not a valid URI",
+                String::from_utf8_lossy(&bytes)
+            ),
+        ),
+    }
+}
+
+/// Checks the values of an already-parsed section.
+///
+/// Problems with the syntax of the section which the parser tolerated, such as reserved bits
+/// which are not set, can only be found by `check()`.
+pub fn check_section(section: &SpliceInfoSection) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+    if section.protocol_version != 0 {
+        report.add(
+            &PROTOCOL_VERSION,
+            format!("protocol_version was {}", section.protocol_version),
+        );
+    }
+    if section.encrypted_packet {
+        report.add(
+            &ENCRYPTED,
+            format!(
+                "encrypted with {:?}; the command and descriptors were not checked",
+                section.encryption_algorithm
+            ),
+        );
+        return report;
+    }
+    check_command(&mut report, &section.splice_command);
+    for (index, descriptor) in section.splice_descriptors.iter().enumerate() {
+        check_descriptor(&mut report, index, descriptor);
+    }
+    report
+}
+
+fn check_command(report: &mut ConformanceReport, command: &SpliceCommand) {
+    match command {
+        SpliceCommand::SpliceInsert {
+            splice_detail:
+                SpliceInsert::Insert {
+                    network_indicator,
+                    splice_mode,
+                    duration,
+                    ..
+                },
+            ..
+        } => {
+            if let SpliceMode::Components(components) = splice_mode {
+                check_components(
+                    report,
+                    "splice_insert()",
+                    components.iter().map(|c| c.component_tag),
+                );
+            }
+            if duration.is_some() && *network_indicator == NetworkIndicator::In {
+                report.add(
+                    &BREAK_DURATION_IN,
+                    "splice_insert() has a break_duration, but out_of_network_indicator is 0",
+                );
+            }
+        }
+        SpliceCommand::SpliceSchedule { events } => {
+            for event in events {
+                if let ScheduledSplice::Insert {
+                    splice_mode: ScheduledSpliceMode::Components(components),
+                    ..
+                } = &event.splice_detail
+                {
+                    check_components(
+                        report,
+                        &format!("splice_schedule() event {}", event.splice_event_id),
+                        components.iter().map(|c| c.component_tag),
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_descriptor(report: &mut ConformanceReport, index: usize, descriptor: &SpliceDescriptor) {
+    match descriptor {
+        SpliceDescriptor::DTMFDescriptor { .. } => {
+            if let Some(Err(e)) = descriptor.dtmf_str() {
+                report.add(&DTMF_CHAR, format!("descriptor {}: {}", index, e));
+            }
+        }
+        SpliceDescriptor::TimeDescriptor {
+            tai_nanoseconds, ..
+        } if *tai_nanoseconds >= 1_000_000_000 => {
+            report.add(
+                &TAI_NANOSECONDS,
+                format!("descriptor {}: TAI_ns was {}", index, tai_nanoseconds),
+            );
+        }
+        SpliceDescriptor::SegmentationDescriptor {
+            descriptor_detail:
+                SegmentationDescriptor::Insert {
+                    segmentation_mode,
+                    segmentation_type_id,
+                    segment_num,
+                    segments_expected,
+                    sub_segments,
+                    ..
+                },
+            ..
+        } => {
+            let type_id = segmentation_type_id.id();
+            // the Opening and Closing Credit types are defined, though they have no variants
+            if std::matches!(segmentation_type_id, SegmentationTypeId::Reserved(_))
+                && !std::matches!(type_id, 0x24..=0x27)
+            {
+                report.add(
+                    &SEGMENTATION_TYPE,
+                    format!(
+                        "descriptor {}: segmentation_type_id {:#04x} is reserved",
+                        index, type_id
+                    ),
+                );
+            }
+            if let SegmentationMode::Component { components } = segmentation_mode {
+                check_components(
+                    report,
+                    &format!("descriptor {}", index),
+                    components.iter().map(|c| c.component_tag),
+                );
+            }
+            check_segment_num(
+                report,
+                index,
+                "segment_num",
+                *segment_num,
+                "segments_expected",
+                *segments_expected,
+            );
+            if let Some(sub_segments) = sub_segments {
+                check_segment_num(
+                    report,
+                    index,
+                    "sub_segment_num",
+                    sub_segments.sub_segment_num,
+                    "sub_segments_expected",
+                    sub_segments.sub_segments_expected,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_components(
+    report: &mut ConformanceReport,
+    location: &str,
+    component_tags: impl Iterator<Item = u8>,
+) {
+    let mut seen = [false; 256];
+    let mut reported = [false; 256];
+    for tag in component_tags {
+        let i = usize::from(tag);
+        if seen[i] && !reported[i] {
+            reported[i] = true;
+            report.add(
+                &DUPLICATE_COMPONENT,
+                format!("{}: component_tag {} appears more than once", location, tag),
+            );
+        }
+        seen[i] = true;
+    }
+}
+
+/// A zero value for the expected number is permitted, as indicating that numbering is not used
+fn check_segment_num(
+    report: &mut ConformanceReport,
+    index: usize,
+    num_name: &str,
+    num: u8,
+    expected_name: &str,
+    expected: u8,
+) {
+    if expected != 0 && num > expected {
+        report.add(
+            &SEGMENT_NUM,
+            format!(
+                "descriptor {}: {} {} is greater than {} {}",
+                index, num_name, num, expected_name, expected
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use crate::{ComponentSplice, ReturnMode, SpliceDuration, SpliceTime};
+    use hex_literal::*;
+
+    fn rule_ids(report: &ConformanceReport) -> Vec<&'static str> {
+        report.findings.iter().map(|f| f.rule.id).collect()
+    }
+
+    #[test]
+    fn conformant() {
+        let report = check(&TIME_SIGNAL);
+        assert_eq!(report, ConformanceReport::default());
+        assert!(report.is_conformant());
+        assert_eq!(report.worst(), None);
+    }
+
+    #[test]
+    fn syntax() {
+        // a time_signal() with zeroed reserved bits, a DTMF_descriptor() with an invalid
+        // character, and a segmentation_descriptor() for a Program Start with a segment_num
+        // greater than segments_expected, followed by sub-segment fields which its type can not
+        // carry
+        let data = with_crc(
+            hex!(
+                "fc3034 00 0000000000 00 fff005 06 8000000000 001e"
+                "0109 43554549 00 7f 313258"
+                "0211 43554549 00000001 7f bf 0000 10 0302 0101"
+            )
+            .to_vec(),
+        );
+        let report = check(&data);
+        assert_eq!(
+            rule_ids(&report),
+            [
+                "syntax.reserved-bits",
+                "syntax.trailing-data",
+                "dtmf.char",
+                "segmentation.segment-num",
+            ]
+        );
+        assert!(!report.is_conformant());
+        assert_eq!(report.worst(), Some(Severity::Error));
+        assert_eq!(
+            report.findings[0].to_string(),
+            "warning [syntax.reserved-bits] splice_time.reserved was 0x00"
+        );
+        assert_eq!(
+            report.findings[1].message,
+            "segmentation_descriptor() was 13 bytes long, but its content ended after 11 bytes"
+        );
+
+        let mut bad_crc = data.clone();
+        bad_crc[5] ^= 1;
+        assert_eq!(rule_ids(&check(&bad_crc)), ["section.crc"]);
+        assert_eq!(rule_ids(&check(&data[..10])), ["section.invalid"]);
+    }
+
+    #[test]
+    fn upid_length() {
+        // a segmentation_descriptor() with an 11 byte Ad-ID
+        let data = with_crc(
+            hex!(
+                "fc3032 00 0000000000 00 fff005 06 fe00000000 001c"
+                "021a43554549 00000001 7f bf 030b 4142434430303031303030 10 0000"
+            )
+            .to_vec(),
+        );
+        let report = check(&data);
+        assert_eq!(rule_ids(&report), ["segmentation.upid-length"]);
+        assert_eq!(
+            report.findings[0].message,
+            "Ad-ID UPID was 11 bytes long, rather than 12"
+        );
+    }
+
+    #[test]
+    fn upid_uri() {
+        // a segmentation_descriptor() with a URI UPID lacking a scheme
+        let data = with_crc(
+            hex!(
+                "fc3030 00 0000000000 00 fff005 06 fe00000000 001a"
+                "021843554549 00000001 7f bf 0f09 6e6f20736368656d65 10 0000"
+            )
+            .to_vec(),
+        );
+        let report = check(&data);
+        assert_eq!(rule_ids(&report), ["segmentation.upid-uri"]);
+        assert_eq!(
+            report.findings[0].message,
+            "URI UPID \"no scheme\" is not a valid URI"
+        );
+    }
+
+    #[test]
+    fn values() {
+        let mut section = SpliceInfoSection::parse(&SPLICE_INSERT).unwrap();
+        assert_eq!(check_section(&section), ConformanceReport::default());
+        section.protocol_version = 1;
+        section.splice_command = SpliceCommand::SpliceInsert {
+            splice_event_id: 1,
+            reserved: 0x7f,
+            splice_detail: SpliceInsert::Insert {
+                network_indicator: NetworkIndicator::In,
+                splice_mode: SpliceMode::Components(
+                    [1, 2, 1, 1]
+                        .iter()
+                        .map(|&component_tag| ComponentSplice {
+                            component_tag,
+                            splice_time: SpliceTime::Immediate,
+                        })
+                        .collect(),
+                ),
+                duration: Some(SpliceDuration {
+                    return_mode: ReturnMode::Automatic,
+                    duration: 90_000,
+                }),
+                unique_program_id: 1,
+                avail_num: 0,
+                avails_expected: 0,
+            },
+        };
+        section.splice_descriptors = vec![SpliceDescriptor::TimeDescriptor {
+            tai_seconds: 0,
+            tai_nanoseconds: 1_000_000_000,
+            utc_offset: 37,
+        }];
+        let report = check_section(&section);
+        assert_eq!(
+            rule_ids(&report),
+            [
+                "section.protocol-version",
+                "component.duplicate",
+                "splice-insert.duration-in",
+                "time.nanoseconds",
+            ]
+        );
+        assert_eq!(
+            report.findings[1].message,
+            "splice_insert(): component_tag 1 appears more than once"
+        );
+
+        section.encrypted_packet = true;
+        assert_eq!(
+            rule_ids(&check_section(&section)),
+            ["section.protocol-version", "section.encrypted"]
+        );
+    }
+
+    #[test]
+    fn rule_ids_unique() {
+        for (i, rule) in RULES.iter().enumerate() {
+            assert!(
+                RULES[..i].iter().all(|r| r.id != rule.id),
+                "duplicate {}",
+                rule.id
+            );
+        }
+    }
+}
@@ -0,0 +1,384 @@
+//! An iterator that extracts SCTE-35 cues from Transport Stream packets, taking care of locating
+//! the SCTE-35 elementary streams of every program along the way.
+//!
+//! ## Example
+//!
+//! ```
+//! # let packets: Vec<[u8; 188]> = vec![];
+//! for cue in scte35_reader::cue::cues(&packets) {
+//!     match cue {
+//!         Ok(cue) => println!(
+//!             "program {}: {:?}",
+//!             cue.stream.program_number, cue.section.splice_command
+//!         ),
+//!         Err(e) => println!("bad cue: {:?}", e),
+//!     }
+//! }
+//! ```
+//!
+//! Cues can also be read directly from a file or other `io::Read`, using `scan_file()` or
+//! `scan_reader()`:
+//!
+//! ```no_run
+//! for cue in scte35_reader::cue::scan_file("capture.ts").unwrap() {
+//!     let cue = cue.unwrap();
+//!     println!(
+//!         "PID {:?} at PCR {:?}: {:?}",
+//!         cue.stream.elementary_pid, cue.pcr, cue.section.splice_command
+//!     );
+//! }
+//! ```
+
+use crate::demux::{Scte35DemuxContext, Scte35Stream, SpliceInfoProcessorFactory};
+use crate::{
+    CrcMode, Scte35Diagnostics, Scte35Error, SpliceCommand, SpliceDescriptors, SpliceInfoHeader,
+    SpliceInfoProcessor, SpliceInfoSection,
+};
+use mpeg2ts_reader::demultiplex;
+use mpeg2ts_reader::packet;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use std::{fmt, fs, io, path};
+
+/// A _splice_info_section_, together with the details of the stream on which it was found.
+#[derive(Debug)]
+pub struct Cue {
+    pub stream: Scte35Stream,
+    pub section: SpliceInfoSection,
+    /// The complete encoded section, from `table_id` through to `CRC_32`
+    pub bytes: Vec<u8>,
+    /// The last PCR value (in 27MHz units) seen on the program's `PCR_PID` before the section
+    /// was complete, or `None` if there has been none yet
+    pub pcr: Option<u64>,
+}
+
+/// Creates an iterator over the cues found within the given sequence of 188-byte TS packets.
+pub fn cues<I>(packets: I) -> Cues<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    Cues {
+        packets: packets.into_iter(),
+        demux: CueDemux::new(CrcMode::default()),
+    }
+}
+
+/// Iterator over the cues within a sequence of TS packets, created by [`cues()`](fn.cues.html).
+///
+/// Sections which fail to parse are produced as `Err` items, and iteration continues with the
+/// next section.
+pub struct Cues<I> {
+    packets: I,
+    demux: CueDemux,
+}
+impl<I> Cues<I> {
+    /// How sections with an incorrect `CRC_32` are treated, as for
+    /// `Scte35SectionProcessor::with_crc_mode()`.  Defaults to `CrcMode::Strict`.
+    ///
+    /// The demultiplexer is restarted, so this is to be used before iteration begins.
+    pub fn with_crc_mode(mut self, crc_mode: CrcMode) -> Self {
+        self.demux = CueDemux::new(crc_mode);
+        self
+    }
+}
+impl<I> Iterator for Cues<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    type Item = Result<Cue, Scte35Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(cue) = self.demux.next_cue() {
+                return Some(cue);
+            }
+            let pk = self.packets.next()?;
+            self.demux.push(pk.as_ref());
+        }
+    }
+}
+
+/// Creates an iterator over the cues found within the Transport Stream read from the given
+/// reader, through to its end.
+///
+/// Any trailing partial packet at the end of the input is ignored.
+pub fn scan_reader<R: io::Read>(reader: R) -> Scan<R> {
+    Scan {
+        reader: Some(reader),
+        demux: CueDemux::new(CrcMode::default()),
+    }
+}
+
+/// Opens the given Transport Stream file, and creates an iterator over the cues within it, as
+/// for `scan_reader()`.
+pub fn scan_file<P: AsRef<path::Path>>(path: P) -> io::Result<Scan<io::BufReader<fs::File>>> {
+    Ok(scan_reader(io::BufReader::new(fs::File::open(path)?)))
+}
+
+/// Problems reported by the iterator returned from `scan_reader()`
+#[derive(Debug)]
+pub enum ScanError {
+    /// Reading from the underlying reader failed, after which iteration ends
+    Io(io::Error),
+    /// A section could not be parsed; iteration continues with the next section
+    Section(Scte35Error),
+}
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanError::Io(_) => f.write_str("failed to read Transport Stream"),
+            ScanError::Section(_) => f.write_str("invalid section"),
+        }
+    }
+}
+impl std::error::Error for ScanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScanError::Io(e) => Some(e),
+            ScanError::Section(e) => Some(e),
+        }
+    }
+}
+
+/// Iterator over the cues within a Transport Stream read from an `io::Read`, created by
+/// [`scan_reader()`](fn.scan_reader.html).
+pub struct Scan<R> {
+    /// `None` once the end of the input has been reached, or reading has failed
+    reader: Option<R>,
+    demux: CueDemux,
+}
+impl<R> Scan<R> {
+    /// How sections with an incorrect `CRC_32` are treated, as for
+    /// `Scte35SectionProcessor::with_crc_mode()`.  Defaults to `CrcMode::Strict`.
+    ///
+    /// The demultiplexer is restarted, so this is to be used before iteration begins.
+    pub fn with_crc_mode(mut self, crc_mode: CrcMode) -> Self {
+        self.demux = CueDemux::new(crc_mode);
+        self
+    }
+}
+impl<R: io::Read> Iterator for Scan<R> {
+    type Item = Result<Cue, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(cue) = self.demux.next_cue() {
+                return Some(cue.map_err(ScanError::Section));
+            }
+            let reader = self.reader.as_mut()?;
+            let mut pk = [0u8; packet::Packet::SIZE];
+            match reader.read_exact(&mut pk) {
+                Ok(()) => self.demux.push(&pk),
+                Err(e) => {
+                    self.reader = None;
+                    if e.kind() != io::ErrorKind::UnexpectedEof {
+                        return Some(Err(ScanError::Io(e)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The demultiplexer shared by `Cues`, `Scan` and `stream::CueStream`, which notes the PCR
+/// values seen on every PID so that they can be attached to the cues found
+pub(crate) struct CueDemux {
+    ctx: Scte35DemuxContext<CueFactory, CueDiagnostics>,
+    demux: demultiplex::Demultiplex<Scte35DemuxContext<CueFactory, CueDiagnostics>>,
+    cues: CueQueue,
+    pcrs: Rc<RefCell<HashMap<packet::Pid, u64>>>,
+}
+impl CueDemux {
+    pub(crate) fn new(crc_mode: CrcMode) -> CueDemux {
+        let cues = CueQueue::default();
+        let pcrs = Rc::new(RefCell::new(HashMap::new()));
+        let factory = CueFactory {
+            cues: cues.clone(),
+            pcrs: pcrs.clone(),
+        };
+        let mut ctx = Scte35DemuxContext::new(factory)
+            .with_diagnostics(CueDiagnostics(cues.clone()))
+            .with_crc_mode(crc_mode);
+        let demux = demultiplex::Demultiplex::new(&mut ctx);
+        CueDemux {
+            ctx,
+            demux,
+            cues,
+            pcrs,
+        }
+    }
+
+    pub(crate) fn push(&mut self, data: &[u8]) {
+        for buf in data.chunks(packet::Packet::SIZE) {
+            if let Some(pk) = packet::Packet::try_new(buf) {
+                if let Some(Ok(pcr)) = pk.adaptation_field().map(|af| af.pcr()) {
+                    self.pcrs.borrow_mut().insert(pk.pid(), u64::from(pcr));
+                }
+            }
+            self.demux.push(&mut self.ctx, buf);
+        }
+    }
+
+    /// The next of the cues found in the data pushed so far
+    pub(crate) fn next_cue(&mut self) -> Option<Result<Cue, Scte35Error>> {
+        self.cues.borrow_mut().pop_front()
+    }
+}
+
+/// The cues (and the errors in place of cues) found so far, shared between `CueDemux` and the
+/// processors and diagnostics that it creates
+type CueQueue = Rc<RefCell<VecDeque<Result<Cue, Scte35Error>>>>;
+
+/// Creates a `CueProcessor` for each SCTE-35 stream
+struct CueFactory {
+    cues: CueQueue,
+    /// The last PCR seen on each PID
+    pcrs: Rc<RefCell<HashMap<packet::Pid, u64>>>,
+}
+impl SpliceInfoProcessorFactory for CueFactory {
+    type Processor = CueProcessor;
+
+    fn create(&mut self, stream: &Scte35Stream) -> CueProcessor {
+        CueProcessor {
+            stream: Cell::new(*stream),
+            cues: self.cues.clone(),
+            pcrs: self.pcrs.clone(),
+        }
+    }
+
+    fn stream_moved(
+        &mut self,
+        processor: &CueProcessor,
+        _previous: &Scte35Stream,
+        current: &Scte35Stream,
+    ) {
+        processor.stream.set(*current);
+    }
+}
+
+struct CueProcessor {
+    stream: Cell<Scte35Stream>,
+    cues: CueQueue,
+    pcrs: Rc<RefCell<HashMap<packet::Pid, u64>>>,
+}
+impl SpliceInfoProcessor for CueProcessor {
+    fn process(
+        &self,
+        _header: SpliceInfoHeader<'_>,
+        _command: SpliceCommand,
+        _descriptors: SpliceDescriptors<'_>,
+    ) {
+        unreachable!("Scte35SectionProcessor only calls process_section()")
+    }
+
+    fn process_section(
+        &self,
+        section: &[u8],
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        let stream = self.stream.get();
+        let cue = SpliceInfoSection::from_parts(&header, command, &descriptors)
+            .map(|parsed| Cue {
+                stream,
+                section: parsed,
+                bytes: section.to_vec(),
+                pcr: self.pcrs.borrow().get(&stream.pcr_pid).copied(),
+            })
+            .map_err(Scte35Error::Descriptor);
+        self.cues.borrow_mut().push_back(cue);
+    }
+}
+
+/// Queues the sections which could not be parsed as `Err` items, and logs any warnings
+#[derive(Clone)]
+struct CueDiagnostics(CueQueue);
+impl Scte35Diagnostics for CueDiagnostics {
+    fn error(&self, error: &Scte35Error) {
+        self.0.borrow_mut().push_back(Err(error.clone()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use crate::SpliceCommand;
+    use hex_literal::*;
+    use matches::*;
+
+    #[test]
+    fn cues_from_packets() {
+        let pat = with_crc(hex!("00b00d 0001 c1 00 00 0001e100").to_vec());
+        let pmt = with_crc(hex!("02b018 0001 c1 00 00 e1fff006 0504 43554549 86e101f000").to_vec());
+        let mut bad_crc = SPLICE_NULL;
+        bad_crc[19] ^= 1;
+
+        let mut packets = vec![
+            section_packet(0, &pat),
+            section_packet(0x100, &pmt),
+            section_packet(0x101, &SPLICE_NULL),
+            section_packet(0x101, &bad_crc),
+        ];
+        // continuity_counter
+        packets[3][3] |= 1;
+        let cues: Vec<_> = cues(&packets).collect();
+        assert_eq!(cues.len(), 2);
+        let cue = cues[0].as_ref().unwrap();
+        assert_eq!(cue.stream.program_number, 1);
+        assert_eq!(cue.stream.elementary_pid, packet::Pid::new(0x101));
+        assert_matches!(cue.section.splice_command, SpliceCommand::SpliceNull {});
+        assert_eq!(cue.bytes, SPLICE_NULL);
+        assert_matches!(cues[1], Err(Scte35Error::Crc(_)));
+    }
+
+    #[test]
+    fn crc_mode() {
+        let pat = with_crc(hex!("00b00d 0001 c1 00 00 0001e100").to_vec());
+        let pmt = with_crc(hex!("02b018 0001 c1 00 00 e1fff006 0504 43554549 86e101f000").to_vec());
+        let mut bad_crc = SPLICE_NULL;
+        bad_crc[19] ^= 1;
+
+        let packets = vec![
+            section_packet(0, &pat),
+            section_packet(0x100, &pmt),
+            section_packet(0x101, &bad_crc),
+        ];
+        let cues: Vec<_> = cues(&packets).with_crc_mode(CrcMode::Warn).collect();
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].as_ref().unwrap().bytes, bad_crc);
+    }
+
+    #[test]
+    fn scan() {
+        let pat = with_crc(hex!("00b00d 0001 c1 00 00 0001e100").to_vec());
+        // PCR_PID 0x1ff
+        let pmt = with_crc(hex!("02b018 0001 c1 00 00 e1fff006 0504 43554549 86e101f000").to_vec());
+        // adaptation field only (so adaptation_field_length is 183), with a PCR of base 1,
+        // extension 2
+        let mut pcr = vec![0x47, 0x01, 0xff, 0x20, 0xb7, 0x10, 0, 0, 0, 0, 0xfe, 0x02];
+        pcr.resize(packet::Packet::SIZE, 0xff);
+
+        let mut ts = vec![];
+        ts.extend(section_packet(0, &pat));
+        ts.extend(section_packet(0x100, &pmt));
+        ts.extend(section_packet(0x101, &SPLICE_NULL));
+        ts.extend(pcr);
+        let mut second = section_packet(0x101, &SPLICE_NULL);
+        second[3] |= 1;
+        ts.extend(second);
+        // a trailing partial packet
+        ts.extend([0x47, 0x01]);
+
+        let cues: Vec<_> = scan_reader(&ts[..]).collect();
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].as_ref().unwrap().pcr, None);
+        let cue = cues[1].as_ref().unwrap();
+        assert_eq!(cue.stream.elementary_pid, packet::Pid::new(0x101));
+        assert_eq!(cue.pcr, Some(302));
+    }
+}
@@ -0,0 +1,388 @@
+//! Carriage of _SCTE-35_ cues in DASH MPD `EventStream` elements, per _SCTE 214-1_.
+//!
+//! Event timing in an MPD is expressed as a `presentationTime` in the `timescale` of the
+//! `EventStream`, relative to the start of the `Period`, so the caller supplies the mapping
+//! from PTS as a [`Timeline`](trait.Timeline.html) (for example a
+//! [`PeriodTimeline`](struct.PeriodTimeline.html)).
+//!
+//! Generation of `Event` elements requires this crate's `base64` feature, and reading the cues
+//! of an MPD with [`parse_event_streams()`](fn.parse_event_streams.html) requires both the `xml`
+//! and `base64` features.
+//!
+//! ## Example
+//!
+//! ```
+//! # #[cfg(feature = "base64")]
+//! # {
+//! # use hex_literal::*;
+//! # use scte35_reader::SpliceInfoSection;
+//! # use scte35_reader::dash::{event, EventStream, PeriodTimeline};
+//! let section = SpliceInfoSection::parse(&hex!(
+//!     "fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49"
+//! ))
+//! .unwrap();
+//! let timeline = PeriodTimeline {
+//!     timescale: 1000,
+//!     period_start_pts: 0x2d142b00 - 90_000 * 60,
+//!     presentation_time_offset: 0,
+//! };
+//! let stream = EventStream {
+//!     timescale: 1000,
+//!     events: vec![event(&section, 0, &timeline).unwrap()],
+//! };
+//! assert_eq!(
+//!     stream.to_string(),
+//!     "<EventStream schemeIdUri=\"urn:scte:scte35:2014:xml+bin\" timescale=\"1000\">\
+//!      <Event presentationTime=\"60000\" duration=\"212500\" id=\"1\">\
+//!      <Signal xmlns=\"http://www.scte.org/schemas/35/2016\">\
+//!      <Binary>/DAlAAAAAAAAAP/wFAUAAAABf+/+LRQrAP4BI9MIAAEBAQAAfxV6SQ==</Binary>\
+//!      </Signal></Event></EventStream>"
+//! );
+//! # }
+//! ```
+
+use crate::time::{self, PTS_MODULUS};
+#[cfg(feature = "base64")]
+use crate::{
+    encode::EncodeError, SegmentationDescriptor, SpliceCommand, SpliceDescriptor,
+    SpliceInfoSection, SpliceInsert,
+};
+#[cfg(feature = "base64")]
+use std::fmt;
+
+/// The `schemeIdUri` of an `EventStream` whose events each hold a base64 encoded
+/// `splice_info_section()` in a `Signal/Binary` element
+pub const SCHEME_XML_BIN: &str = "urn:scte:scte35:2014:xml+bin";
+
+/// The `schemeIdUri` of an `EventStream` whose events each hold the XML representation of a
+/// `SpliceInfoSection`
+pub const SCHEME_XML: &str = "urn:scte:scte35:2013:xml";
+
+/// Maps PTS values (with any `pts_adjustment` already applied) onto the timeline of an
+/// `EventStream`.
+pub trait Timeline {
+    /// The `timescale` of the `EventStream`, in ticks per second
+    fn timescale(&self) -> u64;
+    /// The `presentationTime` corresponding to the given PTS
+    fn presentation_time(&self, pts: u64) -> u64;
+}
+
+/// A `Timeline` for a `Period` whose start corresponds to a known PTS value.
+///
+/// PTS values are measured forward from `period_start_pts`, allowing for the wrap of the 33-bit
+/// PTS counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeriodTimeline {
+    pub timescale: u64,
+    /// The PTS of the first media sample of the `Period`
+    pub period_start_pts: u64,
+    /// The `presentationTimeOffset` of the `EventStream`, added to all presentation times
+    pub presentation_time_offset: u64,
+}
+impl Timeline for PeriodTimeline {
+    fn timescale(&self) -> u64 {
+        self.timescale
+    }
+
+    fn presentation_time(&self, pts: u64) -> u64 {
+        let elapsed = pts.wrapping_sub(self.period_start_pts) % PTS_MODULUS;
+        self.presentation_time_offset
+            + time::pts_to_timescale(elapsed, self.timescale, time::Rounding::Nearest)
+    }
+}
+
+/// An MPD `Event` element of the `urn:scte:scte35:2014:xml+bin` scheme, produced by
+/// [`event()`](fn.event.html).
+///
+/// The `Display` implementation gives the XML of the element.
+#[cfg(feature = "base64")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub presentation_time: u64,
+    /// The `duration`, in the `timescale` of the `EventStream`
+    pub duration: Option<u64>,
+    pub id: Option<u32>,
+    /// The complete `splice_info_section()`, which will be base64 encoded
+    pub section: Vec<u8>,
+}
+#[cfg(feature = "base64")]
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use base64::Engine;
+        write!(f, "<Event presentationTime=\"{}\"", self.presentation_time)?;
+        if let Some(duration) = self.duration {
+            write!(f, " duration=\"{}\"", duration)?;
+        }
+        if let Some(id) = self.id {
+            write!(f, " id=\"{}\"", id)?;
+        }
+        write!(
+            f,
+            "><Signal xmlns=\"http://www.scte.org/schemas/35/2016\"><Binary>{}</Binary></Signal></Event>",
+            base64::engine::general_purpose::STANDARD.encode(&self.section)
+        )
+    }
+}
+
+/// An MPD `EventStream` element of the `urn:scte:scte35:2014:xml+bin` scheme.
+///
+/// The `Display` implementation gives the XML of the element, including its events.
+#[cfg(feature = "base64")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventStream {
+    pub timescale: u64,
+    pub events: Vec<Event>,
+}
+#[cfg(feature = "base64")]
+impl fmt::Display for EventStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<EventStream schemeIdUri=\"{}\" timescale=\"{}\">",
+            SCHEME_XML_BIN, self.timescale
+        )?;
+        for event in &self.events {
+            write!(f, "{}", event)?;
+        }
+        f.write_str("</EventStream>")
+    }
+}
+
+/// Produces the `Event` element for the given section.
+///
+/// `section_pts` is the PTS at which the section itself appears in the stream, which is used as
+/// the `presentationTime` of immediate splices, and of commands that give no splice time.  The
+/// `id` is taken from the `splice_event_id` of a `splice_insert()`, or else from the
+/// `segmentation_event_id` of the first segmentation descriptor, and the `duration` from the
+/// `break_duration` or `segmentation_duration`.
+///
+/// Fails if the section can not be encoded (for instance because it is encrypted).
+#[cfg(feature = "base64")]
+pub fn event(
+    section: &SpliceInfoSection,
+    section_pts: u64,
+    timeline: &impl Timeline,
+) -> Result<Event, EncodeError> {
+    let pts = section.splice_pts().unwrap_or(section_pts);
+    let segmentation = section.splice_descriptors.iter().find_map(|d| match d {
+        SpliceDescriptor::SegmentationDescriptor {
+            segmentation_event_id,
+            descriptor_detail,
+        } => Some((*segmentation_event_id, descriptor_detail)),
+        _ => None,
+    });
+    let (id, duration) = match (&section.splice_command, segmentation) {
+        (
+            SpliceCommand::SpliceInsert {
+                splice_event_id,
+                splice_detail,
+                ..
+            },
+            _,
+        ) => (
+            Some(*splice_event_id),
+            match splice_detail {
+                SpliceInsert::Insert { duration, .. } => duration.as_ref().map(|d| d.duration),
+                SpliceInsert::Cancel => None,
+            },
+        ),
+        (_, Some((segmentation_event_id, detail))) => (
+            Some(segmentation_event_id),
+            match detail {
+                SegmentationDescriptor::Insert {
+                    segmentation_duration,
+                    ..
+                } => *segmentation_duration,
+                SegmentationDescriptor::Cancel => None,
+            },
+        ),
+        (_, None) => (None, None),
+    };
+    Ok(Event {
+        presentation_time: timeline.presentation_time(pts),
+        duration: duration
+            .map(|d| time::pts_to_timescale(d, timeline.timescale(), time::Rounding::Nearest)),
+        id,
+        section: section.encode()?,
+    })
+}
+
+/// A cue read from an MPD `Event` by [`parse_event_streams()`](fn.parse_event_streams.html)
+#[cfg(all(feature = "xml", feature = "base64"))]
+#[derive(Debug)]
+pub struct EventCue {
+    /// The `timescale` of the `EventStream` holding the event
+    pub timescale: u64,
+    pub presentation_time: u64,
+    pub duration: Option<u64>,
+    pub id: Option<u32>,
+    pub section: SpliceInfoSection,
+}
+
+/// Problems which prevent `parse_event_streams()` reading the cues of an MPD
+#[cfg(all(feature = "xml", feature = "base64"))]
+#[derive(Debug)]
+pub enum EventStreamError {
+    /// The document was not well-formed, an attribute of an `EventStream` or `Event` was
+    /// invalid, or a `SpliceInfoSection` element could not be read
+    Xml(crate::xml::XmlError),
+    /// The content of a `Binary` element was not a valid base64 encoded section
+    Binary(crate::FromBase64Error),
+}
+#[cfg(all(feature = "xml", feature = "base64"))]
+impl fmt::Display for EventStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventStreamError::Xml(_) => f.write_str("invalid EventStream"),
+            EventStreamError::Binary(_) => f.write_str("invalid Binary event"),
+        }
+    }
+}
+#[cfg(all(feature = "xml", feature = "base64"))]
+impl std::error::Error for EventStreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EventStreamError::Xml(e) => Some(e),
+            EventStreamError::Binary(e) => Some(e),
+        }
+    }
+}
+
+/// Reads the cues from every `EventStream` of the `urn:scte:scte35:2014:xml+bin` or
+/// `urn:scte:scte35:2013:xml` schemes within the given document, which may be a complete MPD or
+/// just an `EventStream` element.
+///
+/// Each `Binary` element (for `xml+bin`) or `SpliceInfoSection` element (for `xml`) within an
+/// `Event` produces an `EventCue`, in document order.  `EventStream` elements of other schemes
+/// are ignored.
+///
+/// Available when both of this crate's `xml` and `base64` features are enabled.
+#[cfg(all(feature = "xml", feature = "base64"))]
+pub fn parse_event_streams(xml: &str) -> Result<Vec<EventCue>, EventStreamError> {
+    use crate::xml::{attr, read_section, XmlError};
+
+    let doc =
+        roxmltree::Document::parse(xml).map_err(|e| EventStreamError::Xml(XmlError::Xml(e)))?;
+    let mut cues = vec![];
+    for stream in doc
+        .descendants()
+        .filter(|n| n.tag_name().name() == "EventStream")
+    {
+        let scheme = stream.attribute("schemeIdUri").unwrap_or("");
+        if scheme != SCHEME_XML_BIN && scheme != SCHEME_XML {
+            continue;
+        }
+        let timescale = attr(stream, "timescale")
+            .map_err(EventStreamError::Xml)?
+            .unwrap_or(1);
+        for event in stream.children().filter(|n| n.tag_name().name() == "Event") {
+            let presentation_time = attr(event, "presentationTime")
+                .map_err(EventStreamError::Xml)?
+                .unwrap_or(0);
+            let duration = attr(event, "duration").map_err(EventStreamError::Xml)?;
+            let id = attr(event, "id").map_err(EventStreamError::Xml)?;
+            for node in event.descendants() {
+                let section = match node.tag_name().name() {
+                    "Binary" if scheme == SCHEME_XML_BIN => {
+                        SpliceInfoSection::from_base64(node.text().unwrap_or(""))
+                            .map_err(EventStreamError::Binary)?
+                    }
+                    "SpliceInfoSection" if scheme == SCHEME_XML => {
+                        read_section(node).map_err(EventStreamError::Xml)?
+                    }
+                    _ => continue,
+                };
+                cues.push(EventCue {
+                    timescale,
+                    presentation_time,
+                    duration,
+                    id,
+                    section,
+                });
+            }
+        }
+    }
+    Ok(cues)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn period_timeline() {
+        let timeline = PeriodTimeline {
+            timescale: 1000,
+            period_start_pts: PTS_MODULUS - 90_000,
+            presentation_time_offset: 500,
+        };
+        assert_eq!(timeline.presentation_time(PTS_MODULUS - 90_000), 500);
+        // after the PTS counter wraps
+        assert_eq!(timeline.presentation_time(45_000), 2_000);
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn time_signal_event() {
+        let section = SpliceInfoSection::parse(&crate::test_util::TIME_SIGNAL).unwrap();
+        let timeline = PeriodTimeline {
+            timescale: 90_000,
+            period_start_pts: 0x1_592d_03c0,
+            presentation_time_offset: 0,
+        };
+        let event = event(&section, 0, &timeline).unwrap();
+        assert_eq!(event.presentation_time, 0);
+        assert_eq!(event.duration, None);
+        assert_eq!(event.id, Some(1));
+        assert!(event
+            .to_string()
+            .starts_with("<Event presentationTime=\"0\" id=\"1\"><Signal"));
+    }
+
+    #[cfg(all(feature = "xml", feature = "base64"))]
+    #[test]
+    fn event_streams() {
+        let cues = parse_event_streams(
+            r#"<MPD xmlns="urn:mpeg:dash:schema:mpd:2011">
+              <Period>
+                <EventStream schemeIdUri="urn:scte:scte35:2014:xml+bin" timescale="90000">
+                  <Event presentationTime="1000" duration="19125000" id="1">
+                    <scte35:Signal xmlns:scte35="http://www.scte.org/schemas/35/2016">
+                      <scte35:Binary>/DAlAAAAAAAAAP/wFAUAAAABf+/+LRQrAP4BI9MIAAEBAQAAfxV6SQ==</scte35:Binary>
+                    </scte35:Signal>
+                  </Event>
+                </EventStream>
+                <EventStream schemeIdUri="urn:example:other">
+                  <Event presentationTime="5"><SpliceInfoSection><SpliceNull/></SpliceInfoSection></Event>
+                </EventStream>
+                <EventStream schemeIdUri="urn:scte:scte35:2013:xml">
+                  <Event presentationTime="7">
+                    <SpliceInfoSection xmlns="http://www.scte.org/schemas/35/2016">
+                      <TimeSignal><SpliceTime ptsTime="900000"/></TimeSignal>
+                    </SpliceInfoSection>
+                  </Event>
+                </EventStream>
+              </Period>
+            </MPD>"#,
+        )
+        .unwrap();
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].timescale, 90_000);
+        assert_eq!(cues[0].presentation_time, 1000);
+        assert_eq!(cues[0].duration, Some(19_125_000));
+        assert_eq!(cues[0].id, Some(1));
+        assert_eq!(cues[0].section.splice_pts(), Some(0x2d14_2b00));
+        assert_eq!(cues[1].timescale, 1);
+        assert_eq!(cues[1].presentation_time, 7);
+        assert_eq!(cues[1].id, None);
+        assert_eq!(cues[1].section.splice_pts(), Some(900_000));
+        assert!(matches!(
+            parse_event_streams(
+                r#"<EventStream schemeIdUri="urn:scte:scte35:2014:xml+bin">
+                  <Event><Signal><Binary>!</Binary></Signal></Event>
+                </EventStream>"#
+            ),
+            Err(EventStreamError::Binary(_))
+        ));
+    }
+}
@@ -0,0 +1,890 @@
+//! A ready-made [`DemuxContext`](https://docs.rs/mpeg2ts-reader/*/mpeg2ts_reader/demultiplex/trait.DemuxContext.html)
+//! which locates the SCTE-35 elementary streams of every program in a Transport Stream, and
+//! routes the sections from each of those streams to a `SpliceInfoProcessor` of their own.
+//!
+//! ## Example
+//!
+//! ```
+//! # use mpeg2ts_reader::demultiplex;
+//! # use scte35_reader::demux::{Scte35DemuxContext, Scte35Stream};
+//! pub struct ProgramProcessor {
+//!     stream: Scte35Stream,
+//! }
+//! impl scte35_reader::SpliceInfoProcessor for ProgramProcessor {
+//!     fn process(
+//!         &self,
+//!         header: scte35_reader::SpliceInfoHeader<'_>,
+//!         command: scte35_reader::SpliceCommand,
+//!         descriptors: scte35_reader::SpliceDescriptors<'_>,
+//!     ) {
+//!         println!("program {}: {:?}", self.stream.program_number, command);
+//!     }
+//! }
+//!
+//! let mut ctx = Scte35DemuxContext::new(|stream: &Scte35Stream| ProgramProcessor {
+//!     stream: *stream,
+//! });
+//! let mut demux = demultiplex::Demultiplex::new(&mut ctx);
+//! # let ts_bytes = [0u8; 0];
+//! demux.push(&mut ctx, &ts_bytes[..]);
+//! ```
+//!
+//! Applications with a `DemuxContext` of their own can instead attach a `Scte35PacketFilter` to
+//! each SCTE-35 stream, using `Scte35PacketFilter::for_request()`:
+//!
+//! ```
+//! # use mpeg2ts_reader::{demultiplex, demux_context, packet_filter_switch};
+//! # use scte35_reader::demux::Scte35PacketFilter;
+//! # pub struct DumpProcessor;
+//! # impl scte35_reader::SpliceInfoProcessor for DumpProcessor {
+//! #     fn process(
+//! #         &self,
+//! #         _header: scte35_reader::SpliceInfoHeader<'_>,
+//! #         command: scte35_reader::SpliceCommand,
+//! #         _descriptors: scte35_reader::SpliceDescriptors<'_>,
+//! #     ) {
+//! #         println!("{:?}", command);
+//! #     }
+//! # }
+//! packet_filter_switch! {
+//!     AppFilters<AppContext> {
+//!         Pat: demultiplex::PatPacketFilter<AppContext>,
+//!         Pmt: demultiplex::PmtPacketFilter<AppContext>,
+//!         Scte35: Scte35PacketFilter<DumpProcessor, AppContext>,
+//!         Null: demultiplex::NullPacketFilter<AppContext>,
+//!     }
+//! }
+//! demux_context!(AppContext, AppFilters);
+//! impl AppContext {
+//!     fn do_construct(&mut self, req: demultiplex::FilterRequest<'_, '_>) -> AppFilters {
+//!         if let Some(filter) = Scte35PacketFilter::for_request(&req, |_pid| DumpProcessor) {
+//!             return AppFilters::Scte35(filter);
+//!         }
+//!         match req {
+//!             demultiplex::FilterRequest::ByPid(mpeg2ts_reader::psi::pat::PAT_PID) => {
+//!                 AppFilters::Pat(demultiplex::PatPacketFilter::default())
+//!             }
+//!             demultiplex::FilterRequest::Pmt {
+//!                 pid,
+//!                 program_number,
+//!             } => AppFilters::Pmt(demultiplex::PmtPacketFilter::new(pid, program_number)),
+//!             _ => AppFilters::Null(demultiplex::NullPacketFilter::default()),
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::{
+    cue_stream_type, is_scte35, scte35_pids, CrcMode, CueStreamType, LogDiagnostics,
+    Scte35Diagnostics, Scte35SectionProcessor, SectionContext, SpliceCommand, SpliceDescriptors,
+    SpliceInfoContextProcessor, SpliceInfoHeader, SpliceInfoProcessor, SCTE35_STREAM_TYPE,
+};
+use mpeg2ts_reader::demultiplex;
+use mpeg2ts_reader::packet;
+use mpeg2ts_reader::psi;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Identifies one SCTE-35 elementary stream found within the PMT of a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Scte35Stream {
+    /// The `program_number` given for the program in the PAT
+    pub program_number: u16,
+    /// The PID of the PMT which listed this stream
+    pub pmt_pid: packet::Pid,
+    /// The PID on which this stream's SCTE-35 sections are carried
+    pub elementary_pid: packet::Pid,
+    /// The PID carrying the program's PCR, as given by the PMT's `PCR_PID` field
+    pub pcr_pid: packet::Pid,
+    /// The kinds of message that the stream carries, if the PMT gave a
+    /// `cue_identifier_descriptor()` for it, which can help to choose between several SCTE-35
+    /// streams in a program
+    pub cue_stream_type: Option<CueStreamType>,
+}
+
+/// Creates a new `SpliceInfoProcessor` for each SCTE-35 stream discovered by
+/// `Scte35DemuxContext`, so that each program's cues are handled independently.
+///
+/// Implemented for any `FnMut(&Scte35Stream) -> P` closure.
+pub trait SpliceInfoProcessorFactory: 'static {
+    type Processor: SpliceInfoProcessor;
+
+    fn create(&mut self, stream: &Scte35Stream) -> Self::Processor;
+
+    /// Called when an updated PMT changes the details of a program's SCTE-35 stream, such as by
+    /// moving it from one PID to another.
+    ///
+    /// If the PID has changed, the filter on the `previous` PID has been removed, and the given
+    /// processor (originally created for the `previous` stream) will go on to receive the
+    /// sections found on the `current` stream's PID.  The processor itself is notified of a
+    /// change of PID via `SpliceInfoProcessor::pid_changed()` before this is called.  The default
+    /// implementation does nothing.
+    fn stream_moved(
+        &mut self,
+        _processor: &Self::Processor,
+        _previous: &Scte35Stream,
+        _current: &Scte35Stream,
+    ) {
+    }
+}
+impl<F, P> SpliceInfoProcessorFactory for F
+where
+    F: FnMut(&Scte35Stream) -> P + 'static,
+    P: SpliceInfoProcessor,
+{
+    type Processor = P;
+
+    fn create(&mut self, stream: &Scte35Stream) -> P {
+        self(stream)
+    }
+}
+
+type Scte35SectionConsumer<P, Ctx, D> = psi::SectionPacketConsumer<
+    psi::CompactSyntaxSectionProcessor<
+        psi::BufferCompactSyntaxParser<Scte35SectionProcessor<P, Ctx, (), D>>,
+    >,
+>;
+
+/// A `PacketFilter` which reassembles the sections of a single SCTE-35 elementary stream and
+/// passes them to a `Scte35SectionProcessor`.
+pub struct Scte35PacketFilter<P, Ctx, D = LogDiagnostics>
+where
+    P: SpliceInfoProcessor,
+    Ctx: demultiplex::DemuxContext,
+    D: Scte35Diagnostics,
+{
+    section: Scte35SectionConsumer<P, Ctx, D>,
+}
+impl<P, Ctx, D> Scte35PacketFilter<P, Ctx, D>
+where
+    P: SpliceInfoProcessor,
+    Ctx: demultiplex::DemuxContext,
+    D: Scte35Diagnostics,
+{
+    /// Creates a filter passing the sections it reassembles to the given
+    /// `Scte35SectionProcessor`, for applications needing to configure the processor, for
+    /// example with `Scte35SectionProcessor::with_crc_mode()`.
+    pub fn with_section_processor(
+        processor: Scte35SectionProcessor<P, Ctx, (), D>,
+    ) -> Scte35PacketFilter<P, Ctx, D> {
+        Scte35PacketFilter {
+            section: psi::SectionPacketConsumer::new(psi::CompactSyntaxSectionProcessor::new(
+                psi::BufferCompactSyntaxParser::new(processor),
+            )),
+        }
+    }
+}
+impl<P, Ctx> Scte35PacketFilter<P, Ctx>
+where
+    P: SpliceInfoProcessor,
+    Ctx: demultiplex::DemuxContext,
+{
+    pub fn new(processor: P) -> Scte35PacketFilter<P, Ctx> {
+        Scte35PacketFilter::with_section_processor(Scte35SectionProcessor::new(processor))
+    }
+
+    /// If the given request is for an SCTE-35 stream (one of `stream_type` `0x86` within a PMT
+    /// carrying the `CUEI` registration, per `is_scte35()`), creates a filter for it, passing
+    /// sections to the processor that the given function returns for the stream's PID.
+    ///
+    /// Returns `None` for any other request, which the application's `DemuxContext` should go
+    /// on to handle as usual.
+    pub fn for_request<F>(
+        req: &demultiplex::FilterRequest<'_, '_>,
+        processor: F,
+    ) -> Option<Scte35PacketFilter<P, Ctx>>
+    where
+        F: FnOnce(packet::Pid) -> P,
+    {
+        match *req {
+            demultiplex::FilterRequest::ByStream {
+                stream_type: SCTE35_STREAM_TYPE,
+                pmt,
+                stream_info,
+                ..
+            } if is_scte35(pmt) => {
+                let pid = stream_info.elementary_pid();
+                Some(Scte35PacketFilter::with_section_processor(
+                    Scte35SectionProcessor::new(processor(pid)).with_pid(pid),
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+impl<P, Ctx, D> demultiplex::PacketFilter for Scte35PacketFilter<P, Ctx, D>
+where
+    P: SpliceInfoProcessor,
+    Ctx: demultiplex::DemuxContext,
+    D: Scte35Diagnostics,
+{
+    type Ctx = Ctx;
+
+    fn consume(&mut self, ctx: &mut Self::Ctx, pk: &packet::Packet<'_>) {
+        self.section.consume(ctx, pk);
+    }
+}
+
+/// Adapts a `SpliceInfoContextProcessor` to the `SpliceInfoProcessor` interface, supplying the
+/// context that the owning `Scte35ContextFilter` records as each packet is consumed
+struct ContextAdapter<P> {
+    processor: RefCell<P>,
+    context: Rc<Cell<SectionContext>>,
+}
+impl<P: SpliceInfoContextProcessor> SpliceInfoProcessor for ContextAdapter<P> {
+    fn process(
+        &self,
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        self.processor
+            .borrow_mut()
+            .process(&self.context.get(), header, command, descriptors)
+    }
+
+    fn reset(&self) {
+        self.processor.borrow_mut().reset()
+    }
+}
+
+/// A `PacketFilter` like `Scte35PacketFilter`, but passing sections to a
+/// `SpliceInfoContextProcessor` together with the `SectionContext` in which they were found.
+///
+/// PCR values found in the adaptation fields of the filtered PID are reported as the
+/// `last_pcr`.  Since the PCR is usually carried on a different PID (typically the video
+/// stream), applications may instead share a cell via `with_pcr()`, updating it as they see
+/// the program's PCR PID.
+///
+/// `Scte35DemuxContext` does not create these filters.  Applications wanting the
+/// `SectionContext` must construct one for each SCTE-35 stream from their own `DemuxContext`,
+/// for requests which `is_scte35()` identifies (in the manner of
+/// `Scte35PacketFilter::for_request()`), and call `reset()` themselves on any discontinuity.
+pub struct Scte35ContextFilter<P, Ctx>
+where
+    P: SpliceInfoContextProcessor,
+    Ctx: demultiplex::DemuxContext,
+{
+    section: Scte35SectionConsumer<SharedProcessor<ContextAdapter<P>>, Ctx, LogDiagnostics>,
+    adapter: Rc<ContextAdapter<P>>,
+    context: Rc<Cell<SectionContext>>,
+    pcr: Rc<Cell<Option<u64>>>,
+    packet_count: u64,
+}
+impl<P, Ctx> Scte35ContextFilter<P, Ctx>
+where
+    P: SpliceInfoContextProcessor,
+    Ctx: demultiplex::DemuxContext,
+{
+    pub fn new(processor: P) -> Scte35ContextFilter<P, Ctx> {
+        let context = Rc::new(Cell::new(SectionContext {
+            pid: packet::Pid::new(0x1fff),
+            packet_index: 0,
+            last_pcr: None,
+        }));
+        let adapter = Rc::new(ContextAdapter {
+            processor: RefCell::new(processor),
+            context: context.clone(),
+        });
+        Scte35ContextFilter {
+            section: psi::SectionPacketConsumer::new(psi::CompactSyntaxSectionProcessor::new(
+                psi::BufferCompactSyntaxParser::new(Scte35SectionProcessor::new(SharedProcessor(
+                    adapter.clone(),
+                ))),
+            )),
+            adapter,
+            context,
+            pcr: Rc::new(Cell::new(None)),
+            packet_count: 0,
+        }
+    }
+
+    /// Takes the `last_pcr` of each `SectionContext` from the given cell, which the application
+    /// updates with each PCR value (in 27MHz units) it sees on the program's PCR PID.
+    pub fn with_pcr(mut self, pcr: Rc<Cell<Option<u64>>>) -> Self {
+        self.pcr = pcr;
+        self
+    }
+
+    /// To be called when the application detects a discontinuity in the stream, or switches
+    /// to a different input, so that the `SpliceInfoContextProcessor` can discard stale state.
+    pub fn reset(&mut self) {
+        SpliceInfoProcessor::reset(&*self.adapter);
+    }
+}
+impl<P, Ctx> demultiplex::PacketFilter for Scte35ContextFilter<P, Ctx>
+where
+    P: SpliceInfoContextProcessor,
+    Ctx: demultiplex::DemuxContext,
+{
+    type Ctx = Ctx;
+
+    fn consume(&mut self, ctx: &mut Self::Ctx, pk: &packet::Packet<'_>) {
+        if let Some(Ok(pcr)) = pk.adaptation_field().map(|af| af.pcr()) {
+            self.pcr.set(Some(u64::from(pcr)));
+        }
+        self.context.set(SectionContext {
+            pid: pk.pid(),
+            packet_index: self.packet_count,
+            last_pcr: self.pcr.get(),
+        });
+        self.packet_count += 1;
+        self.section.consume(ctx, pk);
+    }
+}
+
+/// A `SpliceInfoProcessor` shared between the successive filters that `Scte35DemuxContext`
+/// creates for a stream, so that the processor survives PMT updates.
+pub struct SharedProcessor<P: SpliceInfoProcessor>(Rc<P>);
+impl<P: SpliceInfoProcessor> SpliceInfoProcessor for SharedProcessor<P> {
+    fn process(
+        &self,
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        self.0.process(header, command, descriptors)
+    }
+
+    fn process_section(
+        &self,
+        section: &[u8],
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        self.0
+            .process_section(section, header, command, descriptors)
+    }
+
+    fn reset(&self) {
+        self.0.reset()
+    }
+
+    fn pid_changed(&self, previous: packet::Pid, current: packet::Pid) {
+        self.0.pid_changed(previous, current)
+    }
+}
+
+/// The `PacketFilter` implementations used by `Scte35DemuxContext`.
+pub enum Scte35Filter<F, D = LogDiagnostics>
+where
+    F: SpliceInfoProcessorFactory,
+    D: Scte35Diagnostics + Clone + 'static,
+{
+    Pat(demultiplex::PatPacketFilter<Scte35DemuxContext<F, D>>),
+    Pmt(demultiplex::PmtPacketFilter<Scte35DemuxContext<F, D>>),
+    Scte35(Scte35PacketFilter<SharedProcessor<F::Processor>, Scte35DemuxContext<F, D>, D>),
+    Null(demultiplex::NullPacketFilter<Scte35DemuxContext<F, D>>),
+}
+impl<F, D> demultiplex::PacketFilter for Scte35Filter<F, D>
+where
+    F: SpliceInfoProcessorFactory,
+    D: Scte35Diagnostics + Clone + 'static,
+{
+    type Ctx = Scte35DemuxContext<F, D>;
+
+    fn consume(&mut self, ctx: &mut Self::Ctx, pk: &packet::Packet<'_>) {
+        match self {
+            Scte35Filter::Pat(f) => f.consume(ctx, pk),
+            Scte35Filter::Pmt(f) => f.consume(ctx, pk),
+            Scte35Filter::Scte35(f) => f.consume(ctx, pk),
+            Scte35Filter::Null(f) => f.consume(ctx, pk),
+        }
+    }
+}
+
+/// A stream, together with the processor currently handling its sections
+type StreamProcessor<P> = (Scte35Stream, Rc<P>);
+
+/// A `DemuxContext` that tracks every program announced in the PAT, and attaches a separate
+/// `SpliceInfoProcessor` (obtained from the given factory) to each SCTE-35 stream that the
+/// programs' PMTs describe.
+///
+/// When a new version of a PMT is received, the processor already attached to each SCTE-35
+/// stream is retained.  If the update moves a stream to a different PID, the processor is
+/// carried over to the new PID, and both the processor and the factory are informed (via
+/// `SpliceInfoProcessor::pid_changed()` and
+/// [`stream_moved()`](trait.SpliceInfoProcessorFactory.html#method.stream_moved)).  Processors
+/// for streams which an updated PMT no longer lists are dropped.
+pub struct Scte35DemuxContext<F, D = LogDiagnostics>
+where
+    F: SpliceInfoProcessorFactory,
+    D: Scte35Diagnostics + Clone + 'static,
+{
+    changeset: demultiplex::FilterChangeset<Scte35Filter<F, D>>,
+    factory: F,
+    diagnostics: D,
+    crc_mode: CrcMode,
+    programs: HashMap<packet::Pid, u16>,
+    streams: HashMap<packet::Pid, Vec<StreamProcessor<F::Processor>>>,
+}
+impl<F: SpliceInfoProcessorFactory> Scte35DemuxContext<F> {
+    pub fn new(factory: F) -> Scte35DemuxContext<F> {
+        Scte35DemuxContext {
+            changeset: demultiplex::FilterChangeset::default(),
+            factory,
+            diagnostics: LogDiagnostics,
+            crc_mode: CrcMode::default(),
+            programs: HashMap::new(),
+            streams: HashMap::new(),
+        }
+    }
+}
+impl<F, D> Scte35DemuxContext<F, D>
+where
+    F: SpliceInfoProcessorFactory,
+    D: Scte35Diagnostics + Clone + 'static,
+{
+    /// Delivers problems found in the sections of every SCTE-35 stream to a clone of the given
+    /// `Scte35Diagnostics` implementation, rather than logging them.
+    ///
+    /// To be used before the context is given to `Demultiplex::new()`.
+    pub fn with_diagnostics<D2: Scte35Diagnostics + Clone + 'static>(
+        self,
+        diagnostics: D2,
+    ) -> Scte35DemuxContext<F, D2> {
+        Scte35DemuxContext {
+            changeset: demultiplex::FilterChangeset::default(),
+            factory: self.factory,
+            diagnostics,
+            crc_mode: self.crc_mode,
+            programs: self.programs,
+            streams: self.streams,
+        }
+    }
+
+    /// How sections with an incorrect `CRC_32` are treated, as for
+    /// `Scte35SectionProcessor::with_crc_mode()`.  Defaults to `CrcMode::Strict`.
+    pub fn with_crc_mode(mut self, crc_mode: CrcMode) -> Self {
+        self.crc_mode = crc_mode;
+        self
+    }
+
+    /// Finds the processor for the given stream, either the one already in use for it, the one
+    /// belonging to a stream of the same program which the PMT no longer lists (in which case
+    /// the stream has moved), or else a newly created one.
+    fn processor(
+        &mut self,
+        stream: Scte35Stream,
+        pmt: &psi::pmt::PmtSection<'_>,
+    ) -> Rc<F::Processor> {
+        let listed = scte35_pids(pmt);
+        let streams = self.streams.entry(stream.pmt_pid).or_default();
+        if let Some(entry) = streams
+            .iter_mut()
+            .find(|(s, _)| s.elementary_pid == stream.elementary_pid)
+        {
+            let previous = std::mem::replace(&mut entry.0, stream);
+            if previous != stream {
+                self.factory.stream_moved(&entry.1, &previous, &stream);
+            }
+            return entry.1.clone();
+        }
+        if let Some(entry) = streams
+            .iter_mut()
+            .find(|(s, _)| !listed.contains(&s.elementary_pid))
+        {
+            let previous = std::mem::replace(&mut entry.0, stream);
+            entry
+                .1
+                .pid_changed(previous.elementary_pid, stream.elementary_pid);
+            self.factory.stream_moved(&entry.1, &previous, &stream);
+            return entry.1.clone();
+        }
+        let processor = Rc::new(self.factory.create(&stream));
+        streams.push((stream, processor.clone()));
+        processor
+    }
+
+    /// Drops the processors of the given program's streams which its PMT no longer lists,
+    /// keeping only as many as there are newly listed streams that they may yet be moved to.
+    ///
+    /// The demultiplexer requests a filter for each stream of each new version of a PMT, in the
+    /// order that the PMT lists them, so this is called for every such request.
+    fn prune(&mut self, program_pid: packet::Pid, pmt: &psi::pmt::PmtSection<'_>) {
+        let Some(streams) = self.streams.get_mut(&program_pid) else {
+            return;
+        };
+        let listed = scte35_pids(pmt);
+        let pending = listed
+            .iter()
+            .filter(|pid| !streams.iter().any(|(s, _)| s.elementary_pid == **pid))
+            .count();
+        let mut unlisted = 0;
+        streams.retain(|(s, _)| {
+            if listed.contains(&s.elementary_pid) {
+                return true;
+            }
+            unlisted += 1;
+            unlisted <= pending
+        });
+    }
+}
+impl<F, D> demultiplex::DemuxContext for Scte35DemuxContext<F, D>
+where
+    F: SpliceInfoProcessorFactory,
+    D: Scte35Diagnostics + Clone + 'static,
+{
+    type F = Scte35Filter<F, D>;
+
+    fn filter_changeset(&mut self) -> &mut demultiplex::FilterChangeset<Self::F> {
+        &mut self.changeset
+    }
+
+    fn construct(&mut self, req: demultiplex::FilterRequest<'_, '_>) -> Self::F {
+        match req {
+            demultiplex::FilterRequest::ByPid(psi::pat::PAT_PID) => {
+                Scte35Filter::Pat(demultiplex::PatPacketFilter::default())
+            }
+            demultiplex::FilterRequest::ByPid(_) => {
+                Scte35Filter::Null(demultiplex::NullPacketFilter::default())
+            }
+            demultiplex::FilterRequest::ByStream {
+                program_pid,
+                stream_type: SCTE35_STREAM_TYPE,
+                pmt,
+                stream_info,
+            } if is_scte35(pmt) => {
+                self.prune(program_pid, pmt);
+                let stream = Scte35Stream {
+                    program_number: self.programs.get(&program_pid).copied().unwrap_or(0),
+                    pmt_pid: program_pid,
+                    elementary_pid: stream_info.elementary_pid(),
+                    pcr_pid: pmt.pcr_pid(),
+                    cue_stream_type: cue_stream_type(stream_info),
+                };
+                let pid = stream.elementary_pid;
+                let processor = self.processor(stream, pmt);
+                Scte35Filter::Scte35(Scte35PacketFilter::with_section_processor(
+                    Scte35SectionProcessor::new(SharedProcessor(processor))
+                        .with_diagnostics(self.diagnostics.clone())
+                        .with_crc_mode(self.crc_mode)
+                        .with_pid(pid),
+                ))
+            }
+            demultiplex::FilterRequest::ByStream {
+                program_pid, pmt, ..
+            } => {
+                self.prune(program_pid, pmt);
+                Scte35Filter::Null(demultiplex::NullPacketFilter::default())
+            }
+            demultiplex::FilterRequest::Pmt {
+                pid,
+                program_number,
+            } => {
+                self.programs.insert(pid, program_number);
+                Scte35Filter::Pmt(demultiplex::PmtPacketFilter::new(pid, program_number))
+            }
+            demultiplex::FilterRequest::Nit { .. } => {
+                Scte35Filter::Null(demultiplex::NullPacketFilter::default())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use hex_literal::*;
+
+    #[test]
+    fn context_filter_reset() {
+        struct ResettableProcessor(Rc<Cell<u32>>);
+        impl SpliceInfoContextProcessor for ResettableProcessor {
+            fn process(
+                &mut self,
+                _context: &SectionContext,
+                _header: SpliceInfoHeader<'_>,
+                _command: SpliceCommand,
+                _descriptors: SpliceDescriptors<'_>,
+            ) {
+            }
+
+            fn reset(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let resets = Rc::new(Cell::new(0));
+        type Processor = fn(SpliceInfoHeader<'_>, SpliceCommand, SpliceDescriptors<'_>);
+        type Ctx = Scte35DemuxContext<fn(&Scte35Stream) -> Processor>;
+        let mut filter: Scte35ContextFilter<_, Ctx> =
+            Scte35ContextFilter::new(ResettableProcessor(resets.clone()));
+        filter.reset();
+        assert_eq!(resets.get(), 1);
+    }
+
+    struct RecordingProcessor {
+        stream: Cell<Scte35Stream>,
+        seen: Rc<RefCell<Vec<(u16, u16)>>>,
+    }
+    impl SpliceInfoProcessor for RecordingProcessor {
+        fn process(
+            &self,
+            _header: SpliceInfoHeader<'_>,
+            _command: SpliceCommand,
+            _descriptors: SpliceDescriptors<'_>,
+        ) {
+            let stream = self.stream.get();
+            self.seen
+                .borrow_mut()
+                .push((stream.program_number, u16::from(stream.elementary_pid)));
+        }
+
+        fn pid_changed(&self, _previous: packet::Pid, current: packet::Pid) {
+            let mut stream = self.stream.get();
+            stream.elementary_pid = current;
+            self.stream.set(stream);
+        }
+    }
+
+    #[test]
+    fn section_context() {
+        use mpeg2ts_reader::demultiplex::PacketFilter;
+
+        let mut ctx = Scte35DemuxContext::new(|_: &Scte35Stream| {
+            |_: SpliceInfoHeader<'_>, _: SpliceCommand, _: SpliceDescriptors<'_>| {}
+        });
+        let seen = Rc::new(RefCell::new(vec![]));
+        let recorded = seen.clone();
+        let mut filter = Scte35ContextFilter::new(
+            move |context: &SectionContext,
+                  _: SpliceInfoHeader<'_>,
+                  _: SpliceCommand,
+                  _: SpliceDescriptors<'_>| recorded.borrow_mut().push(*context),
+        );
+        // adaptation field only (so adaptation_field_length is 183), with a PCR of base 1,
+        // extension 2
+        let mut pcr = vec![0x47, 0x01, 0x01, 0x20, 0xb7, 0x10, 0, 0, 0, 0, 0xfe, 0x02];
+        pcr.resize(packet::Packet::SIZE, 0xff);
+        filter.consume(&mut ctx, &packet::Packet::new(&pcr));
+        filter.consume(
+            &mut ctx,
+            &packet::Packet::new(&section_packet_cc(0x101, 1, &SPLICE_INSERT)),
+        );
+        assert_eq!(
+            *seen.borrow(),
+            [SectionContext {
+                pid: packet::Pid::new(0x101),
+                packet_index: 1,
+                last_pcr: Some(302),
+            }]
+        );
+    }
+
+    #[test]
+    fn per_program_processors() {
+        // PAT listing program 1 (PMT on PID 0x100) and program 2 (PMT on PID 0x200)
+        let pat = with_crc(hex!("00b011 0001 c1 00 00 0001e100 0002e200").to_vec());
+        // PMTs each carrying the CUEI registration descriptor, and one stream_type=0x86 stream,
+        // the second stream having a cue_identifier_descriptor(cue_stream_type=0x01)
+        let pmt1 =
+            with_crc(hex!("02b018 0001 c1 00 00 e1fff006 0504 43554549 86e101f000").to_vec());
+        let pmt2 = with_crc(
+            hex!("02b01b 0002 c1 00 00 e1fff006 0504 43554549 86e201f003 8a0101").to_vec(),
+        );
+
+        let mut ts = vec![];
+        ts.extend(section_packet(0, &pat));
+        ts.extend(section_packet(0x100, &pmt1));
+        ts.extend(section_packet(0x200, &pmt2));
+        ts.extend(section_packet(0x201, &SPLICE_NULL));
+        ts.extend(section_packet(0x101, &SPLICE_NULL));
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let created = Rc::new(RefCell::new(vec![]));
+        let mut ctx = {
+            let seen = seen.clone();
+            let created = created.clone();
+            Scte35DemuxContext::new(move |stream: &Scte35Stream| {
+                created.borrow_mut().push(*stream);
+                RecordingProcessor {
+                    stream: Cell::new(*stream),
+                    seen: seen.clone(),
+                }
+            })
+        };
+        let mut demux = demultiplex::Demultiplex::new(&mut ctx);
+        demux.push(&mut ctx, &ts[..]);
+
+        assert_eq!(
+            *created.borrow(),
+            vec![
+                Scte35Stream {
+                    program_number: 1,
+                    pmt_pid: packet::Pid::new(0x100),
+                    elementary_pid: packet::Pid::new(0x101),
+                    pcr_pid: packet::Pid::new(0x1ff),
+                    cue_stream_type: None,
+                },
+                Scte35Stream {
+                    program_number: 2,
+                    pmt_pid: packet::Pid::new(0x200),
+                    elementary_pid: packet::Pid::new(0x201),
+                    pcr_pid: packet::Pid::new(0x1ff),
+                    cue_stream_type: Some(CueStreamType::AllCommands),
+                },
+            ]
+        );
+        assert_eq!(*seen.borrow(), vec![(2, 0x201), (1, 0x101)]);
+    }
+
+    struct MoveRecordingFactory {
+        created: Rc<RefCell<Vec<Scte35Stream>>>,
+        moved: Rc<RefCell<Vec<(Scte35Stream, Scte35Stream)>>>,
+        seen: Rc<RefCell<Vec<(u16, u16)>>>,
+    }
+    impl SpliceInfoProcessorFactory for MoveRecordingFactory {
+        type Processor = RecordingProcessor;
+
+        fn create(&mut self, stream: &Scte35Stream) -> RecordingProcessor {
+            self.created.borrow_mut().push(*stream);
+            RecordingProcessor {
+                stream: Cell::new(*stream),
+                seen: self.seen.clone(),
+            }
+        }
+
+        fn stream_moved(
+            &mut self,
+            _processor: &RecordingProcessor,
+            previous: &Scte35Stream,
+            current: &Scte35Stream,
+        ) {
+            self.moved.borrow_mut().push((*previous, *current));
+        }
+    }
+
+    #[test]
+    fn pmt_update_moves_stream() {
+        let pat = with_crc(hex!("00b00d 0001 c1 00 00 0001e100").to_vec());
+        let pmt_v0 =
+            with_crc(hex!("02b018 0001 c1 00 00 e1fff006 0504 43554549 86e101f000").to_vec());
+        // version 1 of the same PMT, now carrying the SCTE-35 stream on PID 0x102
+        let pmt_v1 =
+            with_crc(hex!("02b018 0001 c3 00 00 e1fff006 0504 43554549 86e102f000").to_vec());
+        // version 2 changes nothing of interest, and should not cause the processor to be replaced
+        let pmt_v2 =
+            with_crc(hex!("02b018 0001 c5 00 00 e1fff006 0504 43554549 86e102f000").to_vec());
+
+        let mut ts = vec![];
+        ts.extend(section_packet(0, &pat));
+        ts.extend(section_packet(0x100, &pmt_v0));
+        ts.extend(section_packet(0x101, &SPLICE_NULL));
+        ts.extend(section_packet_cc(0x100, 1, &pmt_v1));
+        // the old PID is no longer monitored
+        ts.extend(section_packet_cc(0x101, 1, &SPLICE_NULL));
+        ts.extend(section_packet(0x102, &SPLICE_NULL));
+        ts.extend(section_packet_cc(0x100, 2, &pmt_v2));
+        ts.extend(section_packet_cc(0x102, 1, &SPLICE_NULL));
+
+        let created = Rc::new(RefCell::new(vec![]));
+        let moved = Rc::new(RefCell::new(vec![]));
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut ctx = Scte35DemuxContext::new(MoveRecordingFactory {
+            created: created.clone(),
+            moved: moved.clone(),
+            seen: seen.clone(),
+        });
+        let mut demux = demultiplex::Demultiplex::new(&mut ctx);
+        demux.push(&mut ctx, &ts[..]);
+
+        let old = Scte35Stream {
+            program_number: 1,
+            pmt_pid: packet::Pid::new(0x100),
+            elementary_pid: packet::Pid::new(0x101),
+            pcr_pid: packet::Pid::new(0x1ff),
+            cue_stream_type: None,
+        };
+        let new = Scte35Stream {
+            elementary_pid: packet::Pid::new(0x102),
+            ..old
+        };
+        assert_eq!(*created.borrow(), vec![old]);
+        assert_eq!(*moved.borrow(), vec![(old, new)]);
+        // the same processor saw cues from both PIDs, and was told about the move
+        assert_eq!(*seen.borrow(), vec![(1, 0x101), (1, 0x102), (1, 0x102)]);
+    }
+
+    #[test]
+    fn pmt_update_removes_stream() {
+        let pat = with_crc(hex!("00b00d 0001 c1 00 00 0001e100").to_vec());
+        let pmt_v0 =
+            with_crc(hex!("02b018 0001 c1 00 00 e1fff006 0504 43554549 86e101f000").to_vec());
+        // version 1 drops the SCTE-35 stream, leaving only a video stream
+        let pmt_v1 =
+            with_crc(hex!("02b018 0001 c3 00 00 e1fff006 0504 43554549 1be110f000").to_vec());
+        // version 2 adds a new SCTE-35 stream, which should not inherit the dropped processor
+        let pmt_v2 = with_crc(
+            hex!("02b01d 0001 c5 00 00 e1fff006 0504 43554549 1be110f000 86e103f000").to_vec(),
+        );
+
+        let mut ts = vec![];
+        ts.extend(section_packet(0, &pat));
+        ts.extend(section_packet(0x100, &pmt_v0));
+        ts.extend(section_packet_cc(0x100, 1, &pmt_v1));
+        ts.extend(section_packet_cc(0x100, 2, &pmt_v2));
+
+        let created = Rc::new(RefCell::new(vec![]));
+        let moved = Rc::new(RefCell::new(vec![]));
+        let mut ctx = Scte35DemuxContext::new(MoveRecordingFactory {
+            created: created.clone(),
+            moved: moved.clone(),
+            seen: Rc::new(RefCell::new(vec![])),
+        });
+        let mut demux = demultiplex::Demultiplex::new(&mut ctx);
+        demux.push(&mut ctx, &ts[..]);
+
+        let old = Scte35Stream {
+            program_number: 1,
+            pmt_pid: packet::Pid::new(0x100),
+            elementary_pid: packet::Pid::new(0x101),
+            pcr_pid: packet::Pid::new(0x1ff),
+            cue_stream_type: None,
+        };
+        let new = Scte35Stream {
+            elementary_pid: packet::Pid::new(0x103),
+            ..old
+        };
+        assert_eq!(*created.borrow(), vec![old, new]);
+        assert!(moved.borrow().is_empty());
+        assert_eq!(ctx.streams[&packet::Pid::new(0x100)].len(), 1);
+    }
+
+    #[test]
+    fn filter_for_request() {
+        type Filter = Scte35PacketFilter<
+            fn(SpliceInfoHeader<'_>, SpliceCommand, SpliceDescriptors<'_>),
+            crate::tests::NullDemuxContext,
+        >;
+        fn nothing(_: SpliceInfoHeader<'_>, _: SpliceCommand, _: SpliceDescriptors<'_>) {}
+
+        // PMT bodies listing a stream_type=0x86 stream on PID 0x101, with and without the CUEI
+        // registration descriptor
+        for (data, expected) in [
+            (&hex!("e1fff006 0504 43554549 86e101f000")[..], true),
+            (&hex!("e1fff000 86e101f000")[..], false),
+        ] {
+            let pmt = psi::pmt::PmtSection::from_bytes(data).unwrap();
+            let stream_info = pmt.streams().next().unwrap();
+            let req = demultiplex::FilterRequest::ByStream {
+                program_pid: packet::Pid::new(0x100),
+                stream_type: stream_info.stream_type(),
+                pmt: &pmt,
+                stream_info: &stream_info,
+            };
+            let mut pid = None;
+            let filter = Filter::for_request(&req, |p| {
+                pid = Some(p);
+                nothing
+            });
+            assert_eq!(filter.is_some(), expected);
+            assert_eq!(pid.is_some(), expected);
+        }
+        let req = demultiplex::FilterRequest::ByPid(packet::Pid::new(0x101));
+        assert!(Filter::for_request(&req, |_| nothing as _).is_none());
+    }
+}
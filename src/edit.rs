@@ -0,0 +1,346 @@
+//! Targeted changes to the bytes of an existing `splice_info_section()`, for equipment which
+//! passes cues through while re-timing or re-distributing a stream.
+//!
+//! Unlike parsing a section and then [encoding](../encode/index.html) the resulting
+//! `SpliceInfoSection`, the functions of this module change only the fields concerned (along
+//! with any lengths that depend on them, and the `CRC_32`), so that every other bit of the
+//! section is preserved exactly, including reserved bits and any content which this library
+//! does not understand.  Besides re-stamping the `pts_adjustment`, descriptors may be removed
+//! (for example, to redact proprietary descriptors before a cue leaves the facility), and the
+//! `tier` may be changed.
+//!
+//! The given section must be complete, and must have a correct `CRC_32`, so that corruption of
+//! the original is not concealed by the recalculated CRC.  Any bytes following the end of the
+//! section (as given by its `section_length`) are not included in the result.
+//!
+//! ## Example
+//!
+//! ```
+//! # use hex_literal::*;
+//! # use scte35_reader::{edit, SpliceInfoSection};
+//! let data = hex!("fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49");
+//! let restamped = edit::set_pts_adjustment(&data, 90_000).unwrap();
+//! assert_eq!(SpliceInfoSection::parse(&restamped).unwrap().pts_adjustment, 90_000);
+//! ```
+
+use crate::{
+    Scte35Error, SpliceCommand, SpliceCommandType, SpliceDescriptorIter, SpliceInfoHeader, Tier,
+    WarningCollector,
+};
+use mpeg2ts_reader::psi;
+use std::ops::Range;
+
+/// The largest value of the 33-bit `pts_adjustment` field
+const MAX_PTS_ADJUSTMENT: u64 = (1 << 33) - 1;
+
+/// Problems which prevent a section being changed
+#[derive(Debug)]
+pub enum EditError {
+    /// The given bytes are not a complete, valid `splice_info_section()`
+    Section(Scte35Error),
+    /// A value is too large to be represented in the number of bits the syntax allows for the
+    /// named field
+    FieldOutOfRange {
+        field_name: &'static str,
+        value: u64,
+    },
+}
+impl std::fmt::Display for EditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditError::Section(e) => write!(f, "invalid section: {}", e),
+            EditError::FieldOutOfRange { field_name, value } => {
+                write!(f, "value {} is out of range for {}", value, field_name)
+            }
+        }
+    }
+}
+impl std::error::Error for EditError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EditError::Section(e) => Some(e),
+            EditError::FieldOutOfRange { .. } => None,
+        }
+    }
+}
+
+/// Returns a copy of the given section with its 33-bit `pts_adjustment` field replaced by the
+/// given value, and the `CRC_32` recalculated.
+///
+/// The `pts_adjustment` precedes any encrypted portion of the section, so encrypted sections
+/// may be changed too.
+pub fn set_pts_adjustment(data: &[u8], pts_adjustment: u64) -> Result<Vec<u8>, EditError> {
+    if pts_adjustment > MAX_PTS_ADJUSTMENT {
+        return Err(EditError::FieldOutOfRange {
+            field_name: "pts_adjustment",
+            value: pts_adjustment,
+        });
+    }
+    let mut section = checked_section(data)?.to_vec();
+    section[4] = section[4] & 0xfe | (pts_adjustment >> 32) as u8;
+    section[5..9].copy_from_slice(&(pts_adjustment as u32).to_be_bytes());
+    update_crc(&mut section);
+    Ok(section)
+}
+
+/// Returns a copy of the given section with its `tier` field replaced by the given value, and
+/// the `CRC_32` recalculated.
+pub fn set_tier(data: &[u8], tier: Tier) -> Result<Vec<u8>, EditError> {
+    let mut section = checked_section(data)?.to_vec();
+    let value = tier.value();
+    section[10] = (value >> 4) as u8;
+    section[11] = section[11] & 0x0f | (value << 4) as u8;
+    update_crc(&mut section);
+    Ok(section)
+}
+
+/// Returns a copy of the given section without those descriptors for which the given function
+/// returns `true`, with the `descriptor_loop_length`, `section_length` and `CRC_32` updated to
+/// match.
+///
+/// The function is given the complete bytes of each descriptor, from its
+/// `splice_descriptor_tag` onwards (so that the `identifier` is at `[2..6]`).  Encrypted
+/// sections can not be changed in this way, and fail with `Scte35Error::Encrypted`.
+///
+/// ```
+/// # use hex_literal::*;
+/// # use scte35_reader::edit;
+/// # let data = hex!("fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49");
+/// // strip all proprietary descriptors before passing the cue on
+/// let stripped = edit::remove_descriptors(&data, |desc| &desc[2..6] != b"CUEI").unwrap();
+/// ```
+pub fn remove_descriptors<F>(data: &[u8], mut remove: F) -> Result<Vec<u8>, EditError>
+where
+    F: FnMut(&[u8]) -> bool,
+{
+    let section = checked_section(data)?;
+    let descriptors = descriptor_loop(section)?;
+    let mut result = section[..descriptors.start - 2].to_vec();
+    result.extend_from_slice(&[0, 0]);
+    let diagnostics = WarningCollector::default();
+    let mut iter = SpliceDescriptorIter::new(&section[descriptors.clone()], &diagnostics);
+    loop {
+        let offset = iter.offset();
+        let (desc, result_desc) = match iter.split_next() {
+            Some(next) => next,
+            None => break,
+        };
+        let desc = result_desc.map_err(|e| {
+            EditError::Section(Scte35Error::Descriptor(e.in_descriptor(desc[0], offset)))
+        })?;
+        if !remove(desc) {
+            result.extend_from_slice(desc);
+        }
+    }
+    let descriptor_loop_length = result.len() - descriptors.start;
+    result[descriptors.start - 2..descriptors.start]
+        .copy_from_slice(&(descriptor_loop_length as u16).to_be_bytes());
+    // any alignment_stuffing, and the space for the CRC_32
+    result.extend_from_slice(&section[descriptors.end..]);
+    let section_length = result.len() - psi::SectionCommonHeader::SIZE;
+    result[1] = result[1] & 0xf0 | (section_length >> 8) as u8;
+    result[2] = section_length as u8;
+    update_crc(&mut result);
+    Ok(result)
+}
+
+/// The position of the descriptors within the given un-encrypted section (excluding the
+/// `descriptor_loop_length` field, which immediately precedes them)
+fn descriptor_loop(section: &[u8]) -> Result<Range<usize>, EditError> {
+    // table_id through to splice_command_type
+    const COMMAND_START: usize = 14;
+    if section[4] & 0x80 != 0 {
+        return Err(EditError::Section(Scte35Error::Encrypted));
+    }
+    let crc_pos = section.len() - 4;
+    let available = crc_pos - COMMAND_START;
+    let splice_command_length = usize::from(section[11] & 0x0f) << 8 | usize::from(section[12]);
+    let command_len = if splice_command_length == SpliceInfoHeader::LEGACY_SPLICE_COMMAND_LENGTH {
+        let (_, len) = SpliceCommand::parse_legacy(
+            SpliceCommandType::from_id(section[13]),
+            &section[COMMAND_START..crc_pos],
+            &WarningCollector::default(),
+        )
+        .map_err(EditError::Section)?;
+        len
+    } else if splice_command_length > available {
+        return Err(EditError::Section(Scte35Error::SpliceCommandLength {
+            splice_command_length,
+            available,
+        }));
+    } else {
+        splice_command_length
+    };
+    let rest = &section[COMMAND_START + command_len..crc_pos];
+    if rest.len() < 2 {
+        return Err(EditError::Section(Scte35Error::MissingDescriptorLoopLength));
+    }
+    let descriptor_loop_length = usize::from(rest[0]) << 8 | usize::from(rest[1]);
+    if descriptor_loop_length + 2 > rest.len() {
+        return Err(EditError::Section(Scte35Error::DescriptorLoopLength {
+            descriptor_loop_length,
+            available: rest.len(),
+        }));
+    }
+    let start = COMMAND_START + command_len + 2;
+    Ok(start..start + descriptor_loop_length)
+}
+
+/// The section at the start of the given data, once its header, length and `CRC_32` have been
+/// checked
+fn checked_section(data: &[u8]) -> Result<&[u8], EditError> {
+    // table_id through to splice_command_type, plus the CRC_32
+    const MIN_LEN: usize = 14 + 4;
+    if data.len() < psi::SectionCommonHeader::SIZE {
+        return Err(EditError::Section(Scte35Error::SectionTooShort {
+            actual: data.len(),
+            expected: psi::SectionCommonHeader::SIZE,
+        }));
+    }
+    if data[0] != 0xfc {
+        return Err(EditError::Section(Scte35Error::BadTableId(data[0])));
+    }
+    let section_end =
+        psi::SectionCommonHeader::SIZE + (usize::from(data[1] & 0x0f) << 8 | usize::from(data[2]));
+    if data.len() < section_end {
+        return Err(EditError::Section(Scte35Error::SectionTooShort {
+            actual: data.len(),
+            expected: section_end,
+        }));
+    }
+    if section_end < MIN_LEN {
+        return Err(EditError::Section(Scte35Error::SectionTooShort {
+            actual: section_end,
+            expected: MIN_LEN,
+        }));
+    }
+    let section = &data[..section_end];
+    let crc = mpeg2ts_reader::mpegts_crc::sum32(section);
+    if crc != 0 {
+        return Err(EditError::Section(Scte35Error::Crc(crc)));
+    }
+    Ok(section)
+}
+
+/// Replaces the final four bytes of the given section with its `CRC_32`
+fn update_crc(section: &mut [u8]) {
+    let crc_pos = section.len() - 4;
+    let crc = mpeg2ts_reader::mpegts_crc::sum32(&section[..crc_pos]);
+    section[crc_pos..].copy_from_slice(&crc.to_be_bytes());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use crate::SpliceInfoSection;
+    use hex_literal::*;
+    use matches::*;
+
+    #[test]
+    fn pts_adjustment() {
+        let mut data = SPLICE_INSERT.to_vec();
+        // trailing stuffing is not included in the result
+        data.extend_from_slice(&[0xff; 3]);
+        let restamped = set_pts_adjustment(&data, 0x1_2345_6789).unwrap();
+        assert_eq!(restamped.len(), SPLICE_INSERT.len());
+        let section = SpliceInfoSection::parse(&restamped).unwrap();
+        assert_eq!(section.pts_adjustment, 0x1_2345_6789);
+        // only the pts_adjustment and CRC_32 bytes differ
+        let changed: Vec<_> = (0..restamped.len())
+            .filter(|&i| restamped[i] != SPLICE_INSERT[i])
+            .collect();
+        assert_eq!(changed, [4, 5, 6, 7, 8, 36, 37, 38, 39]);
+        assert_eq!(
+            set_pts_adjustment(&restamped, 0).unwrap(),
+            SPLICE_INSERT.to_vec()
+        );
+
+        assert_matches!(
+            set_pts_adjustment(&SPLICE_INSERT, 1 << 33),
+            Err(EditError::FieldOutOfRange {
+                field_name: "pts_adjustment",
+                value: 0x2_0000_0000
+            })
+        );
+        let mut bad_crc = SPLICE_INSERT;
+        bad_crc[20] ^= 1;
+        assert_matches!(
+            set_pts_adjustment(&bad_crc, 0),
+            Err(EditError::Section(Scte35Error::Crc(_)))
+        );
+        assert_matches!(
+            set_pts_adjustment(&SPLICE_INSERT[..30], 0),
+            Err(EditError::Section(Scte35Error::SectionTooShort {
+                actual: 30,
+                expected: 40
+            }))
+        );
+    }
+
+    #[test]
+    fn tier() {
+        let changed = set_tier(&SPLICE_INSERT, Tier::new(0x123).unwrap()).unwrap();
+        let section = SpliceInfoSection::parse(&changed).unwrap();
+        assert_eq!(section.tier.value(), 0x123);
+        assert_eq!(
+            section.splice_command,
+            SpliceInfoSection::parse(&SPLICE_INSERT)
+                .unwrap()
+                .splice_command
+        );
+        assert_eq!(
+            set_tier(&changed, Tier::ALL).unwrap(),
+            SPLICE_INSERT.to_vec()
+        );
+    }
+
+    #[test]
+    fn descriptors() {
+        // a time_signal() with avail, proprietary and segmentation descriptors
+        let data = with_crc(
+            hex!(
+                "fc303900000000000000fff00506fe00000000 0023"
+                "00084355454900000135"
+                "010641434d451234"
+                "020f43554549000000017fbf0000100101"
+            )
+            .to_vec(),
+        );
+        assert_eq!(
+            SpliceInfoSection::parse(&data)
+                .unwrap()
+                .splice_descriptors
+                .len(),
+            3
+        );
+        let stripped = remove_descriptors(&data, |desc| &desc[2..6] != b"CUEI").unwrap();
+        let section = SpliceInfoSection::parse(&stripped).unwrap();
+        assert_eq!(section.splice_descriptors.len(), 2);
+        let mut expected = data[..21].to_vec();
+        expected[2] = 0x31;
+        expected[20] = 0x1b;
+        expected.extend_from_slice(&data[21..31]);
+        expected.extend_from_slice(&data[39..data.len() - 4]);
+        assert_eq!(stripped[..stripped.len() - 4], expected[..]);
+
+        let none = remove_descriptors(&stripped, |_| true).unwrap();
+        assert_eq!(
+            none[..21],
+            hex!("fc301600000000000000fff00506fe000000000000")
+        );
+        assert!(SpliceInfoSection::parse(&none)
+            .unwrap()
+            .splice_descriptors
+            .is_empty());
+        // removing nothing changes nothing
+        assert_eq!(remove_descriptors(&data, |_| false).unwrap(), data);
+
+        let mut overrun = data[..data.len() - 4].to_vec();
+        overrun[32] = 0x20;
+        assert_matches!(
+            remove_descriptors(&with_crc(overrun), |_| true),
+            Err(EditError::Section(Scte35Error::Descriptor(_)))
+        );
+    }
+}
@@ -0,0 +1,357 @@
+//! Carriage of _SCTE-35_ cues in the ISOBMFF `emsg` (_Event Message_) boxes of DASH and CMAF
+//! media segments, per _SCTE 214-3_.
+//!
+//! Available when this crate's `emsg` feature is enabled.
+//!
+//! ## Example
+//!
+//! ```
+//! # use hex_literal::*;
+//! # use scte35_reader::SpliceInfoSection;
+//! # use scte35_reader::emsg::{Emsg, EmsgTime, SCHEME_ID_URI};
+//! let data = hex!(
+//!     "fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49"
+//! );
+//! let emsg = Emsg {
+//!     scheme_id_uri: SCHEME_ID_URI,
+//!     value: "",
+//!     timescale: 90_000,
+//!     presentation_time: EmsgTime::Absolute(0x2d142b00),
+//!     event_duration: 0x0123d308,
+//!     id: 1,
+//!     message_data: &data,
+//! };
+//! let bytes = emsg.to_bytes();
+//! let section = Emsg::parse(&bytes).unwrap().section().unwrap();
+//! assert_eq!(section.splice_pts(), Some(0x2d142b00));
+//! ```
+
+use crate::{Scte35Error, SpliceInfoSection};
+
+/// The `scheme_id_uri` of `emsg` boxes whose `message_data` is a binary `splice_info_section()`
+pub const SCHEME_ID_URI: &str = "urn:scte:scte35:2013:bin";
+
+/// The `event_duration` value signalling that the duration is unknown
+pub const UNKNOWN_DURATION: u32 = 0xffff_ffff;
+
+/// The timing of an event message, whose form determines the version of the `emsg` box
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmsgTime {
+    /// Version 0 `presentation_time_delta`, relative to the earliest presentation time of the
+    /// segment holding the box
+    Delta(u32),
+    /// Version 1 `presentation_time`, on the timeline of the track
+    Absolute(u64),
+}
+
+/// The fields of an `emsg` box, borrowing the strings and `message_data` from the underlying
+/// buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Emsg<'a> {
+    pub scheme_id_uri: &'a str,
+    pub value: &'a str,
+    /// The ticks per second of `presentation_time` and `event_duration`
+    pub timescale: u32,
+    pub presentation_time: EmsgTime,
+    /// The duration, in `timescale` units, or `UNKNOWN_DURATION`
+    pub event_duration: u32,
+    pub id: u32,
+    pub message_data: &'a [u8],
+}
+impl<'a> Emsg<'a> {
+    /// Parses the complete `emsg` box (including its size and type header) at the start of the
+    /// given buffer.  Any data following the box is ignored.
+    pub fn parse(buf: &'a [u8]) -> Result<Emsg<'a>, EmsgError> {
+        let mut r = Reader { buf, field: "size" };
+        let size = r.u32()?;
+        let box_type = r.bytes(4, "type")?;
+        if box_type != b"emsg" {
+            return Err(EmsgError::NotEmsg([
+                box_type[0],
+                box_type[1],
+                box_type[2],
+                box_type[3],
+            ]));
+        }
+        let (header_len, size) = match size {
+            1 => {
+                r.field = "largesize";
+                (16, r.u64()?)
+            }
+            0 => (8, buf.len() as u64),
+            size => (8, u64::from(size)),
+        };
+        let body_len = size
+            .checked_sub(header_len)
+            .filter(|len| *len <= r.buf.len() as u64)
+            .ok_or(EmsgError::Truncated("size"))?;
+        let mut r = Reader {
+            buf: &r.buf[..body_len as usize],
+            field: "version",
+        };
+        let version = r.bytes(1, "version")?[0];
+        r.bytes(3, "flags")?;
+        let emsg = match version {
+            0 => {
+                let scheme_id_uri = r.string("scheme_id_uri")?;
+                let value = r.string("value")?;
+                r.field = "timescale";
+                let timescale = r.u32()?;
+                r.field = "presentation_time_delta";
+                let presentation_time = EmsgTime::Delta(r.u32()?);
+                r.field = "event_duration";
+                let event_duration = r.u32()?;
+                r.field = "id";
+                let id = r.u32()?;
+                Emsg {
+                    scheme_id_uri,
+                    value,
+                    timescale,
+                    presentation_time,
+                    event_duration,
+                    id,
+                    message_data: r.buf,
+                }
+            }
+            1 => {
+                r.field = "timescale";
+                let timescale = r.u32()?;
+                r.field = "presentation_time";
+                let presentation_time = EmsgTime::Absolute(r.u64()?);
+                r.field = "event_duration";
+                let event_duration = r.u32()?;
+                r.field = "id";
+                let id = r.u32()?;
+                let scheme_id_uri = r.string("scheme_id_uri")?;
+                let value = r.string("value")?;
+                Emsg {
+                    scheme_id_uri,
+                    value,
+                    timescale,
+                    presentation_time,
+                    event_duration,
+                    id,
+                    message_data: r.buf,
+                }
+            }
+            v => return Err(EmsgError::UnsupportedVersion(v)),
+        };
+        Ok(emsg)
+    }
+
+    /// Parses the `message_data` as a `splice_info_section()`, failing if the `scheme_id_uri`
+    /// is not `urn:scte:scte35:2013:bin`.
+    pub fn section(&self) -> Result<SpliceInfoSection, EmsgError> {
+        if self.scheme_id_uri != SCHEME_ID_URI {
+            return Err(EmsgError::UnexpectedScheme(self.scheme_id_uri.to_string()));
+        }
+        SpliceInfoSection::parse(self.message_data).map_err(EmsgError::Section)
+    }
+
+    /// Produces the bytes of the complete box, using version `0` or `1` according to the form
+    /// of `presentation_time`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut body = vec![];
+        match self.presentation_time {
+            EmsgTime::Delta(delta) => {
+                body.extend_from_slice(&[0, 0, 0, 0]);
+                push_string(&mut body, self.scheme_id_uri);
+                push_string(&mut body, self.value);
+                body.extend_from_slice(&self.timescale.to_be_bytes());
+                body.extend_from_slice(&delta.to_be_bytes());
+                body.extend_from_slice(&self.event_duration.to_be_bytes());
+                body.extend_from_slice(&self.id.to_be_bytes());
+            }
+            EmsgTime::Absolute(time) => {
+                body.extend_from_slice(&[1, 0, 0, 0]);
+                body.extend_from_slice(&self.timescale.to_be_bytes());
+                body.extend_from_slice(&time.to_be_bytes());
+                body.extend_from_slice(&self.event_duration.to_be_bytes());
+                body.extend_from_slice(&self.id.to_be_bytes());
+                push_string(&mut body, self.scheme_id_uri);
+                push_string(&mut body, self.value);
+            }
+        }
+        body.extend_from_slice(self.message_data);
+        let mut result = Vec::with_capacity(body.len() + 16);
+        match u32::try_from(body.len() + 8) {
+            Ok(size) => {
+                result.extend_from_slice(&size.to_be_bytes());
+                result.extend_from_slice(b"emsg");
+            }
+            // too big for the 32-bit size field, so 'largesize' is used instead
+            Err(_) => {
+                result.extend_from_slice(&1u32.to_be_bytes());
+                result.extend_from_slice(b"emsg");
+                result.extend_from_slice(&(body.len() as u64 + 16).to_be_bytes());
+            }
+        }
+        result.extend_from_slice(&body);
+        result
+    }
+}
+
+/// Appends the given string, null-terminated
+fn push_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(s.as_bytes());
+    out.push(0);
+}
+
+/// Problems which prevent an `emsg` box being read
+#[derive(Debug)]
+pub enum EmsgError {
+    /// The box has the given type, rather than `emsg`
+    NotEmsg([u8; 4]),
+    /// The box ended before the named field was complete (or, for `size`, the buffer was shorter
+    /// than the size declared in the box header)
+    Truncated(&'static str),
+    /// Only versions `0` and `1` of the box are defined
+    UnsupportedVersion(u8),
+    /// The named string field was not valid UTF-8
+    InvalidString(&'static str),
+    /// The `scheme_id_uri` given was not that of _SCTE-35_ messages
+    UnexpectedScheme(String),
+    /// The `message_data` was not a valid `splice_info_section()`
+    Section(Scte35Error),
+}
+impl std::fmt::Display for EmsgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmsgError::NotEmsg(box_type) => write!(
+                f,
+                "box type {:?} is not emsg",
+                String::from_utf8_lossy(box_type)
+            ),
+            EmsgError::Truncated(field) => write!(f, "emsg box truncated in {}", field),
+            EmsgError::UnsupportedVersion(v) => write!(f, "unsupported emsg version {}", v),
+            EmsgError::InvalidString(field) => write!(f, "{} is not valid UTF-8", field),
+            EmsgError::UnexpectedScheme(scheme) => {
+                write!(f, "scheme_id_uri {:?} is not that of SCTE-35", scheme)
+            }
+            EmsgError::Section(_) => f.write_str("invalid splice_info_section in message_data"),
+        }
+    }
+}
+impl std::error::Error for EmsgError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EmsgError::Section(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    /// The field being read, reported if the data is truncated
+    field: &'static str,
+}
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize, field: &'static str) -> Result<&'a [u8], EmsgError> {
+        if self.buf.len() < len {
+            return Err(EmsgError::Truncated(field));
+        }
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, EmsgError> {
+        let b = self.bytes(4, self.field)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64, EmsgError> {
+        Ok(u64::from(self.u32()?) << 32 | u64::from(self.u32()?))
+    }
+
+    fn string(&mut self, field: &'static str) -> Result<&'a str, EmsgError> {
+        let len = self
+            .buf
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or(EmsgError::Truncated(field))?;
+        let s =
+            std::str::from_utf8(&self.buf[..len]).map_err(|_| EmsgError::InvalidString(field))?;
+        self.buf = &self.buf[len + 1..];
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use hex_literal::*;
+
+    #[test]
+    fn version_0() {
+        let emsg = Emsg {
+            scheme_id_uri: SCHEME_ID_URI,
+            value: "",
+            timescale: 1000,
+            presentation_time: EmsgTime::Delta(500),
+            event_duration: UNKNOWN_DURATION,
+            id: 7,
+            message_data: &SPLICE_INSERT,
+        };
+        let bytes = emsg.to_bytes();
+        assert_eq!(&bytes[..12], &hex!("0000005e 656d7367 00000000"));
+        assert_eq!(Emsg::parse(&bytes).unwrap(), emsg);
+        // the box should be 'complete' even with trailing data following it
+        let mut trailing = bytes.clone();
+        trailing.extend_from_slice(b"more");
+        assert_eq!(Emsg::parse(&trailing).unwrap(), emsg);
+        assert!(matches!(
+            Emsg::parse(&bytes[..bytes.len() - 1]),
+            Err(EmsgError::Truncated("size"))
+        ));
+    }
+
+    #[test]
+    fn version_1() {
+        let emsg = Emsg {
+            scheme_id_uri: SCHEME_ID_URI,
+            value: "1",
+            timescale: 90_000,
+            presentation_time: EmsgTime::Absolute(0x1_0000_0000),
+            event_duration: 0x0123_d308,
+            id: 1,
+            message_data: &SPLICE_INSERT,
+        };
+        let bytes = emsg.to_bytes();
+        assert_eq!(&bytes[8..12], &[1, 0, 0, 0]);
+        let parsed = Emsg::parse(&bytes).unwrap();
+        assert_eq!(parsed, emsg);
+        assert_eq!(parsed.section().unwrap().splice_pts(), Some(0x2d14_2b00));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Emsg::parse(&hex!("00000008 6d646174")),
+            Err(EmsgError::NotEmsg(t)) if &t == b"mdat"
+        ));
+        assert!(matches!(
+            Emsg::parse(&hex!("0000000c 656d7367 02000000")),
+            Err(EmsgError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            Emsg::parse(&hex!("00000010 656d7367 00000000 75726e00")),
+            Err(EmsgError::Truncated("value"))
+        ));
+        let emsg = Emsg {
+            scheme_id_uri: "urn:example",
+            value: "",
+            timescale: 1,
+            presentation_time: EmsgTime::Delta(0),
+            event_duration: 0,
+            id: 0,
+            message_data: &SPLICE_INSERT,
+        };
+        assert!(matches!(
+            emsg.section(),
+            Err(EmsgError::UnexpectedScheme(s)) if s == "urn:example"
+        ));
+    }
+}
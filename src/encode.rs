@@ -0,0 +1,804 @@
+//! Serialization of the owned [`SpliceInfoSection`](../struct.SpliceInfoSection.html) model back
+//! into `splice_info_section()` bytes, so that parsed cues can be modified and re-emitted, or new
+//! cues constructed from scratch.
+//!
+//! ## Example
+//!
+//! ```
+//! # use hex_literal::*;
+//! # use scte35_reader::SpliceInfoSection;
+//! let data = hex!("fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49");
+//! let mut section = SpliceInfoSection::parse(&data).unwrap();
+//! assert_eq!(section.encode().unwrap(), data);
+//!
+//! section.pts_adjustment = 90_000;
+//! let adjusted = section.encode().unwrap();
+//! assert_eq!(SpliceInfoSection::parse(&adjusted).unwrap().pts_adjustment, 90_000);
+//! ```
+//!
+//! Reserved bits are always written as `1`, as the spec requires, except for the `reserved`
+//! field of `SpliceCommand::SpliceInsert` which the model retains.  The `*_flag` fields of
+//! `SegmentationDescriptor::Insert` are not consulted; each flag is instead written according
+//! to the presence of the value it governs (e.g. `segmentation_duration_flag` is set when
+//! `segmentation_duration` is `Some`).
+
+use crate::{
+    DeliveryRestrictionFlags, DeviceRestrictions, NetworkIndicator, ReturnMode, ScheduledSplice,
+    ScheduledSpliceMode, SegmentationDescriptor, SegmentationMode, SegmentationUpid, SpliceCommand,
+    SpliceCommandType, SpliceDescriptor, SpliceDuration, SpliceInfoSection, SpliceInsert,
+    SpliceMode, SpliceTime, MAX_SECTION_LENGTH,
+};
+
+/// Problems which prevent a `SpliceInfoSection` from being encoded
+#[derive(Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The section's `encrypted_packet` flag is set.  Encode the un-encrypted section, and then
+    /// use [`builder::encrypt()`](../builder/fn.encrypt.html) to produce the encrypted form.
+    Encrypted,
+    /// A value is too large to be represented in the number of bits the syntax allows for the
+    /// named field
+    FieldOutOfRange {
+        field_name: &'static str,
+        value: u64,
+    },
+    /// The resulting `section_length` would exceed `MAX_SECTION_LENGTH`
+    SectionTooLong(usize),
+    /// A `SpliceCommand::Reserved` has a `command_type` which the spec does not reserve, and
+    /// which would therefore be parsed as a different command
+    ReservedCommandType(u8),
+}
+impl std::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncodeError::Encrypted => f.write_str("encrypted sections can not be encoded"),
+            EncodeError::FieldOutOfRange { field_name, value } => {
+                write!(f, "value {} is out of range for {}", value, field_name)
+            }
+            EncodeError::SectionTooLong(len) => write!(
+                f,
+                "section_length {} would exceed the maximum of {}",
+                len, MAX_SECTION_LENGTH
+            ),
+            EncodeError::ReservedCommandType(command_type) => write!(
+                f,
+                "splice_command_type {:#04x} is not reserved",
+                command_type
+            ),
+        }
+    }
+}
+impl std::error::Error for EncodeError {}
+
+impl SpliceInfoSection {
+    /// Produces the bytes of a `splice_info_section()` carrying the values of this section,
+    /// with `section_length`, `splice_command_length`, `descriptor_loop_length` and `CRC_32`
+    /// calculated to suit.
+    ///
+    /// See the [`encode`](encode/index.html) module for details.
+    pub fn encode(&self) -> Result<Vec<u8>, EncodeError> {
+        if self.encrypted_packet {
+            return Err(EncodeError::Encrypted);
+        }
+        let mut command = BitWriter::default();
+        let splice_command_type = write_command(&mut command, &self.splice_command)?;
+        let mut descriptors = vec![];
+        for descriptor in &self.splice_descriptors {
+            write_descriptor(&mut descriptors, descriptor)?;
+        }
+        let header = SectionHeader {
+            sap_type: self.sap_type.id(),
+            protocol_version: self.protocol_version,
+            encryption_algorithm: self.encryption_algorithm.id(),
+            pts_adjustment: self.pts_adjustment,
+            cw_index: self.cw_index,
+            tier: self.tier.value(),
+        };
+        section(
+            &header,
+            splice_command_type,
+            &command.into_bytes(),
+            &descriptors,
+        )
+    }
+}
+
+/// The `splice_info_section()` fields preceding `splice_command_length`
+pub(crate) struct SectionHeader {
+    pub sap_type: u8,
+    pub protocol_version: u8,
+    pub encryption_algorithm: u8,
+    pub pts_adjustment: u64,
+    pub cw_index: u8,
+    pub tier: u16,
+}
+
+/// Wraps the given command and descriptor loop in an un-encrypted `splice_info_section()`,
+/// appending the `CRC_32`.
+pub(crate) fn section(
+    header: &SectionHeader,
+    splice_command_type: u8,
+    command: &[u8],
+    descriptors: &[u8],
+) -> Result<Vec<u8>, EncodeError> {
+    // the 11 bytes following section_length, plus the command, descriptor_loop_length,
+    // the descriptors and the CRC
+    let section_length = 11 + command.len() + 2 + descriptors.len() + 4;
+    if section_length > MAX_SECTION_LENGTH {
+        return Err(EncodeError::SectionTooLong(section_length));
+    }
+    let mut w = BitWriter::default();
+    w.write(8, 0xfc);
+    // section_syntax_indicator=0, private_indicator=0
+    w.write(2, 0b00);
+    w.write(2, u64::from(header.sap_type & 0b11));
+    w.write(12, section_length as u64);
+    w.write(8, u64::from(header.protocol_version));
+    // encrypted_packet=0
+    w.write(1, 0);
+    w.checked(6, header.encryption_algorithm, "encryption_algorithm")?;
+    w.checked(33, header.pts_adjustment, "pts_adjustment")?;
+    w.write(8, u64::from(header.cw_index));
+    w.checked(12, header.tier, "tier")?;
+    w.write(12, command.len() as u64);
+    w.write(8, u64::from(splice_command_type));
+    let mut data = w.into_bytes();
+    data.extend_from_slice(command);
+    data.extend_from_slice(&(descriptors.len() as u16).to_be_bytes());
+    data.extend_from_slice(descriptors);
+    let crc = mpeg2ts_reader::mpegts_crc::sum32(&data);
+    data.extend_from_slice(&crc.to_be_bytes());
+    Ok(data)
+}
+
+/// Accumulates values of arbitrary bit-width, most significant bit first
+#[derive(Default)]
+struct BitWriter {
+    data: Vec<u8>,
+    bits: u32,
+}
+impl BitWriter {
+    fn write(&mut self, width: u32, value: u64) {
+        for i in (0..width).rev() {
+            let offset = self.bits % 8;
+            if offset == 0 {
+                self.data.push(0);
+            }
+            let bit = (value >> i) as u8 & 1;
+            *self.data.last_mut().unwrap() |= bit << (7 - offset);
+            self.bits += 1;
+        }
+    }
+
+    fn reserved(&mut self, width: u32) {
+        self.write(width, u64::MAX);
+    }
+
+    fn flag(&mut self, value: bool) {
+        self.write(1, u64::from(value));
+    }
+
+    /// Writes the given value, failing if it does not fit in `width` bits
+    fn checked<T: Into<u64>>(
+        &mut self,
+        width: u32,
+        value: T,
+        field_name: &'static str,
+    ) -> Result<(), EncodeError> {
+        let value = value.into();
+        if value >> width != 0 {
+            return Err(EncodeError::FieldOutOfRange { field_name, value });
+        }
+        self.write(width, value);
+        Ok(())
+    }
+
+    /// Writes the given length as an 8-bit count, failing if it exceeds 255
+    fn count(&mut self, len: usize, field_name: &'static str) -> Result<(), EncodeError> {
+        self.checked(8, len as u64, field_name)
+    }
+
+    fn bytes(&mut self, data: &[u8]) {
+        for b in data {
+            self.write(8, u64::from(*b));
+        }
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        debug_assert_eq!(self.bits % 8, 0);
+        self.data
+    }
+}
+
+/// Writes the command syntax, returning the `splice_command_type` value
+fn write_command(w: &mut BitWriter, command: &SpliceCommand) -> Result<u8, EncodeError> {
+    Ok(match command {
+        SpliceCommand::SpliceNull {} => 0x00,
+        SpliceCommand::SpliceSchedule { events } => {
+            w.count(events.len(), "splice_schedule.splice_count")?;
+            for event in events {
+                w.write(32, u64::from(event.splice_event_id));
+                w.flag(matches!(event.splice_detail, ScheduledSplice::Cancel));
+                w.reserved(7);
+                write_scheduled_splice(w, &event.splice_detail)?;
+            }
+            0x04
+        }
+        SpliceCommand::SpliceInsert {
+            splice_event_id,
+            reserved,
+            splice_detail,
+        } => {
+            w.write(32, u64::from(*splice_event_id));
+            w.flag(matches!(splice_detail, SpliceInsert::Cancel));
+            w.checked(7, *reserved, "splice_insert.reserved")?;
+            write_splice_detail(w, splice_detail)?;
+            0x05
+        }
+        SpliceCommand::TimeSignal { splice_time } => {
+            match splice_time {
+                SpliceTime::Timed(pts_time) => write_splice_time(w, *pts_time)?,
+                // time_signal() has no splice_immediate_flag, but an unspecified time has the
+                // same meaning
+                SpliceTime::Immediate => write_splice_time(w, None)?,
+            }
+            0x06
+        }
+        SpliceCommand::BandwidthReservation {} => 0x07,
+        SpliceCommand::PrivateCommand {
+            identifier,
+            private_bytes,
+        } => {
+            w.bytes(identifier);
+            w.bytes(private_bytes);
+            0xff
+        }
+        SpliceCommand::Reserved {
+            command_type,
+            payload,
+        } => {
+            if !matches!(
+                SpliceCommandType::from_id(*command_type),
+                SpliceCommandType::Reserved(_)
+            ) {
+                return Err(EncodeError::ReservedCommandType(*command_type));
+            }
+            w.bytes(payload);
+            *command_type
+        }
+    })
+}
+
+fn write_scheduled_splice(w: &mut BitWriter, splice: &ScheduledSplice) -> Result<(), EncodeError> {
+    if let ScheduledSplice::Insert {
+        network_indicator,
+        splice_mode,
+        duration,
+        unique_program_id,
+        avail_num,
+        avails_expected,
+    } = splice
+    {
+        w.flag(matches!(network_indicator, NetworkIndicator::Out));
+        w.flag(matches!(splice_mode, ScheduledSpliceMode::Program(_)));
+        w.flag(duration.is_some());
+        w.reserved(5);
+        match splice_mode {
+            ScheduledSpliceMode::Program(utc_splice_time) => {
+                w.write(32, u64::from(utc_splice_time.0))
+            }
+            ScheduledSpliceMode::Components(components) => {
+                w.count(components.len(), "splice_schedule.component_count")?;
+                for component in components {
+                    w.write(8, u64::from(component.component_tag));
+                    w.write(32, u64::from(component.utc_splice_time.0));
+                }
+            }
+        }
+        if let Some(duration) = duration {
+            write_duration(w, duration)?;
+        }
+        w.write(16, u64::from(*unique_program_id));
+        w.write(8, u64::from(*avail_num));
+        w.write(8, u64::from(*avails_expected));
+    }
+    Ok(())
+}
+
+fn write_splice_detail(w: &mut BitWriter, detail: &SpliceInsert) -> Result<(), EncodeError> {
+    if let SpliceInsert::Insert {
+        network_indicator,
+        splice_mode,
+        duration,
+        unique_program_id,
+        avail_num,
+        avails_expected,
+    } = detail
+    {
+        let splice_immediate = match splice_mode {
+            SpliceMode::Program(time) => matches!(time, SpliceTime::Immediate),
+            SpliceMode::Components(components) => {
+                !components.is_empty()
+                    && components
+                        .iter()
+                        .all(|c| matches!(c.splice_time, SpliceTime::Immediate))
+            }
+        };
+        w.flag(matches!(network_indicator, NetworkIndicator::Out));
+        w.flag(matches!(splice_mode, SpliceMode::Program(_)));
+        w.flag(duration.is_some());
+        w.flag(splice_immediate);
+        w.reserved(4);
+        match splice_mode {
+            SpliceMode::Program(time) => {
+                if !splice_immediate {
+                    write_insert_splice_time(w, time)?;
+                }
+            }
+            SpliceMode::Components(components) => {
+                w.count(components.len(), "splice_insert.component_count")?;
+                for component in components {
+                    w.write(8, u64::from(component.component_tag));
+                    if !splice_immediate {
+                        write_insert_splice_time(w, &component.splice_time)?;
+                    }
+                }
+            }
+        }
+        if let Some(duration) = duration {
+            write_duration(w, duration)?;
+        }
+        w.write(16, u64::from(*unique_program_id));
+        w.write(8, u64::from(*avail_num));
+        w.write(8, u64::from(*avails_expected));
+    }
+    Ok(())
+}
+
+/// The `splice_immediate_flag` applies to all components of a `splice_insert()` together, so a
+/// component which is `Immediate` while another is not can not be represented
+fn write_insert_splice_time(w: &mut BitWriter, time: &SpliceTime) -> Result<(), EncodeError> {
+    match time {
+        SpliceTime::Timed(pts_time) => write_splice_time(w, *pts_time),
+        SpliceTime::Immediate => Err(EncodeError::FieldOutOfRange {
+            field_name: "splice_insert.splice_immediate_flag",
+            value: 1,
+        }),
+    }
+}
+
+fn write_splice_time(w: &mut BitWriter, pts_time: Option<u64>) -> Result<(), EncodeError> {
+    match pts_time {
+        Some(pts) => {
+            w.flag(true);
+            w.reserved(6);
+            w.checked(33, pts, "splice_time.pts_time")
+        }
+        None => {
+            w.flag(false);
+            w.reserved(7);
+            Ok(())
+        }
+    }
+}
+
+fn write_duration(w: &mut BitWriter, duration: &SpliceDuration) -> Result<(), EncodeError> {
+    w.flag(matches!(duration.return_mode, ReturnMode::Automatic));
+    w.reserved(6);
+    w.checked(33, duration.duration, "break_duration.duration")
+}
+
+/// Appends the complete `splice_descriptor()`, including tag, length and identifier
+fn write_descriptor(out: &mut Vec<u8>, descriptor: &SpliceDescriptor) -> Result<(), EncodeError> {
+    let mut w = BitWriter::default();
+    let (tag, identifier) = match descriptor {
+        SpliceDescriptor::AvailDescriptor { provider_avail_id } => {
+            w.write(32, u64::from(*provider_avail_id));
+            (0x00, b"CUEI")
+        }
+        SpliceDescriptor::DTMFDescriptor {
+            preroll,
+            dtmf_chars,
+        } => {
+            w.write(8, u64::from(*preroll));
+            w.checked(3, dtmf_chars.len() as u64, "dtmf_descriptor.dtmf_count")?;
+            w.reserved(5);
+            w.bytes(dtmf_chars);
+            (0x01, b"CUEI")
+        }
+        SpliceDescriptor::SegmentationDescriptor {
+            segmentation_event_id,
+            descriptor_detail,
+        } => {
+            w.write(32, u64::from(*segmentation_event_id));
+            w.flag(matches!(descriptor_detail, SegmentationDescriptor::Cancel));
+            w.reserved(7);
+            write_segmentation_detail(&mut w, descriptor_detail)?;
+            (0x02, b"CUEI")
+        }
+        SpliceDescriptor::TimeDescriptor {
+            tai_seconds,
+            tai_nanoseconds,
+            utc_offset,
+        } => {
+            w.checked(48, *tai_seconds, "time_descriptor.TAI_seconds")?;
+            w.write(32, u64::from(*tai_nanoseconds));
+            w.write(16, u64::from(*utc_offset));
+            (0x03, b"CUEI")
+        }
+        SpliceDescriptor::AudioDescriptor { components } => {
+            w.checked(4, components.len() as u64, "audio_descriptor.audio_count")?;
+            w.reserved(4);
+            for component in components {
+                w.write(8, u64::from(component.component_tag));
+                w.bytes(&component.iso_code);
+                w.checked(
+                    3,
+                    component.bit_stream_mode,
+                    "audio_descriptor.Bit_Stream_Mode",
+                )?;
+                w.checked(4, component.num_channels, "audio_descriptor.Num_Channels")?;
+                w.flag(component.full_srvc_audio);
+            }
+            (0x04, b"CUEI")
+        }
+        SpliceDescriptor::Reserved {
+            tag,
+            identifier,
+            private_bytes,
+        } => {
+            w.bytes(private_bytes);
+            (*tag, identifier)
+        }
+    };
+    let payload = w.into_bytes();
+    // the identifier is counted in descriptor_length
+    let descriptor_length = 4 + payload.len();
+    if descriptor_length > 0xff {
+        return Err(EncodeError::FieldOutOfRange {
+            field_name: "splice_descriptor.descriptor_length",
+            value: descriptor_length as u64,
+        });
+    }
+    out.push(tag);
+    out.push(descriptor_length as u8);
+    out.extend_from_slice(identifier);
+    out.extend_from_slice(&payload);
+    Ok(())
+}
+
+fn write_segmentation_detail(
+    w: &mut BitWriter,
+    detail: &SegmentationDescriptor,
+) -> Result<(), EncodeError> {
+    if let SegmentationDescriptor::Insert {
+        delivery_restrictions,
+        segmentation_mode,
+        segmentation_duration,
+        segmentation_upid,
+        segmentation_type_id,
+        segment_num,
+        segments_expected,
+        sub_segments,
+        ..
+    } = detail
+    {
+        w.flag(matches!(segmentation_mode, SegmentationMode::Program));
+        w.flag(segmentation_duration.is_some());
+        match delivery_restrictions {
+            DeliveryRestrictionFlags::None => {
+                w.flag(true);
+                w.reserved(5);
+            }
+            DeliveryRestrictionFlags::DeliveryRestrictions {
+                web_delivery_allowed_flag,
+                no_regional_blackout_flag,
+                archive_allowed_flag,
+                device_restrictions,
+            } => {
+                w.flag(false);
+                w.flag(*web_delivery_allowed_flag);
+                w.flag(*no_regional_blackout_flag);
+                w.flag(*archive_allowed_flag);
+                w.write(
+                    2,
+                    match device_restrictions {
+                        DeviceRestrictions::RestrictGroup0 => 0,
+                        DeviceRestrictions::RestrictGroup1 => 1,
+                        DeviceRestrictions::RestrictGroup2 => 2,
+                        DeviceRestrictions::None => 3,
+                    },
+                );
+            }
+        }
+        if let SegmentationMode::Component { components } = segmentation_mode {
+            w.count(components.len(), "segmentation_descriptor.component_count")?;
+            for component in components {
+                w.write(8, u64::from(component.component_tag));
+                w.reserved(7);
+                w.checked(
+                    33,
+                    component.pts_offset,
+                    "segmentation_descriptor.component.pts_offset",
+                )?;
+            }
+        }
+        if let Some(duration) = segmentation_duration {
+            w.checked(
+                40,
+                *duration,
+                "segmentation_descriptor.segmentation_duration",
+            )?;
+        }
+        w.write(
+            8,
+            u64::from(segmentation_upid.segmentation_upid_type().id()),
+        );
+        let mut upid = vec![];
+        write_upid(&mut upid, segmentation_upid)?;
+        w.count(
+            upid.len(),
+            "segmentation_descriptor.segmentation_upid_length",
+        )?;
+        w.bytes(&upid);
+        w.write(8, u64::from(segmentation_type_id.id()));
+        w.write(8, u64::from(*segment_num));
+        w.write(8, u64::from(*segments_expected));
+        if let Some(sub_segments) = sub_segments {
+            w.write(8, u64::from(sub_segments.sub_segment_num));
+            w.write(8, u64::from(sub_segments.sub_segments_expected));
+        }
+    }
+    Ok(())
+}
+
+/// Appends the `segmentation_upid()` bytes, without type or length
+pub(crate) fn write_upid(out: &mut Vec<u8>, upid: &SegmentationUpid) -> Result<(), EncodeError> {
+    match upid {
+        SegmentationUpid::None => {}
+        SegmentationUpid::UserDefined(v) => out.extend_from_slice(&v.0),
+        SegmentationUpid::Isci(v) => out.extend_from_slice(v.0.as_bytes()),
+        SegmentationUpid::AdID(v) => out.extend_from_slice(v.0.as_bytes()),
+        SegmentationUpid::IsanDeprecated(v) => out.extend_from_slice(&v.0),
+        SegmentationUpid::Isan(v) => out.extend_from_slice(&v.0),
+        SegmentationUpid::Umid(v) => out.extend_from_slice(&v.0),
+        SegmentationUpid::TID(v) => out.extend_from_slice(v.0.as_bytes()),
+        SegmentationUpid::TI(v) => out.extend_from_slice(&v.0),
+        SegmentationUpid::ADI(v) => out.extend_from_slice(v.0.as_bytes()),
+        SegmentationUpid::EIDR(v) => out.extend_from_slice(&v.0),
+        SegmentationUpid::ATSC(v) => {
+            let mut w = BitWriter::default();
+            w.write(16, u64::from(v.tsid));
+            w.reserved(2);
+            w.checked(5, v.end_of_day, "ATSC_content_identifier.end_of_day")?;
+            w.checked(9, v.unique_for, "ATSC_content_identifier.unique_for")?;
+            w.bytes(&v.content_id);
+            out.extend_from_slice(&w.into_bytes());
+        }
+        SegmentationUpid::MPU(v) => out.extend_from_slice(&v.0),
+        SegmentationUpid::MID(upids) => {
+            for upid in upids {
+                let mut payload = vec![];
+                write_upid(&mut payload, upid)?;
+                if payload.len() > 0xff {
+                    return Err(EncodeError::FieldOutOfRange {
+                        field_name: "MID.segmentation_upid_length",
+                        value: payload.len() as u64,
+                    });
+                }
+                out.push(upid.segmentation_upid_type().id());
+                out.push(payload.len() as u8);
+                out.extend_from_slice(&payload);
+            }
+        }
+        SegmentationUpid::ADS(v) => out.extend_from_slice(&v.0),
+        SegmentationUpid::URI(v) => out.extend_from_slice(v.0.as_str().as_bytes()),
+        SegmentationUpid::UUID(v) => out.extend_from_slice(&v.0),
+        SegmentationUpid::SCR(v) => out.extend_from_slice(&v.0),
+        SegmentationUpid::Reserved(_, v) => out.extend_from_slice(v),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use crate::{
+        upid, ComponentSplice, EncryptionAlgorithm, SapType, SegmentationTypeId, SubSegments, Tier,
+    };
+
+    fn round_trip(data: &[u8]) {
+        let section = SpliceInfoSection::parse(data).unwrap();
+        assert_eq!(section.encode().unwrap(), data);
+    }
+
+    #[test]
+    fn round_trips() {
+        // splice_null()
+        round_trip(&SPLICE_NULL);
+        // splice_insert()
+        round_trip(&SPLICE_INSERT);
+        // time_signal() with a segmentation_descriptor()
+        round_trip(&TIME_SIGNAL);
+    }
+
+    #[test]
+    fn all_descriptors() {
+        let section = SpliceInfoSection {
+            sap_type: SapType::Type2,
+            protocol_version: 0,
+            encrypted_packet: false,
+            encryption_algorithm: EncryptionAlgorithm::None,
+            pts_adjustment: 0x1_0000_0000,
+            cw_index: 0,
+            tier: Tier::new(0x123).unwrap(),
+            splice_command: SpliceCommand::TimeSignal {
+                splice_time: SpliceTime::Timed(Some(0x1_2345_6789)),
+            },
+            splice_descriptors: vec![
+                SpliceDescriptor::AvailDescriptor {
+                    provider_avail_id: 0x12345678,
+                },
+                SpliceDescriptor::DTMFDescriptor {
+                    preroll: 10,
+                    dtmf_chars: b"12#".to_vec(),
+                },
+                SpliceDescriptor::SegmentationDescriptor {
+                    segmentation_event_id: 7,
+                    descriptor_detail: SegmentationDescriptor::Insert {
+                        program_segmentation_flag: true,
+                        segmentation_duration_flag: true,
+                        delivery_not_restricted_flag: false,
+                        delivery_restrictions: DeliveryRestrictionFlags::DeliveryRestrictions {
+                            web_delivery_allowed_flag: true,
+                            no_regional_blackout_flag: false,
+                            archive_allowed_flag: true,
+                            device_restrictions: DeviceRestrictions::RestrictGroup1,
+                        },
+                        segmentation_mode: SegmentationMode::Program,
+                        segmentation_duration: Some(2_700_000),
+                        segmentation_upid: SegmentationUpid::MID(vec![
+                            SegmentationUpid::AdID(upid::AdID("ABCD0001000H".to_string())),
+                            SegmentationUpid::TI(upid::TI(vec![1, 2, 3, 4, 5, 6, 7, 8])),
+                            SegmentationUpid::ATSC(upid::ATSC {
+                                tsid: 0x1234,
+                                end_of_day: 21,
+                                unique_for: 286,
+                                content_id: b"ab".to_vec(),
+                            }),
+                        ]),
+                        segmentation_type_id: SegmentationTypeId::ProviderPlacementOpportunityStart,
+                        segment_num: 1,
+                        segments_expected: 2,
+                        sub_segments: Some(SubSegments {
+                            sub_segment_num: 3,
+                            sub_segments_expected: 4,
+                        }),
+                    },
+                },
+                SpliceDescriptor::TimeDescriptor {
+                    tai_seconds: 0x1234_5678_9abc,
+                    tai_nanoseconds: 500,
+                    utc_offset: 37,
+                },
+                SpliceDescriptor::AudioDescriptor {
+                    components: vec![crate::AudioComponent {
+                        component_tag: 1,
+                        iso_code: *b"eng",
+                        bit_stream_mode: 0,
+                        num_channels: 2,
+                        full_srvc_audio: true,
+                    }],
+                },
+                SpliceDescriptor::Reserved {
+                    tag: 0x80,
+                    identifier: *b"ABCD",
+                    private_bytes: vec![0xaa, 0xbb],
+                },
+            ],
+        };
+        let data = section.encode().unwrap();
+        round_trip(&data);
+        let parsed = SpliceInfoSection::parse(&data).unwrap();
+        assert_eq!(parsed.sap_type, SapType::Type2);
+        assert_eq!(parsed.pts_adjustment, 0x1_0000_0000);
+        assert_eq!(parsed.tier, Tier::new(0x123).unwrap());
+        assert_eq!(parsed.splice_descriptors.len(), 6);
+        assert_eq!(
+            format!("{:?}", parsed.splice_descriptors),
+            format!("{:?}", section.splice_descriptors)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut section = SpliceInfoSection::parse(&SPLICE_INSERT).unwrap();
+        section.pts_adjustment = 1 << 33;
+        assert_eq!(
+            section.encode(),
+            Err(EncodeError::FieldOutOfRange {
+                field_name: "pts_adjustment",
+                value: 1 << 33
+            })
+        );
+        section.pts_adjustment = 0;
+        section.encrypted_packet = true;
+        assert_eq!(section.encode(), Err(EncodeError::Encrypted));
+        section.encrypted_packet = false;
+        section.splice_command = SpliceCommand::PrivateCommand {
+            identifier: *b"ABCD",
+            private_bytes: vec![0; MAX_SECTION_LENGTH],
+        };
+        assert!(matches!(
+            section.encode(),
+            Err(EncodeError::SectionTooLong(_))
+        ));
+        section.splice_command = SpliceCommand::Reserved {
+            command_type: 0x05,
+            payload: vec![],
+        };
+        assert_eq!(
+            section.encode(),
+            Err(EncodeError::ReservedCommandType(0x05))
+        );
+    }
+
+    fn assert_command_round_trips(section: &SpliceInfoSection) {
+        let parsed = SpliceInfoSection::parse(&section.encode().unwrap()).unwrap();
+        assert_eq!(
+            format!("{:?}", parsed.splice_command),
+            format!("{:?}", section.splice_command)
+        );
+    }
+
+    #[test]
+    fn component_times() {
+        let mut section = SpliceInfoSection::parse(&SPLICE_INSERT).unwrap();
+        let component = |component_tag, splice_time| ComponentSplice {
+            component_tag,
+            splice_time,
+        };
+        let with_components = |section: &mut SpliceInfoSection, components| {
+            if let SpliceCommand::SpliceInsert {
+                splice_detail: SpliceInsert::Insert { splice_mode, .. },
+                ..
+            } = &mut section.splice_command
+            {
+                *splice_mode = SpliceMode::Components(components);
+            }
+        };
+        with_components(
+            &mut section,
+            vec![
+                component(1, SpliceTime::Timed(Some(1000))),
+                component(2, SpliceTime::Timed(None)),
+            ],
+        );
+        assert_command_round_trips(&section);
+
+        with_components(
+            &mut section,
+            vec![
+                component(1, SpliceTime::Immediate),
+                component(2, SpliceTime::Immediate),
+            ],
+        );
+        assert_command_round_trips(&section);
+
+        // a Timed component would otherwise lose its time to the splice_immediate_flag
+        with_components(
+            &mut section,
+            vec![
+                component(1, SpliceTime::Timed(Some(1000))),
+                component(2, SpliceTime::Immediate),
+            ],
+        );
+        assert_eq!(
+            section.encode(),
+            Err(EncodeError::FieldOutOfRange {
+                field_name: "splice_insert.splice_immediate_flag",
+                value: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,401 @@
+//! Messages of the CableLabs _Event Signaling and Management_ (ESAM) API, through which an
+//! encoder or packager consults a _Placement Opportunity Information Service_ (POIS) about each
+//! cue it acquires, per _OC-SP-ESAM-API_.
+//!
+//! The encoder sends a [`SignalProcessingEvent`](struct.SignalProcessingEvent.html) holding the
+//! acquired sections, and the POIS replies with a
+//! [`SignalProcessingNotification`](struct.SignalProcessingNotification.html) saying what to do
+//! with each of them.  Both can be generated (via their `Display` implementations) and parsed
+//! (via their `from_xml()` functions), so that either side of the exchange can be implemented.
+//!
+//! Available when this crate's `esam` feature is enabled.
+//!
+//! ## Example
+//!
+//! ```
+//! # use hex_literal::*;
+//! # use scte35_reader::esam::{AcquiredSignal, SignalProcessingEvent};
+//! let event = SignalProcessingEvent {
+//!     acquired_signals: vec![AcquiredSignal {
+//!         acquisition_point_identity: "encoder-1".to_string(),
+//!         acquisition_signal_id: "0a3f6e1c".to_string(),
+//!         utc_point: "2021-03-17T07:06:22Z".to_string(),
+//!         binary: hex!("fc301100000000000000fff0000000007a4fbfff").to_vec(),
+//!     }],
+//! };
+//! let xml = event.to_string();
+//! let parsed = SignalProcessingEvent::from_xml(&xml).unwrap();
+//! assert_eq!(parsed, event);
+//! ```
+
+use crate::xml::{child, children, invalid, required, XmlError};
+use crate::{Scte35Error, SpliceInfoSection};
+use base64::Engine;
+use roxmltree::Node;
+use std::fmt;
+
+const SIGNAL_NS: &str = "urn:cablelabs:iptvservices:esam:xsd:signal:1";
+const SIGNALING_NS: &str = "urn:cablelabs:md:xsd:signaling:3.0";
+const COMMON_NS: &str = "urn:cablelabs:iptvservices:esam:xsd:common:1";
+
+/// A request from an encoder to the POIS, listing the cues it has acquired
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignalProcessingEvent {
+    pub acquired_signals: Vec<AcquiredSignal>,
+}
+
+/// A cue acquired by an encoder, within a `SignalProcessingEvent`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcquiredSignal {
+    /// Identifies the encoder or packager which acquired the signal
+    pub acquisition_point_identity: String,
+    /// Identifies this signal, so that the response can be matched to it
+    pub acquisition_signal_id: String,
+    /// The wall-clock time of the signal, as an `xsd:dateTime` (see
+    /// [`time::format_rfc3339()`](../time/fn.format_rfc3339.html))
+    pub utc_point: String,
+    /// The encoded `splice_info_section()`
+    pub binary: Vec<u8>,
+}
+impl AcquiredSignal {
+    /// Parses the `binary` section
+    pub fn section(&self) -> Result<SpliceInfoSection, Scte35Error> {
+        SpliceInfoSection::parse(&self.binary)
+    }
+}
+
+/// What the POIS directs the encoder to do with an acquired signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Insert the given section, where there was no signal
+    Create,
+    /// Replace the acquired section with the given one
+    Replace,
+    /// Remove the acquired section from the output
+    Delete,
+    /// Pass the acquired section through unchanged
+    Noop,
+}
+impl Action {
+    /// The value of the `action` attribute
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Create => "create",
+            Action::Replace => "replace",
+            Action::Delete => "delete",
+            Action::Noop => "noop",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        match name {
+            "create" => Some(Action::Create),
+            "replace" => Some(Action::Replace),
+            "delete" => Some(Action::Delete),
+            "noop" => Some(Action::Noop),
+            _ => None,
+        }
+    }
+}
+
+/// The response from the POIS to a `SignalProcessingEvent`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignalProcessingNotification {
+    pub acquisition_point_identity: String,
+    /// The `classCode` of the `StatusCode`, where `0` indicates success
+    pub status_code: Option<u32>,
+    pub response_signals: Vec<ResponseSignal>,
+}
+
+/// The POIS's direction for one acquired signal, within a `SignalProcessingNotification`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseSignal {
+    pub action: Action,
+    pub acquisition_point_identity: String,
+    /// The `acquisition_signal_id` of the `AcquiredSignal` to which this responds
+    pub acquisition_signal_id: String,
+    pub signal_point_id: Option<String>,
+    pub utc_point: String,
+    /// The encoded `splice_info_section()` to be output, absent for `delete` and `noop` actions
+    pub binary: Option<Vec<u8>>,
+}
+impl ResponseSignal {
+    /// Parses the `binary` section, if present
+    pub fn section(&self) -> Option<Result<SpliceInfoSection, Scte35Error>> {
+        self.binary.as_deref().map(SpliceInfoSection::parse)
+    }
+}
+
+/// Problems which prevent an ESAM message being read
+#[derive(Debug)]
+pub enum EsamError {
+    /// The document was not well-formed, or lacked a required element or attribute
+    Xml(XmlError),
+    /// The content of a `BinaryData` element was not valid base64
+    Binary(base64::DecodeError),
+}
+impl fmt::Display for EsamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EsamError::Xml(_) => f.write_str("invalid ESAM document"),
+            EsamError::Binary(_) => f.write_str("invalid base64 in BinaryData"),
+        }
+    }
+}
+impl std::error::Error for EsamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EsamError::Xml(e) => Some(e),
+            EsamError::Binary(e) => Some(e),
+        }
+    }
+}
+impl From<XmlError> for EsamError {
+    fn from(e: XmlError) -> EsamError {
+        EsamError::Xml(e)
+    }
+}
+
+/// Escapes the given text for use within a double-quoted attribute value
+struct Escaped<'a>(&'a str);
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_signal(f: &mut fmt::Formatter<'_>, utc_point: &str, binary: Option<&[u8]>) -> fmt::Result {
+    write!(f, "<sig:UTCPoint utcPoint=\"{}\"/>", Escaped(utc_point))?;
+    if let Some(binary) = binary {
+        write!(
+            f,
+            "<sig:BinaryData signalType=\"SCTE35\">{}</sig:BinaryData>",
+            base64::engine::general_purpose::STANDARD.encode(binary)
+        )?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for SignalProcessingEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<SignalProcessingEvent xmlns=\"{}\" xmlns:sig=\"{}\">",
+            SIGNAL_NS, SIGNALING_NS
+        )?;
+        for signal in &self.acquired_signals {
+            write!(
+                f,
+                "<AcquiredSignal acquisitionPointIdentity=\"{}\" acquisitionSignalID=\"{}\">",
+                Escaped(&signal.acquisition_point_identity),
+                Escaped(&signal.acquisition_signal_id)
+            )?;
+            write_signal(f, &signal.utc_point, Some(&signal.binary))?;
+            f.write_str("</AcquiredSignal>")?;
+        }
+        f.write_str("</SignalProcessingEvent>")
+    }
+}
+
+impl fmt::Display for SignalProcessingNotification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<SignalProcessingNotification xmlns=\"{}\" xmlns:sig=\"{}\" xmlns:common=\"{}\" acquisitionPointIdentity=\"{}\">",
+            SIGNAL_NS,
+            SIGNALING_NS,
+            COMMON_NS,
+            Escaped(&self.acquisition_point_identity)
+        )?;
+        for signal in &self.response_signals {
+            write!(
+                f,
+                "<ResponseSignal action=\"{}\" acquisitionPointIdentity=\"{}\" acquisitionSignalID=\"{}\"",
+                signal.action.name(),
+                Escaped(&signal.acquisition_point_identity),
+                Escaped(&signal.acquisition_signal_id)
+            )?;
+            if let Some(id) = &signal.signal_point_id {
+                write!(f, " signalPointID=\"{}\"", Escaped(id))?;
+            }
+            f.write_str(">")?;
+            write_signal(f, &signal.utc_point, signal.binary.as_deref())?;
+            f.write_str("</ResponseSignal>")?;
+        }
+        if let Some(class_code) = self.status_code {
+            write!(f, "<common:StatusCode classCode=\"{}\"/>", class_code)?;
+        }
+        f.write_str("</SignalProcessingNotification>")
+    }
+}
+
+/// Finds the root element of the given name, which may be nested within other elements (such as
+/// a SOAP envelope)
+fn find<'a, 'i>(
+    doc: &'a roxmltree::Document<'i>,
+    name: &'static str,
+) -> Result<Node<'a, 'i>, XmlError> {
+    doc.descendants()
+        .find(|n| n.tag_name().name() == name)
+        .ok_or(XmlError::MissingElement(name))
+}
+
+/// The `utcPoint` of the `UTCPoint` and the decoded content of the `BinaryData` (if any) within
+/// the given signal element
+fn read_signal(node: Node<'_, '_>) -> Result<(String, Option<Vec<u8>>), EsamError> {
+    let utc_point = required(
+        child(node, "UTCPoint").ok_or(XmlError::MissingElement("UTCPoint"))?,
+        "utcPoint",
+    )?;
+    let binary = match child(node, "BinaryData") {
+        Some(b) => Some(
+            base64::engine::general_purpose::STANDARD
+                .decode(b.text().unwrap_or("").trim())
+                .map_err(EsamError::Binary)?,
+        ),
+        None => None,
+    };
+    Ok((utc_point, binary))
+}
+
+impl SignalProcessingEvent {
+    /// Reads a `SignalProcessingEvent` document
+    pub fn from_xml(xml: &str) -> Result<SignalProcessingEvent, EsamError> {
+        let doc = roxmltree::Document::parse(xml).map_err(XmlError::Xml)?;
+        let root = find(&doc, "SignalProcessingEvent")?;
+        let acquired_signals = children(root, "AcquiredSignal")
+            .map(|node| {
+                let (utc_point, binary) = read_signal(node)?;
+                Ok(AcquiredSignal {
+                    acquisition_point_identity: required(node, "acquisitionPointIdentity")?,
+                    acquisition_signal_id: required(node, "acquisitionSignalID")?,
+                    utc_point,
+                    binary: binary.ok_or(XmlError::MissingElement("BinaryData"))?,
+                })
+            })
+            .collect::<Result<_, EsamError>>()?;
+        Ok(SignalProcessingEvent { acquired_signals })
+    }
+}
+
+impl SignalProcessingNotification {
+    /// Reads a `SignalProcessingNotification` document
+    pub fn from_xml(xml: &str) -> Result<SignalProcessingNotification, EsamError> {
+        let doc = roxmltree::Document::parse(xml).map_err(XmlError::Xml)?;
+        let root = find(&doc, "SignalProcessingNotification")?;
+        let status_code = match child(root, "StatusCode") {
+            Some(s) => Some(required(s, "classCode")?),
+            None => None,
+        };
+        let response_signals = children(root, "ResponseSignal")
+            .map(|node| {
+                let action: String = required(node, "action")?;
+                let (utc_point, binary) = read_signal(node)?;
+                Ok(ResponseSignal {
+                    action: Action::from_name(&action)
+                        .ok_or_else(|| invalid(node, "action", &action))?,
+                    acquisition_point_identity: required(node, "acquisitionPointIdentity")?,
+                    acquisition_signal_id: required(node, "acquisitionSignalID")?,
+                    signal_point_id: node.attribute("signalPointID").map(str::to_string),
+                    utc_point,
+                    binary,
+                })
+            })
+            .collect::<Result<_, EsamError>>()?;
+        Ok(SignalProcessingNotification {
+            acquisition_point_identity: required(root, "acquisitionPointIdentity")?,
+            status_code,
+            response_signals,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+
+    #[test]
+    fn notification() {
+        // as might be returned by a POIS
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <SignalProcessingNotification xmlns="urn:cablelabs:iptvservices:esam:xsd:signal:1"
+                xmlns:sig="urn:cablelabs:md:xsd:signaling:3.0"
+                xmlns:common="urn:cablelabs:iptvservices:esam:xsd:common:1"
+                acquisitionPointIdentity="encoder-1">
+              <common:StatusCode classCode="0"/>
+              <ResponseSignal action="replace" acquisitionPointIdentity="encoder-1"
+                  acquisitionSignalID="a&amp;b" signalPointID="spot-1">
+                <sig:UTCPoint utcPoint="2021-03-17T07:06:22Z"/>
+                <sig:BinaryData signalType="SCTE35">
+                  /DAlAAAAAAAAAP/wFAUAAAABf+/+LRQrAP4BI9MIAAEBAQAAfxV6SQ==
+                </sig:BinaryData>
+              </ResponseSignal>
+              <ResponseSignal action="delete" acquisitionPointIdentity="encoder-1"
+                  acquisitionSignalID="c">
+                <sig:UTCPoint utcPoint="2021-03-17T07:07:00Z"/>
+              </ResponseSignal>
+            </SignalProcessingNotification>"#;
+        let notification = SignalProcessingNotification::from_xml(xml).unwrap();
+        assert_eq!(notification.acquisition_point_identity, "encoder-1");
+        assert_eq!(notification.status_code, Some(0));
+        assert_eq!(notification.response_signals.len(), 2);
+        let replace = &notification.response_signals[0];
+        assert_eq!(replace.action, Action::Replace);
+        assert_eq!(replace.acquisition_signal_id, "a&b");
+        assert_eq!(replace.signal_point_id.as_deref(), Some("spot-1"));
+        assert_eq!(
+            replace.section().unwrap().unwrap().splice_pts(),
+            Some(0x2d14_2b00)
+        );
+        let delete = &notification.response_signals[1];
+        assert_eq!(delete.action, Action::Delete);
+        assert_eq!(delete.binary, None);
+
+        let round_tripped =
+            SignalProcessingNotification::from_xml(&notification.to_string()).unwrap();
+        assert_eq!(round_tripped, notification);
+    }
+
+    #[test]
+    fn event() {
+        let event = SignalProcessingEvent {
+            acquired_signals: vec![AcquiredSignal {
+                acquisition_point_identity: "encoder-1".to_string(),
+                acquisition_signal_id: "<1>".to_string(),
+                utc_point: "2021-03-17T07:06:22Z".to_string(),
+                binary: SPLICE_NULL.to_vec(),
+            }],
+        };
+        assert_eq!(
+            event.to_string(),
+            "<SignalProcessingEvent xmlns=\"urn:cablelabs:iptvservices:esam:xsd:signal:1\" \
+             xmlns:sig=\"urn:cablelabs:md:xsd:signaling:3.0\">\
+             <AcquiredSignal acquisitionPointIdentity=\"encoder-1\" acquisitionSignalID=\"&lt;1&gt;\">\
+             <sig:UTCPoint utcPoint=\"2021-03-17T07:06:22Z\"/>\
+             <sig:BinaryData signalType=\"SCTE35\">/DARAAAAAAAAAP/wAAAAAHpPv/8=</sig:BinaryData>\
+             </AcquiredSignal></SignalProcessingEvent>"
+        );
+        assert!(event.acquired_signals[0].section().is_ok());
+        assert!(matches!(
+            SignalProcessingNotification::from_xml(
+                "<SignalProcessingNotification acquisitionPointIdentity=\"a\">\
+                 <ResponseSignal action=\"explode\" acquisitionPointIdentity=\"a\" acquisitionSignalID=\"b\">\
+                 <UTCPoint utcPoint=\"x\"/></ResponseSignal></SignalProcessingNotification>"
+            ),
+            Err(EsamError::Xml(XmlError::InvalidValue {
+                attribute: "action",
+                ..
+            }))
+        ));
+    }
+}
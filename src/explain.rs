@@ -0,0 +1,704 @@
+//! An annotated breakdown of the bit-fields of a section, for diagnosing interoperability
+//! problems with encoders, and for tools which teach the _SCTE-35_ syntax.
+//!
+//! [`explain()`](fn.explain.html) walks the syntax of a complete _splice_info_section_,
+//! producing a [`Field`](struct.Field.html) for each syntax element it holds, in order, giving
+//! the element's name, position, width and raw value, together with a decoding of the value
+//! where there is something more to say than the number itself.
+//!
+//! Rather than giving up at the first problem, `explain()` returns the fields which precede the
+//! problem, so that the dump shows how far a malformed section could be understood.
+//!
+//! ## Example
+//!
+//! ```
+//! # use scte35_reader::explain::explain;
+//! let data = scte35_reader::presets::cue_in(1).encode().unwrap();
+//! let explanation = explain(&data);
+//! assert!(explanation.error.is_none());
+//! let command_type = explanation
+//!     .fields
+//!     .iter()
+//!     .find(|f| f.name == "splice_command_type")
+//!     .unwrap();
+//! assert_eq!(command_type.decoded.as_deref(), Some("splice_insert"));
+//! println!("{}", explanation);
+//! ```
+
+use crate::time::PTS_TIMESCALE;
+use crate::{
+    DeviceRestrictions, EncryptionAlgorithm, NetworkIndicator, ReturnMode, SapType, Scte35Error,
+    SegmentationTypeId, SegmentationUpidType, SpliceCommandType, SpliceDescriptorErr,
+    SpliceInfoHeader,
+};
+use std::fmt;
+
+/// The value held by a `Field`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RawValue {
+    /// The value of a numeric field or flag, of up to 64 bits
+    Bits(u64),
+    /// The bytes of a field holding a byte string, such as `private_byte` or `segmentation_upid`
+    Bytes(Vec<u8>),
+}
+impl fmt::Display for RawValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawValue::Bits(value) => write!(f, "{:#x}", value),
+            RawValue::Bytes(bytes) => {
+                for b in bytes {
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A single syntax element of a section
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Field {
+    /// The name of the element, as given in the syntax tables of the spec
+    pub name: &'static str,
+    /// The offset of the first bit of the element from the start of the section (that is, from
+    /// the most significant bit of `table_id`)
+    pub bit_offset: usize,
+    /// The size of the element, in bits
+    pub width: usize,
+    pub raw: RawValue,
+    /// A description of the meaning of the value, for elements whose value is not just a
+    /// number, such as `splice_command_type`, or a time in 90kHz units
+    pub decoded: Option<String>,
+}
+
+/// The result of [`explain()`](fn.explain.html)
+#[derive(Debug)]
+pub struct Explanation {
+    /// The elements of the section, in the order in which they appear
+    pub fields: Vec<Field>,
+    /// The problem which stopped the section being explained any further, if any.  The `fields`
+    /// then end with the last element which could be read before the problem.
+    pub error: Option<Scte35Error>,
+}
+
+/// Formats the fields one per line, giving the byte and bit offset of each, followed by any
+/// error on a final line
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for field in &self.fields {
+            write!(
+                f,
+                "{:4}.{} {:<45} {:>3}  {}",
+                field.bit_offset / 8,
+                field.bit_offset % 8,
+                field.name,
+                field.width,
+                field.raw
+            )?;
+            if let Some(ref decoded) = field.decoded {
+                write!(f, "  ({})", decoded)?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(ref error) = self.error {
+            writeln!(f, "error: {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Breaks down the given complete section, from `table_id` through to `CRC_32`, into its
+/// syntax elements.
+///
+/// Unlike `SpliceInfoSection::parse()`, the values of fields are not checked (so that, for
+/// example, a `table_id` other than `0xfc` does not stop the explanation, and a failed CRC
+/// check is reported in the decoding of `CRC_32`), and the command and descriptor loop of
+/// encrypted sections are given as a single `encrypted_data` field.  Any bytes following the
+/// end of the section (as given by its `section_length`) are ignored.
+pub fn explain(data: &[u8]) -> Explanation {
+    let mut explainer = Explainer {
+        data,
+        pos: 0,
+        end: data.len() * 8,
+        fields: vec![],
+    };
+    let error = explainer.section().err();
+    Explanation {
+        fields: explainer.fields,
+        error,
+    }
+}
+
+struct Explainer<'a> {
+    data: &'a [u8],
+    /// the bit position of the next element
+    pos: usize,
+    /// the bit position at which the structure currently being read ends
+    end: usize,
+    fields: Vec<Field>,
+}
+impl Explainer<'_> {
+    fn take(&mut self, name: &'static str, width: usize) -> Result<u64, SpliceDescriptorErr> {
+        if self.pos + width > self.end {
+            return Err(SpliceDescriptorErr::NotEnoughData {
+                field_name: name,
+                expected: width.div_ceil(8),
+                actual: (self.end - self.pos) / 8,
+                bit_offset: self.pos as u64,
+            });
+        }
+        let mut value = 0;
+        for i in self.pos..self.pos + width {
+            value = value << 1 | u64::from(self.data[i / 8] >> (7 - i % 8) & 1);
+        }
+        self.pos += width;
+        Ok(value)
+    }
+
+    fn read(&mut self, name: &'static str, width: usize) -> Result<u64, SpliceDescriptorErr> {
+        let bit_offset = self.pos;
+        let value = self.take(name, width)?;
+        self.fields.push(Field {
+            name,
+            bit_offset,
+            width,
+            raw: RawValue::Bits(value),
+            decoded: None,
+        });
+        Ok(value)
+    }
+
+    fn flag(&mut self, name: &'static str) -> Result<bool, SpliceDescriptorErr> {
+        Ok(self.read(name, 1)? == 1)
+    }
+
+    fn bytes(&mut self, name: &'static str, len: usize) -> Result<&[u8], SpliceDescriptorErr> {
+        let bit_offset = self.pos;
+        let start = self.pos / 8;
+        for _ in 0..len {
+            self.take(name, 8)?;
+        }
+        let bytes = &self.data[start..start + len];
+        self.fields.push(Field {
+            name,
+            bit_offset,
+            width: len * 8,
+            raw: RawValue::Bytes(bytes.to_vec()),
+            decoded: None,
+        });
+        Ok(bytes)
+    }
+
+    /// The bytes from the current position up to the given bit position, for structures whose
+    /// length is known before their content is read
+    fn bytes_to(&mut self, name: &'static str, end: usize) -> Result<(), SpliceDescriptorErr> {
+        if end > self.pos {
+            self.bytes(name, (end - self.pos) / 8)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the decoding of the most recently read field
+    fn decode(&mut self, decoded: impl fmt::Display) {
+        if let Some(field) = self.fields.last_mut() {
+            field.decoded = Some(decoded.to_string());
+        }
+    }
+
+    fn time(&mut self, name: &'static str, width: usize) -> Result<u64, SpliceDescriptorErr> {
+        let ticks = self.read(name, width)?;
+        self.decode(format_args!("{:.6}s", ticks as f64 / PTS_TIMESCALE as f64));
+        Ok(ticks)
+    }
+
+    /// Reads the structure of the given length in bytes with the given function, limiting reads
+    /// to the structure, and then gives any bytes remaining in it as `trailing_bytes`
+    fn within<T>(
+        &mut self,
+        len: usize,
+        f: impl FnOnce(&mut Self) -> Result<T, SpliceDescriptorErr>,
+    ) -> Result<T, SpliceDescriptorErr> {
+        let outer_end = self.end;
+        let end = self.pos + len * 8;
+        self.end = end.min(outer_end);
+        let result = f(self).and_then(|value| {
+            self.bytes_to("trailing_bytes", end)?;
+            Ok(value)
+        });
+        self.end = outer_end;
+        result
+    }
+
+    fn section(&mut self) -> Result<(), Scte35Error> {
+        let too_short = |data: &[u8], e: SpliceDescriptorErr| match e {
+            SpliceDescriptorErr::NotEnoughData {
+                expected,
+                bit_offset,
+                ..
+            } => Scte35Error::SectionTooShort {
+                actual: data.len(),
+                expected: bit_offset as usize / 8 + expected,
+            },
+            _ => Scte35Error::Command(e),
+        };
+        let data = self.data;
+        let (command_len, command_type, encrypted) =
+            self.splice_info_header().map_err(|e| too_short(data, e))?;
+        let section_end = self.end;
+        let crc_pos = section_end.saturating_sub(32).max(self.pos);
+        if encrypted {
+            // includes the E_CRC_32, which is itself encrypted
+            self.bytes_to("encrypted_data", crc_pos)
+                .map_err(|e| too_short(data, e))?;
+        } else {
+            self.end = crc_pos;
+            self.command_and_descriptors(command_len, command_type)?;
+            self.bytes_to("alignment_stuffing", crc_pos)
+                .map_err(|e| too_short(data, e))?;
+            self.end = section_end;
+        }
+        self.read("CRC_32", 32).map_err(|e| too_short(data, e))?;
+        let crc = mpeg2ts_reader::mpegts_crc::sum32(&data[..section_end / 8]);
+        if crc == 0 {
+            self.decode("valid");
+        } else {
+            self.decode(format_args!("invalid, residue {:#010x}", crc));
+        }
+        Ok(())
+    }
+
+    /// Reads the fields up to and including `splice_command_type`, returning the values of
+    /// `splice_command_length`, `splice_command_type` and `encrypted_packet`
+    fn splice_info_header(&mut self) -> Result<(usize, u8, bool), SpliceDescriptorErr> {
+        self.read("table_id", 8)?;
+        self.flag("section_syntax_indicator")?;
+        self.flag("private_indicator")?;
+        let sap_type = self.read("sap_type", 2)?;
+        self.decode(format_args!("{:?}", SapType::from_id(sap_type as u8)));
+        let section_length = self.read("section_length", 12)? as usize;
+        self.end = self.end.min((3 + section_length) * 8);
+        self.read("protocol_version", 8)?;
+        let encrypted = self.flag("encrypted_packet")?;
+        let encryption_algorithm = self.read("encryption_algorithm", 6)?;
+        self.decode(EncryptionAlgorithm::from_id(encryption_algorithm as u8));
+        self.time("pts_adjustment", 33)?;
+        self.read("cw_index", 8)?;
+        self.read("tier", 12)?;
+        let command_len = self.read("splice_command_length", 12)? as usize;
+        let command_type = self.read("splice_command_type", 8)? as u8;
+        self.decode(SpliceCommandType::from_id(command_type));
+        Ok((command_len, command_type, encrypted))
+    }
+
+    fn command_and_descriptors(
+        &mut self,
+        command_len: usize,
+        command_type: u8,
+    ) -> Result<(), Scte35Error> {
+        let command_type = SpliceCommandType::from_id(command_type);
+        if command_len == SpliceInfoHeader::LEGACY_SPLICE_COMMAND_LENGTH {
+            match command_type {
+                SpliceCommandType::PrivateCommand | SpliceCommandType::Reserved(_) => {
+                    return Err(Scte35Error::SpliceCommandLength {
+                        splice_command_length: command_len,
+                        available: (self.end - self.pos) / 8,
+                    })
+                }
+                _ => self.command(command_type).map_err(Scte35Error::Command)?,
+            }
+        } else {
+            if self.pos + command_len * 8 > self.end {
+                return Err(Scte35Error::SpliceCommandLength {
+                    splice_command_length: command_len,
+                    available: (self.end - self.pos) / 8,
+                });
+            }
+            self.within(command_len, |ex| ex.command(command_type))
+                .map_err(Scte35Error::Command)?;
+        }
+        let descriptor_loop_length =
+            self.read("descriptor_loop_length", 16)
+                .map_err(|_| Scte35Error::MissingDescriptorLoopLength)? as usize;
+        if self.pos + descriptor_loop_length * 8 > self.end {
+            return Err(Scte35Error::DescriptorLoopLength {
+                descriptor_loop_length,
+                available: (self.end - self.pos) / 8 + 2,
+            });
+        }
+        let loop_start = self.pos;
+        let loop_end = loop_start + descriptor_loop_length * 8;
+        while self.pos < loop_end {
+            let offset = (self.pos - loop_start) / 8;
+            let tag = self.data[self.pos / 8];
+            self.within((loop_end - self.pos) / 8, |ex| ex.descriptor())
+                .map_err(|e| Scte35Error::Descriptor(e.in_descriptor(tag, offset)))?;
+        }
+        Ok(())
+    }
+
+    fn command(&mut self, command_type: SpliceCommandType) -> Result<(), SpliceDescriptorErr> {
+        match command_type {
+            SpliceCommandType::SpliceNull | SpliceCommandType::BandwidthReservation => Ok(()),
+            SpliceCommandType::SpliceSchedule => self.splice_schedule(),
+            SpliceCommandType::SpliceInsert => self.splice_insert(),
+            SpliceCommandType::TimeSignal => self.splice_time(),
+            SpliceCommandType::PrivateCommand => {
+                self.identifier()?;
+                self.bytes_to("private_byte", self.end)
+            }
+            SpliceCommandType::Reserved(_) => self.bytes_to("reserved", self.end),
+        }
+    }
+
+    fn identifier(&mut self) -> Result<[u8; 4], SpliceDescriptorErr> {
+        let id = (self.read("identifier", 32)? as u32).to_be_bytes();
+        if id.iter().all(|b| b.is_ascii_graphic()) {
+            self.decode(String::from_utf8_lossy(&id));
+        }
+        Ok(id)
+    }
+
+    fn splice_schedule(&mut self) -> Result<(), SpliceDescriptorErr> {
+        let splice_count = self.read("splice_count", 8)?;
+        for _ in 0..splice_count {
+            self.read("splice_event_id", 32)?;
+            let cancel = self.flag("splice_event_cancel_indicator")?;
+            self.read("reserved", 7)?;
+            if cancel {
+                continue;
+            }
+            self.network_indicator()?;
+            let program_splice_flag = self.flag("program_splice_flag")?;
+            let duration_flag = self.flag("duration_flag")?;
+            self.read("reserved", 5)?;
+            if program_splice_flag {
+                self.read("utc_splice_time", 32)?;
+            } else {
+                let component_count = self.read("component_count", 8)?;
+                for _ in 0..component_count {
+                    self.read("component_tag", 8)?;
+                    self.read("utc_splice_time", 32)?;
+                }
+            }
+            if duration_flag {
+                self.break_duration()?;
+            }
+            self.read("unique_program_id", 16)?;
+            self.read("avail_num", 8)?;
+            self.read("avails_expected", 8)?;
+        }
+        Ok(())
+    }
+
+    fn splice_insert(&mut self) -> Result<(), SpliceDescriptorErr> {
+        self.read("splice_event_id", 32)?;
+        let cancel = self.flag("splice_event_cancel_indicator")?;
+        self.read("reserved", 7)?;
+        if cancel {
+            return Ok(());
+        }
+        self.network_indicator()?;
+        let program_splice_flag = self.flag("program_splice_flag")?;
+        let duration_flag = self.flag("duration_flag")?;
+        let splice_immediate_flag = self.flag("splice_immediate_flag")?;
+        self.read("reserved", 4)?;
+        if program_splice_flag {
+            if !splice_immediate_flag {
+                self.splice_time()?;
+            }
+        } else {
+            let component_count = self.read("component_count", 8)?;
+            for _ in 0..component_count {
+                self.read("component_tag", 8)?;
+                if !splice_immediate_flag {
+                    self.splice_time()?;
+                }
+            }
+        }
+        if duration_flag {
+            self.break_duration()?;
+        }
+        self.read("unique_program_id", 16)?;
+        self.read("avail_num", 8)?;
+        self.read("avails_expected", 8)?;
+        Ok(())
+    }
+
+    fn network_indicator(&mut self) -> Result<(), SpliceDescriptorErr> {
+        let indicator = self.read("out_of_network_indicator", 1)?;
+        self.decode(format_args!(
+            "{:?}",
+            NetworkIndicator::try_from(indicator as u8)?
+        ));
+        Ok(())
+    }
+
+    fn splice_time(&mut self) -> Result<(), SpliceDescriptorErr> {
+        if self.flag("time_specified_flag")? {
+            self.read("reserved", 6)?;
+            self.time("pts_time", 33)?;
+        } else {
+            self.read("reserved", 7)?;
+        }
+        Ok(())
+    }
+
+    fn break_duration(&mut self) -> Result<(), SpliceDescriptorErr> {
+        let auto_return = self.read("auto_return", 1)?;
+        self.decode(format_args!(
+            "{:?}",
+            ReturnMode::try_from(auto_return as u8)?
+        ));
+        self.read("reserved", 6)?;
+        self.time("duration", 33)?;
+        Ok(())
+    }
+
+    fn descriptor(&mut self) -> Result<(), SpliceDescriptorErr> {
+        let tag = self.read("splice_descriptor_tag", 8)?;
+        let tag_field = self.fields.len() - 1;
+        let descriptor_length = self.read("descriptor_length", 8)? as usize;
+        if self.pos + descriptor_length * 8 > self.end {
+            return Err(SpliceDescriptorErr::NotEnoughData {
+                field_name: "descriptor_length",
+                expected: descriptor_length,
+                actual: (self.end - self.pos) / 8,
+                bit_offset: self.pos as u64,
+            });
+        }
+        self.within(descriptor_length, |ex| {
+            if ex.identifier()? != *b"CUEI" {
+                return ex.bytes_to("private_byte", ex.end);
+            }
+            let name = match tag {
+                0x00 => "avail_descriptor",
+                0x01 => "DTMF_descriptor",
+                0x02 => "segmentation_descriptor",
+                0x03 => "time_descriptor",
+                0x04 => "audio_descriptor",
+                _ => return ex.bytes_to("private_byte", ex.end),
+            };
+            ex.fields[tag_field].decoded = Some(name.to_string());
+            match tag {
+                0x00 => ex.read("provider_avail_id", 32).map(|_| ()),
+                0x01 => ex.dtmf_descriptor(),
+                0x02 => ex.segmentation_descriptor(),
+                0x03 => ex.time_descriptor(),
+                _ => ex.audio_descriptor(),
+            }
+        })
+    }
+
+    fn dtmf_descriptor(&mut self) -> Result<(), SpliceDescriptorErr> {
+        self.read("preroll", 8)?;
+        let dtmf_count = self.read("dtmf_count", 3)?;
+        self.read("reserved", 5)?;
+        for _ in 0..dtmf_count {
+            let c = self.read("DTMF_char", 8)? as u8;
+            if c.is_ascii_graphic() {
+                self.decode(c as char);
+            }
+        }
+        Ok(())
+    }
+
+    fn segmentation_descriptor(&mut self) -> Result<(), SpliceDescriptorErr> {
+        self.read("segmentation_event_id", 32)?;
+        let cancel = self.flag("segmentation_event_cancel_indicator")?;
+        self.read("reserved", 7)?;
+        if cancel {
+            return Ok(());
+        }
+        let program_segmentation_flag = self.flag("program_segmentation_flag")?;
+        let segmentation_duration_flag = self.flag("segmentation_duration_flag")?;
+        if self.flag("delivery_not_restricted_flag")? {
+            self.read("reserved", 5)?;
+        } else {
+            self.flag("web_delivery_allowed_flag")?;
+            self.flag("no_regional_blackout_flag")?;
+            self.flag("archive_allowed_flag")?;
+            let device_restrictions = self.read("device_restrictions", 2)?;
+            self.decode(format_args!(
+                "{:?}",
+                DeviceRestrictions::try_from(device_restrictions as u8)?
+            ));
+        }
+        if !program_segmentation_flag {
+            let component_count = self.read("component_count", 8)?;
+            for _ in 0..component_count {
+                self.read("component_tag", 8)?;
+                self.read("reserved", 7)?;
+                self.time("pts_offset", 33)?;
+            }
+        }
+        if segmentation_duration_flag {
+            self.time("segmentation_duration", 40)?;
+        }
+        let upid_type = self.read("segmentation_upid_type", 8)?;
+        self.decode(SegmentationUpidType::from_type(upid_type as u8));
+        let upid_length = self.read("segmentation_upid_length", 8)?;
+        let upid = self.bytes("segmentation_upid", upid_length as usize)?;
+        if !upid.is_empty() && upid.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+            let text = String::from_utf8_lossy(upid).into_owned();
+            self.decode(text);
+        }
+        let type_id = self.read("segmentation_type_id", 8)?;
+        self.decode(SegmentationTypeId::from_id(type_id as u8));
+        self.read("segment_num", 8)?;
+        self.read("segments_expected", 8)?;
+        // the optional sub_segment_num and sub_segments_expected are present only if the
+        // descriptor_length leaves room for them
+        if self.pos < self.end {
+            self.read("sub_segment_num", 8)?;
+            self.read("sub_segments_expected", 8)?;
+        }
+        Ok(())
+    }
+
+    fn time_descriptor(&mut self) -> Result<(), SpliceDescriptorErr> {
+        self.read("TAI_seconds", 48)?;
+        self.read("TAI_ns", 32)?;
+        self.read("UTC_offset", 16)?;
+        Ok(())
+    }
+
+    fn audio_descriptor(&mut self) -> Result<(), SpliceDescriptorErr> {
+        let audio_count = self.read("audio_count", 4)?;
+        self.read("reserved", 4)?;
+        for _ in 0..audio_count {
+            self.read("component_tag", 8)?;
+            let iso_code = (self.read("ISO_code", 24)? as u32).to_be_bytes();
+            if iso_code[1..].iter().all(|b| b.is_ascii_alphabetic()) {
+                self.decode(String::from_utf8_lossy(&iso_code[1..]));
+            }
+            self.read("Bit_Stream_Mode", 3)?;
+            self.read("Num_Channels", 4)?;
+            self.flag("Full_Srvc_Audio")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use hex_literal::*;
+    use matches::*;
+
+    fn field<'a>(explanation: &'a Explanation, name: &str) -> &'a Field {
+        explanation
+            .fields
+            .iter()
+            .find(|f| f.name == name)
+            .unwrap_or_else(|| panic!("no {} field", name))
+    }
+
+    #[test]
+    fn splice_insert() {
+        let explanation = explain(&SPLICE_INSERT);
+        assert_matches!(explanation.error, None);
+        // the fields cover the whole section, without gaps
+        let mut pos = 0;
+        for f in &explanation.fields {
+            assert_eq!(f.bit_offset, pos, "{}", f.name);
+            pos += f.width;
+        }
+        assert_eq!(pos, SPLICE_INSERT.len() * 8);
+
+        assert_eq!(
+            field(&explanation, "splice_event_id"),
+            &Field {
+                name: "splice_event_id",
+                bit_offset: 14 * 8,
+                width: 32,
+                raw: RawValue::Bits(1),
+                decoded: None,
+            }
+        );
+        assert_eq!(
+            field(&explanation, "duration").decoded.as_deref(),
+            Some("212.500000s")
+        );
+        assert_eq!(
+            field(&explanation, "out_of_network_indicator")
+                .decoded
+                .as_deref(),
+            Some("Out")
+        );
+        assert_eq!(
+            field(&explanation, "CRC_32").decoded.as_deref(),
+            Some("valid")
+        );
+        let dump = explanation.to_string();
+        assert!(dump.contains("  14.0 splice_event_id"), "{}", dump);
+    }
+
+    #[test]
+    fn time_signal_with_segmentation_descriptor() {
+        let explanation = explain(&TIME_SIGNAL);
+        assert_matches!(explanation.error, None);
+        assert_eq!(
+            field(&explanation, "splice_descriptor_tag")
+                .decoded
+                .as_deref(),
+            Some("segmentation_descriptor")
+        );
+        assert_eq!(
+            field(&explanation, "identifier").decoded.as_deref(),
+            Some("CUEI")
+        );
+        assert_eq!(
+            field(&explanation, "segmentation_type_id").raw,
+            RawValue::Bits(0x10)
+        );
+        assert_matches!(
+            field(&explanation, "segmentation_upid").raw,
+            RawValue::Bytes(ref bytes) if bytes.is_empty()
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        // the descriptor claims to be one byte longer than the loop holding it
+        let mut data = hex!(
+            "fc302700000000000000fff00506ff592d03c00011021043554549000000017fbf000010010112ce0e6b"
+        );
+        let explanation = explain(&data);
+        assert_matches!(
+            explanation.error,
+            Some(Scte35Error::Descriptor(SpliceDescriptorErr::Descriptor {
+                splice_descriptor_tag: 0x02,
+                offset: 0,
+                ..
+            }))
+        );
+        // the fields before the problem are still given
+        assert_eq!(explanation.fields.last().unwrap().name, "descriptor_length");
+        assert!(explanation.to_string().contains("error: "));
+
+        // a corrupted CRC does not stop the explanation
+        data[5] ^= 1;
+        data[22] = 0x0f;
+        let explanation = explain(&data);
+        assert_matches!(explanation.error, None);
+        assert_matches!(
+            field(&explanation, "CRC_32").decoded,
+            Some(ref d) if d.starts_with("invalid")
+        );
+
+        // truncated within the header
+        let explanation = explain(&data[..8]);
+        assert_matches!(
+            explanation.error,
+            Some(Scte35Error::SectionTooShort {
+                actual: 8,
+                expected: 9
+            })
+        );
+        assert_eq!(
+            explanation.fields.last().unwrap().name,
+            "encryption_algorithm"
+        );
+    }
+}
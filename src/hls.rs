@@ -0,0 +1,569 @@
+//! Generation of HLS `#EXT-X-DATERANGE` tags carrying _SCTE-35_ cues, as described in section
+//! 4.4.5.1.1 of [RFC 8216](https://tools.ietf.org/html/rfc8216), for use by packagers.
+//!
+//! The legacy `#EXT-X-CUE-OUT` / `#EXT-X-CUE-IN` tags still required by many CDNs are also
+//! supported, via [`cue_tag()`](fn.cue_tag.html) and [`CueTag`](enum.CueTag.html), and sections
+//! carried by the `#EXT-OATCLS-SCTE35` and `#EXT-X-CUE` dialects can be read with
+//! [`parse_manifest_cue()`](fn.parse_manifest_cue.html).
+//!
+//! Tags sharing an `ID` must agree on the values of their common attributes (RFC 8216 section
+//! 4.3.2.7), but each tag is produced independently of any other, so the `SCTE35-IN` tag can not
+//! repeat the `START-DATE` of the `SCTE35-OUT` tag that began the same splice event.  It is
+//! instead given an `ID` of its own, with the suffix `-in` (and similarly, the tag for a
+//! cancellation has the suffix `-cancel`).
+//!
+//! The splice times within a section are PTS values, whereas `START-DATE` is a wall-clock time,
+//! so the caller supplies the mapping between the two as a [`WallClock`](trait.WallClock.html)
+//! (either a closure, or a [`PtsAnchor`](struct.PtsAnchor.html) relating one PTS value to the
+//! `EXT-X-PROGRAM-DATE-TIME` of the media).
+//!
+//! ## Example
+//!
+//! ```
+//! # use hex_literal::*;
+//! # use scte35_reader::SpliceInfoSection;
+//! # use scte35_reader::hls::{date_range, PtsAnchor};
+//! # use std::time::{Duration, UNIX_EPOCH};
+//! let section = SpliceInfoSection::parse(&hex!(
+//!     "fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49"
+//! ))
+//! .unwrap();
+//! let anchor = PtsAnchor {
+//!     pts: 0x2d142b00,
+//!     wall_clock: UNIX_EPOCH + Duration::from_secs(1_600_000_000),
+//! };
+//! let tag = date_range(&section, 0x2d142b00, &anchor).unwrap();
+//! assert_eq!(
+//!     tag.to_string(),
+//!     "#EXT-X-DATERANGE:ID=\"splice-1\",START-DATE=\"2020-09-13T12:26:40Z\",\
+//!      PLANNED-DURATION=212.500,SCTE35-OUT=0xFC302500000000000000FFF01405000000017FEFFE2D142B00\
+//!      FE0123D3080001010100007F157A49"
+//! );
+//! ```
+
+use crate::encode::EncodeError;
+use crate::time::{self, PTS_MODULUS, PTS_TIMESCALE};
+use crate::{
+    NetworkIndicator, SegmentationDescriptor, SegmentationTypeId, SpliceCommand, SpliceDescriptor,
+    SpliceInfoSection, SpliceInsert,
+};
+use hex_slice::AsHex;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// Maps PTS values (with any `pts_adjustment` already applied) onto wall-clock time.
+///
+/// Implemented for any `Fn(u64) -> SystemTime` closure.
+pub trait WallClock {
+    fn wall_clock(&self, pts: u64) -> SystemTime;
+}
+impl<F: Fn(u64) -> SystemTime> WallClock for F {
+    fn wall_clock(&self, pts: u64) -> SystemTime {
+        self(pts)
+    }
+}
+
+/// A `WallClock` derived from a single known pairing of PTS value and wall-clock time (for
+/// example, the first PTS of a segment and its `EXT-X-PROGRAM-DATE-TIME`).
+///
+/// Other PTS values are mapped by their distance from the anchor, allowing for the wrap of the
+/// 33-bit PTS counter as for `time::cmp_pts()`, so the anchor should be kept within a few hours
+/// of the cues being mapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtsAnchor {
+    pub pts: u64,
+    pub wall_clock: SystemTime,
+}
+impl WallClock for PtsAnchor {
+    fn wall_clock(&self, pts: u64) -> SystemTime {
+        let diff = pts.wrapping_sub(self.pts) % PTS_MODULUS;
+        if diff < PTS_MODULUS / 2 {
+            self.wall_clock + ticks_to_duration(diff)
+        } else {
+            self.wall_clock - ticks_to_duration(PTS_MODULUS - diff)
+        }
+    }
+}
+
+fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::from_nanos(time::rescale(
+        ticks,
+        PTS_TIMESCALE,
+        1_000_000_000,
+        time::Rounding::Nearest,
+    ))
+}
+
+/// Which of the `SCTE35-*` attributes of a `DateRange` carries the section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scte35Attribute {
+    /// `SCTE35-OUT`, for a splice out of the network (the start of a break or placement
+    /// opportunity)
+    Out,
+    /// `SCTE35-IN`, for the corresponding return to the network
+    In,
+    /// `SCTE35-CMD`, for any other command
+    Cmd,
+}
+impl Scte35Attribute {
+    /// The attribute name, e.g. `SCTE35-OUT`
+    pub fn name(self) -> &'static str {
+        match self {
+            Scte35Attribute::Out => "SCTE35-OUT",
+            Scte35Attribute::In => "SCTE35-IN",
+            Scte35Attribute::Cmd => "SCTE35-CMD",
+        }
+    }
+}
+
+/// The values of one `#EXT-X-DATERANGE` tag, produced by [`date_range()`](fn.date_range.html).
+///
+/// The `Display` implementation gives the complete tag line (without a trailing newline).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateRange {
+    /// The `ID` attribute, derived from the event id of the splice event, e.g. `splice-1` for
+    /// the `SCTE35-OUT` tag and `splice-1-in` for the corresponding `SCTE35-IN` tag
+    pub id: String,
+    pub start_date: SystemTime,
+    /// The `PLANNED-DURATION`, in 90kHz ticks
+    pub planned_duration: Option<u64>,
+    pub attribute: Scte35Attribute,
+    /// The complete `splice_info_section()`, given as the value of `attribute`
+    pub section: Vec<u8>,
+}
+impl fmt::Display for DateRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#EXT-X-DATERANGE:ID=\"{}\",START-DATE=\"{}\"",
+            self.id,
+            time::format_rfc3339(self.start_date)
+        )?;
+        if let Some(duration) = self.planned_duration {
+            write!(
+                f,
+                ",PLANNED-DURATION={:.3}",
+                duration as f64 / PTS_TIMESCALE as f64
+            )?;
+        }
+        write!(
+            f,
+            ",{}=0x{:02X}",
+            self.attribute.name(),
+            self.section.plain_hex(false)
+        )
+    }
+}
+
+/// Produces the `#EXT-X-DATERANGE` tag for the given section.
+///
+/// `section_pts` is the PTS at which the section itself appears in the stream, which is used as
+/// the start of immediate splices, and of commands that give no splice time (such as
+/// `splice_null()`).
+///
+/// - A `splice_insert()` gives `SCTE35-OUT` or `SCTE35-IN` according to its
+///   `out_of_network_indicator`, with an `ID` derived from its `splice_event_id`, and any
+///   `break_duration` as the `PLANNED-DURATION`.
+/// - Otherwise, a segmentation descriptor signalling the start of a break, advertisement,
+///   placement opportunity or ad block gives `SCTE35-OUT`, and one signalling the end of those
+///   gives `SCTE35-IN`, with an `ID` derived from its `segmentation_event_id`, and any
+///   `segmentation_duration` as the `PLANNED-DURATION`.
+/// - Anything else gives `SCTE35-CMD`, with an `ID` derived from the PTS.
+///
+/// Fails if the section can not be encoded (for instance because it is encrypted).
+pub fn date_range(
+    section: &SpliceInfoSection,
+    section_pts: u64,
+    clock: &impl WallClock,
+) -> Result<DateRange, EncodeError> {
+    let pts = section.splice_pts().unwrap_or(section_pts);
+    let (id, attribute, planned_duration) =
+        signal(section).unwrap_or_else(|| (format!("pts-{}", pts), Scte35Attribute::Cmd, None));
+    Ok(DateRange {
+        id,
+        start_date: clock.wall_clock(pts),
+        planned_duration,
+        attribute,
+        section: section.encode()?,
+    })
+}
+
+/// The `ID`, attribute and duration signalled by a `splice_insert()`, or otherwise by the
+/// section's segmentation descriptors
+fn signal(section: &SpliceInfoSection) -> Option<(String, Scte35Attribute, Option<u64>)> {
+    match &section.splice_command {
+        SpliceCommand::SpliceInsert {
+            splice_event_id,
+            splice_detail,
+            ..
+        } => {
+            let id = format!("splice-{}", splice_event_id);
+            Some(match splice_detail {
+                SpliceInsert::Insert {
+                    network_indicator: NetworkIndicator::Out,
+                    duration,
+                    ..
+                } => (
+                    id,
+                    Scte35Attribute::Out,
+                    duration.as_ref().map(|d| d.duration),
+                ),
+                SpliceInsert::Insert { .. } => (format!("{}-in", id), Scte35Attribute::In, None),
+                SpliceInsert::Cancel => (format!("{}-cancel", id), Scte35Attribute::Cmd, None),
+            })
+        }
+        _ => segmentation(section),
+    }
+}
+
+/// The `ID`, attribute and duration given by the first segmentation descriptor marking the start
+/// or end of an avail, or failing that, by the first segmentation descriptor of any kind
+fn segmentation(section: &SpliceInfoSection) -> Option<(String, Scte35Attribute, Option<u64>)> {
+    let descriptors: Vec<_> = section
+        .splice_descriptors
+        .iter()
+        .filter_map(|d| match d {
+            SpliceDescriptor::SegmentationDescriptor {
+                segmentation_event_id,
+                descriptor_detail,
+            } => {
+                let id = format!("segmentation-{}", segmentation_event_id);
+                Some(match descriptor_detail {
+                    SegmentationDescriptor::Insert {
+                        segmentation_type_id,
+                        segmentation_duration,
+                        ..
+                    } => match avail_boundary(segmentation_type_id) {
+                        Some(Scte35Attribute::Out) => {
+                            (id, Scte35Attribute::Out, *segmentation_duration)
+                        }
+                        Some(attribute) => (format!("{}-in", id), attribute, None),
+                        None => (id, Scte35Attribute::Cmd, None),
+                    },
+                    SegmentationDescriptor::Cancel => {
+                        (format!("{}-cancel", id), Scte35Attribute::Cmd, None)
+                    }
+                })
+            }
+            _ => None,
+        })
+        .collect();
+    let first = descriptors
+        .iter()
+        .position(|(_, attribute, _)| *attribute != Scte35Attribute::Cmd)
+        .unwrap_or(0);
+    descriptors.into_iter().nth(first)
+}
+
+/// Classifies the segmentation types which delimit the avails that packagers act upon
+fn avail_boundary(segmentation_type_id: &SegmentationTypeId) -> Option<Scte35Attribute> {
+    if segmentation_type_id.is_out() {
+        Some(Scte35Attribute::Out)
+    } else if segmentation_type_id.is_in() {
+        Some(Scte35Attribute::In)
+    } else {
+        None
+    }
+}
+
+/// The legacy `#EXT-X-CUE-OUT` / `#EXT-X-CUE-IN` style of marking ad breaks, which carries only
+/// the break boundaries and duration rather than the section itself.
+///
+/// The `Display` implementation gives the tag line (e.g. `#EXT-X-CUE-OUT:30.0`), and `FromStr`
+/// reads it back, accepting both the plain `#EXT-X-CUE-OUT:30.0` form and the
+/// `#EXT-X-CUE-OUT:DURATION=30.0` form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CueTag {
+    /// `#EXT-X-CUE-OUT`, with the break duration in 90kHz ticks, if known
+    Out { duration: Option<u64> },
+    /// `#EXT-X-CUE-IN`
+    In,
+}
+impl fmt::Display for CueTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CueTag::Out { duration: None } => f.write_str("#EXT-X-CUE-OUT"),
+            CueTag::Out {
+                duration: Some(duration),
+            } => {
+                let secs = format!("{:.3}", *duration as f64 / PTS_TIMESCALE as f64);
+                let secs = secs.trim_end_matches('0');
+                write!(f, "#EXT-X-CUE-OUT:{}", secs)?;
+                if secs.ends_with('.') {
+                    f.write_str("0")?;
+                }
+                Ok(())
+            }
+            CueTag::In => f.write_str("#EXT-X-CUE-IN"),
+        }
+    }
+}
+impl std::str::FromStr for CueTag {
+    type Err = ParseCueTagError;
+
+    fn from_str(line: &str) -> Result<CueTag, ParseCueTagError> {
+        let line = line.trim();
+        if line == "#EXT-X-CUE-IN" {
+            return Ok(CueTag::In);
+        }
+        let value = match line.strip_prefix("#EXT-X-CUE-OUT") {
+            Some("") => return Ok(CueTag::Out { duration: None }),
+            Some(rest) => rest.strip_prefix(':').ok_or(ParseCueTagError::NotCueTag)?,
+            None => return Err(ParseCueTagError::NotCueTag),
+        };
+        let secs = value.strip_prefix("DURATION=").unwrap_or(value);
+        match secs.parse::<f64>() {
+            Ok(secs) if secs.is_finite() && secs >= 0.0 => Ok(CueTag::Out {
+                duration: Some((secs * PTS_TIMESCALE as f64).round() as u64),
+            }),
+            _ => Err(ParseCueTagError::InvalidDuration(value.to_string())),
+        }
+    }
+}
+
+/// Problems which prevent a line being read as a `CueTag`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCueTagError {
+    /// The line is not an `#EXT-X-CUE-OUT` or `#EXT-X-CUE-IN` tag (other tags, including
+    /// `#EXT-X-CUE-OUT-CONT`, are reported this way)
+    NotCueTag,
+    /// The attributes of an `#EXT-X-CUE-OUT` tag did not give a duration in seconds
+    InvalidDuration(String),
+}
+impl fmt::Display for ParseCueTagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCueTagError::NotCueTag => {
+                f.write_str("not an #EXT-X-CUE-OUT or #EXT-X-CUE-IN tag")
+            }
+            ParseCueTagError::InvalidDuration(value) => {
+                write!(f, "invalid #EXT-X-CUE-OUT duration {:?}", value)
+            }
+        }
+    }
+}
+impl std::error::Error for ParseCueTagError {}
+
+/// Produces the legacy `CueTag` for the given section, classified as for `date_range()`, or
+/// `None` if the section marks neither the start nor the end of a break.
+pub fn cue_tag(section: &SpliceInfoSection) -> Option<CueTag> {
+    match signal(section)? {
+        (_, Scte35Attribute::Out, duration) => Some(CueTag::Out { duration }),
+        (_, Scte35Attribute::In, _) => Some(CueTag::In),
+        (_, Scte35Attribute::Cmd, _) => None,
+    }
+}
+
+/// Reads the section carried by a manifest tag of one of the base64 dialects,
+///
+///  - the Elemental-style `#EXT-OATCLS-SCTE35:<base64>`
+///  - `#EXT-X-CUE:<attribute-list>`, taking the section from the `CUE` attribute
+///
+/// Available when this crate's `base64` feature is enabled.
+#[cfg(feature = "base64")]
+pub fn parse_manifest_cue(line: &str) -> Result<SpliceInfoSection, ManifestCueError> {
+    let line = line.trim();
+    let payload = if let Some(payload) = line.strip_prefix("#EXT-OATCLS-SCTE35:") {
+        payload
+    } else if let Some(list) = line.strip_prefix("#EXT-X-CUE:") {
+        attributes(list)
+            .find(|(name, _)| *name == "CUE")
+            .map(|(_, value)| value)
+            .ok_or(ManifestCueError::MissingCueAttribute)?
+    } else {
+        return Err(ManifestCueError::NotCueTag);
+    };
+    SpliceInfoSection::from_base64(payload).map_err(ManifestCueError::Cue)
+}
+
+/// Problems which prevent `parse_manifest_cue()` producing a section
+#[cfg(feature = "base64")]
+#[derive(Debug)]
+pub enum ManifestCueError {
+    /// The line is not one of the supported tags
+    NotCueTag,
+    /// An `#EXT-X-CUE` tag had no `CUE` attribute
+    MissingCueAttribute,
+    /// The tag's payload was not a valid base64 encoded section
+    Cue(crate::FromBase64Error),
+}
+#[cfg(feature = "base64")]
+impl fmt::Display for ManifestCueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestCueError::NotCueTag => f.write_str("not a supported cue tag"),
+            ManifestCueError::MissingCueAttribute => f.write_str("#EXT-X-CUE tag lacks CUE"),
+            ManifestCueError::Cue(_) => f.write_str("invalid cue payload"),
+        }
+    }
+}
+#[cfg(feature = "base64")]
+impl std::error::Error for ManifestCueError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ManifestCueError::Cue(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Splits an HLS attribute-list into `(name, value)` pairs, removing the quotes from
+/// quoted-string values (which may themselves contain commas)
+#[cfg(feature = "base64")]
+fn attributes(mut list: &str) -> impl Iterator<Item = (&str, &str)> {
+    std::iter::from_fn(move || {
+        let (name, rest) = list.split_once('=')?;
+        let (value, rest) = match rest.strip_prefix('"') {
+            Some(quoted) => {
+                let (value, rest) = quoted.split_once('"').unwrap_or((quoted, ""));
+                (value, rest.split_once(',').map_or("", |(_, rest)| rest))
+            }
+            None => rest.split_once(',').unwrap_or((rest, "")),
+        };
+        list = rest;
+        Some((name.trim(), value))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn anchor_wrap() {
+        let wall_clock = UNIX_EPOCH + Duration::from_secs(1_000);
+        let anchor = PtsAnchor {
+            pts: PTS_MODULUS - 90_000,
+            wall_clock,
+        };
+        assert_eq!(
+            anchor.wall_clock(45_000),
+            wall_clock + Duration::from_millis(1_500)
+        );
+        assert_eq!(
+            anchor.wall_clock(PTS_MODULUS - 180_000),
+            wall_clock - Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn time_signal() {
+        // a time_signal() with a 'Program Start' segmentation_descriptor()
+        let section = SpliceInfoSection::parse(&TIME_SIGNAL).unwrap();
+        let tag = date_range(&section, 0, &|pts| {
+            UNIX_EPOCH + Duration::from_secs(pts / PTS_TIMESCALE)
+        })
+        .unwrap();
+        assert_eq!(tag.id, "segmentation-1");
+        assert_eq!(tag.attribute, Scte35Attribute::Cmd);
+        assert_eq!(tag.start_date, UNIX_EPOCH + Duration::from_secs(64345));
+        assert_eq!(tag.planned_duration, None);
+        assert!(tag.to_string().ends_with(",SCTE35-CMD=0xFC302700000000000000FFF00506FF592D03C00011020F43554549000000017FBF000010010112CE0E6B"));
+    }
+
+    #[test]
+    fn splice_in() {
+        let mut section = SpliceInfoSection::parse(&SPLICE_INSERT).unwrap();
+        let clock = |pts| UNIX_EPOCH + Duration::from_secs(pts / PTS_TIMESCALE);
+        let out = date_range(&section, 0, &clock).unwrap();
+        assert_eq!(out.id, "splice-1");
+        if let SpliceCommand::SpliceInsert {
+            splice_detail:
+                SpliceInsert::Insert {
+                    network_indicator,
+                    duration,
+                    ..
+                },
+            ..
+        } = &mut section.splice_command
+        {
+            *network_indicator = NetworkIndicator::In;
+            *duration = None;
+        }
+        let tag = date_range(&section, 0, &clock).unwrap();
+        assert_eq!(tag.attribute, Scte35Attribute::In);
+        assert_eq!(tag.id, "splice-1-in");
+    }
+
+    #[test]
+    fn splice_null() {
+        let section = SpliceInfoSection::parse(&SPLICE_NULL).unwrap();
+        let tag = date_range(&section, 90_000, &|pts| {
+            UNIX_EPOCH + Duration::from_secs(pts / PTS_TIMESCALE)
+        })
+        .unwrap();
+        assert_eq!(tag.id, "pts-90000");
+        assert_eq!(tag.start_date, UNIX_EPOCH + Duration::from_secs(1));
+        assert_eq!(tag.attribute, Scte35Attribute::Cmd);
+    }
+
+    #[test]
+    fn cue_tags() {
+        let section = SpliceInfoSection::parse(&SPLICE_INSERT).unwrap();
+        let tag = cue_tag(&section).unwrap();
+        assert_eq!(
+            tag,
+            CueTag::Out {
+                duration: Some(19_125_000)
+            }
+        );
+        assert_eq!(tag.to_string(), "#EXT-X-CUE-OUT:212.5");
+        assert_eq!("#EXT-X-CUE-OUT:212.5".parse(), Ok(tag));
+        assert_eq!(
+            CueTag::Out {
+                duration: Some(30 * PTS_TIMESCALE)
+            }
+            .to_string(),
+            "#EXT-X-CUE-OUT:30.0"
+        );
+        assert_eq!(
+            "#EXT-X-CUE-OUT:DURATION=30".parse(),
+            Ok(CueTag::Out {
+                duration: Some(30 * PTS_TIMESCALE)
+            })
+        );
+        assert_eq!("#EXT-X-CUE-OUT".parse(), Ok(CueTag::Out { duration: None }));
+        assert_eq!("#EXT-X-CUE-IN\r\n".parse(), Ok(CueTag::In));
+        assert_eq!(
+            "#EXT-X-CUE-OUT-CONT:ElapsedTime=5".parse::<CueTag>(),
+            Err(ParseCueTagError::NotCueTag)
+        );
+        assert_eq!(
+            "#EXT-X-CUE-OUT:soon".parse::<CueTag>(),
+            Err(ParseCueTagError::InvalidDuration("soon".to_string()))
+        );
+        // a 'Program Start' segmentation_descriptor() is not an ad break boundary
+        let section = SpliceInfoSection::parse(&TIME_SIGNAL).unwrap();
+        assert_eq!(cue_tag(&section), None);
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn manifest_cues() {
+        let section = parse_manifest_cue(
+            "#EXT-OATCLS-SCTE35:/DAlAAAAAAAAAP/wFAUAAAABf+/+LRQrAP4BI9MIAAEBAQAAfxV6SQ==",
+        )
+        .unwrap();
+        assert_eq!(section.splice_pts(), Some(0x2d14_2b00));
+        let section = parse_manifest_cue(
+            "#EXT-X-CUE:ID=\"1\",TYPE=\"SpliceOut\",DURATION=212.5,\
+             CUE=\"/DAlAAAAAAAAAP/wFAUAAAABf+/+LRQrAP4BI9MIAAEBAQAAfxV6SQ==\",ELAPSED=0",
+        )
+        .unwrap();
+        assert_eq!(section.splice_pts(), Some(0x2d14_2b00));
+        assert!(matches!(
+            parse_manifest_cue("#EXT-X-CUE:ID=\"a,b\",DURATION=30"),
+            Err(ManifestCueError::MissingCueAttribute)
+        ));
+        assert!(matches!(
+            parse_manifest_cue("#EXT-X-CUE-IN"),
+            Err(ManifestCueError::NotCueTag)
+        ));
+        assert!(matches!(
+            parse_manifest_cue("#EXT-OATCLS-SCTE35:!"),
+            Err(ManifestCueError::Cue(crate::FromBase64Error::Base64(_)))
+        ));
+    }
+}
@@ -0,0 +1,201 @@
+//! Carriage of _SCTE-35_ cues in the ID3 `PRIV` frames of HLS timed metadata, where the frame's
+//! owner identifier is `www.scte.org` and its private data is a binary `splice_info_section()`.
+//!
+//! ## Example
+//!
+//! ```
+//! # use hex_literal::*;
+//! # use scte35_reader::id3;
+//! let data = hex!(
+//!     "fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49"
+//! );
+//! let tag = id3::encode_tag(&data);
+//! let section = id3::decode_tag(&tag).unwrap();
+//! assert_eq!(section.splice_pts(), Some(0x2d142b00));
+//! ```
+
+use crate::{Scte35Error, SpliceInfoSection};
+
+/// The owner identifier of `PRIV` frames carrying _SCTE-35_ sections
+pub const PRIV_OWNER: &str = "www.scte.org";
+
+/// Wraps the given encoded `splice_info_section()` in an ID3v2.4 tag holding a single `PRIV`
+/// frame.
+pub fn encode_tag(section: &[u8]) -> Vec<u8> {
+    let frame_len = PRIV_OWNER.len() + 1 + section.len();
+    let mut tag = Vec::with_capacity(10 + 10 + frame_len);
+    tag.extend_from_slice(b"ID3\x04\x00\x00");
+    tag.extend_from_slice(&syncsafe(10 + frame_len));
+    tag.extend_from_slice(b"PRIV");
+    tag.extend_from_slice(&syncsafe(frame_len));
+    tag.extend_from_slice(&[0, 0]);
+    tag.extend_from_slice(PRIV_OWNER.as_bytes());
+    tag.push(0);
+    tag.extend_from_slice(section);
+    tag
+}
+
+/// Finds the `www.scte.org` `PRIV` frame within the given ID3v2.3 or ID3v2.4 tag, and parses the
+/// section it carries.
+///
+/// Tags using unsynchronisation, and frames which are compressed or encrypted, are not
+/// supported.
+pub fn decode_tag(tag: &[u8]) -> Result<SpliceInfoSection, Id3Error> {
+    if tag.len() < 10 || &tag[..3] != b"ID3" {
+        return Err(Id3Error::NotId3);
+    }
+    let version = tag[3];
+    if version != 3 && version != 4 {
+        return Err(Id3Error::UnsupportedVersion(version));
+    }
+    let flags = tag[5];
+    if flags & 0x80 != 0 {
+        return Err(Id3Error::Unsynchronised);
+    }
+    let size = from_syncsafe(&tag[6..10]);
+    let mut frames = tag.get(10..10 + size).ok_or(Id3Error::Truncated)?;
+    if flags & 0x40 != 0 {
+        // skip the extended header, whose size includes itself only in ID3v2.4
+        let len = match (version, frames.get(..4)) {
+            (4, Some(len)) => from_syncsafe(len),
+            (_, Some(len)) => 4 + u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize,
+            (_, None) => return Err(Id3Error::Truncated),
+        };
+        frames = frames.get(len..).ok_or(Id3Error::Truncated)?;
+    }
+    // frames continue until the end of the tag, or until the start of any padding
+    while frames.len() >= 10 && frames[0] != 0 {
+        let len = if version == 4 {
+            from_syncsafe(&frames[4..8])
+        } else {
+            u32::from_be_bytes([frames[4], frames[5], frames[6], frames[7]]) as usize
+        };
+        let format_flags = frames[9];
+        let data = frames.get(10..10 + len).ok_or(Id3Error::Truncated)?;
+        if &frames[..4] == b"PRIV" {
+            if let Some(section) = data
+                .strip_prefix(PRIV_OWNER.as_bytes())
+                .and_then(|rest| rest.strip_prefix(&[0]))
+            {
+                if format_flags != 0 {
+                    return Err(Id3Error::UnsupportedFrameFormat(format_flags));
+                }
+                return SpliceInfoSection::parse(section).map_err(Id3Error::Section);
+            }
+        }
+        frames = &frames[10 + len..];
+    }
+    Err(Id3Error::NoScte35Frame)
+}
+
+fn syncsafe(value: usize) -> [u8; 4] {
+    [
+        (value >> 21 & 0x7f) as u8,
+        (value >> 14 & 0x7f) as u8,
+        (value >> 7 & 0x7f) as u8,
+        (value & 0x7f) as u8,
+    ]
+}
+
+fn from_syncsafe(data: &[u8]) -> usize {
+    data.iter()
+        .fold(0, |acc, b| acc << 7 | usize::from(b & 0x7f))
+}
+
+/// Problems which prevent `decode_tag()` producing a section
+#[derive(Debug)]
+pub enum Id3Error {
+    /// The data does not start with an ID3v2 tag header
+    NotId3,
+    /// Only ID3v2.3 and ID3v2.4 tags are supported
+    UnsupportedVersion(u8),
+    /// The tag uses the unsynchronisation scheme, which is not supported
+    Unsynchronised,
+    /// The `PRIV` frame has the given _format flags_ set (for compression, encryption and so on),
+    /// which are not supported
+    UnsupportedFrameFormat(u8),
+    /// The tag, or one of its frames, is shorter than its header declares
+    Truncated,
+    /// The tag does not contain a `PRIV` frame owned by `www.scte.org`
+    NoScte35Frame,
+    /// The `PRIV` frame's data was not a valid `splice_info_section()`
+    Section(Scte35Error),
+}
+impl std::fmt::Display for Id3Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Id3Error::NotId3 => f.write_str("not an ID3v2 tag"),
+            Id3Error::UnsupportedVersion(v) => write!(f, "unsupported ID3v2.{} tag", v),
+            Id3Error::Unsynchronised => f.write_str("unsynchronised ID3 tags are not supported"),
+            Id3Error::UnsupportedFrameFormat(flags) => {
+                write!(f, "unsupported PRIV frame format flags {:#04x}", flags)
+            }
+            Id3Error::Truncated => f.write_str("ID3 tag is truncated"),
+            Id3Error::NoScte35Frame => f.write_str("no www.scte.org PRIV frame"),
+            Id3Error::Section(_) => f.write_str("invalid splice_info_section in PRIV frame"),
+        }
+    }
+}
+impl std::error::Error for Id3Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Id3Error::Section(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use hex_literal::*;
+
+    #[test]
+    fn round_trip() {
+        let tag = encode_tag(&SPLICE_NULL);
+        assert_eq!(&tag[..10], &hex!("4944330400000000002b"));
+        assert!(matches!(
+            decode_tag(&tag),
+            Ok(SpliceInfoSection {
+                splice_command: crate::SpliceCommand::SpliceNull {},
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn other_frames() {
+        // an ID3v2.3 tag with a TXXX frame, another owner's PRIV frame and some padding
+        let mut tag = hex!("49443303000000000048").to_vec();
+        tag.extend_from_slice(&hex!("54585858 00000003 0000 000000"));
+        tag.extend_from_slice(&hex!("50524956 00000002 0000 6100"));
+        tag.extend_from_slice(&hex!("50524956 00000021 0000"));
+        tag.extend_from_slice(b"www.scte.org\0");
+        tag.extend_from_slice(&SPLICE_NULL);
+        tag.extend_from_slice(&[0; 4]);
+        assert!(decode_tag(&tag).is_ok());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(decode_tag(b"TAG"), Err(Id3Error::NotId3)));
+        assert!(matches!(
+            decode_tag(&hex!("49443302000000000000")),
+            Err(Id3Error::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            decode_tag(&hex!("49443304008000000000")),
+            Err(Id3Error::Unsynchronised)
+        ));
+        assert!(matches!(
+            decode_tag(&hex!("49443304000000000000")),
+            Err(Id3Error::NoScte35Frame)
+        ));
+        let tag = encode_tag(&SPLICE_NULL);
+        assert!(matches!(
+            decode_tag(&tag[..tag.len() - 1]),
+            Err(Id3Error::Truncated)
+        ));
+    }
+}
@@ -0,0 +1,157 @@
+//! Push-based parsing of sections whose bytes arrive in fragments (for example, as they are read
+//! from a ring buffer), without the section assembly performed by `mpeg2ts-reader`.
+//!
+//! A [`SectionAssembler`](struct.SectionAssembler.html) buffers the bytes given to it until the
+//! complete section, as given by its `section_length`, is available, and then parses it.  The
+//! bytes may hold several consecutive sections, and a section may be split at any point.
+//!
+//! ## Example
+//!
+//! ```
+//! # use scte35_reader::incremental::SectionAssembler;
+//! # let data = scte35_reader::presets::cue_in(1).encode().unwrap();
+//! let mut assembler = SectionAssembler::new();
+//! let (first, rest) = data.split_at(5);
+//! assert!(assembler.push(first).is_empty());
+//! let sections = assembler.push(rest);
+//! assert_eq!(sections.len(), 1);
+//! assert!(sections[0].is_ok());
+//! ```
+
+use crate::{Scte35Error, SpliceInfoSection, MAX_SECTION_LENGTH};
+
+/// `table_id`, `section_syntax_indicator`, `private_indicator`, `sap_type` and `section_length`
+const COMMON_HEADER_SIZE: usize = 3;
+
+/// Buffers fragments of a sequence of sections, parsing each section once all of its bytes have
+/// been given to `push()`.
+///
+/// `0xff` bytes found where a section would start are skipped, as the stuffing which may follow
+/// sections in a Transport Stream.
+#[derive(Debug)]
+pub struct SectionAssembler {
+    buf: Vec<u8>,
+    max_section_length: usize,
+}
+impl Default for SectionAssembler {
+    fn default() -> Self {
+        SectionAssembler {
+            buf: Vec::new(),
+            max_section_length: MAX_SECTION_LENGTH,
+        }
+    }
+}
+impl SectionAssembler {
+    pub fn new() -> SectionAssembler {
+        Self::default()
+    }
+
+    /// Sections with a `section_length` greater than the given value are reported as
+    /// `Scte35Error::SectionTooLong` as soon as their header is seen, rather than being
+    /// buffered.  Defaults to `MAX_SECTION_LENGTH`.
+    pub fn with_max_section_length(mut self, max_section_length: usize) -> Self {
+        self.max_section_length = max_section_length;
+        self
+    }
+
+    /// Appends the given bytes to those already buffered, and returns the result of parsing
+    /// each section that they complete, in order.
+    ///
+    /// Since the end of a section whose `section_length` is too large can not be trusted, all
+    /// buffered bytes are discarded when one is found, and assembly starts afresh with the
+    /// following call.
+    pub fn push(&mut self, data: &[u8]) -> Vec<Result<SpliceInfoSection, Scte35Error>> {
+        self.buf.extend_from_slice(data);
+        let mut results = vec![];
+        let mut start = 0;
+        loop {
+            while self.buf.get(start) == Some(&0xff) {
+                start += 1;
+            }
+            let header = match self.buf.get(start..start + COMMON_HEADER_SIZE) {
+                Some(header) => header,
+                None => break,
+            };
+            let section_length = usize::from(header[1] & 0x0f) << 8 | usize::from(header[2]);
+            if section_length > self.max_section_length {
+                results.push(Err(Scte35Error::SectionTooLong {
+                    section_length,
+                    max: self.max_section_length,
+                }));
+                start = self.buf.len();
+                break;
+            }
+            let end = start + COMMON_HEADER_SIZE + section_length;
+            if end > self.buf.len() {
+                break;
+            }
+            results.push(SpliceInfoSection::parse(&self.buf[start..end]));
+            start = end;
+        }
+        self.buf.drain(..start);
+        results
+    }
+
+    /// The number of bytes buffered towards a section which is not yet complete
+    pub fn pending(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Discards any buffered bytes, for use when the source of the data is interrupted
+    pub fn reset(&mut self) {
+        self.buf.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::presets;
+    use matches::*;
+
+    #[test]
+    fn fragments() {
+        let first = presets::cue_in(1).encode().unwrap();
+        let second = presets::cue_in(2).encode().unwrap();
+        let mut data = first.clone();
+        data.extend_from_slice(&[0xff, 0xff]);
+        data.extend_from_slice(&second);
+
+        // feed the data a byte at a time
+        let mut assembler = SectionAssembler::new();
+        let mut sections = vec![];
+        for b in &data {
+            sections.extend(assembler.push(&[*b]));
+        }
+        assert_eq!(assembler.pending(), 0);
+        assert_eq!(
+            sections
+                .into_iter()
+                .map(|s| s.unwrap().splice_command)
+                .collect::<Vec<_>>(),
+            vec![
+                presets::cue_in(1).splice_command,
+                presets::cue_in(2).splice_command
+            ]
+        );
+
+        // and all at once, but with the last byte of the second section missing
+        let sections = assembler.push(&data[..data.len() - 1]);
+        assert_eq!(sections.len(), 1);
+        assert_eq!(assembler.pending(), second.len() - 1);
+        assembler.reset();
+        assert_eq!(assembler.pending(), 0);
+    }
+
+    #[test]
+    fn too_long() {
+        let data = presets::cue_in(1).encode().unwrap();
+        let mut assembler = SectionAssembler::new().with_max_section_length(10);
+        let sections = assembler.push(&data[..4]);
+        assert_matches!(
+            sections[..],
+            [Err(Scte35Error::SectionTooLong { max: 10, .. })]
+        );
+        assert_eq!(assembler.pending(), 0);
+    }
+}
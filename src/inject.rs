@@ -0,0 +1,268 @@
+//! Scheduling of `splice_info_section()` insertion, for playout or test-signal generation.
+//!
+//! An [`InjectionScheduler`](struct.InjectionScheduler.html) is given complete sections, each
+//! together with the time of the splice point it signals, and is then told of the progress of the
+//! stream clock.  It passes each section to a [`SectionSink`](trait.SectionSink.html) (e.g. a
+//! [`PacketizingSink`](../packetize/struct.PacketizingSink.html), producing TS packets) at the
+//! configured _lead time_ ahead of the splice point, and then again at the configured repetition
+//! interval until the splice point is reached.
+//!
+//! ## Example
+//!
+//! ```
+//! # use scte35_reader::inject::{CueTime, InjectionScheduler};
+//! # use std::time::{Duration, SystemTime};
+//! # let section = vec![];
+//! let mut sent = vec![];
+//! let mut scheduler = InjectionScheduler::new(|section: &[u8]| sent.push(section.to_vec()))
+//!     .lead_time(Duration::from_secs(4))
+//!     .repeat_interval(Duration::from_secs(1));
+//! scheduler.schedule(CueTime::Pts(10 * 90_000), section);
+//! let start = SystemTime::now();
+//! for secs in 0..12 {
+//!     scheduler.advance(secs * 90_000, start + Duration::from_secs(secs));
+//! }
+//! drop(scheduler);
+//! assert_eq!(sent.len(), 4);
+//! ```
+
+use crate::time::{adjust_pts, cmp_pts, SpliceTimestamp, PTS_MODULUS};
+use std::time::{Duration, SystemTime};
+
+const TICKS_PER_SECOND: u64 = 90_000;
+
+/// Receives sections from an `InjectionScheduler` at the moment they are due to be inserted into
+/// the stream.
+///
+/// Implemented for any `FnMut(&[u8])` closure.
+pub trait SectionSink {
+    fn emit(&mut self, section: &[u8]);
+}
+impl<F: FnMut(&[u8])> SectionSink for F {
+    fn emit(&mut self, section: &[u8]) {
+        self(section)
+    }
+}
+
+/// The time of the splice point signalled by a scheduled section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CueTime {
+    /// A presentation timestamp in 90kHz units, on the same timeline as the values given to
+    /// `InjectionScheduler::advance()`
+    Pts(u64),
+    /// A wall-clock time, mapped onto the stream timeline using the most recent `(pts, wall_clock)`
+    /// pair given to `InjectionScheduler::advance()`
+    WallClock(SystemTime),
+}
+
+struct ScheduledCue {
+    time: CueTime,
+    section: Vec<u8>,
+    next_emit: Option<u64>,
+}
+
+/// Hands scheduled sections to a `SectionSink` as the stream clock approaches their splice
+/// points.
+pub struct InjectionScheduler<S: SectionSink> {
+    sink: S,
+    lead_time: u64,
+    repeat_interval: u64,
+    pending: Vec<ScheduledCue>,
+}
+impl<S: SectionSink> InjectionScheduler<S> {
+    /// Creates a scheduler with a lead time of 4 seconds and a repetition interval of 1 second.
+    pub fn new(sink: S) -> InjectionScheduler<S> {
+        InjectionScheduler {
+            sink,
+            lead_time: 4 * TICKS_PER_SECOND,
+            repeat_interval: TICKS_PER_SECOND,
+            pending: vec![],
+        }
+    }
+
+    /// How far ahead of its splice point a section is first emitted.
+    pub fn lead_time(mut self, lead_time: Duration) -> Self {
+        self.lead_time = duration_to_ticks(lead_time);
+        self
+    }
+
+    /// How often a section is repeated between its first emission and its splice point.  A zero
+    /// interval causes each section to be emitted just once.
+    pub fn repeat_interval(mut self, interval: Duration) -> Self {
+        self.repeat_interval = duration_to_ticks(interval);
+        self
+    }
+
+    /// Queues the given complete `splice_info_section()` for insertion ahead of the given splice
+    /// point.
+    pub fn schedule(&mut self, time: CueTime, section: Vec<u8>) {
+        self.pending.push(ScheduledCue {
+            time,
+            section,
+            next_emit: None,
+        });
+    }
+
+    /// Discards all pending sections, for use when the stream clock becomes discontinuous or
+    /// the output is switched to a different source.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+    }
+
+    /// The number of sections whose splice point has not yet been reached.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Informs the scheduler of the current position of the stream clock, emitting any sections
+    /// which have become due.  Sections whose splice point has been reached are discarded.
+    ///
+    /// PTS values are compared allowing for the wrap of the 33-bit counter (see
+    /// `time::cmp_pts()`).  A `CueTime::WallClock` cue is left pending, unmapped, until it falls
+    /// within half the range of the counter (about 13 hours) of the current time.
+    pub fn advance(&mut self, pts: u64, wall_clock: SystemTime) {
+        let lead_time = self.lead_time;
+        let repeat_interval = self.repeat_interval;
+        let sink = &mut self.sink;
+        self.pending.retain_mut(|cue| {
+            let splice_pts = match cue.time {
+                CueTime::Pts(splice_pts) => splice_pts,
+                CueTime::WallClock(t) => match t.duration_since(wall_clock) {
+                    Ok(d) => {
+                        let ticks = duration_to_ticks(d);
+                        if ticks >= PTS_MODULUS / 2 {
+                            // too far ahead to be placed on the wrapping PTS timeline yet
+                            return true;
+                        }
+                        adjust_pts(pts, ticks)
+                    }
+                    Err(_) => pts,
+                },
+            };
+            if cmp_pts(pts, splice_pts).is_ge() {
+                if cue.next_emit.is_none() {
+                    warn!(
+                        "Splice point {} passed before cue could be inserted; dropping",
+                        splice_pts
+                    );
+                }
+                return false;
+            }
+            let due = cue
+                .next_emit
+                .unwrap_or_else(|| (SpliceTimestamp::new(splice_pts) - lead_time).value());
+            if cmp_pts(pts, due).is_ge() {
+                sink.emit(&cue.section);
+                if repeat_interval == 0 {
+                    return false;
+                }
+                cue.next_emit = Some(adjust_pts(pts, repeat_interval));
+            }
+            true
+        });
+    }
+}
+
+/// Saturates rather than overflowing for durations beyond any the PTS timeline could represent
+fn duration_to_ticks(d: Duration) -> u64 {
+    d.as_secs()
+        .saturating_mul(TICKS_PER_SECOND)
+        .saturating_add(u64::from(d.subsec_nanos()) * TICKS_PER_SECOND / 1_000_000_000)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pts_repetition() {
+        let mut emitted = vec![];
+        let now = std::cell::Cell::new(0);
+        {
+            let mut scheduler = InjectionScheduler::new(|_: &[u8]| emitted.push(now.get()))
+                .lead_time(Duration::from_secs(4))
+                .repeat_interval(Duration::from_secs(1));
+            scheduler.schedule(CueTime::Pts(10 * TICKS_PER_SECOND), vec![0xfc]);
+            for half_secs in 0..24 {
+                now.set(half_secs * TICKS_PER_SECOND / 2);
+                scheduler.advance(now.get(), SystemTime::UNIX_EPOCH);
+            }
+            assert_eq!(scheduler.pending(), 0);
+        }
+        let secs: Vec<_> = emitted.iter().map(|t| t / TICKS_PER_SECOND).collect();
+        assert_eq!(secs, vec![6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn pts_wrap() {
+        let mut emitted = vec![];
+        {
+            let mut scheduler = InjectionScheduler::new(|_: &[u8]| emitted.push(()))
+                .lead_time(Duration::from_secs(4))
+                .repeat_interval(Duration::from_secs(1));
+            // the splice point falls 2 seconds after the 33-bit PTS counter wraps
+            let start = PTS_MODULUS - 4 * TICKS_PER_SECOND;
+            scheduler.schedule(CueTime::Pts(2 * TICKS_PER_SECOND), vec![0xfc]);
+            scheduler.advance(start, SystemTime::UNIX_EPOCH);
+            assert_eq!(scheduler.pending(), 1);
+            for secs in 1..8 {
+                let pts = adjust_pts(start, secs * TICKS_PER_SECOND);
+                scheduler.advance(pts, SystemTime::UNIX_EPOCH);
+            }
+            assert_eq!(scheduler.pending(), 0);
+        }
+        assert_eq!(emitted.len(), 4);
+    }
+
+    #[test]
+    fn wall_clock() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut count = 0;
+        let mut scheduler = InjectionScheduler::new(|_: &[u8]| count += 1)
+            .lead_time(Duration::from_secs(2))
+            .repeat_interval(Duration::ZERO);
+        scheduler.schedule(
+            CueTime::WallClock(start + Duration::from_secs(5)),
+            vec![0xfc],
+        );
+        // the stream clock starts at an arbitrary PTS value
+        let base = 123_456;
+        scheduler.advance(base, start);
+        scheduler.advance(base + 2 * TICKS_PER_SECOND, start + Duration::from_secs(2));
+        assert_eq!(scheduler.pending(), 1);
+        scheduler.advance(base + 3 * TICKS_PER_SECOND, start + Duration::from_secs(3));
+        assert_eq!(scheduler.pending(), 0);
+        drop(scheduler);
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn wall_clock_far_ahead() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let day = 24 * 60 * 60;
+        let mut count = 0;
+        let mut scheduler = InjectionScheduler::new(|_: &[u8]| count += 1)
+            .lead_time(Duration::from_secs(2))
+            .repeat_interval(Duration::ZERO);
+        scheduler.schedule(
+            CueTime::WallClock(start + Duration::from_secs(day)),
+            vec![0xfc],
+        );
+        // far enough ahead that its tick count would overflow
+        scheduler.schedule(
+            CueTime::WallClock(SystemTime::UNIX_EPOCH + Duration::from_secs(1 << 62)),
+            vec![0xfc],
+        );
+        let base = 123_456;
+        for secs in [0, day / 2, day - 3] {
+            let pts = adjust_pts(base, secs * TICKS_PER_SECOND);
+            scheduler.advance(pts, start + Duration::from_secs(secs));
+            assert_eq!(scheduler.pending(), 2);
+        }
+        let pts = adjust_pts(base, (day - 1) * TICKS_PER_SECOND);
+        scheduler.advance(pts, start + Duration::from_secs(day - 1));
+        assert_eq!(scheduler.pending(), 1);
+        drop(scheduler);
+        assert_eq!(count, 1);
+    }
+}
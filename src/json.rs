@@ -0,0 +1,1678 @@
+//! Alternative serialized forms of a [`SpliceInfoSection`](../struct.SpliceInfoSection.html).
+//!
+//! The `serde::Serialize` implementations of the types in this crate mirror the shapes of the
+//! Rust types themselves.  The wrappers in this module instead serialize a section using the
+//! field names and nesting of other tooling, so that output can be exchanged with that tooling
+//! without further conversion.  They work with any serde data format, though JSON is the usual
+//! choice.
+//!
+//!  - [`StandardJson`](struct.StandardJson.html) follows the SCTE-35 XML schema
+//!  - [`ThreefiveJson`](struct.ThreefiveJson.html) follows the output of the Python `threefive`
+//!    tool
+//!
+//! ## Example
+//!
+//! ```
+//! # use hex_literal::*;
+//! # use scte35_reader::SpliceInfoSection;
+//! # use scte35_reader::json::{StandardJson, ThreefiveJson};
+//! let section = SpliceInfoSection::parse(&hex!(
+//!     "fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49"
+//! ))
+//! .unwrap();
+//! let json = serde_json::to_value(StandardJson(&section)).unwrap();
+//! assert_eq!(json["spliceInsert"]["spliceEventId"], 1);
+//! assert_eq!(json["spliceInsert"]["program"]["spliceTime"]["ptsTime"], 0x2d142b00);
+//!
+//! let json = serde_json::to_value(ThreefiveJson::encoded(&section)).unwrap();
+//! assert_eq!(json["command"]["pts_time"], 8403.293867);
+//! ```
+
+use crate::{
+    DeliveryRestrictionFlags, DeviceRestrictions, NetworkIndicator, ReturnMode, ScheduledSplice,
+    ScheduledSpliceMode, SegmentationDescriptor, SegmentationMode, SegmentationUpid, SpliceCommand,
+    SpliceDescriptor, SpliceDuration, SpliceInfoSection, SpliceInsert, SpliceMode, SpliceTime,
+};
+use hex_slice::AsHex;
+use serde_derive::Serialize;
+
+/// Serializes a section according to the representation defined by the SCTE-35 XML schema
+/// (namespace `http://www.scte.org/schemas/35`), with element and attribute names given in
+/// `camelCase`, as used for the JSON form of SCTE-35 messages.
+///
+/// The command is held in a single field named for its type (e.g. `spliceInsert` or
+/// `timeSignal`), and descriptors in a `spliceDescriptors` list, each an object with a single
+/// field named for the kind of descriptor.  Binary values such as `privateBytes` are given as
+/// hex strings, and `segmentationUpids` lists the UPIDs of a `MID()` individually.
+pub struct StandardJson<'a>(pub &'a SpliceInfoSection);
+impl serde::Serialize for StandardJson<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Section::from(self.0).serialize(serializer)
+    }
+}
+
+fn hex(data: &[u8]) -> String {
+    format!("{:02x}", data.plain_hex(false))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Section {
+    protocol_version: u8,
+    pts_adjustment: u64,
+    tier: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encrypted_packet: Option<EncryptedPacket>,
+    #[serde(flatten)]
+    command: Command,
+    splice_descriptors: Vec<DescriptorEntry>,
+}
+impl From<&SpliceInfoSection> for Section {
+    fn from(s: &SpliceInfoSection) -> Section {
+        Section {
+            protocol_version: s.protocol_version,
+            pts_adjustment: s.pts_adjustment,
+            tier: s.tier.value(),
+            encrypted_packet: if s.encrypted_packet {
+                Some(EncryptedPacket {
+                    encryption_algorithm: s.encryption_algorithm.id(),
+                    cw_index: s.cw_index,
+                })
+            } else {
+                None
+            },
+            command: Command::from(&s.splice_command),
+            splice_descriptors: s
+                .splice_descriptors
+                .iter()
+                .map(DescriptorEntry::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptedPacket {
+    encryption_algorithm: u8,
+    cw_index: u8,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Command {
+    SpliceNull {},
+    SpliceSchedule { events: Vec<ScheduleEvent> },
+    SpliceInsert(Insert),
+    TimeSignal(TimeSignal),
+    BandwidthReservation {},
+    PrivateCommand(PrivateCommand),
+    Reserved(ReservedCommand),
+}
+impl From<&SpliceCommand> for Command {
+    fn from(command: &SpliceCommand) -> Command {
+        match command {
+            SpliceCommand::SpliceNull {} => Command::SpliceNull {},
+            SpliceCommand::SpliceSchedule { events } => Command::SpliceSchedule {
+                events: events
+                    .iter()
+                    .map(|e| ScheduleEvent::new(e.splice_event_id, &e.splice_detail))
+                    .collect(),
+            },
+            SpliceCommand::SpliceInsert {
+                splice_event_id,
+                splice_detail,
+                ..
+            } => Command::SpliceInsert(Insert::new(*splice_event_id, splice_detail)),
+            SpliceCommand::TimeSignal { splice_time } => Command::TimeSignal(TimeSignal {
+                splice_time: Time::from(splice_time),
+            }),
+            SpliceCommand::BandwidthReservation {} => Command::BandwidthReservation {},
+            SpliceCommand::PrivateCommand {
+                identifier,
+                private_bytes,
+            } => Command::PrivateCommand(PrivateCommand {
+                identifier: u32::from_be_bytes(*identifier),
+                private_bytes: hex(private_bytes),
+            }),
+            SpliceCommand::Reserved {
+                command_type,
+                payload,
+            } => Command::Reserved(ReservedCommand {
+                command_type: *command_type,
+                payload: hex(payload),
+            }),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeSignal {
+    splice_time: Time,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PrivateCommand {
+    identifier: u32,
+    private_bytes: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReservedCommand {
+    command_type: u8,
+    payload: String,
+}
+
+/// `splice_time()`, whose `ptsTime` is absent if `time_specified_flag` is not set
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Time {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pts_time: Option<u64>,
+}
+impl From<&SpliceTime> for Time {
+    fn from(time: &SpliceTime) -> Time {
+        Time {
+            pts_time: match time {
+                SpliceTime::Timed(pts_time) => *pts_time,
+                SpliceTime::Immediate => None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BreakDuration {
+    auto_return: bool,
+    duration: u64,
+}
+impl From<&SpliceDuration> for BreakDuration {
+    fn from(d: &SpliceDuration) -> BreakDuration {
+        BreakDuration {
+            auto_return: matches!(d.return_mode, ReturnMode::Automatic),
+            duration: d.duration,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Insert {
+    splice_event_id: u32,
+    splice_event_cancel_indicator: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    out_of_network_indicator: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    splice_immediate_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unique_program_id: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avail_num: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avails_expected: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    program: Option<InsertProgram>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    components: Option<Vec<InsertComponent>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    break_duration: Option<BreakDuration>,
+}
+impl Insert {
+    fn new(splice_event_id: u32, detail: &SpliceInsert) -> Insert {
+        let mut result = Insert {
+            splice_event_id,
+            splice_event_cancel_indicator: true,
+            out_of_network_indicator: None,
+            splice_immediate_flag: None,
+            unique_program_id: None,
+            avail_num: None,
+            avails_expected: None,
+            program: None,
+            components: None,
+            break_duration: None,
+        };
+        if let SpliceInsert::Insert {
+            network_indicator,
+            splice_mode,
+            duration,
+            unique_program_id,
+            avail_num,
+            avails_expected,
+        } = detail
+        {
+            let immediate = match splice_mode {
+                SpliceMode::Program(time) => matches!(time, SpliceTime::Immediate),
+                SpliceMode::Components(c) => c
+                    .iter()
+                    .any(|c| matches!(c.splice_time, SpliceTime::Immediate)),
+            };
+            result.splice_event_cancel_indicator = false;
+            result.out_of_network_indicator =
+                Some(matches!(network_indicator, NetworkIndicator::Out));
+            result.splice_immediate_flag = Some(immediate);
+            result.unique_program_id = Some(*unique_program_id);
+            result.avail_num = Some(*avail_num);
+            result.avails_expected = Some(*avails_expected);
+            match splice_mode {
+                SpliceMode::Program(time) => {
+                    result.program = Some(InsertProgram {
+                        splice_time: if immediate {
+                            None
+                        } else {
+                            Some(Time::from(time))
+                        },
+                    })
+                }
+                SpliceMode::Components(components) => {
+                    result.components = Some(
+                        components
+                            .iter()
+                            .map(|c| InsertComponent {
+                                component_tag: c.component_tag,
+                                splice_time: if immediate {
+                                    None
+                                } else {
+                                    Some(Time::from(&c.splice_time))
+                                },
+                            })
+                            .collect(),
+                    )
+                }
+            }
+            result.break_duration = duration.as_ref().map(BreakDuration::from);
+        }
+        result
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InsertProgram {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    splice_time: Option<Time>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InsertComponent {
+    component_tag: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    splice_time: Option<Time>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScheduleEvent {
+    splice_event_id: u32,
+    splice_event_cancel_indicator: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    out_of_network_indicator: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unique_program_id: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avail_num: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avails_expected: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    program: Option<ScheduleProgram>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    components: Option<Vec<ScheduleComponent>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    break_duration: Option<BreakDuration>,
+}
+impl ScheduleEvent {
+    fn new(splice_event_id: u32, detail: &ScheduledSplice) -> ScheduleEvent {
+        let mut result = ScheduleEvent {
+            splice_event_id,
+            splice_event_cancel_indicator: true,
+            out_of_network_indicator: None,
+            unique_program_id: None,
+            avail_num: None,
+            avails_expected: None,
+            program: None,
+            components: None,
+            break_duration: None,
+        };
+        if let ScheduledSplice::Insert {
+            network_indicator,
+            splice_mode,
+            duration,
+            unique_program_id,
+            avail_num,
+            avails_expected,
+        } = detail
+        {
+            result.splice_event_cancel_indicator = false;
+            result.out_of_network_indicator =
+                Some(matches!(network_indicator, NetworkIndicator::Out));
+            result.unique_program_id = Some(*unique_program_id);
+            result.avail_num = Some(*avail_num);
+            result.avails_expected = Some(*avails_expected);
+            match splice_mode {
+                ScheduledSpliceMode::Program(t) => {
+                    result.program = Some(ScheduleProgram {
+                        utc_splice_time: t.0,
+                    })
+                }
+                ScheduledSpliceMode::Components(components) => {
+                    result.components = Some(
+                        components
+                            .iter()
+                            .map(|c| ScheduleComponent {
+                                component_tag: c.component_tag,
+                                utc_splice_time: c.utc_splice_time.0,
+                            })
+                            .collect(),
+                    )
+                }
+            }
+            result.break_duration = duration.as_ref().map(BreakDuration::from);
+        }
+        result
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScheduleProgram {
+    utc_splice_time: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScheduleComponent {
+    component_tag: u8,
+    utc_splice_time: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum DescriptorEntry {
+    AvailDescriptor {
+        #[serde(rename = "providerAvailId")]
+        provider_avail_id: u32,
+    },
+    #[serde(rename = "dtmfDescriptor")]
+    Dtmf {
+        preroll: u8,
+        chars: String,
+    },
+    SegmentationDescriptor(Box<Segmentation>),
+    TimeDescriptor {
+        #[serde(rename = "taiSeconds")]
+        tai_seconds: u64,
+        #[serde(rename = "taiNs")]
+        tai_ns: u32,
+        #[serde(rename = "utcOffset")]
+        utc_offset: u16,
+    },
+    AudioDescriptor {
+        #[serde(rename = "audioChannels")]
+        audio_channels: Vec<AudioChannel>,
+    },
+    #[serde(rename = "privateDescriptor")]
+    Private {
+        tag: u8,
+        identifier: u32,
+        #[serde(rename = "privateBytes")]
+        private_bytes: String,
+    },
+}
+impl From<&SpliceDescriptor> for DescriptorEntry {
+    fn from(d: &SpliceDescriptor) -> DescriptorEntry {
+        match d {
+            SpliceDescriptor::AvailDescriptor { provider_avail_id } => {
+                DescriptorEntry::AvailDescriptor {
+                    provider_avail_id: *provider_avail_id,
+                }
+            }
+            SpliceDescriptor::DTMFDescriptor {
+                preroll,
+                dtmf_chars,
+            } => DescriptorEntry::Dtmf {
+                preroll: *preroll,
+                chars: String::from_utf8_lossy(dtmf_chars).into_owned(),
+            },
+            SpliceDescriptor::SegmentationDescriptor {
+                segmentation_event_id,
+                descriptor_detail,
+            } => DescriptorEntry::SegmentationDescriptor(Box::new(Segmentation::new(
+                *segmentation_event_id,
+                descriptor_detail,
+            ))),
+            SpliceDescriptor::TimeDescriptor {
+                tai_seconds,
+                tai_nanoseconds,
+                utc_offset,
+            } => DescriptorEntry::TimeDescriptor {
+                tai_seconds: *tai_seconds,
+                tai_ns: *tai_nanoseconds,
+                utc_offset: *utc_offset,
+            },
+            SpliceDescriptor::AudioDescriptor { components } => DescriptorEntry::AudioDescriptor {
+                audio_channels: components
+                    .iter()
+                    .map(|c| AudioChannel {
+                        component_tag: c.component_tag,
+                        iso_code: String::from_utf8_lossy(&c.iso_code).into_owned(),
+                        bit_stream_mode: c.bit_stream_mode,
+                        num_channels: c.num_channels,
+                        full_srvc_audio: c.full_srvc_audio,
+                    })
+                    .collect(),
+            },
+            SpliceDescriptor::Reserved {
+                tag,
+                identifier,
+                private_bytes,
+            } => DescriptorEntry::Private {
+                tag: *tag,
+                identifier: u32::from_be_bytes(*identifier),
+                private_bytes: hex(private_bytes),
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AudioChannel {
+    component_tag: u8,
+    #[serde(rename = "ISOCode")]
+    iso_code: String,
+    bit_stream_mode: u8,
+    num_channels: u8,
+    full_srvc_audio: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Segmentation {
+    segmentation_event_id: u32,
+    segmentation_event_cancel_indicator: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segmentation_duration: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segmentation_type_id: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segment_num: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segments_expected: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sub_segment_num: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sub_segments_expected: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delivery_restrictions: Option<DeliveryRestrictions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segmentation_upids: Option<Vec<Upid>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    components: Option<Vec<SegmentationComponent>>,
+}
+impl Segmentation {
+    fn new(segmentation_event_id: u32, detail: &SegmentationDescriptor) -> Segmentation {
+        let mut result = Segmentation {
+            segmentation_event_id,
+            segmentation_event_cancel_indicator: true,
+            segmentation_duration: None,
+            segmentation_type_id: None,
+            segment_num: None,
+            segments_expected: None,
+            sub_segment_num: None,
+            sub_segments_expected: None,
+            delivery_restrictions: None,
+            segmentation_upids: None,
+            components: None,
+        };
+        if let SegmentationDescriptor::Insert {
+            delivery_restrictions,
+            segmentation_mode,
+            segmentation_duration,
+            segmentation_upid,
+            segmentation_type_id,
+            segment_num,
+            segments_expected,
+            sub_segments,
+            ..
+        } = detail
+        {
+            result.segmentation_event_cancel_indicator = false;
+            result.segmentation_duration = *segmentation_duration;
+            result.segmentation_type_id = Some(segmentation_type_id.id());
+            result.segment_num = Some(*segment_num);
+            result.segments_expected = Some(*segments_expected);
+            if let Some(sub_segments) = sub_segments {
+                result.sub_segment_num = Some(sub_segments.sub_segment_num);
+                result.sub_segments_expected = Some(sub_segments.sub_segments_expected);
+            }
+            if let DeliveryRestrictionFlags::DeliveryRestrictions {
+                web_delivery_allowed_flag,
+                no_regional_blackout_flag,
+                archive_allowed_flag,
+                device_restrictions,
+            } = delivery_restrictions
+            {
+                result.delivery_restrictions = Some(DeliveryRestrictions {
+                    web_delivery_allowed_flag: *web_delivery_allowed_flag,
+                    no_regional_blackout_flag: *no_regional_blackout_flag,
+                    archive_allowed_flag: *archive_allowed_flag,
+                    device_restrictions: match device_restrictions {
+                        DeviceRestrictions::RestrictGroup0 => 0,
+                        DeviceRestrictions::RestrictGroup1 => 1,
+                        DeviceRestrictions::RestrictGroup2 => 2,
+                        DeviceRestrictions::None => 3,
+                    },
+                });
+            }
+            let mut upids = vec![];
+            Upid::collect(segmentation_upid, &mut upids);
+            result.segmentation_upids = Some(upids);
+            if let SegmentationMode::Component { components } = segmentation_mode {
+                result.components = Some(
+                    components
+                        .iter()
+                        .map(|c| SegmentationComponent {
+                            component_tag: c.component_tag,
+                            pts_offset: c.pts_offset,
+                        })
+                        .collect(),
+                );
+            }
+        }
+        result
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeliveryRestrictions {
+    web_delivery_allowed_flag: bool,
+    no_regional_blackout_flag: bool,
+    archive_allowed_flag: bool,
+    device_restrictions: u8,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SegmentationComponent {
+    component_tag: u8,
+    pts_offset: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Upid {
+    segmentation_upid_type: u8,
+    /// `"text"` for UPID types defined as character strings, and otherwise `"hexbinary"`
+    segmentation_upid_format: &'static str,
+    value: String,
+}
+impl Upid {
+    /// Appends the given UPID, or each of the UPIDs within a `MID()`
+    fn collect(upid: &SegmentationUpid, out: &mut Vec<Upid>) {
+        let text = |s: &str| ("text", s.to_string());
+        let (segmentation_upid_format, value) = match upid {
+            SegmentationUpid::None => return,
+            SegmentationUpid::MID(upids) => {
+                for upid in upids {
+                    Upid::collect(upid, out);
+                }
+                return;
+            }
+            SegmentationUpid::Isci(v) => text(&v.0),
+            SegmentationUpid::AdID(v) => text(&v.0),
+            SegmentationUpid::TID(v) => text(&v.0),
+            SegmentationUpid::ADI(v) => text(&v.0),
+            SegmentationUpid::URI(v) => text(v.0.as_str()),
+            _ => {
+                let mut bytes = vec![];
+                // encoding a single UPID can only fail for a MID() or ATSC with out of range
+                // fields, in which case the value is left empty
+                let _ = crate::encode::write_upid(&mut bytes, upid);
+                ("hexbinary", hex(&bytes))
+            }
+        };
+        out.push(Upid {
+            segmentation_upid_type: upid.segmentation_upid_type().id(),
+            segmentation_upid_format,
+            value,
+        });
+    }
+}
+
+/// Serializes a section using the field names, structure and value formatting of the JSON output
+/// of the Python [`threefive`](https://github.com/superkabuki/threefive) tool, so that the
+/// output of the two may be compared.
+///
+/// Like `threefive`, fields which do not apply are omitted rather than given as `null`, times
+/// appear both in seconds (rounded to 6 decimal places) and as `*_ticks` of the 90kHz clock,
+/// and identifiers such as `tier` and `segmentation_event_id` are hex strings.
+///
+/// The `sap_type`, `section_length`, `splice_command_length`, `descriptor_length`,
+/// `descriptor_loop_length` and `crc` values are not held in `SpliceInfoSection`, so are taken
+/// from the bytes the section was parsed from, when given to `new()`.
+pub struct ThreefiveJson<'a> {
+    section: &'a SpliceInfoSection,
+    data: Option<&'a [u8]>,
+}
+impl<'a> ThreefiveJson<'a> {
+    /// Serializes the given section, which was parsed from the given `splice_info_section()`
+    /// bytes
+    pub fn new(section: &'a SpliceInfoSection, data: &'a [u8]) -> ThreefiveJson<'a> {
+        ThreefiveJson {
+            section,
+            data: Some(data),
+        }
+    }
+
+    /// Serializes a section for which the original bytes are not available (for instance, one
+    /// constructed by the application), taking the lengths and `crc` from re-encoding the
+    /// section (see
+    /// [`SpliceInfoSection::encode()`](../struct.SpliceInfoSection.html#method.encode)).  These
+    /// values are omitted if the section can not be encoded.
+    pub fn encoded(section: &'a SpliceInfoSection) -> ThreefiveJson<'a> {
+        ThreefiveJson {
+            section,
+            data: None,
+        }
+    }
+}
+impl serde::Serialize for ThreefiveJson<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let encoded = match self.data {
+            Some(data) => Encoded::new(data),
+            None => self
+                .section
+                .encode()
+                .ok()
+                .and_then(|data| Encoded::new(&data)),
+        };
+        Threefive::new(self.section, encoded).serialize(serializer)
+    }
+}
+
+/// Time in seconds, as `threefive` presents 90kHz values
+fn seconds(ticks: u64) -> f64 {
+    (ticks as f64 / 90.0 * 1000.0).round() / 1_000_000.0
+}
+
+/// Hex string formatted like Python's `hex()` function
+fn py_hex(value: u64) -> String {
+    format!("{:#x}", value)
+}
+
+/// The values from the bytes of a section which are not held in `SpliceInfoSection`
+struct Encoded {
+    sap_type: u8,
+    section_length: u16,
+    splice_command_length: u16,
+    descriptor_loop_length: u16,
+    descriptor_lengths: Vec<u8>,
+    crc: u32,
+}
+impl Encoded {
+    /// Reads the values from the given `splice_info_section()`, or produces `None` if the lengths
+    /// it gives do not fit within it
+    fn new(data: &[u8]) -> Option<Encoded> {
+        let section_length = u16::from_be_bytes([*data.get(1)?, *data.get(2)?]) & 0xfff;
+        let data = data.get(..3 + usize::from(section_length))?;
+        let splice_command_length = u16::from_be_bytes([*data.get(11)?, *data.get(12)?]) & 0xfff;
+        let loop_start = 14 + usize::from(splice_command_length);
+        let descriptor_loop_length =
+            u16::from_be_bytes([*data.get(loop_start)?, *data.get(loop_start + 1)?]);
+        let mut descriptor_lengths = vec![];
+        let mut rest =
+            data.get(loop_start + 2..loop_start + 2 + usize::from(descriptor_loop_length))?;
+        while rest.len() >= 2 {
+            let len = rest[1];
+            descriptor_lengths.push(len);
+            rest = rest.get(2 + usize::from(len)..).unwrap_or(&[]);
+        }
+        let crc = data.get(data.len().checked_sub(4)?..)?;
+        Some(Encoded {
+            sap_type: (data[1] >> 4) & 0b11,
+            section_length,
+            splice_command_length,
+            descriptor_loop_length,
+            descriptor_lengths,
+            crc: u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]),
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct Threefive {
+    info_section: InfoSection,
+    command: ThreefiveCommand,
+    descriptors: Vec<ThreefiveDescriptor>,
+}
+impl Threefive {
+    fn new(s: &SpliceInfoSection, encoded: Option<Encoded>) -> Threefive {
+        let mut command = ThreefiveCommand::from(&s.splice_command);
+        command.command_length = encoded.as_ref().map(|e| e.splice_command_length);
+        let descriptors = s
+            .splice_descriptors
+            .iter()
+            .enumerate()
+            .map(|(i, d)| {
+                let mut d = ThreefiveDescriptor::from(d);
+                d.descriptor_length = encoded
+                    .as_ref()
+                    .and_then(|e| e.descriptor_lengths.get(i).copied());
+                d
+            })
+            .collect();
+        Threefive {
+            info_section: InfoSection {
+                table_id: "0xfc",
+                section_syntax_indicator: false,
+                private: false,
+                sap_type: encoded.as_ref().map(|e| py_hex(e.sap_type.into())),
+                sap_details: encoded.as_ref().map(|e| match e.sap_type {
+                    0 => "Type 1 Closed GOP with no leading pictures",
+                    1 => "Type 2 Closed GOP with leading pictures",
+                    2 => "Type 3 Open GOP",
+                    _ => "No Sap Type",
+                }),
+                section_length: encoded.as_ref().map(|e| e.section_length),
+                protocol_version: s.protocol_version,
+                encrypted_packet: s.encrypted_packet,
+                encryption_algorithm: s.encryption_algorithm.id(),
+                pts_adjustment_ticks: s.pts_adjustment,
+                pts_adjustment: seconds(s.pts_adjustment),
+                cw_index: py_hex(s.cw_index.into()),
+                tier: py_hex(s.tier.value().into()),
+                splice_command_length: encoded.as_ref().map(|e| e.splice_command_length),
+                splice_command_type: command.command_type,
+                descriptor_loop_length: encoded.as_ref().map(|e| e.descriptor_loop_length),
+                crc: encoded.as_ref().map(|e| py_hex(e.crc.into())),
+            },
+            command,
+            descriptors,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct InfoSection {
+    table_id: &'static str,
+    section_syntax_indicator: bool,
+    private: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sap_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sap_details: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    section_length: Option<u16>,
+    protocol_version: u8,
+    encrypted_packet: bool,
+    encryption_algorithm: u8,
+    pts_adjustment_ticks: u64,
+    pts_adjustment: f64,
+    cw_index: String,
+    tier: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    splice_command_length: Option<u16>,
+    splice_command_type: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    descriptor_loop_length: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    crc: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+struct ThreefiveCommand {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command_length: Option<u16>,
+    command_type: u8,
+    name: &'static str,
+    #[serde(flatten)]
+    splice: Option<ThreefiveSplice>,
+    #[serde(flatten)]
+    time: Option<ThreefiveTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    splice_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events: Option<Vec<ThreefiveSplice>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identifier: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    private_bytes: Option<String>,
+}
+impl From<&SpliceCommand> for ThreefiveCommand {
+    fn from(command: &SpliceCommand) -> ThreefiveCommand {
+        match command {
+            SpliceCommand::SpliceNull {} => ThreefiveCommand {
+                command_type: 0,
+                name: "Splice Null",
+                ..Default::default()
+            },
+            SpliceCommand::SpliceSchedule { events } => ThreefiveCommand {
+                command_type: 4,
+                name: "Splice Schedule",
+                splice_count: Some(events.len()),
+                events: Some(
+                    events
+                        .iter()
+                        .map(|e| ThreefiveSplice::scheduled(e.splice_event_id, &e.splice_detail))
+                        .collect(),
+                ),
+                ..Default::default()
+            },
+            SpliceCommand::SpliceInsert {
+                splice_event_id,
+                splice_detail,
+                ..
+            } => ThreefiveCommand {
+                command_type: 5,
+                name: "Splice Insert",
+                splice: Some(ThreefiveSplice::insert(*splice_event_id, splice_detail)),
+                ..Default::default()
+            },
+            SpliceCommand::TimeSignal { splice_time } => ThreefiveCommand {
+                command_type: 6,
+                name: "Time Signal",
+                time: Some(ThreefiveTime::from(splice_time)),
+                ..Default::default()
+            },
+            SpliceCommand::BandwidthReservation {} => ThreefiveCommand {
+                command_type: 7,
+                name: "Bandwidth Reservation",
+                ..Default::default()
+            },
+            SpliceCommand::PrivateCommand {
+                identifier,
+                private_bytes,
+            } => ThreefiveCommand {
+                command_type: 0xff,
+                name: "Private Command",
+                identifier: Some(u32::from_be_bytes(*identifier)),
+                private_bytes: Some(format!("0x{}", hex(private_bytes))),
+                ..Default::default()
+            },
+            SpliceCommand::Reserved {
+                command_type,
+                payload,
+            } => ThreefiveCommand {
+                command_type: *command_type,
+                name: "Reserved",
+                private_bytes: Some(format!("0x{}", hex(payload))),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// `splice_time()`, where `pts_time` is absent unless `time_specified_flag` is set
+#[derive(Serialize)]
+struct ThreefiveTime {
+    time_specified_flag: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pts_time: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pts_time_ticks: Option<u64>,
+}
+impl From<&SpliceTime> for ThreefiveTime {
+    fn from(time: &SpliceTime) -> ThreefiveTime {
+        let ticks = match time {
+            SpliceTime::Timed(pts_time) => *pts_time,
+            SpliceTime::Immediate => None,
+        };
+        ThreefiveTime {
+            time_specified_flag: ticks.is_some(),
+            pts_time: ticks.map(seconds),
+            pts_time_ticks: ticks,
+        }
+    }
+}
+
+/// The fields shared by `splice_insert()` and each event of `splice_schedule()`
+#[derive(Serialize, Default)]
+struct ThreefiveSplice {
+    splice_event_id: u32,
+    splice_event_cancel_indicator: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    out_of_network_indicator: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    program_splice_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    splice_immediate_flag: Option<bool>,
+    #[serde(flatten)]
+    time: Option<ThreefiveTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    utc_splice_time: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    components: Option<Vec<ThreefiveComponent>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    break_auto_return: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    break_duration: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    break_duration_ticks: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unique_program_id: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avail_num: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avail_expected: Option<u8>,
+}
+impl ThreefiveSplice {
+    fn cancelled(splice_event_id: u32) -> ThreefiveSplice {
+        ThreefiveSplice {
+            splice_event_id,
+            splice_event_cancel_indicator: true,
+            ..Default::default()
+        }
+    }
+
+    fn with_details(
+        mut self,
+        network_indicator: &NetworkIndicator,
+        duration: &Option<SpliceDuration>,
+        unique_program_id: u16,
+        avail_num: u8,
+        avails_expected: u8,
+    ) -> ThreefiveSplice {
+        self.out_of_network_indicator = Some(matches!(network_indicator, NetworkIndicator::Out));
+        self.duration_flag = Some(duration.is_some());
+        if let Some(d) = duration {
+            self.break_auto_return = Some(matches!(d.return_mode, ReturnMode::Automatic));
+            self.break_duration = Some(seconds(d.duration));
+            self.break_duration_ticks = Some(d.duration);
+        }
+        self.unique_program_id = Some(unique_program_id);
+        self.avail_num = Some(avail_num);
+        self.avail_expected = Some(avails_expected);
+        self
+    }
+
+    fn insert(splice_event_id: u32, detail: &SpliceInsert) -> ThreefiveSplice {
+        let result = ThreefiveSplice::cancelled(splice_event_id);
+        let SpliceInsert::Insert {
+            network_indicator,
+            splice_mode,
+            duration,
+            unique_program_id,
+            avail_num,
+            avails_expected,
+        } = detail
+        else {
+            return result;
+        };
+        let mut result = ThreefiveSplice {
+            splice_event_cancel_indicator: false,
+            ..result
+        }
+        .with_details(
+            network_indicator,
+            duration,
+            *unique_program_id,
+            *avail_num,
+            *avails_expected,
+        );
+        match splice_mode {
+            SpliceMode::Program(time) => {
+                let immediate = matches!(time, SpliceTime::Immediate);
+                result.program_splice_flag = Some(true);
+                result.splice_immediate_flag = Some(immediate);
+                if !immediate {
+                    result.time = Some(ThreefiveTime::from(time));
+                }
+            }
+            SpliceMode::Components(components) => {
+                result.program_splice_flag = Some(false);
+                result.splice_immediate_flag = Some(
+                    components
+                        .iter()
+                        .any(|c| matches!(c.splice_time, SpliceTime::Immediate)),
+                );
+                result.components = Some(
+                    components
+                        .iter()
+                        .map(|c| {
+                            let time = ThreefiveTime::from(&c.splice_time);
+                            ThreefiveComponent {
+                                component_tag: c.component_tag,
+                                pts_time: time.pts_time,
+                                pts_time_ticks: time.pts_time_ticks,
+                                utc_splice_time: None,
+                            }
+                        })
+                        .collect(),
+                );
+            }
+        }
+        result
+    }
+
+    fn scheduled(splice_event_id: u32, detail: &ScheduledSplice) -> ThreefiveSplice {
+        let result = ThreefiveSplice::cancelled(splice_event_id);
+        let ScheduledSplice::Insert {
+            network_indicator,
+            splice_mode,
+            duration,
+            unique_program_id,
+            avail_num,
+            avails_expected,
+        } = detail
+        else {
+            return result;
+        };
+        let mut result = ThreefiveSplice {
+            splice_event_cancel_indicator: false,
+            ..result
+        }
+        .with_details(
+            network_indicator,
+            duration,
+            *unique_program_id,
+            *avail_num,
+            *avails_expected,
+        );
+        match splice_mode {
+            ScheduledSpliceMode::Program(t) => {
+                result.program_splice_flag = Some(true);
+                result.utc_splice_time = Some(t.0);
+            }
+            ScheduledSpliceMode::Components(components) => {
+                result.program_splice_flag = Some(false);
+                result.components = Some(
+                    components
+                        .iter()
+                        .map(|c| ThreefiveComponent {
+                            component_tag: c.component_tag,
+                            pts_time: None,
+                            pts_time_ticks: None,
+                            utc_splice_time: Some(c.utc_splice_time.0),
+                        })
+                        .collect(),
+                );
+            }
+        }
+        result
+    }
+}
+
+#[derive(Serialize)]
+struct ThreefiveComponent {
+    component_tag: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pts_time: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pts_time_ticks: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    utc_splice_time: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct ThreefiveDescriptor {
+    tag: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    descriptor_length: Option<u8>,
+    name: &'static str,
+    identifier: String,
+    #[serde(flatten)]
+    detail: ThreefiveDescriptorDetail,
+}
+impl From<&SpliceDescriptor> for ThreefiveDescriptor {
+    fn from(d: &SpliceDescriptor) -> ThreefiveDescriptor {
+        let (tag, name, detail) = match d {
+            SpliceDescriptor::AvailDescriptor { provider_avail_id } => (
+                0,
+                "Avail Descriptor",
+                ThreefiveDescriptorDetail::Avail {
+                    provider_avail_id: *provider_avail_id,
+                },
+            ),
+            SpliceDescriptor::DTMFDescriptor {
+                preroll,
+                dtmf_chars,
+            } => (
+                1,
+                "DTMF Descriptor",
+                ThreefiveDescriptorDetail::Dtmf {
+                    preroll: *preroll,
+                    dtmf_count: dtmf_chars.len(),
+                    dtmf_chars: dtmf_chars
+                        .iter()
+                        .map(|c| char::from(*c).to_string())
+                        .collect(),
+                },
+            ),
+            SpliceDescriptor::SegmentationDescriptor {
+                segmentation_event_id,
+                descriptor_detail,
+            } => (
+                2,
+                "Segmentation Descriptor",
+                ThreefiveDescriptorDetail::Segmentation(Box::new(ThreefiveSegmentation::new(
+                    *segmentation_event_id,
+                    descriptor_detail,
+                ))),
+            ),
+            SpliceDescriptor::TimeDescriptor {
+                tai_seconds,
+                tai_nanoseconds,
+                utc_offset,
+            } => (
+                3,
+                "Time Descriptor",
+                ThreefiveDescriptorDetail::Time {
+                    tai_seconds: *tai_seconds,
+                    tai_ns: *tai_nanoseconds,
+                    utc_offset: *utc_offset,
+                },
+            ),
+            SpliceDescriptor::AudioDescriptor { components } => (
+                4,
+                "Audio Descriptor",
+                ThreefiveDescriptorDetail::Audio {
+                    components: components
+                        .iter()
+                        .map(|c| ThreefiveAudioComponent {
+                            component_tag: c.component_tag,
+                            iso_code: String::from_utf8_lossy(&c.iso_code).into_owned(),
+                            bit_stream_mode: c.bit_stream_mode,
+                            num_channels: c.num_channels,
+                            full_srvc_audio: c.full_srvc_audio,
+                        })
+                        .collect(),
+                },
+            ),
+            SpliceDescriptor::Reserved {
+                tag,
+                identifier,
+                private_bytes,
+            } => {
+                return ThreefiveDescriptor {
+                    tag: *tag,
+                    descriptor_length: None,
+                    name: "Private Descriptor",
+                    identifier: String::from_utf8_lossy(identifier).into_owned(),
+                    detail: ThreefiveDescriptorDetail::Private {
+                        private_data: format!("0x{}", hex(private_bytes)),
+                    },
+                }
+            }
+        };
+        ThreefiveDescriptor {
+            tag,
+            descriptor_length: None,
+            name,
+            identifier: "CUEI".to_string(),
+            detail,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum ThreefiveDescriptorDetail {
+    Avail {
+        provider_avail_id: u32,
+    },
+    Dtmf {
+        preroll: u8,
+        dtmf_count: usize,
+        dtmf_chars: Vec<String>,
+    },
+    Segmentation(Box<ThreefiveSegmentation>),
+    Time {
+        tai_seconds: u64,
+        tai_ns: u32,
+        utc_offset: u16,
+    },
+    Audio {
+        components: Vec<ThreefiveAudioComponent>,
+    },
+    Private {
+        private_data: String,
+    },
+}
+
+#[derive(Serialize)]
+struct ThreefiveAudioComponent {
+    component_tag: u8,
+    iso_code: String,
+    bit_stream_mode: u8,
+    num_channels: u8,
+    full_srvc_audio: bool,
+}
+
+#[derive(Serialize, Default)]
+struct ThreefiveSegmentation {
+    segmentation_event_id: String,
+    segmentation_event_cancel_indicator: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    program_segmentation_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segmentation_duration_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delivery_not_restricted_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    web_delivery_allowed_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    no_regional_blackout_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive_allowed_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device_restrictions: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    components: Option<Vec<ThreefiveSegmentationComponent>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segmentation_duration: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segmentation_duration_ticks: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segmentation_message: Option<&'static str>,
+    #[serde(flatten)]
+    upid: Option<ThreefiveUpid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segmentation_type_id: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segment_num: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segments_expected: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sub_segment_num: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sub_segments_expected: Option<u8>,
+}
+impl ThreefiveSegmentation {
+    fn new(segmentation_event_id: u32, detail: &SegmentationDescriptor) -> ThreefiveSegmentation {
+        let mut result = ThreefiveSegmentation {
+            segmentation_event_id: py_hex(segmentation_event_id.into()),
+            segmentation_event_cancel_indicator: true,
+            ..Default::default()
+        };
+        let SegmentationDescriptor::Insert {
+            delivery_restrictions,
+            segmentation_mode,
+            segmentation_duration,
+            segmentation_upid,
+            segmentation_type_id,
+            segment_num,
+            segments_expected,
+            sub_segments,
+            ..
+        } = detail
+        else {
+            return result;
+        };
+        result.segmentation_event_cancel_indicator = false;
+        result.program_segmentation_flag =
+            Some(matches!(segmentation_mode, SegmentationMode::Program));
+        result.segmentation_duration_flag = Some(segmentation_duration.is_some());
+        result.delivery_not_restricted_flag = Some(matches!(
+            delivery_restrictions,
+            DeliveryRestrictionFlags::None
+        ));
+        if let DeliveryRestrictionFlags::DeliveryRestrictions {
+            web_delivery_allowed_flag,
+            no_regional_blackout_flag,
+            archive_allowed_flag,
+            device_restrictions,
+        } = delivery_restrictions
+        {
+            result.web_delivery_allowed_flag = Some(*web_delivery_allowed_flag);
+            result.no_regional_blackout_flag = Some(*no_regional_blackout_flag);
+            result.archive_allowed_flag = Some(*archive_allowed_flag);
+            result.device_restrictions = Some(match device_restrictions {
+                DeviceRestrictions::RestrictGroup0 => "Restrict Group 0",
+                DeviceRestrictions::RestrictGroup1 => "Restrict Group 1",
+                DeviceRestrictions::RestrictGroup2 => "Restrict Group 2",
+                DeviceRestrictions::None => "No Restrictions",
+            });
+        }
+        if let SegmentationMode::Component { components } = segmentation_mode {
+            result.components = Some(
+                components
+                    .iter()
+                    .map(|c| ThreefiveSegmentationComponent {
+                        component_tag: c.component_tag,
+                        pts_offset: seconds(c.pts_offset),
+                        pts_offset_ticks: c.pts_offset,
+                    })
+                    .collect(),
+            );
+        }
+        result.segmentation_duration = segmentation_duration.map(seconds);
+        result.segmentation_duration_ticks = *segmentation_duration;
+        result.segmentation_message = segmentation_message(segmentation_type_id.id());
+        result.upid = Some(ThreefiveUpid::new(segmentation_upid));
+        result.segmentation_type_id = Some(segmentation_type_id.id());
+        result.segment_num = Some(*segment_num);
+        result.segments_expected = Some(*segments_expected);
+        if let Some(sub_segments) = sub_segments {
+            result.sub_segment_num = Some(sub_segments.sub_segment_num);
+            result.sub_segments_expected = Some(sub_segments.sub_segments_expected);
+        }
+        result
+    }
+}
+
+#[derive(Serialize)]
+struct ThreefiveSegmentationComponent {
+    component_tag: u8,
+    pts_offset: f64,
+    pts_offset_ticks: u64,
+}
+
+#[derive(Serialize)]
+struct ThreefiveUpid {
+    segmentation_upid_type: u8,
+    segmentation_upid_type_name: &'static str,
+    segmentation_upid_length: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segmentation_upid: Option<ThreefiveUpidValue>,
+}
+impl ThreefiveUpid {
+    fn new(upid: &SegmentationUpid) -> ThreefiveUpid {
+        let upid_type = upid.segmentation_upid_type().id();
+        let mut bytes = vec![];
+        // encoding a single UPID can only fail for a MID() or ATSC with out of range fields
+        let _ = crate::encode::write_upid(&mut bytes, upid);
+        let text = |s: &str| Some(ThreefiveUpidValue::Text(s.to_string()));
+        let segmentation_upid = match upid {
+            SegmentationUpid::None => None,
+            SegmentationUpid::Isci(v) => text(&v.0),
+            SegmentationUpid::AdID(v) => text(&v.0),
+            SegmentationUpid::TID(v) => text(&v.0),
+            SegmentationUpid::ADI(v) => text(&v.0),
+            SegmentationUpid::URI(v) => text(v.0.as_str()),
+            SegmentationUpid::ADS(v) => text(&String::from_utf8_lossy(&v.0)),
+            // threefive omits the trailing check character
+            SegmentationUpid::EIDR(v) => {
+                let canonical = v.to_canonical();
+                text(&canonical[..canonical.len() - 2])
+            }
+            SegmentationUpid::ATSC(v) => Some(ThreefiveUpidValue::Atsc {
+                tsid: v.tsid,
+                reserved: 3,
+                end_of_day: v.end_of_day,
+                unique_for: v.unique_for,
+                content_id: format!("0x{}", hex(&v.content_id)),
+            }),
+            SegmentationUpid::MPU(v) => Some(ThreefiveUpidValue::Mpu {
+                format_identifier: format!("0x{}", hex(&v.format_identifier().unwrap_or_default())),
+                private_data: format!("0x{}", hex(v.private_data())),
+            }),
+            SegmentationUpid::MID(upids) => Some(ThreefiveUpidValue::Mid(
+                upids.iter().map(ThreefiveUpid::new).collect(),
+            )),
+            _ => Some(ThreefiveUpidValue::Text(format!("0x{}", hex(&bytes)))),
+        };
+        ThreefiveUpid {
+            segmentation_upid_type: upid_type,
+            segmentation_upid_type_name: upid_type_name(upid_type),
+            segmentation_upid_length: bytes.len(),
+            segmentation_upid,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum ThreefiveUpidValue {
+    Text(String),
+    Atsc {
+        #[serde(rename = "TSID")]
+        tsid: u16,
+        reserved: u8,
+        end_of_day: u8,
+        unique_for: u16,
+        content_id: String,
+    },
+    Mpu {
+        format_identifier: String,
+        private_data: String,
+    },
+    Mid(Vec<ThreefiveUpid>),
+}
+
+fn upid_type_name(upid_type: u8) -> &'static str {
+    match upid_type {
+        0x00 => "No UPID",
+        0x01 | 0x02 => "Deprecated",
+        0x03 => "AdID",
+        0x04 => "UMID",
+        0x05 | 0x06 => "ISAN",
+        0x07 => "TID",
+        0x08 => "AiringID",
+        0x09 => "ADI",
+        0x0a => "EIDR",
+        0x0b => "ATSC",
+        0x0c => "MPU",
+        0x0d => "MID",
+        0x0e => "ADS Info",
+        0x0f => "URI",
+        0x10 => "UUID",
+        0x11 => "SCR",
+        _ => "Reserved",
+    }
+}
+
+/// The description `threefive` gives to a `segmentation_type_id` value
+fn segmentation_message(segmentation_type_id: u8) -> Option<&'static str> {
+    Some(match segmentation_type_id {
+        0x00 => "Not Indicated",
+        0x01 => "Content Identification",
+        0x02 => "Call Ad Server",
+        0x10 => "Program Start",
+        0x11 => "Program End",
+        0x12 => "Program Early Termination",
+        0x13 => "Program Breakaway",
+        0x14 => "Program Resumption",
+        0x15 => "Program Runover Planned",
+        0x16 => "Program Runover Unplanned",
+        0x17 => "Program Overlap Start",
+        0x18 => "Program Blackout Override",
+        0x19 => "Program Start - In Progress",
+        0x20 => "Chapter Start",
+        0x21 => "Chapter End",
+        0x22 => "Break Start",
+        0x23 => "Break End",
+        0x24 => "Opening Credit Start",
+        0x25 => "Opening Credit End",
+        0x26 => "Closing Credit Start",
+        0x27 => "Closing Credit End",
+        0x30 => "Provider Advertisement Start",
+        0x31 => "Provider Advertisement End",
+        0x32 => "Distributor Advertisement Start",
+        0x33 => "Distributor Advertisement End",
+        0x34 => "Provider Placement Opportunity Start",
+        0x35 => "Provider Placement Opportunity End",
+        0x36 => "Distributor Placement Opportunity Start",
+        0x37 => "Distributor Placement Opportunity End",
+        0x38 => "Provider Overlay Placement Opportunity Start",
+        0x39 => "Provider Overlay Placement Opportunity End",
+        0x3a => "Distributor Overlay Placement Opportunity Start",
+        0x3b => "Distributor Overlay Placement Opportunity End",
+        0x3c => "Provider Promo Start",
+        0x3d => "Provider Promo End",
+        0x3e => "Distributor Promo Start",
+        0x3f => "Distributor Promo End",
+        0x40 => "Unscheduled Event Start",
+        0x41 => "Unscheduled Event End",
+        0x42 => "Alternate Content Opportunity Start",
+        0x43 => "Alternate Content Opportunity End",
+        0x44 => "Provider Ad Block Start",
+        0x45 => "Provider Ad Block End",
+        0x46 => "Distributor Ad Block Start",
+        0x47 => "Distributor Ad Block End",
+        0x50 => "Network Start",
+        0x51 => "Network End",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use hex_literal::*;
+
+    #[test]
+    fn time_signal() {
+        let section = SpliceInfoSection::parse(&TIME_SIGNAL).unwrap();
+        assert_eq!(
+            serde_json::to_value(StandardJson(&section)).unwrap(),
+            serde_json::json!({
+                "protocolVersion": 0,
+                "ptsAdjustment": 0,
+                "tier": 4095,
+                "timeSignal": { "spliceTime": { "ptsTime": 0x1_592d_03c0_u64 } },
+                "spliceDescriptors": [
+                    {
+                        "segmentationDescriptor": {
+                            "segmentationEventId": 1,
+                            "segmentationEventCancelIndicator": false,
+                            "segmentationTypeId": 16,
+                            "segmentNum": 1,
+                            "segmentsExpected": 1,
+                            "segmentationUpids": []
+                        }
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn splice_insert() {
+        let section = SpliceInfoSection::parse(&SPLICE_INSERT).unwrap();
+        assert_eq!(
+            serde_json::to_value(StandardJson(&section)).unwrap()["spliceInsert"],
+            serde_json::json!({
+                "spliceEventId": 1,
+                "spliceEventCancelIndicator": false,
+                "outOfNetworkIndicator": true,
+                "spliceImmediateFlag": false,
+                "uniqueProgramId": 1,
+                "availNum": 1,
+                "availsExpected": 1,
+                "program": { "spliceTime": { "ptsTime": 0x2d14_2b00 } },
+                "breakDuration": { "autoReturn": true, "duration": 0x0123_d308 }
+            })
+        );
+    }
+
+    #[test]
+    fn threefive_splice_insert() {
+        let section = SpliceInfoSection::parse(&SPLICE_INSERT).unwrap();
+        assert_eq!(
+            serde_json::to_value(ThreefiveJson::encoded(&section)).unwrap(),
+            serde_json::json!({
+                "info_section": {
+                    "table_id": "0xfc",
+                    "section_syntax_indicator": false,
+                    "private": false,
+                    "sap_type": "0x3",
+                    "sap_details": "No Sap Type",
+                    "section_length": 37,
+                    "protocol_version": 0,
+                    "encrypted_packet": false,
+                    "encryption_algorithm": 0,
+                    "pts_adjustment_ticks": 0,
+                    "pts_adjustment": 0.0,
+                    "cw_index": "0x0",
+                    "tier": "0xfff",
+                    "splice_command_length": 20,
+                    "splice_command_type": 5,
+                    "descriptor_loop_length": 0,
+                    "crc": "0x7f157a49"
+                },
+                "command": {
+                    "command_length": 20,
+                    "command_type": 5,
+                    "name": "Splice Insert",
+                    "splice_event_id": 1,
+                    "splice_event_cancel_indicator": false,
+                    "out_of_network_indicator": true,
+                    "program_splice_flag": true,
+                    "duration_flag": true,
+                    "splice_immediate_flag": false,
+                    "time_specified_flag": true,
+                    "pts_time": 8403.293867,
+                    "pts_time_ticks": 756296448,
+                    "break_auto_return": true,
+                    "break_duration": 212.5,
+                    "break_duration_ticks": 19125000,
+                    "unique_program_id": 1,
+                    "avail_num": 1,
+                    "avail_expected": 1
+                },
+                "descriptors": []
+            })
+        );
+    }
+
+    #[test]
+    fn threefive_original_bytes() {
+        // a time_signal() whose splice_time() has its reserved bits set to 0, which encode()
+        // would instead set to 1
+        let mut data =
+            hex!("fc302700000000000000fff00506 81592d03c0 0011020f43554549000000017fbf0000100101")
+                .to_vec();
+        let crc = mpeg2ts_reader::mpegts_crc::sum32(&data);
+        data.extend_from_slice(&crc.to_be_bytes());
+        let section = SpliceInfoSection::parse(&data).unwrap();
+        let json = serde_json::to_value(ThreefiveJson::new(&section, &data)).unwrap();
+        assert_eq!(json["info_section"]["crc"], format!("{:#x}", crc));
+        assert_eq!(json["info_section"]["section_length"], 0x27);
+        assert_eq!(json["info_section"]["descriptor_loop_length"], 0x11);
+        assert_eq!(json["descriptors"][0]["descriptor_length"], 0x0f);
+        let json = serde_json::to_value(ThreefiveJson::encoded(&section)).unwrap();
+        assert_ne!(json["info_section"]["crc"], format!("{:#x}", crc));
+    }
+
+    #[test]
+    fn threefive_segmentation_descriptor() {
+        let section = SpliceInfoSection::parse(&TIME_SIGNAL).unwrap();
+        let json = serde_json::to_value(ThreefiveJson::encoded(&section)).unwrap();
+        assert_eq!(
+            json["command"],
+            serde_json::json!({
+                "command_length": 5,
+                "command_type": 6,
+                "name": "Time Signal",
+                "time_specified_flag": true,
+                "pts_time": 64345.44,
+                "pts_time_ticks": 5791089600_u64
+            })
+        );
+        assert_eq!(
+            json["descriptors"],
+            serde_json::json!([{
+                "tag": 2,
+                "descriptor_length": 15,
+                "name": "Segmentation Descriptor",
+                "identifier": "CUEI",
+                "segmentation_event_id": "0x1",
+                "segmentation_event_cancel_indicator": false,
+                "program_segmentation_flag": true,
+                "segmentation_duration_flag": false,
+                "delivery_not_restricted_flag": true,
+                "segmentation_message": "Program Start",
+                "segmentation_upid_type": 0,
+                "segmentation_upid_type_name": "No UPID",
+                "segmentation_upid_length": 0,
+                "segmentation_type_id": 16,
+                "segment_num": 1,
+                "segments_expected": 1
+            }])
+        );
+    }
+}
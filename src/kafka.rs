@@ -0,0 +1,194 @@
+//! A `SpliceInfoProcessor` which publishes each cue, serialized as JSON, to a Kafka topic.
+//!
+//! Available when this crate's `kafka` feature is enabled.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! # use rdkafka::config::ClientConfig;
+//! # use rdkafka::producer::BaseProducer;
+//! # use scte35_reader::kafka::{KafkaProcessor, MessageKey};
+//! let producer: BaseProducer = ClientConfig::new()
+//!     .set("bootstrap.servers", "localhost:9092")
+//!     .create()
+//!     .unwrap();
+//! let processor = KafkaProcessor::new(producer, "cues").with_key(MessageKey::Upid);
+//! // ...then pass to Scte35SectionProcessor::new(), or create from a demux::Scte35DemuxContext
+//! // factory, as with any other SpliceInfoProcessor
+//! ```
+
+use crate::encode::write_upid;
+use crate::{
+    SegmentationDescriptor, SegmentationUpid, SpliceCommand, SpliceDescriptor, SpliceDescriptors,
+    SpliceInfoHeader, SpliceInfoProcessor, SpliceInfoSection,
+};
+use hex_slice::AsHex;
+use rdkafka::producer::{BaseProducer, BaseRecord};
+use std::time::Duration;
+
+/// Selects the value used as the key of each Kafka message, which determines how the messages
+/// are partitioned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKey {
+    /// Messages are published without a key
+    None,
+    /// The `splice_event_id` of a `splice_insert()` command, or otherwise the
+    /// `segmentation_event_id` of the first `segmentation_descriptor()`, in decimal
+    EventId,
+    /// The `segmentation_upid_type` of the first `segmentation_descriptor()` which has a
+    /// `segmentation_upid`, and the encoded bytes of that UPID, in hex and separated by a colon
+    /// (e.g. `01:616263` for a _User Defined_ UPID of `abc`)
+    Upid,
+}
+impl MessageKey {
+    /// Produces the key for the given section, or `None` if the section does not carry the
+    /// value this `MessageKey` selects.
+    pub fn key_for(&self, section: &SpliceInfoSection) -> Option<String> {
+        match self {
+            MessageKey::None => None,
+            MessageKey::EventId => match section.splice_command {
+                SpliceCommand::SpliceInsert {
+                    splice_event_id, ..
+                } => Some(splice_event_id.to_string()),
+                _ => section.splice_descriptors.iter().find_map(|d| match d {
+                    SpliceDescriptor::SegmentationDescriptor {
+                        segmentation_event_id,
+                        ..
+                    } => Some(segmentation_event_id.to_string()),
+                    _ => None,
+                }),
+            },
+            MessageKey::Upid => section.splice_descriptors.iter().find_map(|d| match d {
+                SpliceDescriptor::SegmentationDescriptor {
+                    descriptor_detail:
+                        SegmentationDescriptor::Insert {
+                            segmentation_upid, ..
+                        },
+                    ..
+                } if !matches!(segmentation_upid, SegmentationUpid::None) => {
+                    upid_key(segmentation_upid)
+                }
+                _ => None,
+            }),
+        }
+    }
+}
+
+/// The key of `MessageKey::Upid` for the given UPID, or `None` if it could not be encoded
+fn upid_key(upid: &SegmentationUpid) -> Option<String> {
+    let mut bytes = vec![];
+    if let Err(e) = write_upid(&mut bytes, upid) {
+        error!("failed to encode UPID for message key: {:?}", e);
+        return None;
+    }
+    Some(format!(
+        "{:02x}:{:02x}",
+        upid.segmentation_upid_type().id(),
+        bytes.plain_hex(false)
+    ))
+}
+
+/// Publishes each `splice_info_section()` it is given to a Kafka topic, as the JSON
+/// serialization of a `SpliceInfoSection`.
+///
+/// Messages are queued with the producer without blocking; delivery failures are logged.  The
+/// application remains responsible for calling `flush()` on the producer (available via
+/// `producer()`) before shutting down.
+pub struct KafkaProcessor {
+    producer: BaseProducer,
+    topic: String,
+    key: MessageKey,
+}
+impl KafkaProcessor {
+    /// Creates a processor publishing to the given topic, keying messages by
+    /// `MessageKey::EventId`.
+    pub fn new(producer: BaseProducer, topic: impl Into<String>) -> KafkaProcessor {
+        KafkaProcessor {
+            producer,
+            topic: topic.into(),
+            key: MessageKey::EventId,
+        }
+    }
+
+    /// Chooses the value used as the key of each published message
+    pub fn with_key(mut self, key: MessageKey) -> KafkaProcessor {
+        self.key = key;
+        self
+    }
+
+    /// The producer used to publish messages, e.g. for use in calling `flush()` at shutdown
+    pub fn producer(&self) -> &BaseProducer {
+        &self.producer
+    }
+}
+impl SpliceInfoProcessor for KafkaProcessor {
+    fn process(
+        &self,
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        let section = match SpliceInfoSection::from_parts(&header, command, &descriptors) {
+            Ok(section) => section,
+            Err(e) => {
+                error!("not publishing section with bad descriptor: {:?}", e);
+                return;
+            }
+        };
+        let payload = match serde_json::to_vec(&section) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("failed to serialize section: {}", e);
+                return;
+            }
+        };
+        let key = self.key.key_for(&section);
+        let mut record: BaseRecord<'_, str, [u8]> = BaseRecord::to(&self.topic).payload(&payload);
+        if let Some(ref key) = key {
+            record = record.key(key.as_str());
+        }
+        if let Err((e, _)) = self.producer.send(record) {
+            error!("failed to queue message for topic {}: {}", self.topic, e);
+        }
+        // serve delivery callbacks, without waiting
+        self.producer.poll(Duration::ZERO);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use crate::LogDiagnostics;
+    use hex_literal::*;
+
+    #[test]
+    fn keys() {
+        let splice_insert = SpliceInfoSection::parse(&SPLICE_INSERT).unwrap();
+        assert_eq!(
+            MessageKey::EventId.key_for(&splice_insert),
+            Some("1".to_string())
+        );
+        assert_eq!(MessageKey::Upid.key_for(&splice_insert), None);
+        assert_eq!(MessageKey::None.key_for(&splice_insert), None);
+
+        // time_signal() with a segmentation_descriptor() for event 1, having no UPID
+        let time_signal = SpliceInfoSection::parse(&TIME_SIGNAL).unwrap();
+        assert_eq!(
+            MessageKey::EventId.key_for(&time_signal),
+            Some("1".to_string())
+        );
+        assert_eq!(MessageKey::Upid.key_for(&time_signal), None);
+
+        let mut with_upid = time_signal;
+        with_upid.splice_descriptors = vec![SpliceDescriptor::parse(
+            &hex!("0212 43554549 00000002 7f bf 0103616263 30 00 00"),
+            &LogDiagnostics,
+        )
+        .unwrap()];
+        assert_eq!(
+            MessageKey::Upid.key_for(&with_upid),
+            Some("01:616263".to_string())
+        );
+    }
+}
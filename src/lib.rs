@@ -51,7 +51,7 @@
 //! Output:
 //!
 //! ```plain
-//! SpliceInfoHeader { protocol_version: 0, encrypted_packet: false, encryption_algorithm: None, pts_adjustment: 0, cw_index: 0, tier: 4095 } SpliceInsert {
+//! SpliceInfoHeader { sap_type: Unspecified, protocol_version: 0, encrypted_packet: false, encryption_algorithm: None, pts_adjustment: 0, cw_index: 0, tier: Tier(4095) } SpliceInsert {
 //!     splice_event_id: 1,
 //!     reserved: 127,
 //!     splice_detail: Insert {
@@ -79,10 +79,87 @@
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms, future_incompatible)]
 
+// Logging goes via these wrappers so that, when the `log` feature is disabled, messages are
+// discarded without leaving their arguments unused.
+macro_rules! error {
+    (target: $target:expr, $($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        log::error!(target: $target, $($arg)+);
+        #[cfg(not(feature = "log"))]
+        let _ = ($target, format_args!($($arg)+));
+    }};
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        log::error!($($arg)+);
+        #[cfg(not(feature = "log"))]
+        let _ = format_args!($($arg)+);
+    }};
+}
+macro_rules! debug {
+    (target: $target:expr, $($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        log::debug!(target: $target, $($arg)+);
+        #[cfg(not(feature = "log"))]
+        let _ = ($target, format_args!($($arg)+));
+    }};
+}
+macro_rules! warn {
+    (target: $target:expr, $($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        log::warn!(target: $target, $($arg)+);
+        #[cfg(not(feature = "log"))]
+        let _ = ($target, format_args!($($arg)+));
+    }};
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        log::warn!($($arg)+);
+        #[cfg(not(feature = "log"))]
+        let _ = format_args!($($arg)+);
+    }};
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+pub mod builder;
+pub mod conformance;
+pub mod cue;
+pub mod dash;
+pub mod demux;
+pub mod edit;
+#[cfg(feature = "emsg")]
+pub mod emsg;
+pub mod encode;
+#[cfg(feature = "esam")]
+pub mod esam;
+pub mod explain;
+pub mod hls;
+pub mod id3;
+pub mod incremental;
+pub mod inject;
+pub mod json;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod metrics;
+pub mod packetize;
+pub mod presets;
+pub mod probe;
+pub mod registry;
+#[cfg(feature = "replay")]
+pub mod replay;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+#[cfg(feature = "tokio")]
+pub mod stream;
+#[cfg(test)]
+pub(crate) mod test_util;
+pub mod time;
+pub mod tracker;
 pub mod upid;
+pub mod view;
+#[cfg(feature = "xml")]
+pub mod xml;
 
 use bitreader::BitReaderError;
-use log::error;
 use mpeg2ts_reader::demultiplex;
 use mpeg2ts_reader::psi;
 use mpeg2ts_reader::smptera::FormatIdentifier;
@@ -94,11 +171,23 @@ use std::marker;
 pub const SCTE35_STREAM_TYPE: mpeg2ts_reader::StreamType =
     mpeg2ts_reader::StreamType::Private(0x86);
 
+/// The largest `section_length` value permitted for a `splice_info_section()`, per _SCTE-35,
+/// section 9.6_.
+pub const MAX_SECTION_LENGTH: usize = 4093;
+
+/// The `descriptor_tag` value of the `cue_identifier_descriptor()` defined in _SCTE-35, section
+/// 8.2_.
+pub const CUE_IDENTIFIER_DESCRIPTOR_TAG: u8 = 0x8a;
+
 /// Utility function to search the PTM section for a `CUEI` registration descriptor per
 /// _SCTE-35, section 8.1_, which indicates that streams with `stream_type` equal to the private
 /// value `0x86` within this PMT section are formatted according to SCTE-35.
 ///
-/// Returns `true` if the descriptor is attached to the given PMT section and `false` otherwise.
+/// Many muxers instead (or additionally) attach a `cue_identifier_descriptor()` to the SCTE-35
+/// elementary stream itself (_SCTE-35, section 8.2_), so the presence of that descriptor on any
+/// of the PMT's streams is also accepted.
+///
+/// Returns `true` if either descriptor is present in the given PMT section and `false` otherwise.
 pub fn is_scte35(pmt: &mpeg2ts_reader::psi::pmt::PmtSection<'_>) -> bool {
     for d in pmt.descriptors().flatten() {
         if let mpeg2ts_reader::descriptor::CoreDescriptors::Registration(reg) = d {
@@ -107,9 +196,136 @@ pub fn is_scte35(pmt: &mpeg2ts_reader::psi::pmt::PmtSection<'_>) -> bool {
             }
         }
     }
-    false
+    pmt.streams()
+        .any(|stream_info| cue_stream_type(&stream_info).is_some())
+}
+
+/// The elementary PIDs of the SCTE-35 streams in the given PMT section, being those with
+/// `stream_type` `0x86`, provided that `is_scte35()` accepts the section.
+///
+/// The PIDs are given in the order in which the PMT lists the streams; `cue_stream_type()` can
+/// help to choose between them when there are several.
+pub fn scte35_pids(pmt: &psi::pmt::PmtSection<'_>) -> Vec<mpeg2ts_reader::packet::Pid> {
+    if !is_scte35(pmt) {
+        return vec![];
+    }
+    pmt.streams()
+        .filter(|stream_info| stream_info.stream_type() == SCTE35_STREAM_TYPE)
+        .map(|stream_info| stream_info.elementary_pid())
+        .collect()
+}
+
+/// Searches the descriptors attached to the given elementary stream for a
+/// `cue_identifier_descriptor()`, returning the `cue_stream_type` value it carries.
+///
+/// Returns `None` if the stream has no such descriptor.
+pub fn cue_stream_type(stream_info: &psi::pmt::StreamInfo<'_>) -> Option<CueStreamType> {
+    for d in stream_info.descriptors().flatten() {
+        if let mpeg2ts_reader::descriptor::CoreDescriptors::UserPrivate(desc) = d {
+            if desc.tag == CUE_IDENTIFIER_DESCRIPTOR_TAG && !desc.payload.is_empty() {
+                return Some(CueStreamType::from_id(desc.payload[0]));
+            }
+        }
+    }
+    None
+}
+
+/// The `cue_stream_type` from a `cue_identifier_descriptor()`, describing which kinds of
+/// _splice-command_ may be carried on the elementary stream.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
+pub enum CueStreamType {
+    /// Only `splice_insert()`, `splice_null()` and `splice_schedule()` commands
+    InsertNullSchedule,
+    /// All message types
+    AllCommands,
+    /// `time_signal()` messages carrying `segmentation_descriptor()`
+    Segmentation,
+    /// Tiered splicing
+    TieredSplicing,
+    /// Tiered segmentation
+    TieredSegmentation,
+    Reserved(u8),
+    UserDefined(u8),
+}
+impl CueStreamType {
+    pub fn from_id(id: u8) -> CueStreamType {
+        match id {
+            0x00 => CueStreamType::InsertNullSchedule,
+            0x01 => CueStreamType::AllCommands,
+            0x02 => CueStreamType::Segmentation,
+            0x03 => CueStreamType::TieredSplicing,
+            0x04 => CueStreamType::TieredSegmentation,
+            0x05..=0x7f => CueStreamType::Reserved(id),
+            _ => CueStreamType::UserDefined(id),
+        }
+    }
+}
+
+/// The `descriptor_tag` value of the DVB `stream_identifier_descriptor()`, which associates a
+/// `component_tag` with an elementary stream.
+pub const STREAM_IDENTIFIER_DESCRIPTOR_TAG: u8 = 0x52;
+
+/// Mapping from the `component_tag` values used by component-mode splices to the PIDs of the
+/// elementary streams they identify, as declared by `stream_identifier_descriptor()` entries in
+/// a PMT.
+#[derive(Debug, Default, Clone)]
+pub struct ComponentTagMap {
+    pids: std::collections::HashMap<u8, mpeg2ts_reader::packet::Pid>,
+}
+impl ComponentTagMap {
+    /// Builds the mapping from the `stream_identifier_descriptor()` attached to each of the
+    /// elementary streams of the given PMT.  Streams without such a descriptor are ignored.
+    pub fn from_pmt(pmt: &psi::pmt::PmtSection<'_>) -> ComponentTagMap {
+        let mut pids = std::collections::HashMap::new();
+        for stream_info in pmt.streams() {
+            for d in stream_info.descriptors().flatten() {
+                if let mpeg2ts_reader::descriptor::CoreDescriptors::UserPrivate(desc) = d {
+                    if desc.tag == STREAM_IDENTIFIER_DESCRIPTOR_TAG && !desc.payload.is_empty() {
+                        pids.insert(desc.payload[0], stream_info.elementary_pid());
+                    }
+                }
+            }
+        }
+        ComponentTagMap { pids }
+    }
+
+    /// The PID of the elementary stream with the given `component_tag`, if the PMT declared one.
+    pub fn pid(&self, component_tag: u8) -> Option<mpeg2ts_reader::packet::Pid> {
+        self.pids.get(&component_tag).copied()
+    }
+
+    /// Pairs each component of a component-mode `splice_insert()` with the PID it refers to.
+    pub fn splice_components<'a>(
+        &'a self,
+        components: &'a [ComponentSplice],
+    ) -> impl Iterator<Item = (&'a ComponentSplice, Option<mpeg2ts_reader::packet::Pid>)> + 'a {
+        components
+            .iter()
+            .map(move |c| (c, self.pid(c.component_tag)))
+    }
+
+    /// Pairs each component of a component-mode `segmentation_descriptor()` with the PID it
+    /// refers to.
+    pub fn segmentation_components<'a>(
+        &'a self,
+        components: &'a [SegmentationModeComponent],
+    ) -> impl Iterator<
+        Item = (
+            &'a SegmentationModeComponent,
+            Option<mpeg2ts_reader::packet::Pid>,
+        ),
+    > + 'a {
+        components
+            .iter()
+            .map(move |c| (c, self.pid(c.component_tag)))
+    }
 }
-#[derive(Debug, PartialEq, serde_derive::Serialize)]
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
 pub enum EncryptionAlgorithm {
     None,
     DesEcb,
@@ -134,9 +350,38 @@ impl EncryptionAlgorithm {
             }
         }
     }
+
+    /// The `encryption_algorithm` value which identifies this algorithm
+    pub fn id(&self) -> u8 {
+        match *self {
+            EncryptionAlgorithm::None => 0,
+            EncryptionAlgorithm::DesEcb => 1,
+            EncryptionAlgorithm::DesCbc => 2,
+            EncryptionAlgorithm::TripleDesEde3Ecb => 3,
+            EncryptionAlgorithm::Reserved(id) => id,
+            EncryptionAlgorithm::Private(id) => id,
+        }
+    }
+}
+
+/// Formats the algorithm's description from the _SCTE-35_ table of `encryption_algorithm`
+/// values, e.g. `DES - ECB mode`, with the value appended for reserved and private algorithms
+impl std::fmt::Display for EncryptionAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            EncryptionAlgorithm::None => f.write_str("No encryption"),
+            EncryptionAlgorithm::DesEcb => f.write_str("DES - ECB mode"),
+            EncryptionAlgorithm::DesCbc => f.write_str("DES - CBC mode"),
+            EncryptionAlgorithm::TripleDesEde3Ecb => f.write_str("Triple DES EDE3 - ECB mode"),
+            EncryptionAlgorithm::Reserved(id) => write!(f, "Reserved ({:#04x})", id),
+            EncryptionAlgorithm::Private(id) => write!(f, "User private ({:#04x})", id),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, serde_derive::Serialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
 pub enum SpliceCommandType {
     SpliceNull,
     Reserved(u8),
@@ -158,6 +403,81 @@ impl SpliceCommandType {
             _ => SpliceCommandType::Reserved(id),
         }
     }
+
+    /// The `splice_command_type` value which identifies this kind of command
+    pub fn id(&self) -> u8 {
+        match *self {
+            SpliceCommandType::SpliceNull => 0x00,
+            SpliceCommandType::SpliceSchedule => 0x04,
+            SpliceCommandType::SpliceInsert => 0x05,
+            SpliceCommandType::TimeSignal => 0x06,
+            SpliceCommandType::BandwidthReservation => 0x07,
+            SpliceCommandType::PrivateCommand => 0xff,
+            SpliceCommandType::Reserved(id) => id,
+        }
+    }
+}
+
+/// Formats the command's name from the _SCTE-35_ table of `splice_command_type` values, e.g.
+/// `splice_insert`, with the value appended for reserved types
+impl std::fmt::Display for SpliceCommandType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            SpliceCommandType::SpliceNull => f.write_str("splice_null"),
+            SpliceCommandType::SpliceSchedule => f.write_str("splice_schedule"),
+            SpliceCommandType::SpliceInsert => f.write_str("splice_insert"),
+            SpliceCommandType::TimeSignal => f.write_str("time_signal"),
+            SpliceCommandType::BandwidthReservation => f.write_str("bandwidth_reservation"),
+            SpliceCommandType::PrivateCommand => f.write_str("private_command"),
+            SpliceCommandType::Reserved(id) => write!(f, "Reserved ({:#04x})", id),
+        }
+    }
+}
+
+/// The `sap_type` field of a _splice_info_section_, giving the type of the Stream Access Point
+/// at the splice point
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    serde_derive::Serialize,
+    serde_derive::Deserialize,
+)]
+pub enum SapType {
+    /// Type 1: closed GOP with no leading pictures
+    Type1,
+    /// Type 2: closed GOP with leading pictures
+    Type2,
+    /// Type 3: open GOP
+    Type3,
+    /// The type of SAP, if any, is not signalled
+    #[default]
+    Unspecified,
+}
+impl SapType {
+    /// The `SapType` for the given 2-bit `sap_type` value; any higher bits are ignored
+    pub fn from_id(id: u8) -> SapType {
+        match id & 0b11 {
+            0 => SapType::Type1,
+            1 => SapType::Type2,
+            2 => SapType::Type3,
+            _ => SapType::Unspecified,
+        }
+    }
+
+    /// The `sap_type` value which identifies this type
+    pub fn id(self) -> u8 {
+        match self {
+            SapType::Type1 => 0,
+            SapType::Type2 => 1,
+            SapType::Type3 => 2,
+            SapType::Unspecified => 3,
+        }
+    }
 }
 
 /// Header element within a SCTE-43 _splice_info_section_ containing metadata generic across all kinds of _splice-command_.
@@ -166,24 +486,46 @@ impl SpliceCommandType {
 /// methods are called.
 pub struct SpliceInfoHeader<'a> {
     buf: &'a [u8],
+    sap_type: SapType,
 }
 impl<'a> SpliceInfoHeader<'a> {
     const HEADER_LENGTH: usize = 11;
+    /// The `splice_command_length` value used by older encoders to indicate that the length of
+    /// the _splice-command_ is not given
+    const LEGACY_SPLICE_COMMAND_LENGTH: usize = 0xfff;
 
     /// Splits the given buffer into a `SpliceInfoHeader` element, and a remainder which will
     /// include the _splice-command_ itself, plus any _descriptor_loop_.
+    ///
+    /// Panics if the buffer is shorter than the 11 bytes of the header; use `try_new()` to have
+    /// malformed input reported as `Scte35Error::SectionTooShort` instead.
     pub fn new(buf: &'a [u8]) -> (SpliceInfoHeader<'a>, &'a [u8]) {
-        if buf.len() < 11 {
-            panic!("buffer too short: {} (expected 11)", buf.len());
+        match SpliceInfoHeader::try_new(buf) {
+            Ok(result) => result,
+            Err(_) => panic!("buffer too short: {} (expected 11)", buf.len()),
+        }
+    }
+
+    /// As `new()`, but failing with `Scte35Error::SectionTooShort` if the buffer is shorter than
+    /// the 11 bytes of the header.
+    pub fn try_new(buf: &'a [u8]) -> Result<(SpliceInfoHeader<'a>, &'a [u8]), Scte35Error> {
+        if buf.len() < Self::HEADER_LENGTH {
+            return Err(Scte35Error::SectionTooShort {
+                actual: buf.len(),
+                expected: Self::HEADER_LENGTH,
+            });
         }
-        let (head, tail) = buf.split_at(11);
-        (SpliceInfoHeader { buf: head }, tail)
-        // TODO: change this to return Err if the protocol_version or encrypted_packet values are
-        //       unsupported
+        let (head, tail) = buf.split_at(Self::HEADER_LENGTH);
+        let header = SpliceInfoHeader {
+            buf: head,
+            sap_type: SapType::Unspecified,
+        };
+        Ok((header, tail))
     }
 
     /// The version of the SCTE-35 data structures carried in this _splice_info_section_ (only
-    /// version `0` is supported by this library).
+    /// version `0` is supported by this library; see
+    /// `Scte35SectionProcessor::with_protocol_version_handler()`).
     pub fn protocol_version(&self) -> u8 {
         self.buf[0]
     }
@@ -213,8 +555,8 @@ impl<'a> SpliceInfoHeader<'a> {
         self.buf[6]
     }
     /// 12-bit authorization tier.
-    pub fn tier(&self) -> u16 {
-        u16::from(self.buf[7]) << 4 | u16::from(self.buf[8]) >> 4
+    pub fn tier(&self) -> Tier {
+        Tier(u16::from(self.buf[7]) << 4 | u16::from(self.buf[8]) >> 4)
     }
     /// Length in bytes of the _splice-command_ data within this message.
     pub fn splice_command_length(&self) -> u16 {
@@ -224,13 +566,20 @@ impl<'a> SpliceInfoHeader<'a> {
     pub fn splice_command_type(&self) -> SpliceCommandType {
         SpliceCommandType::from_id(self.buf[10])
     }
+    /// The `sap_type` given in the section header preceding these fields.  Since that is not part
+    /// of the buffer given to `new()`, this is `SapType::Unspecified` unless the header was
+    /// produced by a `Scte35SectionProcessor`.
+    pub fn sap_type(&self) -> SapType {
+        self.sap_type
+    }
 }
 impl<'a> serde::Serialize for SpliceInfoHeader<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let mut s = serializer.serialize_struct("SpliceInfoHeader", 6)?;
+        let mut s = serializer.serialize_struct("SpliceInfoHeader", 7)?;
+        s.serialize_field("sap_type", &self.sap_type())?;
         s.serialize_field("protocol_version", &self.protocol_version())?;
         s.serialize_field("encrypted_packet", &self.encrypted_packet())?;
         s.serialize_field("encryption_algorithm", &self.encryption_algorithm())?;
@@ -243,6 +592,7 @@ impl<'a> serde::Serialize for SpliceInfoHeader<'a> {
 impl<'a> std::fmt::Debug for SpliceInfoHeader<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut s = f.debug_struct("SpliceInfoHeader");
+        s.field("sap_type", &self.sap_type());
         s.field("protocol_version", &self.protocol_version());
         s.field("encrypted_packet", &self.encrypted_packet());
         s.field("encryption_algorithm", &self.encryption_algorithm());
@@ -253,10 +603,540 @@ impl<'a> std::fmt::Debug for SpliceInfoHeader<'a> {
     }
 }
 
+/// An owned copy of the values of a `SpliceInfoHeader`, having the same serialized form, so
+/// that serialized headers can be read back.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
+pub struct OwnedSpliceInfoHeader {
+    /// The `sap_type` from the section header, which is `SapType::Unspecified` for
+    /// serialized headers predating this field
+    #[serde(default)]
+    pub sap_type: SapType,
+    pub protocol_version: u8,
+    pub encrypted_packet: bool,
+    pub encryption_algorithm: EncryptionAlgorithm,
+    pub pts_adjustment: u64,
+    pub cw_index: u8,
+    pub tier: Tier,
+}
+impl<'a> From<&SpliceInfoHeader<'a>> for OwnedSpliceInfoHeader {
+    fn from(header: &SpliceInfoHeader<'a>) -> OwnedSpliceInfoHeader {
+        OwnedSpliceInfoHeader {
+            sap_type: header.sap_type(),
+            protocol_version: header.protocol_version(),
+            encrypted_packet: header.encrypted_packet(),
+            encryption_algorithm: header.encryption_algorithm(),
+            pts_adjustment: header.pts_adjustment(),
+            cw_index: header.cw_index(),
+            tier: header.tier(),
+        }
+    }
+}
+
+/// An owned representation of a complete _splice_info_section_, which (unlike
+/// `SpliceInfoHeader` and `SpliceDescriptors`) can be stored or sent between threads.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
+pub struct SpliceInfoSection {
+    /// The `sap_type` from the section header, which is `SapType::Unspecified` for
+    /// serialized sections predating this field
+    #[serde(default)]
+    pub sap_type: SapType,
+    pub protocol_version: u8,
+    pub encrypted_packet: bool,
+    pub encryption_algorithm: EncryptionAlgorithm,
+    pub pts_adjustment: u64,
+    pub cw_index: u8,
+    pub tier: Tier,
+    pub splice_command: SpliceCommand,
+    pub splice_descriptors: Vec<SpliceDescriptor>,
+}
+impl SpliceInfoSection {
+    /// Builds an owned section from the values given to `SpliceInfoProcessor::process()`,
+    /// failing if any of the descriptors can not be parsed.
+    pub fn from_parts(
+        header: &SpliceInfoHeader<'_>,
+        splice_command: SpliceCommand,
+        descriptors: &SpliceDescriptors<'_>,
+    ) -> Result<SpliceInfoSection, SpliceDescriptorErr> {
+        let splice_descriptors = descriptors.into_iter().collect::<Result<_, _>>()?;
+        Ok(Self::from_header(
+            header,
+            splice_command,
+            splice_descriptors,
+        ))
+    }
+
+    /// As `from_parts()`, but rather than failing, omits any descriptors which can not be
+    /// parsed from the section, returning the problems found with them alongside it.  The
+    /// errors identify the position of each omitted descriptor (see
+    /// `SpliceDescriptorErr::Descriptor`).
+    pub fn from_parts_recovering(
+        header: &SpliceInfoHeader<'_>,
+        splice_command: SpliceCommand,
+        descriptors: &SpliceDescriptors<'_>,
+    ) -> (SpliceInfoSection, Vec<SpliceDescriptorErr>) {
+        let mut errors = vec![];
+        let splice_descriptors = descriptors
+            .into_iter()
+            .filter_map(|d| d.map_err(|e| errors.push(e)).ok())
+            .collect();
+        let section = Self::from_header(header, splice_command, splice_descriptors);
+        (section, errors)
+    }
+
+    fn from_header(
+        header: &SpliceInfoHeader<'_>,
+        splice_command: SpliceCommand,
+        splice_descriptors: Vec<SpliceDescriptor>,
+    ) -> SpliceInfoSection {
+        SpliceInfoSection {
+            sap_type: header.sap_type(),
+            protocol_version: header.protocol_version(),
+            encrypted_packet: header.encrypted_packet(),
+            encryption_algorithm: header.encryption_algorithm(),
+            pts_adjustment: header.pts_adjustment(),
+            cw_index: header.cw_index(),
+            tier: header.tier(),
+            splice_command,
+            splice_descriptors,
+        }
+    }
+
+    /// Parses a complete section, from `table_id` through to `CRC_32`, such as one taken from a
+    /// manifest or other side channel rather than from a Transport Stream.
+    ///
+    /// Any bytes following the end of the section (as given by its `section_length`) are
+    /// ignored.  Problems which do not prevent parsing are logged (see `LogDiagnostics`).
+    pub fn parse(data: &[u8]) -> Result<SpliceInfoSection, Scte35Error> {
+        Self::parse_with(data, &LogDiagnostics)
+    }
+
+    /// As `parse()`, but rather than logging the problems which do not prevent parsing, returns
+    /// them in a `ParseReport` alongside the section, so that QC tools can present them.
+    pub fn parse_with_report(data: &[u8]) -> Result<(SpliceInfoSection, ParseReport), Scte35Error> {
+        let collector = WarningCollector::default();
+        let section = Self::parse_with(data, &collector)?;
+        let report = ParseReport {
+            warnings: collector.0.into_inner(),
+        };
+        Ok((section, report))
+    }
+
+    fn parse_with(
+        data: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceInfoSection, Scte35Error> {
+        if data.len() < psi::SectionCommonHeader::SIZE {
+            return Err(Scte35Error::SectionTooShort {
+                actual: data.len(),
+                expected: psi::SectionCommonHeader::SIZE,
+            });
+        }
+        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+        let section_end = psi::SectionCommonHeader::SIZE + header.section_length;
+        if data.len() < section_end {
+            return Err(Scte35Error::SectionTooShort {
+                actual: data.len(),
+                expected: section_end,
+            });
+        }
+        let (splice_header, splice_command, descriptors) = parse_section(
+            &header,
+            &data[..section_end],
+            MAX_SECTION_LENGTH,
+            CrcMode::default(),
+            reject_protocol_version,
+            diagnostics,
+        )?;
+        SpliceInfoSection::from_parts(&splice_header, splice_command, &descriptors)
+            .map_err(Scte35Error::Descriptor)
+    }
+
+    /// Decodes the given hexadecimal text, such as `"0xFC3025..."`, and parses the resulting
+    /// section as for `parse()`.
+    ///
+    /// An optional `0x` prefix is accepted, digits may be upper or lower case, and whitespace
+    /// anywhere in the text is ignored.  The same conversion is available via `str::parse()`.
+    pub fn from_hex(data: &str) -> Result<SpliceInfoSection, FromHexError> {
+        let trimmed = data.trim_start();
+        let start = data.len() - trimmed.len();
+        let (start, digits) = match trimmed
+            .strip_prefix("0x")
+            .or_else(|| trimmed.strip_prefix("0X"))
+        {
+            Some(rest) => (start + 2, rest),
+            None => (start, trimmed),
+        };
+        let mut bytes = Vec::with_capacity(digits.len() / 2);
+        let mut high = None;
+        let mut count = 0;
+        for (i, c) in digits.char_indices() {
+            if c.is_whitespace() {
+                continue;
+            }
+            let value = c.to_digit(16).ok_or(FromHexError::InvalidDigit {
+                offset: start + i,
+                character: c,
+            })? as u8;
+            count += 1;
+            match high.take() {
+                None => high = Some(value),
+                Some(h) => bytes.push(h << 4 | value),
+            }
+        }
+        if high.is_some() {
+            return Err(FromHexError::OddNumberOfDigits(count));
+        }
+        SpliceInfoSection::parse(&bytes).map_err(FromHexError::Section)
+    }
+
+    /// Decodes the given base64 text, as used to carry cues in HLS playlists and DASH manifests,
+    /// and parses the resulting section as for `parse()`.
+    ///
+    /// Leading and trailing whitespace is ignored.  Available when this crate's `base64` feature
+    /// is enabled.
+    #[cfg(feature = "base64")]
+    pub fn from_base64(data: &str) -> Result<SpliceInfoSection, FromBase64Error> {
+        use base64::Engine;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .map_err(FromBase64Error::Base64)?;
+        SpliceInfoSection::parse(&bytes).map_err(FromBase64Error::Section)
+    }
+
+    /// The PTS at which the splice signalled by this section takes effect, with the section's
+    /// `pts_adjustment` applied (see `SpliceCommand::splice_pts()`).
+    pub fn splice_pts(&self) -> Option<u64> {
+        self.splice_command.splice_pts(self.pts_adjustment)
+    }
+
+    /// Orders two sections by their `splice_pts()`, allowing for the wrap of the PTS counter as
+    /// `time::cmp_pts()` does.  Sections without a splice PTS, such as immediate splices, take
+    /// effect as soon as they are received, and so are placed before any with one.
+    pub fn cmp_by_pts(&self, other: &SpliceInfoSection) -> std::cmp::Ordering {
+        cmp_splice_pts(self.splice_pts(), other.splice_pts())
+    }
+
+    /// Every PTS given by this section, with the section's `pts_adjustment` applied.
+    ///
+    /// These are the splice times of the command (see `SpliceCommand::adjusted_times()`),
+    /// followed by the time of each component of any component-mode segmentation descriptors
+    /// (see `SegmentationDescriptor::component_times()`).
+    pub fn adjusted_times(&self) -> Vec<AdjustedTime> {
+        let mut times = self.splice_command.adjusted_times(self.pts_adjustment);
+        if let Some(pts) = self.splice_pts() {
+            for descriptor in &self.splice_descriptors {
+                if let SpliceDescriptor::SegmentationDescriptor {
+                    descriptor_detail, ..
+                } = descriptor
+                {
+                    times
+                        .extend(descriptor_detail.component_times(time::SpliceTimestamp::new(pts)));
+                }
+            }
+        }
+        times
+    }
+
+    /// A one-line English description of the section, such as
+    /// `SpliceInsert OUT event 42 at PTS 756296448, 30.0s auto-return break`, for dashboards,
+    /// alerts and logs.
+    ///
+    /// The PTS given is that of `splice_pts()`, with the section's `pts_adjustment` applied.
+    /// Any segmentation descriptors are summarised after the command, separated by `; `.  The
+    /// wording is intended for people to read, and may change between releases, so should not
+    /// be parsed.
+    ///
+    /// ```
+    /// # use scte35_reader::builder::SpliceInsertBuilder;
+    /// # use scte35_reader::ReturnMode;
+    /// let section = SpliceInsertBuilder::new(42)
+    ///     .out_of_network()
+    ///     .at(756296448)
+    ///     .break_duration(ReturnMode::Automatic, 30 * 90_000)
+    ///     .build_section()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     section.summarize(),
+    ///     "SpliceInsert OUT event 42 at PTS 756296448, 30.0s auto-return break"
+    /// );
+    /// ```
+    pub fn summarize(&self) -> String {
+        let seconds = |ticks: u64| ticks as f64 / time::PTS_TIMESCALE as f64;
+        let mut summary = match &self.splice_command {
+            SpliceCommand::SpliceNull {} => "SpliceNull".to_string(),
+            SpliceCommand::SpliceSchedule { events } => {
+                format!("SpliceSchedule of {} event(s)", events.len())
+            }
+            SpliceCommand::SpliceInsert {
+                splice_event_id,
+                splice_detail: SpliceInsert::Cancel,
+                ..
+            } => format!("SpliceInsert cancel event {}", splice_event_id),
+            SpliceCommand::SpliceInsert {
+                splice_event_id,
+                splice_detail:
+                    SpliceInsert::Insert {
+                        network_indicator,
+                        splice_mode,
+                        duration,
+                        ..
+                    },
+                ..
+            } => {
+                let direction = match network_indicator {
+                    NetworkIndicator::Out => "OUT",
+                    NetworkIndicator::In => "IN",
+                };
+                let mut summary = format!("SpliceInsert {} event {}", direction, splice_event_id);
+                match splice_mode {
+                    SpliceMode::Program(SpliceTime::Immediate) => summary.push_str(" immediate"),
+                    SpliceMode::Program(SpliceTime::Timed(_)) => {}
+                    SpliceMode::Components(components) => {
+                        summary.push_str(&format!(" for {} component(s)", components.len()))
+                    }
+                }
+                if let Some(pts) = self.splice_pts() {
+                    summary.push_str(&format!(" at PTS {}", pts));
+                }
+                if let Some(duration) = duration {
+                    let return_mode = match duration.return_mode {
+                        ReturnMode::Automatic => "auto-return",
+                        ReturnMode::Manual => "manual-return",
+                    };
+                    summary.push_str(&format!(
+                        ", {:.1}s {} break",
+                        seconds(duration.duration),
+                        return_mode
+                    ));
+                }
+                summary
+            }
+            SpliceCommand::TimeSignal { .. } => match self.splice_pts() {
+                Some(pts) => format!("TimeSignal at PTS {}", pts),
+                None => "TimeSignal without a time".to_string(),
+            },
+            SpliceCommand::BandwidthReservation {} => "BandwidthReservation".to_string(),
+            SpliceCommand::PrivateCommand {
+                identifier,
+                private_bytes,
+            } => format!(
+                "PrivateCommand {}, {} byte(s)",
+                String::from_utf8_lossy(identifier),
+                private_bytes.len()
+            ),
+            SpliceCommand::Reserved {
+                command_type,
+                payload,
+            } => format!(
+                "Reserved command {:#04x}, {} byte(s)",
+                command_type,
+                payload.len()
+            ),
+        };
+        for descriptor in &self.splice_descriptors {
+            if let SpliceDescriptor::SegmentationDescriptor {
+                segmentation_event_id,
+                descriptor_detail,
+            } = descriptor
+            {
+                match descriptor_detail {
+                    SegmentationDescriptor::Cancel => summary.push_str(&format!(
+                        "; cancel segmentation event {}",
+                        segmentation_event_id
+                    )),
+                    SegmentationDescriptor::Insert {
+                        segmentation_type_id,
+                        segmentation_duration,
+                        segment_num,
+                        segments_expected,
+                        ..
+                    } => {
+                        summary.push_str(&format!(
+                            "; {} segmentation event {}",
+                            segmentation_type_id, segmentation_event_id
+                        ));
+                        if *segments_expected > 1 {
+                            summary.push_str(&format!(
+                                " (segment {} of {})",
+                                segment_num, segments_expected
+                            ));
+                        }
+                        if let Some(duration) = segmentation_duration {
+                            summary.push_str(&format!(", {:.1}s", seconds(*duration)));
+                        }
+                    }
+                }
+            }
+        }
+        summary
+    }
+}
+
+/// Parses each of the given complete sections with `SpliceInfoSection::parse()`, in parallel
+/// using rayon's global thread pool, returning the results in the same order as the input.
+///
+/// Available when this crate's `rayon` feature is enabled.
+#[cfg(feature = "rayon")]
+pub fn parse_batch(sections: &[Vec<u8>]) -> Vec<Result<SpliceInfoSection, Scte35Error>> {
+    use rayon::prelude::*;
+    sections
+        .par_iter()
+        .map(|data| SpliceInfoSection::parse(data))
+        .collect()
+}
+
+/// The non-fatal problems found by `SpliceInfoSection::parse_with_report()`, in the order in
+/// which they were found
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParseReport {
+    pub warnings: Vec<Scte35Warning>,
+}
+impl ParseReport {
+    /// `true` if no problems were found
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+#[derive(Default)]
+struct WarningCollector(std::cell::RefCell<Vec<Scte35Warning>>);
+impl Scte35Diagnostics for WarningCollector {
+    fn warning(&self, warning: &Scte35Warning) {
+        self.0.borrow_mut().push(warning.clone());
+    }
+}
+
+/// A PTS given by a section, with `pts_adjustment` applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AdjustedTime {
+    /// The component to which the time applies, or `None` for the whole program
+    pub component_tag: Option<u8>,
+    pub pts: time::SpliceTimestamp,
+}
+
+impl std::str::FromStr for SpliceInfoSection {
+    type Err = FromHexError;
+
+    /// Equivalent to `SpliceInfoSection::from_hex()`
+    fn from_str(s: &str) -> Result<SpliceInfoSection, FromHexError> {
+        SpliceInfoSection::from_hex(s)
+    }
+}
+
+/// Problems which prevent `SpliceInfoSection::from_hex()` producing a section
+#[derive(Debug)]
+pub enum FromHexError {
+    /// A character which is neither a hex digit nor whitespace was found at the given byte
+    /// offset within the text
+    InvalidDigit { offset: usize, character: char },
+    /// The text held the given odd number of hex digits, so can not represent a whole number of
+    /// bytes
+    OddNumberOfDigits(usize),
+    /// The decoded bytes were not a valid `splice_info_section()`
+    Section(Scte35Error),
+}
+impl std::fmt::Display for FromHexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FromHexError::InvalidDigit { offset, character } => {
+                write!(f, "invalid hex digit {:?} at offset {}", character, offset)
+            }
+            FromHexError::OddNumberOfDigits(count) => {
+                write!(f, "odd number of hex digits ({})", count)
+            }
+            FromHexError::Section(_) => f.write_str("invalid splice_info_section"),
+        }
+    }
+}
+impl std::error::Error for FromHexError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FromHexError::Section(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Problems which prevent `SpliceInfoSection::from_base64()` producing a section
+#[cfg(feature = "base64")]
+#[derive(Debug)]
+pub enum FromBase64Error {
+    /// The given text was not valid base64
+    Base64(base64::DecodeError),
+    /// The decoded bytes were not a valid `splice_info_section()`
+    Section(Scte35Error),
+}
+#[cfg(feature = "base64")]
+impl std::fmt::Display for FromBase64Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FromBase64Error::Base64(_) => f.write_str("invalid base64"),
+            FromBase64Error::Section(_) => f.write_str("invalid splice_info_section"),
+        }
+    }
+}
+#[cfg(feature = "base64")]
+impl std::error::Error for FromBase64Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FromBase64Error::Base64(e) => Some(e),
+            FromBase64Error::Section(e) => Some(e),
+        }
+    }
+}
+
+/// The 12-bit authorization tier of a _splice_info_section_, which allows a message to be
+/// targeted at only those devices authorized for that tier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize)]
+#[serde(transparent)]
+pub struct Tier(u16);
+impl Tier {
+    /// The value `0xfff`, indicating that a message is to be acted upon by all devices
+    pub const ALL: Tier = Tier(0xfff);
+
+    /// Returns `None` if the given value does not fit in 12 bits
+    pub fn new(value: u16) -> Option<Tier> {
+        if value <= 0xfff {
+            Some(Tier(value))
+        } else {
+            None
+        }
+    }
+
+    pub fn value(self) -> u16 {
+        self.0
+    }
+
+    /// `true` for the value `0xfff`, meaning 'all tiers'
+    pub fn is_all(self) -> bool {
+        self == Tier::ALL
+    }
+
+    /// Should a message with this tier be acted upon by a device which is authorized for the
+    /// given tier?  This is the case if the tiers are equal, or if either is `Tier::ALL`.
+    pub fn matches(self, authorized: Tier) -> bool {
+        self.is_all() || authorized.is_all() || self == authorized
+    }
+}
+impl<'de> serde::Deserialize<'de> for Tier {
+    fn deserialize<D>(deserializer: D) -> Result<Tier, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = u16::deserialize(deserializer)?;
+        Tier::new(value).ok_or_else(|| {
+            serde::de::Error::custom(format!("tier value {:#x} exceeds 12 bits", value))
+        })
+    }
+}
+
 #[non_exhaustive]
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub enum SpliceCommand {
     SpliceNull {},
+    SpliceSchedule {
+        events: Vec<SpliceScheduleEvent>,
+    },
     SpliceInsert {
         splice_event_id: u32,
         reserved: u8,
@@ -267,31 +1147,113 @@ pub enum SpliceCommand {
     },
     BandwidthReservation {},
     PrivateCommand {
-        identifier: u32,
+        /// The registered `format_identifier` of the organisation defining the command
+        identifier: [u8; 4],
         private_bytes: Vec<u8>,
+    },
+    /// A command of a `splice_command_type` which the spec reserves, delivered unparsed so that
+    /// applications can log or forward it
+    Reserved {
+        command_type: u8,
+        payload: Vec<u8>,
+    },
+}
+
+/// Orders optional splice PTS values, with `None` (a splice taking effect on receipt) first
+fn cmp_splice_pts(a: Option<u64>, b: Option<u64>) -> std::cmp::Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => time::cmp_pts(a, b),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+impl SpliceCommand {
+    /// The PTS at which the splice signalled by this command takes effect, with the given
+    /// `pts_adjustment` (from the `SpliceInfoHeader`) applied.
+    ///
+    /// Only program-mode `splice_insert()` and `time_signal()` commands with a specified
+    /// `pts_time` have such a value; `None` is returned in all other cases.  Use
+    /// `time::cmp_pts()` to order the results.
+    pub fn splice_pts(&self, pts_adjustment: u64) -> Option<u64> {
+        let splice_time = match self {
+            SpliceCommand::SpliceInsert {
+                splice_detail:
+                    SpliceInsert::Insert {
+                        splice_mode: SpliceMode::Program(splice_time),
+                        ..
+                    },
+                ..
+            } => splice_time,
+            SpliceCommand::TimeSignal { splice_time } => splice_time,
+            _ => return None,
+        };
+        match splice_time {
+            SpliceTime::Timed(Some(pts)) => Some(time::adjust_pts(*pts, pts_adjustment)),
+            _ => None,
+        }
+    }
+
+    /// Orders two commands by their `splice_pts()` (without any `pts_adjustment`), allowing for
+    /// the wrap of the PTS counter as `time::cmp_pts()` does.  Commands without a splice PTS,
+    /// such as immediate splices, are placed before any with one.
+    pub fn cmp_by_pts(&self, other: &SpliceCommand) -> std::cmp::Ordering {
+        cmp_splice_pts(self.splice_pts(0), other.splice_pts(0))
+    }
+
+    /// Every `pts_time` given by this command, with the given `pts_adjustment` applied; one
+    /// for each component of a component-mode `splice_insert()`, otherwise at most one for the
+    /// whole program.  Immediate splices and those without a `pts_time` are omitted.
+    pub fn adjusted_times(&self, pts_adjustment: u64) -> Vec<AdjustedTime> {
+        let timed = |component_tag, splice_time: &SpliceTime| match splice_time {
+            SpliceTime::Timed(Some(pts)) => Some(AdjustedTime {
+                component_tag,
+                pts: time::SpliceTimestamp::new(*pts).adjust(pts_adjustment),
+            }),
+            _ => None,
+        };
+        match self {
+            SpliceCommand::SpliceInsert {
+                splice_detail: SpliceInsert::Insert { splice_mode, .. },
+                ..
+            } => match splice_mode {
+                SpliceMode::Program(splice_time) => timed(None, splice_time).into_iter().collect(),
+                SpliceMode::Components(components) => components
+                    .iter()
+                    .filter_map(|c| timed(Some(c.component_tag), &c.splice_time))
+                    .collect(),
+            },
+            SpliceCommand::TimeSignal { splice_time } => {
+                timed(None, splice_time).into_iter().collect()
+            }
+            _ => vec![],
+        }
     }
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
 pub enum NetworkIndicator {
     Out,
     In,
 }
-impl NetworkIndicator {
-    /// panics if `id` is something other than `0` or `1`
-    pub fn from_flag(id: u8) -> NetworkIndicator {
+impl TryFrom<u8> for NetworkIndicator {
+    type Error = SpliceDescriptorErr;
+
+    /// Fails if `id` is something other than `0` or `1`
+    fn try_from(id: u8) -> Result<NetworkIndicator, SpliceDescriptorErr> {
         match id {
-            0 => NetworkIndicator::In,
-            1 => NetworkIndicator::Out,
-            _ => panic!(
-                "Invalid out_of_network_indicator value: {} (expected 0 or 1)",
-                id
-            ),
+            0 => Ok(NetworkIndicator::In),
+            1 => Ok(NetworkIndicator::Out),
+            _ => Err(SpliceDescriptorErr::InvalidFieldValue {
+                field_name: "out_of_network_indicator",
+                value: id,
+            }),
         }
     }
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub enum SpliceInsert {
     Cancel,
     Insert {
@@ -304,40 +1266,118 @@ pub enum SpliceInsert {
     },
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+/// One of the splice events announced ahead of time by a `splice_schedule()` command.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
+pub struct SpliceScheduleEvent {
+    pub splice_event_id: u32,
+    pub splice_detail: ScheduledSplice,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
+pub enum ScheduledSplice {
+    Cancel,
+    Insert {
+        network_indicator: NetworkIndicator,
+        splice_mode: ScheduledSpliceMode,
+        duration: Option<SpliceDuration>,
+        unique_program_id: u16,
+        avail_num: u8,
+        avails_expected: u8,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
+pub enum ScheduledSpliceMode {
+    Program(UtcSpliceTime),
+    Components(Vec<ScheduledComponentSplice>),
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
+pub struct ScheduledComponentSplice {
+    pub component_tag: u8,
+    pub utc_splice_time: UtcSpliceTime,
+}
+
+/// The wall-clock time of a scheduled splice, given as the number of seconds since the GPS epoch
+/// of `1980-01-06T00:00:00Z`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
+pub struct UtcSpliceTime(pub u32);
+impl UtcSpliceTime {
+    /// Converts to a `SystemTime`, first subtracting the given number of leap seconds (see
+    /// [`time::CURRENT_GPS_UTC_OFFSET`](time/constant.CURRENT_GPS_UTC_OFFSET.html)).
+    ///
+    /// SCTE-35 counts this value "with the count of intervening leap seconds included", and
+    /// notes that it may be converted to UTC without the `GPS_UTC_offset` of the _System Time
+    /// Table_, so `0` is the correct offset for conforming encoders.  Pass the current GPS–UTC
+    /// offset only when the source is known to emit raw GPS time.
+    pub fn to_system_time(&self, gps_utc_offset: u32) -> std::time::SystemTime {
+        time::gps_seconds_to_system_time(self.0, gps_utc_offset)
+    }
+
+    /// Formats as an RFC 3339 UTC timestamp, applying `gps_utc_offset` as for `to_system_time()`.
+    pub fn to_rfc3339(&self, gps_utc_offset: u32) -> String {
+        time::format_rfc3339(self.to_system_time(gps_utc_offset))
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
 pub enum SpliceTime {
     Immediate,
     Timed(Option<u64>),
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct ComponentSplice {
     component_tag: u8,
     splice_time: SpliceTime,
 }
+impl ComponentSplice {
+    pub fn component_tag(&self) -> u8 {
+        self.component_tag
+    }
+    pub fn splice_time(&self) -> &SpliceTime {
+        &self.splice_time
+    }
+}
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub enum SpliceMode {
     Program(SpliceTime),
     Components(Vec<ComponentSplice>),
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
 pub enum ReturnMode {
     Automatic,
     Manual,
 }
-impl ReturnMode {
-    pub fn from_flag(flag: u8) -> ReturnMode {
+impl TryFrom<u8> for ReturnMode {
+    type Error = SpliceDescriptorErr;
+
+    /// Fails if `flag` is something other than `0` or `1`
+    fn try_from(flag: u8) -> Result<ReturnMode, SpliceDescriptorErr> {
         match flag {
-            0 => ReturnMode::Manual,
-            1 => ReturnMode::Automatic,
-            _ => panic!("Invalid auto_return value: {} (expected 0 or 1)", flag),
+            0 => Ok(ReturnMode::Manual),
+            1 => Ok(ReturnMode::Automatic),
+            _ => Err(SpliceDescriptorErr::InvalidFieldValue {
+                field_name: "auto_return",
+                value: flag,
+            }),
         }
     }
 }
 
-#[derive(Debug, PartialEq, serde_derive::Serialize, Copy, Clone)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
 pub enum SegmentationUpidType {
     NotUsed,
     UserDefinedDeprecated,
@@ -357,6 +1397,9 @@ pub enum SegmentationUpidType {
     MID,
     ADS,
     URI,
+    UUID,
+    /// _Subscriber Company Reporting_
+    SCR,
     Reserved(u8),
 }
 impl SegmentationUpidType {
@@ -378,15 +1421,91 @@ impl SegmentationUpidType {
             13 => SegmentationUpidType::MID,
             14 => SegmentationUpidType::ADS,
             15 => SegmentationUpidType::URI,
+            16 => SegmentationUpidType::UUID,
+            17 => SegmentationUpidType::SCR,
             _ => SegmentationUpidType::Reserved(id),
         }
     }
+
+    /// The `segmentation_upid_type` value which identifies this kind of UPID
+    pub fn id(&self) -> u8 {
+        match *self {
+            SegmentationUpidType::NotUsed => 0,
+            SegmentationUpidType::UserDefinedDeprecated => 1,
+            SegmentationUpidType::ISCIDeprecated => 2,
+            SegmentationUpidType::AdID => 3,
+            SegmentationUpidType::UMID => 4,
+            SegmentationUpidType::ISANDeprecated => 5,
+            SegmentationUpidType::ISAN => 6,
+            SegmentationUpidType::TID => 7,
+            SegmentationUpidType::TI => 8,
+            SegmentationUpidType::ADI => 9,
+            SegmentationUpidType::EIDR => 10,
+            SegmentationUpidType::ATSC => 11,
+            SegmentationUpidType::MPU => 12,
+            SegmentationUpidType::MID => 13,
+            SegmentationUpidType::ADS => 14,
+            SegmentationUpidType::URI => 15,
+            SegmentationUpidType::UUID => 16,
+            SegmentationUpidType::SCR => 17,
+            SegmentationUpidType::Reserved(id) => id,
+        }
+    }
+
+    /// The number of bytes that every UPID of this type must have, or `None` for types whose
+    /// length may vary
+    pub fn expected_length(&self) -> Option<usize> {
+        match *self {
+            SegmentationUpidType::ISCIDeprecated => Some(8),
+            SegmentationUpidType::AdID => Some(12),
+            SegmentationUpidType::UMID => Some(32),
+            SegmentationUpidType::ISANDeprecated => Some(8),
+            SegmentationUpidType::ISAN => Some(12),
+            SegmentationUpidType::TID => Some(12),
+            SegmentationUpidType::TI => Some(8),
+            SegmentationUpidType::EIDR => Some(12),
+            SegmentationUpidType::UUID => Some(16),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, serde_derive::Serialize)]
-pub enum SegmentationTypeId {
+/// Formats the UPID type's name from the _SCTE-35_ table of `segmentation_upid_type` values,
+/// e.g. `Ad-ID`, with the value appended for reserved types
+impl std::fmt::Display for SegmentationUpidType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match *self {
+            SegmentationUpidType::NotUsed => "Not Used",
+            SegmentationUpidType::UserDefinedDeprecated => "User Defined (Deprecated)",
+            SegmentationUpidType::ISCIDeprecated => "ISCI (Deprecated)",
+            SegmentationUpidType::AdID => "Ad-ID",
+            SegmentationUpidType::UMID => "UMID",
+            SegmentationUpidType::ISANDeprecated => "ISAN (Deprecated)",
+            SegmentationUpidType::ISAN => "ISAN",
+            SegmentationUpidType::TID => "TID",
+            SegmentationUpidType::TI => "TI",
+            SegmentationUpidType::ADI => "ADI",
+            SegmentationUpidType::EIDR => "EIDR",
+            SegmentationUpidType::ATSC => "ATSC Content Identifier",
+            SegmentationUpidType::MPU => "MPU()",
+            SegmentationUpidType::MID => "MID()",
+            SegmentationUpidType::ADS => "ADS Information",
+            SegmentationUpidType::URI => "URI",
+            SegmentationUpidType::UUID => "UUID",
+            SegmentationUpidType::SCR => "SCR",
+            SegmentationUpidType::Reserved(id) => return write!(f, "Reserved ({:#04x})", id),
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
+pub enum SegmentationTypeId {
     NotIndicated,
     ContentIdentification,
+    CallAdServer,
     ProgramStart,
     ProgramEnd,
     ProgramEarlyTermination,
@@ -409,8 +1528,22 @@ pub enum SegmentationTypeId {
     ProviderPlacementOpportunityEnd,
     DistributorPlacementOpportunityStart,
     DistributorPlacementOpportunityEnd,
+    ProviderOverlayPlacementOpportunityStart,
+    ProviderOverlayPlacementOpportunityEnd,
+    DistributorOverlayPlacementOpportunityStart,
+    DistributorOverlayPlacementOpportunityEnd,
+    ProviderPromoStart,
+    ProviderPromoEnd,
+    DistributorPromoStart,
+    DistributorPromoEnd,
     UnscheduledEventStart,
     UnscheduledEventEnd,
+    AlternateContentOpportunityStart,
+    AlternateContentOpportunityEnd,
+    ProviderAdBlockStart,
+    ProviderAdBlockEnd,
+    DistributorAdBlockStart,
+    DistributorAdBlockEnd,
     NetworkStart,
     NetworkEnd,
     Reserved(u8),
@@ -420,6 +1553,7 @@ impl SegmentationTypeId {
         match id {
             0 => SegmentationTypeId::NotIndicated,
             1 => SegmentationTypeId::ContentIdentification,
+            2 => SegmentationTypeId::CallAdServer,
             16 => SegmentationTypeId::ProgramStart,
             17 => SegmentationTypeId::ProgramEnd,
             18 => SegmentationTypeId::ProgramEarlyTermination,
@@ -442,22 +1576,234 @@ impl SegmentationTypeId {
             53 => SegmentationTypeId::ProviderPlacementOpportunityEnd,
             54 => SegmentationTypeId::DistributorPlacementOpportunityStart,
             55 => SegmentationTypeId::DistributorPlacementOpportunityEnd,
+            56 => SegmentationTypeId::ProviderOverlayPlacementOpportunityStart,
+            57 => SegmentationTypeId::ProviderOverlayPlacementOpportunityEnd,
+            58 => SegmentationTypeId::DistributorOverlayPlacementOpportunityStart,
+            59 => SegmentationTypeId::DistributorOverlayPlacementOpportunityEnd,
+            60 => SegmentationTypeId::ProviderPromoStart,
+            61 => SegmentationTypeId::ProviderPromoEnd,
+            62 => SegmentationTypeId::DistributorPromoStart,
+            63 => SegmentationTypeId::DistributorPromoEnd,
             64 => SegmentationTypeId::UnscheduledEventStart,
             65 => SegmentationTypeId::UnscheduledEventEnd,
+            66 => SegmentationTypeId::AlternateContentOpportunityStart,
+            67 => SegmentationTypeId::AlternateContentOpportunityEnd,
+            68 => SegmentationTypeId::ProviderAdBlockStart,
+            69 => SegmentationTypeId::ProviderAdBlockEnd,
+            70 => SegmentationTypeId::DistributorAdBlockStart,
+            71 => SegmentationTypeId::DistributorAdBlockEnd,
             80 => SegmentationTypeId::NetworkStart,
             81 => SegmentationTypeId::NetworkEnd,
             _ => SegmentationTypeId::Reserved(id),
         }
     }
+
+    /// The `segmentation_type_id` value which identifies this type of segment
+    pub fn id(&self) -> u8 {
+        match *self {
+            SegmentationTypeId::NotIndicated => 0,
+            SegmentationTypeId::ContentIdentification => 1,
+            SegmentationTypeId::CallAdServer => 2,
+            SegmentationTypeId::ProgramStart => 16,
+            SegmentationTypeId::ProgramEnd => 17,
+            SegmentationTypeId::ProgramEarlyTermination => 18,
+            SegmentationTypeId::ProgramBreakaway => 19,
+            SegmentationTypeId::ProgramResumption => 20,
+            SegmentationTypeId::ProgramRunoverPlanned => 21,
+            SegmentationTypeId::ProgramRunoverUnplanned => 22,
+            SegmentationTypeId::ProgramOverlapStart => 23,
+            SegmentationTypeId::ProgramBlackoutOverride => 24,
+            SegmentationTypeId::ProgramStartInProgress => 25,
+            SegmentationTypeId::ChapterStart => 32,
+            SegmentationTypeId::ChapterEnd => 33,
+            SegmentationTypeId::BreakStart => 34,
+            SegmentationTypeId::BreakEnd => 35,
+            SegmentationTypeId::ProviderAdvertisementStart => 48,
+            SegmentationTypeId::ProviderAdvertisementEnd => 49,
+            SegmentationTypeId::DistributorAdvertisementStart => 50,
+            SegmentationTypeId::DistributorAdvertisementEnd => 51,
+            SegmentationTypeId::ProviderPlacementOpportunityStart => 52,
+            SegmentationTypeId::ProviderPlacementOpportunityEnd => 53,
+            SegmentationTypeId::DistributorPlacementOpportunityStart => 54,
+            SegmentationTypeId::DistributorPlacementOpportunityEnd => 55,
+            SegmentationTypeId::ProviderOverlayPlacementOpportunityStart => 56,
+            SegmentationTypeId::ProviderOverlayPlacementOpportunityEnd => 57,
+            SegmentationTypeId::DistributorOverlayPlacementOpportunityStart => 58,
+            SegmentationTypeId::DistributorOverlayPlacementOpportunityEnd => 59,
+            SegmentationTypeId::ProviderPromoStart => 60,
+            SegmentationTypeId::ProviderPromoEnd => 61,
+            SegmentationTypeId::DistributorPromoStart => 62,
+            SegmentationTypeId::DistributorPromoEnd => 63,
+            SegmentationTypeId::UnscheduledEventStart => 64,
+            SegmentationTypeId::UnscheduledEventEnd => 65,
+            SegmentationTypeId::AlternateContentOpportunityStart => 66,
+            SegmentationTypeId::AlternateContentOpportunityEnd => 67,
+            SegmentationTypeId::ProviderAdBlockStart => 68,
+            SegmentationTypeId::ProviderAdBlockEnd => 69,
+            SegmentationTypeId::DistributorAdBlockStart => 70,
+            SegmentationTypeId::DistributorAdBlockEnd => 71,
+            SegmentationTypeId::NetworkStart => 80,
+            SegmentationTypeId::NetworkEnd => 81,
+            SegmentationTypeId::Reserved(id) => id,
+        }
+    }
+
+    /// Whether the spec's table of segmentation types gives this type the optional
+    /// `sub_segment_num` and `sub_segments_expected` fields
+    pub(crate) fn has_sub_segments(&self) -> bool {
+        matches!(self.id(), 0x34 | 0x36 | 0x38 | 0x3a | 0x44 | 0x46)
+    }
+
+    /// Whether this type starts a segment which is ended by the `corresponding_end_type()`
+    pub fn is_start(&self) -> bool {
+        self.corresponding_end_type().is_some()
+    }
+
+    /// Whether this type ends a segment which was started by the `corresponding_start_type()`
+    pub fn is_end(&self) -> bool {
+        self.corresponding_start_type().is_some()
+    }
+
+    /// For a type starting a segment, the type that ends it, e.g. _Break End_ for _Break
+    /// Start_.  `None` for types which do not start a segment, including _Program Start_'s
+    /// alternatives such as _Program Start - In Progress_.
+    pub fn corresponding_end_type(&self) -> Option<SegmentationTypeId> {
+        let id = self.id();
+        let is_start = match id {
+            // Program, Chapter, Break, Opening and Closing Credit, and Network Start
+            0x10 | 0x20 | 0x22 | 0x24 | 0x26 | 0x50 => true,
+            // the advertisement, placement opportunity, promo, unscheduled event, alternate
+            // content and ad block starts
+            0x30..=0x46 => id & 1 == 0,
+            _ => false,
+        };
+        if is_start {
+            Some(SegmentationTypeId::from_id(id + 1))
+        } else {
+            None
+        }
+    }
+
+    /// For a type ending a segment, the type that started it, e.g. _Break Start_ for _Break
+    /// End_
+    pub fn corresponding_start_type(&self) -> Option<SegmentationTypeId> {
+        let id = self.id();
+        if id == 0 {
+            return None;
+        }
+        let start = SegmentationTypeId::from_id(id - 1);
+        start.corresponding_end_type().map(|_| start)
+    }
+
+    /// Whether this type marks the start of an avail, where ad-insertion equipment leaves the
+    /// network feed: a _Break_, _Advertisement_, _Placement Opportunity_ or _Ad Block_ start
+    pub fn is_out(&self) -> bool {
+        std::matches!(
+            self,
+            SegmentationTypeId::BreakStart
+                | SegmentationTypeId::ProviderAdvertisementStart
+                | SegmentationTypeId::DistributorAdvertisementStart
+                | SegmentationTypeId::ProviderPlacementOpportunityStart
+                | SegmentationTypeId::DistributorPlacementOpportunityStart
+                | SegmentationTypeId::ProviderAdBlockStart
+                | SegmentationTypeId::DistributorAdBlockStart
+        )
+    }
+
+    /// Whether this type marks the end of an avail started by a type for which `is_out()` is
+    /// true, where ad-insertion equipment returns to the network feed
+    pub fn is_in(&self) -> bool {
+        self.corresponding_start_type()
+            .map(|start| start.is_out())
+            .unwrap_or(false)
+    }
+}
+
+/// Formats the segmentation type's name from the _SCTE-35_ table of `segmentation_type_id`
+/// values, e.g. `Provider Placement Opportunity Start`.
+///
+/// Values without a variant of their own, including the _Opening Credit_ and _Closing Credit_
+/// types, are formatted as `Reserved` with the value appended.
+impl std::fmt::Display for SegmentationTypeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match *self {
+            SegmentationTypeId::NotIndicated => "Not Indicated",
+            SegmentationTypeId::ContentIdentification => "Content Identification",
+            SegmentationTypeId::CallAdServer => "Call Ad Server",
+            SegmentationTypeId::ProgramStart => "Program Start",
+            SegmentationTypeId::ProgramEnd => "Program End",
+            SegmentationTypeId::ProgramEarlyTermination => "Program Early Termination",
+            SegmentationTypeId::ProgramBreakaway => "Program Breakaway",
+            SegmentationTypeId::ProgramResumption => "Program Resumption",
+            SegmentationTypeId::ProgramRunoverPlanned => "Program Runover Planned",
+            SegmentationTypeId::ProgramRunoverUnplanned => "Program Runover Unplanned",
+            SegmentationTypeId::ProgramOverlapStart => "Program Overlap Start",
+            SegmentationTypeId::ProgramBlackoutOverride => "Program Blackout Override",
+            SegmentationTypeId::ProgramStartInProgress => "Program Start - In Progress",
+            SegmentationTypeId::ChapterStart => "Chapter Start",
+            SegmentationTypeId::ChapterEnd => "Chapter End",
+            SegmentationTypeId::BreakStart => "Break Start",
+            SegmentationTypeId::BreakEnd => "Break End",
+            SegmentationTypeId::ProviderAdvertisementStart => "Provider Advertisement Start",
+            SegmentationTypeId::ProviderAdvertisementEnd => "Provider Advertisement End",
+            SegmentationTypeId::DistributorAdvertisementStart => "Distributor Advertisement Start",
+            SegmentationTypeId::DistributorAdvertisementEnd => "Distributor Advertisement End",
+            SegmentationTypeId::ProviderPlacementOpportunityStart => {
+                "Provider Placement Opportunity Start"
+            }
+            SegmentationTypeId::ProviderPlacementOpportunityEnd => {
+                "Provider Placement Opportunity End"
+            }
+            SegmentationTypeId::DistributorPlacementOpportunityStart => {
+                "Distributor Placement Opportunity Start"
+            }
+            SegmentationTypeId::DistributorPlacementOpportunityEnd => {
+                "Distributor Placement Opportunity End"
+            }
+            SegmentationTypeId::ProviderOverlayPlacementOpportunityStart => {
+                "Provider Overlay Placement Opportunity Start"
+            }
+            SegmentationTypeId::ProviderOverlayPlacementOpportunityEnd => {
+                "Provider Overlay Placement Opportunity End"
+            }
+            SegmentationTypeId::DistributorOverlayPlacementOpportunityStart => {
+                "Distributor Overlay Placement Opportunity Start"
+            }
+            SegmentationTypeId::DistributorOverlayPlacementOpportunityEnd => {
+                "Distributor Overlay Placement Opportunity End"
+            }
+            SegmentationTypeId::ProviderPromoStart => "Provider Promo Start",
+            SegmentationTypeId::ProviderPromoEnd => "Provider Promo End",
+            SegmentationTypeId::DistributorPromoStart => "Distributor Promo Start",
+            SegmentationTypeId::DistributorPromoEnd => "Distributor Promo End",
+            SegmentationTypeId::UnscheduledEventStart => "Unscheduled Event Start",
+            SegmentationTypeId::UnscheduledEventEnd => "Unscheduled Event End",
+            SegmentationTypeId::AlternateContentOpportunityStart => {
+                "Alternate Content Opportunity Start"
+            }
+            SegmentationTypeId::AlternateContentOpportunityEnd => {
+                "Alternate Content Opportunity End"
+            }
+            SegmentationTypeId::ProviderAdBlockStart => "Provider Ad Block Start",
+            SegmentationTypeId::ProviderAdBlockEnd => "Provider Ad Block End",
+            SegmentationTypeId::DistributorAdBlockStart => "Distributor Ad Block Start",
+            SegmentationTypeId::DistributorAdBlockEnd => "Distributor Ad Block End",
+            SegmentationTypeId::NetworkStart => "Network Start",
+            SegmentationTypeId::NetworkEnd => "Network End",
+            SegmentationTypeId::Reserved(id) => return write!(f, "Reserved ({:#04x})", id),
+        };
+        f.write_str(name)
+    }
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub enum SegmentationUpid {
     None,
     UserDefined(upid::UserDefinedDeprecated),
     Isci(upid::IsciDeprecated),
     AdID(upid::AdID),
     IsanDeprecated(upid::IsanDeprecated),
+    Isan(upid::Isan),
     Umid(upid::Umid),
     TID(upid::TID),
     TI(upid::TI),
@@ -465,9 +1811,13 @@ pub enum SegmentationUpid {
     EIDR(upid::EIDR),
     ATSC(upid::ATSC),
     MPU(upid::MPU),
+    /// _Multiple UPID_ structure, holding each of the UPIDs it contains, in order, already
+    /// decoded according to their individual `segmentation_upid_type`
     MID(Vec<SegmentationUpid>),
     ADS(upid::ADSInformation),
     URI(upid::Url),
+    UUID(upid::Uuid),
+    SCR(upid::SCR),
     Reserved(SegmentationUpidType, Vec<u8>),
 }
 impl SegmentationUpid {
@@ -475,24 +1825,46 @@ impl SegmentationUpid {
         r: &mut bitreader::BitReader<'_>,
         segmentation_upid_type: SegmentationUpidType,
         segmentation_upid_length: u8,
+        diagnostics: &dyn Scte35Diagnostics,
     ) -> Result<SegmentationUpid, SpliceDescriptorErr> {
         if segmentation_upid_length > 0 {
+            check_count(
+                r,
+                segmentation_upid_length.into(),
+                8,
+                "segmentation_descriptor.segmentation_upid_length",
+            )?;
             let upid_result: Result<Vec<u8>, bitreader::BitReaderError> = (0
                 ..segmentation_upid_length)
                 .map(|_| r.read_u8(8))
                 .collect();
             let upid = upid_result.named("segmentation_descriptor.segmentation_upid")?;
-            SegmentationUpid::parse_payload(segmentation_upid_type, upid)
+            SegmentationUpid::parse_payload_with(segmentation_upid_type, upid, diagnostics)
         } else {
+            check_upid_length(segmentation_upid_type, 0, diagnostics)?;
             Ok(SegmentationUpid::None)
         }
     }
 
     // TODO: rework 'upid' param from Vec<u8> into &[u8]
+    #[cfg(any(test, feature = "xml"))]
     fn parse_payload(
         segmentation_upid_type: SegmentationUpidType,
         upid: Vec<u8>,
     ) -> Result<SegmentationUpid, SpliceDescriptorErr> {
+        Self::parse_payload_with(segmentation_upid_type, upid, &LogDiagnostics)
+    }
+
+    /// As `parse_payload()`, but treating a UPID of the wrong length for its type according to
+    /// the given diagnostics' `upid_length_mode()`
+    fn parse_payload_with(
+        segmentation_upid_type: SegmentationUpidType,
+        upid: Vec<u8>,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SegmentationUpid, SpliceDescriptorErr> {
+        if !check_upid_length(segmentation_upid_type, upid.len(), diagnostics)? {
+            return Ok(SegmentationUpid::Reserved(segmentation_upid_type, upid));
+        }
         match segmentation_upid_type {
             SegmentationUpidType::NotUsed => Err(
                 SpliceDescriptorErr::SegmentationUpidLengthTypeMismatch(segmentation_upid_type),
@@ -509,9 +1881,11 @@ impl SegmentationUpid {
             SegmentationUpidType::EIDR => Self::parse_eidr(upid),
             SegmentationUpidType::ATSC => Self::parse_atsc(upid),
             SegmentationUpidType::MPU => Self::parse_mpu(upid),
-            SegmentationUpidType::MID => Self::parse_mid(upid),
+            SegmentationUpidType::MID => Self::parse_mid(upid, diagnostics),
             SegmentationUpidType::ADS => Self::parse_ads(upid),
-            SegmentationUpidType::URI => Self::parse_url(upid),
+            SegmentationUpidType::URI => Self::parse_url(upid, diagnostics),
+            SegmentationUpidType::UUID => Self::parse_uuid(upid),
+            SegmentationUpidType::SCR => Self::parse_scr(upid),
             SegmentationUpidType::Reserved(_) => Self::parse_reserved(segmentation_upid_type, upid),
         }
     }
@@ -523,12 +1897,13 @@ impl SegmentationUpid {
             SegmentationUpid::Isci(_) => 8,
             SegmentationUpid::AdID(_) => 12,
             SegmentationUpid::IsanDeprecated(_) => 8,
+            SegmentationUpid::Isan(_) => 12,
             SegmentationUpid::Umid(_) => 32,
             SegmentationUpid::TID(_) => 12,
             SegmentationUpid::TI(_) => 8,
             SegmentationUpid::ADI(adi) => adi.0.len(),
             SegmentationUpid::EIDR(_) => 12,
-            SegmentationUpid::ATSC(atsc) => atsc.0.len(),
+            SegmentationUpid::ATSC(atsc) => 4 + atsc.content_id.len(),
             SegmentationUpid::MPU(m) => m.0.len(),
             SegmentationUpid::MID(v) => {
                 v.len() * 2
@@ -538,6 +1913,8 @@ impl SegmentationUpid {
             }
             SegmentationUpid::ADS(a) => a.0.len(),
             SegmentationUpid::URI(u) => u.0.as_str().len(),
+            SegmentationUpid::UUID(_) => 16,
+            SegmentationUpid::SCR(s) => s.0.len(),
             SegmentationUpid::Reserved(_, r) => r.len(),
         }
     }
@@ -548,7 +1925,8 @@ impl SegmentationUpid {
             SegmentationUpid::UserDefined(_) => SegmentationUpidType::UserDefinedDeprecated,
             SegmentationUpid::Isci(_) => SegmentationUpidType::ISCIDeprecated,
             SegmentationUpid::AdID(_) => SegmentationUpidType::AdID,
-            SegmentationUpid::IsanDeprecated(_) => SegmentationUpidType::ISAN,
+            SegmentationUpid::IsanDeprecated(_) => SegmentationUpidType::ISANDeprecated,
+            SegmentationUpid::Isan(_) => SegmentationUpidType::ISAN,
             SegmentationUpid::Umid(_) => SegmentationUpidType::UMID,
             SegmentationUpid::TID(_) => SegmentationUpidType::TID,
             SegmentationUpid::TI(_) => SegmentationUpidType::TI,
@@ -559,6 +1937,8 @@ impl SegmentationUpid {
             SegmentationUpid::MID(_) => SegmentationUpidType::MID,
             SegmentationUpid::ADS(_) => SegmentationUpidType::ADS,
             SegmentationUpid::URI(_) => SegmentationUpidType::URI,
+            SegmentationUpid::UUID(_) => SegmentationUpidType::UUID,
+            SegmentationUpid::SCR(_) => SegmentationUpidType::SCR,
             SegmentationUpid::Reserved(t, _) => *t,
         }
     }
@@ -584,11 +1964,15 @@ impl SegmentationUpid {
     }
     fn parse_isan_deprecated(upid: Vec<u8>) -> Result<SegmentationUpid, SpliceDescriptorErr> {
         chk_upid(&upid, 8, SegmentationUpidType::ISANDeprecated)?;
-        Ok(SegmentationUpid::IsanDeprecated(upid::IsanDeprecated(upid)))
+        Ok(SegmentationUpid::IsanDeprecated(upid::IsanDeprecated(
+            upid.as_slice().try_into().unwrap(),
+        )))
     }
     fn parse_isan(upid: Vec<u8>) -> Result<SegmentationUpid, SpliceDescriptorErr> {
         chk_upid(&upid, 12, SegmentationUpidType::ISAN)?;
-        Ok(SegmentationUpid::IsanDeprecated(upid::IsanDeprecated(upid)))
+        Ok(SegmentationUpid::Isan(upid::Isan(
+            upid.as_slice().try_into().unwrap(),
+        )))
     }
     fn parse_tid(upid: Vec<u8>) -> Result<SegmentationUpid, SpliceDescriptorErr> {
         chk_upid(&upid, 12, SegmentationUpidType::TID)?;
@@ -608,17 +1992,43 @@ impl SegmentationUpid {
         )))
     }
     fn parse_atsc(upid: Vec<u8>) -> Result<SegmentationUpid, SpliceDescriptorErr> {
-        Ok(SegmentationUpid::ATSC(upid::ATSC(upid)))
+        let mut r = bitreader::BitReader::new(&upid);
+        let tsid = r.read_u16(16).named("ATSC_content_identifier.TSID")?;
+        r.skip(2).named("ATSC_content_identifier.reserved")?;
+        let end_of_day = r.read_u8(5).named("ATSC_content_identifier.end_of_day")?;
+        let unique_for = r.read_u16(9).named("ATSC_content_identifier.unique_for")?;
+        Ok(SegmentationUpid::ATSC(upid::ATSC {
+            tsid,
+            end_of_day,
+            unique_for,
+            content_id: upid[4..].to_vec(),
+        }))
     }
     fn parse_mpu(upid: Vec<u8>) -> Result<SegmentationUpid, SpliceDescriptorErr> {
+        if upid.len() < 4 {
+            return Err(SpliceDescriptorErr::not_enough_data(
+                "MPU.format_identifier",
+                0,
+                4,
+                upid.len(),
+            ));
+        }
         Ok(SegmentationUpid::MPU(upid::MPU(upid)))
     }
-    fn parse_mid(upid: Vec<u8>) -> Result<SegmentationUpid, SpliceDescriptorErr> {
+    fn parse_mid(
+        upid: Vec<u8>,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SegmentationUpid, SpliceDescriptorErr> {
         let mut data = &upid[..];
         let mut result = vec![];
         while !data.is_empty() {
             if data.len() < 2 {
-                return Err(SpliceDescriptorErr::not_enough_data("MID.length", 1, 0));
+                return Err(SpliceDescriptorErr::not_enough_data(
+                    "MID.length",
+                    ((upid.len() - data.len()) * 8 + 8) as u64,
+                    1,
+                    0,
+                ));
             }
             let segmentation_upid_type = SegmentationUpidType::from_type(data[0]);
             let length = data[1] as usize;
@@ -626,14 +2036,16 @@ impl SegmentationUpid {
             if data.len() < payload_end {
                 return Err(SpliceDescriptorErr::not_enough_data(
                     "MID.segmentation_upid",
+                    ((upid.len() - data.len()) * 8 + 16) as u64,
                     length,
                     data.len() - 2,
                 ));
             }
             let payload = &data[2..payload_end];
-            result.push(Self::parse_payload(
+            result.push(Self::parse_payload_with(
                 segmentation_upid_type,
                 payload.to_vec(),
+                diagnostics,
             )?);
             data = &data[payload_end..];
         }
@@ -642,15 +2054,31 @@ impl SegmentationUpid {
     fn parse_ads(upid: Vec<u8>) -> Result<SegmentationUpid, SpliceDescriptorErr> {
         Ok(SegmentationUpid::ADS(upid::ADSInformation(upid)))
     }
-    fn parse_url(upid: Vec<u8>) -> Result<SegmentationUpid, SpliceDescriptorErr> {
-        upid_from_utf8(upid, SegmentationUpidType::URI)
-            .and_then(|s| {
-                url::Url::parse(&s).map_err(|_| SpliceDescriptorErr::InvalidUpidContent {
-                    upid_type: SegmentationUpidType::URI,
-                    bytes: s.into_bytes(),
-                })
-            })
-            .map(|u| SegmentationUpid::URI(upid::Url(u)))
+    /// A UPID which is not a valid URI does not fail the descriptor, but is reported as a
+    /// `Scte35Warning::MalformedUri`, and given as `SegmentationUpid::Reserved`
+    fn parse_url(
+        upid: Vec<u8>,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SegmentationUpid, SpliceDescriptorErr> {
+        let parsed = std::str::from_utf8(&upid)
+            .ok()
+            .and_then(|s| url::Url::parse(s).ok());
+        Ok(match parsed {
+            Some(u) => SegmentationUpid::URI(upid::Url(u)),
+            None => {
+                diagnostics.warning(&Scte35Warning::MalformedUri(upid.clone()));
+                SegmentationUpid::Reserved(SegmentationUpidType::URI, upid)
+            }
+        })
+    }
+    fn parse_uuid(upid: Vec<u8>) -> Result<SegmentationUpid, SpliceDescriptorErr> {
+        chk_upid(&upid, 16, SegmentationUpidType::UUID)?;
+        Ok(SegmentationUpid::UUID(upid::Uuid(
+            upid.as_slice().try_into().unwrap(),
+        )))
+    }
+    fn parse_scr(upid: Vec<u8>) -> Result<SegmentationUpid, SpliceDescriptorErr> {
+        Ok(SegmentationUpid::SCR(upid::SCR(upid)))
     }
     fn parse_reserved(
         segmentation_upid_type: SegmentationUpidType,
@@ -660,6 +2088,21 @@ impl SegmentationUpid {
     }
 }
 
+/// Checks that the given `DTMF_char` values are all permitted characters, which being ASCII are
+/// then also valid UTF-8
+fn dtmf_str(dtmf_chars: &[u8]) -> Result<&str, SpliceDescriptorErr> {
+    match dtmf_chars
+        .iter()
+        .find(|c| !matches!(c, b'0'..=b'9' | b'*' | b'#'))
+    {
+        Some(c) => Err(SpliceDescriptorErr::InvalidFieldValue {
+            field_name: "DTMF_char",
+            value: *c,
+        }),
+        None => Ok(std::str::from_utf8(dtmf_chars).expect("ASCII")),
+    }
+}
+
 /// helper wrapping String::from_utf8() and producing a useful error type
 fn upid_from_utf8(
     upid: Vec<u8>,
@@ -671,6 +2114,34 @@ fn upid_from_utf8(
     })
 }
 
+/// Checks the length of a UPID against that which its type requires, returning `false` if the
+/// length is wrong but `UpidLengthMode::Warn` is in effect, so that the UPID should be kept
+/// as bytes rather than decoded
+fn check_upid_length(
+    upid_type: SegmentationUpidType,
+    actual: usize,
+    diagnostics: &dyn Scte35Diagnostics,
+) -> Result<bool, SpliceDescriptorErr> {
+    match upid_type.expected_length() {
+        Some(expected) if expected != actual => match diagnostics.upid_length_mode() {
+            UpidLengthMode::Strict => Err(SpliceDescriptorErr::InvalidUpidLength {
+                upid_type,
+                expected,
+                actual,
+            }),
+            UpidLengthMode::Warn => {
+                diagnostics.warning(&Scte35Warning::UpidLength {
+                    upid_type,
+                    expected,
+                    actual,
+                });
+                Ok(false)
+            }
+        },
+        _ => Ok(true),
+    }
+}
+
 fn chk_upid(
     upid: &[u8],
     expected: usize,
@@ -687,30 +2158,34 @@ fn chk_upid(
     }
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
 pub enum DeviceRestrictions {
     RestrictGroup0,
     RestrictGroup1,
     RestrictGroup2,
     None,
 }
-impl DeviceRestrictions {
-    /// panics if `id` is something other than `0`, `1`, `2` or `3`
-    pub fn from_bits(restriction: u8) -> DeviceRestrictions {
+impl TryFrom<u8> for DeviceRestrictions {
+    type Error = SpliceDescriptorErr;
+
+    /// Fails if `restriction` is something other than `0`, `1`, `2` or `3`
+    fn try_from(restriction: u8) -> Result<DeviceRestrictions, SpliceDescriptorErr> {
         match restriction {
-            0 => DeviceRestrictions::RestrictGroup0,
-            1 => DeviceRestrictions::RestrictGroup1,
-            2 => DeviceRestrictions::RestrictGroup2,
-            3 => DeviceRestrictions::None,
-            _ => panic!(
-                "Invalid device_restrictions value: {} (expected 0, 1, 2, 3)",
-                restriction
-            ),
+            0 => Ok(DeviceRestrictions::RestrictGroup0),
+            1 => Ok(DeviceRestrictions::RestrictGroup1),
+            2 => Ok(DeviceRestrictions::RestrictGroup2),
+            3 => Ok(DeviceRestrictions::None),
+            _ => Err(SpliceDescriptorErr::InvalidFieldValue {
+                field_name: "device_restrictions",
+                value: restriction,
+            }),
         }
     }
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub enum DeliveryRestrictionFlags {
     None,
     DeliveryRestrictions {
@@ -721,7 +2196,7 @@ pub enum DeliveryRestrictionFlags {
     },
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub enum SegmentationMode {
     Program,
     Component {
@@ -729,13 +2204,23 @@ pub enum SegmentationMode {
     },
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
 pub struct SegmentationModeComponent {
     component_tag: u8,
     pts_offset: u64,
 }
+impl SegmentationModeComponent {
+    pub fn component_tag(&self) -> u8 {
+        self.component_tag
+    }
+    pub fn pts_offset(&self) -> u64 {
+        self.pts_offset
+    }
+}
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub enum SegmentationDescriptor {
     Cancel,
     Insert {
@@ -753,17 +2238,112 @@ pub enum SegmentationDescriptor {
     },
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+impl SegmentationDescriptor {
+    /// The `segmentation_type_id`, or `None` for a cancelled descriptor
+    pub fn segmentation_type_id(&self) -> Option<SegmentationTypeId> {
+        match self {
+            SegmentationDescriptor::Cancel => None,
+            SegmentationDescriptor::Insert {
+                segmentation_type_id,
+                ..
+            } => Some(*segmentation_type_id),
+        }
+    }
+
+    /// See `SegmentationTypeId::is_out()`; `false` for a cancelled descriptor
+    pub fn is_out(&self) -> bool {
+        self.segmentation_type_id()
+            .map(|t| t.is_out())
+            .unwrap_or(false)
+    }
+
+    /// See `SegmentationTypeId::is_in()`; `false` for a cancelled descriptor
+    pub fn is_in(&self) -> bool {
+        self.segmentation_type_id()
+            .map(|t| t.is_in())
+            .unwrap_or(false)
+    }
+
+    /// For a descriptor starting a segment and carrying a `segmentation_duration`, the time at
+    /// which the segment is expected to end, given the (already adjusted) time of its start
+    pub fn expected_end_pts(
+        &self,
+        start_pts: time::SpliceTimestamp,
+    ) -> Option<time::SpliceTimestamp> {
+        match self {
+            SegmentationDescriptor::Insert {
+                segmentation_type_id,
+                segmentation_duration: Some(duration),
+                ..
+            } if segmentation_type_id.is_start() => Some(start_pts + *duration),
+            _ => None,
+        }
+    }
+
+    /// For a component-mode descriptor, the time of each component, being its `pts_offset`
+    /// added to the given (already adjusted) splice time of the section.  Empty for
+    /// program-mode and cancelled descriptors.
+    pub fn component_times(&self, splice_pts: time::SpliceTimestamp) -> Vec<AdjustedTime> {
+        match self {
+            SegmentationDescriptor::Insert {
+                segmentation_mode: SegmentationMode::Component { components },
+                ..
+            } => components
+                .iter()
+                .map(|c| AdjustedTime {
+                    component_tag: Some(c.component_tag),
+                    pts: splice_pts + c.pts_offset,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
 pub struct SubSegments {
     sub_segment_num: u8,
     sub_segments_expected: u8,
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
 pub struct SpliceDuration {
     return_mode: ReturnMode,
     duration: u64,
 }
+impl SpliceDuration {
+    /// Creates a `break_duration()` of the given `Duration`, rounded to a whole number of 90kHz
+    /// ticks as directed.  Fails if the duration exceeds the 33-bit range of the field.
+    pub fn from_duration(
+        return_mode: ReturnMode,
+        duration: std::time::Duration,
+        rounding: time::Rounding,
+    ) -> Option<SpliceDuration> {
+        Some(SpliceDuration {
+            return_mode,
+            duration: time::from_duration(duration, time::TickField::Bits33, rounding)?,
+        })
+    }
+
+    pub fn return_mode(&self) -> &ReturnMode {
+        &self.return_mode
+    }
+
+    /// The duration in 90kHz units
+    pub fn duration(&self) -> u64 {
+        self.duration
+    }
+
+    /// The duration as a `Duration`, with the given rounding of the fractional part (see
+    /// `time::as_duration()`)
+    pub fn as_duration(&self, rounding: time::Rounding) -> std::time::Duration {
+        time::as_duration(self.duration, rounding)
+    }
+}
 
 pub trait SpliceInfoProcessor {
     fn process(
@@ -772,9 +2352,229 @@ pub trait SpliceInfoProcessor {
         command: SpliceCommand,
         descriptors: SpliceDescriptors<'_>,
     );
+
+    /// Like `process()`, but additionally given the complete encoded section (from `table_id`
+    /// through to `CRC_32`), for applications which forward or archive sections byte-exactly.
+    ///
+    /// `Scte35SectionProcessor` calls this method rather than `process()`; the default
+    /// implementation ignores `section` and calls `process()`.
+    fn process_section(
+        &self,
+        section: &[u8],
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        let _ = section;
+        self.process(header, command, descriptors)
+    }
+
+    /// Called via `Scte35SectionProcessor::reset()` when the application detects a
+    /// discontinuity or a switch of input, so that any state accumulated from earlier messages
+    /// can be discarded.  Does nothing by default.
+    fn reset(&self) {}
+
+    /// Called by `demux::Scte35DemuxContext` when an updated PMT moves the stream whose sections
+    /// this processor receives from the `previous` PID to the `current` one.  Does nothing by
+    /// default.
+    fn pid_changed(
+        &self,
+        previous: mpeg2ts_reader::packet::Pid,
+        current: mpeg2ts_reader::packet::Pid,
+    ) {
+        let _ = (previous, current);
+    }
+}
+
+/// Allows a closure to be used as a `SpliceInfoProcessor`.
+///
+/// Since `process()` takes `&self`, this applies to `Fn` closures; wrap an `FnMut` closure in
+/// `FnMutProcessor` instead.
+impl<F> SpliceInfoProcessor for F
+where
+    F: Fn(SpliceInfoHeader<'_>, SpliceCommand, SpliceDescriptors<'_>),
+{
+    fn process(
+        &self,
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        self(header, command, descriptors)
+    }
+}
+
+/// Adapts an `FnMut` closure to the `SpliceInfoProcessor` trait.
+///
+/// ```
+/// # use scte35_reader::{FnMutProcessor, Scte35SectionProcessor};
+/// # mpeg2ts_reader::demux_context!(
+/// #        NullDemuxContext,
+/// #        mpeg2ts_reader::demultiplex::NullPacketFilter<NullDemuxContext>
+/// #    );
+/// # impl NullDemuxContext {
+/// #    fn do_construct(
+/// #        &mut self,
+/// #        _req: mpeg2ts_reader::demultiplex::FilterRequest<'_, '_>,
+/// #    ) -> mpeg2ts_reader::demultiplex::NullPacketFilter<NullDemuxContext> {
+/// #        unimplemented!();
+/// #    }
+/// # }
+/// let mut count = 0;
+/// let parser: Scte35SectionProcessor<_, NullDemuxContext> =
+///     Scte35SectionProcessor::new(FnMutProcessor::new(|_header, command, _descriptors| {
+///         count += 1;
+///         println!("{:?}", command);
+///     }));
+/// ```
+pub struct FnMutProcessor<F>(std::cell::RefCell<F>);
+impl<F> FnMutProcessor<F>
+where
+    F: FnMut(SpliceInfoHeader<'_>, SpliceCommand, SpliceDescriptors<'_>),
+{
+    pub fn new(f: F) -> FnMutProcessor<F> {
+        FnMutProcessor(std::cell::RefCell::new(f))
+    }
+
+    /// Gives back the wrapped closure
+    pub fn into_inner(self) -> F {
+        self.0.into_inner()
+    }
+}
+impl<F> SpliceInfoProcessor for FnMutProcessor<F>
+where
+    F: FnMut(SpliceInfoHeader<'_>, SpliceCommand, SpliceDescriptors<'_>),
+{
+    fn process(
+        &self,
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        (self.0.borrow_mut())(header, command, descriptors)
+    }
+}
+
+/// Like `SpliceInfoProcessor`, but additionally given a reference to the user value that was
+/// supplied to `Scte35SectionProcessor::with_user()`, allowing access to shared application
+/// state.
+///
+/// Every `SpliceInfoProcessor` is also a `SpliceInfoUserProcessor<()>`.
+pub trait SpliceInfoUserProcessor<U> {
+    fn process_with(
+        &self,
+        user: &U,
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    );
+
+    /// See `SpliceInfoProcessor::process_section()`
+    fn process_section_with(
+        &self,
+        user: &U,
+        section: &[u8],
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        let _ = section;
+        self.process_with(user, header, command, descriptors)
+    }
+
+    /// See `SpliceInfoProcessor::reset()`
+    fn reset(&self) {}
+}
+impl<P: SpliceInfoProcessor> SpliceInfoUserProcessor<()> for P {
+    fn process_with(
+        &self,
+        _user: &(),
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        self.process(header, command, descriptors)
+    }
+
+    fn process_section_with(
+        &self,
+        _user: &(),
+        section: &[u8],
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        self.process_section(section, header, command, descriptors)
+    }
+
+    fn reset(&self) {
+        SpliceInfoProcessor::reset(self)
+    }
+}
+
+/// Where a section was found within the Transport Stream, given to a
+/// `SpliceInfoContextProcessor` alongside the section itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionContext {
+    /// The PID on which the section was carried
+    pub pid: mpeg2ts_reader::packet::Pid,
+    /// The number of packets on `pid` which preceded the packet that completed the section
+    pub packet_index: u64,
+    /// The most recent PCR known when the section completed, in 27MHz units (base and
+    /// extension combined), if any
+    pub last_pcr: Option<u64>,
+}
+
+/// Like `SpliceInfoProcessor`, but taking `&mut self` so that stateful processors need no
+/// interior mutability, and additionally given the `SectionContext` of each section so that
+/// cues can be correlated with the Transport Stream.
+///
+/// Sections are delivered to implementations by a `demux::Scte35ContextFilter`.  Implemented
+/// for any `FnMut(&SectionContext, SpliceInfoHeader, SpliceCommand, SpliceDescriptors)` closure.
+pub trait SpliceInfoContextProcessor {
+    fn process(
+        &mut self,
+        context: &SectionContext,
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    );
+
+    /// See `SpliceInfoProcessor::reset()`
+    fn reset(&mut self) {}
+}
+impl<F> SpliceInfoContextProcessor for F
+where
+    F: FnMut(&SectionContext, SpliceInfoHeader<'_>, SpliceCommand, SpliceDescriptors<'_>),
+{
+    fn process(
+        &mut self,
+        context: &SectionContext,
+        header: SpliceInfoHeader<'_>,
+        command: SpliceCommand,
+        descriptors: SpliceDescriptors<'_>,
+    ) {
+        self(context, header, command, descriptors)
+    }
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+/// Describes one of the audio components of the program, within an `audio_descriptor()`
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize,
+)]
+pub struct AudioComponent {
+    pub component_tag: u8,
+    /// The ISO 639-2 language code of the component, e.g. `b"eng"`
+    pub iso_code: [u8; 3],
+    /// The `bsmod` value, as defined for AC-3 audio in _ATSC A/52_
+    pub bit_stream_mode: u8,
+    /// The `acmod` derived channel configuration, as defined in _ATSC A/52_
+    pub num_channels: u8,
+    /// Whether this component is a full service, suitable for presentation on its own
+    pub full_srvc_audio: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub enum SpliceDescriptor {
     AvailDescriptor {
         provider_avail_id: u32,
@@ -792,6 +2592,9 @@ pub enum SpliceDescriptor {
         tai_nanoseconds: u32,
         utc_offset: u16,
     },
+    AudioDescriptor {
+        components: Vec<AudioComponent>,
+    },
     Reserved {
         tag: u8,
         identifier: [u8; 4],
@@ -799,9 +2602,47 @@ pub enum SpliceDescriptor {
     },
 }
 impl SpliceDescriptor {
+    /// For a `DTMFDescriptor`, the `DTMF_char` values as a string, failing if any is not one of
+    /// the characters `0`-`9`, `*` or `#` that the spec permits.  `None` for other descriptors.
+    pub fn dtmf_str(&self) -> Option<Result<&str, SpliceDescriptorErr>> {
+        match self {
+            SpliceDescriptor::DTMFDescriptor { dtmf_chars, .. } => Some(dtmf_str(dtmf_chars)),
+            _ => None,
+        }
+    }
+
+    /// For a `TimeDescriptor`, the wall-clock time it carries, converted from TAI to UTC using
+    /// its `utc_offset` (see `time::tai_to_system_time()`).  `None` for other descriptors, or if
+    /// the time can't be represented.
+    pub fn utc_time(&self) -> Option<std::time::SystemTime> {
+        match *self {
+            SpliceDescriptor::TimeDescriptor {
+                tai_seconds,
+                tai_nanoseconds,
+                utc_offset,
+            } => time::tai_to_system_time(tai_seconds, tai_nanoseconds, utc_offset),
+            _ => None,
+        }
+    }
+
+    /// As `utc_time()`, but producing a `chrono::DateTime` (see `time::tai_to_utc()`).
+    /// Available when this crate's `chrono` feature is enabled.
+    #[cfg(feature = "chrono")]
+    pub fn utc_date_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        match *self {
+            SpliceDescriptor::TimeDescriptor {
+                tai_seconds,
+                tai_nanoseconds,
+                utc_offset,
+            } => time::tai_to_utc(tai_seconds, tai_nanoseconds, utc_offset),
+            _ => None,
+        }
+    }
+
     fn parse_segmentation_descriptor_details(
         r: &mut bitreader::BitReader<'_>,
         cancelled: bool,
+        diagnostics: &dyn Scte35Diagnostics,
     ) -> Result<SegmentationDescriptor, SpliceDescriptorErr> {
         if cancelled {
             Ok(SegmentationDescriptor::Cancel)
@@ -827,20 +2668,26 @@ impl SpliceDescriptor {
                     archive_allowed_flag: r
                         .read_bool()
                         .named("segmentation_descriptor.archive_allowed_flag")?,
-                    device_restrictions: DeviceRestrictions::from_bits(
+                    device_restrictions: DeviceRestrictions::try_from(
                         r.read_u8(2)
                             .named("segmentation_descriptor.device_restrictions")?,
-                    ),
+                    )?,
                 }
             } else {
                 delivery_restrictions = DeliveryRestrictionFlags::None;
-                r.skip(5).named("segmentation_descriptor.reserved")?;
+                skip_reserved(r, 5, "segmentation_descriptor.reserved", diagnostics)?;
             }
             let segmentation_mode = if !program_segmentation_flag {
                 let component_count = r
                     .read_u8(8)
                     .named("segmentation_descriptor.component_count")?;
-                let mut components = Vec::with_capacity(component_count as usize);
+                let component_count = check_count(
+                    r,
+                    component_count as usize,
+                    48,
+                    "segmentation_descriptor.component_count",
+                )?;
+                let mut components = Vec::with_capacity(component_count);
 
                 for _ in 0..component_count {
                     let component_tag = r
@@ -878,8 +2725,12 @@ impl SpliceDescriptor {
             let segmentation_upid_length = r
                 .read_u8(8)
                 .named("segmentation_descriptor.segmentation_upid_length")?;
-            let segmentation_upid =
-                SegmentationUpid::parse(r, segmentation_upid_type, segmentation_upid_length)?;
+            let segmentation_upid = SegmentationUpid::parse(
+                r,
+                segmentation_upid_type,
+                segmentation_upid_length,
+                diagnostics,
+            )?;
 
             let segmentation_type_id =
                 SegmentationTypeId::from_id(r.read_u8(8).named("segmentation_type_id")?);
@@ -888,15 +2739,18 @@ impl SpliceDescriptor {
 
             // The spec notes: "sub_segment_num and sub_segments_expected can form an optional
             // appendix to the segmentation descriptor. The presence or absence of this optional
-            // data block is determined by the descriptor loop's descriptor_length."
-            let sub_segments = if r.relative_reader().skip(1).is_ok() {
-                Some(SubSegments {
-                    sub_segment_num: r.read_u8(8).named("sub_segment_num")?,
-                    sub_segments_expected: r.read_u8(8).named("sub_segments_expected")?,
-                })
-            } else {
-                None
-            };
+            // data block is determined by the descriptor loop's descriptor_length."  Its syntax
+            // only allows them for certain segmentation types; any bytes following other types
+            // are left to be reported as trailing data.
+            let sub_segments =
+                if segmentation_type_id.has_sub_segments() && r.relative_reader().skip(1).is_ok() {
+                    Some(SubSegments {
+                        sub_segment_num: r.read_u8(8).named("sub_segment_num")?,
+                        sub_segments_expected: r.read_u8(8).named("sub_segments_expected")?,
+                    })
+                } else {
+                    None
+                };
 
             Ok(SegmentationDescriptor::Insert {
                 program_segmentation_flag,
@@ -914,64 +2768,67 @@ impl SpliceDescriptor {
         }
     }
 
-    fn parse_segmentation_descriptor(buf: &[u8]) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
+    fn parse_segmentation_descriptor(
+        buf: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
         let mut r = bitreader::BitReader::new(buf);
         let id = r.read_u32(32).named("segmentation_descriptor.id")?;
         let cancel = r.read_bool().named("segmentation_descriptor.cancel")?;
-        r.skip(7).named("segmentation_descriptor.reserved")?;
+        skip_reserved(&mut r, 7, "segmentation_descriptor.reserved", diagnostics)?;
 
         let result = SpliceDescriptor::SegmentationDescriptor {
             segmentation_event_id: id,
-            descriptor_detail: Self::parse_segmentation_descriptor_details(&mut r, cancel)?,
+            descriptor_detail: Self::parse_segmentation_descriptor_details(
+                &mut r,
+                cancel,
+                diagnostics,
+            )?,
         };
 
         // if we end up without reading to the end of a byte, this must indicate a bug in the
-        // parsing routine,
-        assert!(r.is_aligned(1));
-
-        if buf.len() > (r.position() / 8) as usize {
-            error!(
-                "only {} bytes consumed data in segmentation_descriptor of {} bytes",
-                r.position() / 8,
-                buf.len()
-            );
-        }
+        // parsing routine
+        check_aligned(&r, "segmentation_descriptor")?;
+
+        report_trailing_data("segmentation_descriptor", &r, buf, diagnostics);
         Ok(result)
     }
 
-    fn parse_dtmf_descriptor(buf: &[u8]) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
+    fn parse_dtmf_descriptor(
+        buf: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
         let mut r = bitreader::BitReader::new(buf);
         let preroll = r.read_u8(8).named("dtmf_descriptor.preroll")?;
         let dtmf_count = r.read_u8(3).named("dtmf_descriptor.dtmf_count")?;
-        r.skip(5).named("dtmf_descriptor.reserved")?;
+        skip_reserved(&mut r, 5, "dtmf_descriptor.reserved", diagnostics)?;
+        check_count(&r, dtmf_count.into(), 8, "dtmf_descriptor.dtmf_count")?;
         let dtmf_chars_result: Result<Vec<u8>, BitReaderError> =
             (0..dtmf_count).map(|_| r.read_u8(8)).collect();
         let dtmf_chars = dtmf_chars_result.named("dtmf_descriptor")?;
 
         // if we end up without reading to the end of a byte, this must indicate a bug in the
-        // parsing routine,
-        assert!(r.is_aligned(1));
-
-        if buf.len() > (r.position() / 8) as usize {
-            error!(
-                "only {} bytes consumed data in segmentation_descriptor of {} bytes",
-                r.position() / 8,
-                buf.len()
-            );
-        }
+        // parsing routine
+        check_aligned(&r, "dtmf_descriptor")?;
+
+        report_trailing_data("dtmf_descriptor", &r, buf, diagnostics);
 
         Ok(SpliceDescriptor::DTMFDescriptor {
             preroll,
             dtmf_chars,
         })
     }
-    fn parse(buf: &[u8]) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
+    fn parse(
+        buf: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
         if buf.len() < 6 {
-            return Err(SpliceDescriptorErr::NotEnoughData {
-                field_name: "splice_descriptor",
-                actual: buf.len(),
-                expected: 6,
-            });
+            return Err(SpliceDescriptorErr::not_enough_data(
+                "splice_descriptor",
+                0,
+                6,
+                buf.len(),
+            ));
         }
         let splice_descriptor_tag = buf[0];
         let splice_descriptor_len = buf[1] as usize;
@@ -983,21 +2840,30 @@ impl SpliceDescriptor {
         }
         let splice_descriptor_end = splice_descriptor_len + 2;
         if splice_descriptor_end > buf.len() {
-            return Err(SpliceDescriptorErr::NotEnoughData {
-                field_name: "splice_descriptor.private_byte",
-                actual: buf.len(),
-                expected: splice_descriptor_end,
-            });
+            return Err(SpliceDescriptorErr::not_enough_data(
+                "splice_descriptor.private_byte",
+                0,
+                splice_descriptor_end,
+                buf.len(),
+            ));
         }
         let id = &buf[2..6];
         let payload = &buf[6..splice_descriptor_end];
         if id == b"CUEI" {
             match splice_descriptor_tag {
-                0x00 => Self::parse_avail_descriptor(payload),
-                0x01 => Self::parse_dtmf_descriptor(payload),
-                0x02 => Self::parse_segmentation_descriptor(payload),
-                0x03 => Self::parse_time_descriptor(payload),
-                _ => Self::parse_reserved(payload, splice_descriptor_tag, id),
+                0x00 => Self::parse_avail_descriptor(payload, diagnostics),
+                0x01 => Self::parse_dtmf_descriptor(payload, diagnostics),
+                0x02 => Self::parse_segmentation_descriptor(payload, diagnostics),
+                0x03 => Self::parse_time_descriptor(payload, diagnostics),
+                0x04 => Self::parse_audio_descriptor(payload, diagnostics),
+                _ => {
+                    // tags above 0x04 are not defined for the CUEI identifier, which usually
+                    // points to a malformed descriptor or a pre-standard encoder
+                    diagnostics.warning(&Scte35Warning::UnexpectedDescriptorTag(
+                        splice_descriptor_tag,
+                    ));
+                    Self::parse_reserved(payload, splice_descriptor_tag, id)
+                }
             }
         } else {
             Self::parse_reserved(payload, splice_descriptor_tag, id)
@@ -1016,14 +2882,51 @@ impl SpliceDescriptor {
         })
     }
 
-    fn parse_avail_descriptor(buf: &[u8]) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
-        if buf.len() < 4 {
-            return Err(SpliceDescriptorErr::NotEnoughData {
-                field_name: "avail_descriptor",
-                expected: 4,
-                actual: buf.len(),
+    fn parse_audio_descriptor(
+        buf: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
+        let mut r = bitreader::BitReader::new(buf);
+        let audio_count = r.read_u8(4).named("audio_descriptor.audio_count")?;
+        skip_reserved(&mut r, 4, "audio_descriptor.reserved", diagnostics)?;
+        let audio_count =
+            check_count(&r, audio_count as usize, 40, "audio_descriptor.audio_count")?;
+        let mut components = Vec::with_capacity(audio_count);
+        for _ in 0..audio_count {
+            components.push(AudioComponent {
+                component_tag: r.read_u8(8).named("audio_descriptor.component_tag")?,
+                iso_code: [
+                    r.read_u8(8).named("audio_descriptor.ISO_code")?,
+                    r.read_u8(8).named("audio_descriptor.ISO_code")?,
+                    r.read_u8(8).named("audio_descriptor.ISO_code")?,
+                ],
+                bit_stream_mode: r.read_u8(3).named("audio_descriptor.Bit_Stream_Mode")?,
+                num_channels: r.read_u8(4).named("audio_descriptor.Num_Channels")?,
+                full_srvc_audio: r.read_bool().named("audio_descriptor.Full_Srvc_Audio")?,
             });
         }
+
+        // if we end up without reading to the end of a byte, this must indicate a bug in the
+        // parsing routine
+        check_aligned(&r, "audio_descriptor")?;
+
+        report_trailing_data("audio_descriptor", &r, buf, diagnostics);
+        Ok(SpliceDescriptor::AudioDescriptor { components })
+    }
+
+    fn parse_avail_descriptor(
+        buf: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
+        if buf.len() < 4 {
+            return Err(SpliceDescriptorErr::not_enough_data(
+                "avail_descriptor",
+                0,
+                4,
+                buf.len(),
+            ));
+        }
+        report_trailing_bytes("avail_descriptor", 4, buf, diagnostics);
         Ok(SpliceDescriptor::AvailDescriptor {
             provider_avail_id: u32::from(buf[0]) << 24
                 | u32::from(buf[1]) << 16
@@ -1032,14 +2935,19 @@ impl SpliceDescriptor {
         })
     }
 
-    fn parse_time_descriptor(buf: &[u8]) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
+    fn parse_time_descriptor(
+        buf: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
         if buf.len() < 12 {
-            return Err(SpliceDescriptorErr::NotEnoughData {
-                field_name: "time_descriptor",
-                expected: 12,
-                actual: buf.len(),
-            });
+            return Err(SpliceDescriptorErr::not_enough_data(
+                "time_descriptor",
+                0,
+                12,
+                buf.len(),
+            ));
         }
+        report_trailing_bytes("time_descriptor", 12, buf, diagnostics);
         Ok(SpliceDescriptor::TimeDescriptor {
             tai_seconds: u64::from(buf[0]) << 40
                 | u64::from(buf[1]) << 32
@@ -1056,13 +2964,19 @@ impl SpliceDescriptor {
     }
 }
 
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, serde_derive::Serialize)]
 pub enum SpliceDescriptorErr {
+    /// A length field (usually the `descriptor_length`) held a value invalid for the structure
+    /// it describes
     InvalidDescriptorLength(usize),
+    /// The structure being read (a descriptor, command or UPID) ended before the named field.
+    /// `expected` bytes were required from the given offset, in bits from the start of the
+    /// structure, where only `actual` bytes remained.
     NotEnoughData {
         field_name: &'static str,
         expected: usize,
         actual: usize,
+        bit_offset: u64,
     },
     /// The segmentation_upid_length field value was `0`, but the segmentation_upid_type value was
     /// non-`0` (as indicated by the given `SegmentationUpidType` enum variant)
@@ -1078,10 +2992,40 @@ pub enum SpliceDescriptorErr {
         expected: usize,
         actual: usize,
     },
+    /// The named field held a value outside of the range the spec defines for it
+    InvalidFieldValue { field_name: &'static str, value: u8 },
+    /// The named count (or length) field gave more entries than could possibly fit in the data
+    /// remaining in the structure, which could hold at most `max`
+    CountExceedsData {
+        field_name: &'static str,
+        count: usize,
+        max: usize,
+    },
+    /// Parsing of the named syntax structure ended part-way through a byte, at the given bit
+    /// position.  This indicates a bug in this library, rather than a problem with the data.
+    Unaligned {
+        syntax: &'static str,
+        bit_position: u64,
+    },
+    /// Reading the named field failed for a reason other than running out of data.  This
+    /// indicates a bug in this library, rather than a problem with the data.
+    ReaderError {
+        field_name: &'static str,
+        message: String,
+    },
+    /// The given problem was found in the descriptor with the given `splice_descriptor_tag`,
+    /// which starts at the given byte offset within the descriptor loop.  Errors produced when
+    /// iterating over `SpliceDescriptors` are reported this way.
+    Descriptor {
+        splice_descriptor_tag: u8,
+        offset: usize,
+        error: Box<SpliceDescriptorErr>,
+    },
 }
 impl SpliceDescriptorErr {
     fn not_enough_data(
         field_name: &'static str,
+        bit_offset: u64,
         expected: usize,
         actual: usize,
     ) -> SpliceDescriptorErr {
@@ -1089,6 +3033,108 @@ impl SpliceDescriptorErr {
             field_name,
             expected,
             actual,
+            bit_offset,
+        }
+    }
+
+    /// Adds the location of the descriptor in which this problem was found
+    fn in_descriptor(self, splice_descriptor_tag: u8, offset: usize) -> SpliceDescriptorErr {
+        match self {
+            SpliceDescriptorErr::Descriptor { .. } => self,
+            _ => SpliceDescriptorErr::Descriptor {
+                splice_descriptor_tag,
+                offset,
+                error: Box::new(self),
+            },
+        }
+    }
+
+    /// The underlying problem, without the location given by any `Descriptor` wrapper
+    pub fn detail(&self) -> &SpliceDescriptorErr {
+        match self {
+            SpliceDescriptorErr::Descriptor { error, .. } => error,
+            _ => self,
+        }
+    }
+}
+impl std::fmt::Display for SpliceDescriptorErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use hex_slice::AsHex;
+        match self {
+            SpliceDescriptorErr::InvalidDescriptorLength(len) => {
+                write!(f, "invalid descriptor length {}", len)
+            }
+            SpliceDescriptorErr::NotEnoughData {
+                field_name,
+                expected,
+                actual,
+                bit_offset,
+            } => write!(
+                f,
+                "not enough data for {}: {} bytes required at bit offset {}, but only {} remain",
+                field_name, expected, bit_offset, actual
+            ),
+            SpliceDescriptorErr::SegmentationUpidLengthTypeMismatch(upid_type) => write!(
+                f,
+                "segmentation_upid_length is 0, but segmentation_upid_type is {}",
+                upid_type
+            ),
+            SpliceDescriptorErr::InvalidUpidContent { upid_type, bytes } => write!(
+                f,
+                "invalid content for {} UPID: {:02x}",
+                upid_type,
+                bytes.plain_hex(false)
+            ),
+            SpliceDescriptorErr::InvalidUpidLength {
+                upid_type,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} UPID should be {} bytes long, but is {}",
+                upid_type, expected, actual
+            ),
+            SpliceDescriptorErr::InvalidFieldValue { field_name, value } => {
+                write!(f, "invalid {} value {:#04x}", field_name, value)
+            }
+            SpliceDescriptorErr::Unaligned {
+                syntax,
+                bit_position,
+            } => write!(
+                f,
+                "parsing of {} ended part-way through a byte, at bit {}",
+                syntax, bit_position
+            ),
+            SpliceDescriptorErr::ReaderError {
+                field_name,
+                message,
+            } => write!(f, "failed to read {}: {}", field_name, message),
+            SpliceDescriptorErr::CountExceedsData {
+                field_name,
+                count,
+                max,
+            } => write!(
+                f,
+                "{} of {} exceeds the {} entries that the remaining data could hold",
+                field_name, count, max
+            ),
+            SpliceDescriptorErr::Descriptor {
+                splice_descriptor_tag,
+                offset,
+                ..
+            } => write!(
+                f,
+                "invalid splice_descriptor with tag {:#04x} at byte {} of the descriptor loop",
+                splice_descriptor_tag, offset
+            ),
+        }
+    }
+}
+impl std::error::Error for SpliceDescriptorErr {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpliceDescriptorErr::Descriptor { error, .. } => Some(error.as_ref()),
+            _ => None,
         }
     }
 }
@@ -1103,31 +3149,67 @@ impl<T> ErrorFieldNamed<T> for Result<T, bitreader::BitReaderError> {
                 position,
                 length,
                 requested,
-            }) => {
-                // TODO: round numbers up to nearest byte,
-                Err(SpliceDescriptorErr::NotEnoughData {
-                    field_name,
-                    expected: (requested / 8) as usize,
-                    actual: ((length - position) / 8) as usize,
-                })
-            }
-            Err(e) => {
-                panic!("scte35-reader bug: {:?}", e)
-            }
+            }) => Err(SpliceDescriptorErr::NotEnoughData {
+                field_name,
+                expected: requested.div_ceil(8) as usize,
+                actual: ((length - position) / 8) as usize,
+                bit_offset: position,
+            }),
+            Err(e) => Err(SpliceDescriptorErr::ReaderError {
+                field_name,
+                message: e.to_string(),
+            }),
             Ok(v) => Ok(v),
         }
     }
 }
 
+/// The descriptor loop of a section, whose descriptors are parsed as they are iterated over.
+///
+/// A descriptor which can not be parsed produces an `Err` item in its place, after which
+/// iteration continues with the descriptor following it (as located by the failed descriptor's
+/// `descriptor_length`), so that one malformed descriptor does not hide the others.  Iteration
+/// ends early only if a `descriptor_length` runs beyond the end of the loop.
 pub struct SpliceDescriptors<'buf> {
     buf: &'buf [u8],
+    diagnostics: &'buf dyn Scte35Diagnostics,
+}
+impl<'buf> SpliceDescriptors<'buf> {
+    /// Iterates over borrowed views of the descriptors, which (unlike the owned
+    /// `SpliceDescriptor` values produced by iterating over `&SpliceDescriptors`) require no
+    /// heap allocation.  See the [`view`](view/index.html) module.
+    pub fn views(&self) -> view::SpliceDescriptorViews<'buf> {
+        view::SpliceDescriptorViews {
+            iter: SpliceDescriptorIter::new(self.buf, self.diagnostics),
+        }
+    }
+
+    /// Iterates over the descriptors, using the parsers in the given registry for any
+    /// descriptors that it handles.  See the [`registry`](registry/index.html) module.
+    pub fn parse_with<'reg, T>(
+        &self,
+        registry: &'reg registry::DescriptorRegistry<T>,
+    ) -> registry::RegisteredDescriptors<'buf, 'reg, T> {
+        registry::RegisteredDescriptors {
+            iter: SpliceDescriptorIter::new(self.buf, self.diagnostics),
+            registry,
+        }
+    }
+
+    /// The `splice_descriptor_tag` of each descriptor in the loop whose length is valid, found
+    /// without parsing the descriptors
+    pub(crate) fn tags(&self) -> impl Iterator<Item = u8> + 'buf {
+        let mut iter = SpliceDescriptorIter::new(self.buf, self.diagnostics);
+        std::iter::from_fn(move || iter.split_next())
+            .filter_map(|(_, result)| result.ok().map(|desc| desc[0]))
+    }
 }
 impl<'buf> IntoIterator for &SpliceDescriptors<'buf> {
     type Item = Result<SpliceDescriptor, SpliceDescriptorErr>;
     type IntoIter = SpliceDescriptorIter<'buf>;
 
     fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
-        SpliceDescriptorIter::new(self.buf)
+        SpliceDescriptorIter::new(self.buf, self.diagnostics)
     }
 }
 impl<'a> serde::Serialize for SpliceDescriptors<'a> {
@@ -1136,69 +3218,160 @@ impl<'a> serde::Serialize for SpliceDescriptors<'a> {
         S: serde::Serializer,
     {
         let mut s = serializer.serialize_seq(None)?;
-        for elem in self.into_iter().flatten() {
-            s.serialize_element(&elem)?;
+        let mut iter = self.into_iter();
+        while let Some((bytes, result)) = iter.next_with_bytes() {
+            match result {
+                Ok(elem) => s.serialize_element(&elem)?,
+                Err(error) => s.serialize_element(&SpliceDescriptorErrEntry::Error {
+                    error: &error,
+                    bytes,
+                })?,
+            }
         }
         s.end()
     }
 }
 
+/// Serialized in place of a descriptor that could not be parsed, so that consumers of the
+/// serialized form can distinguish a clean message from one with a malformed descriptor
+#[derive(serde_derive::Serialize)]
+enum SpliceDescriptorErrEntry<'a> {
+    Error {
+        error: &'a SpliceDescriptorErr,
+        bytes: &'a [u8],
+    },
+}
+
+/// The bytes of a descriptor (or the remaining bytes of the loop, on failure), along with the
+/// bytes of the descriptor alone if its length could be determined
+type DescriptorBytes<'buf> = (&'buf [u8], Result<&'buf [u8], SpliceDescriptorErr>);
+
 pub struct SpliceDescriptorIter<'buf> {
     buf: &'buf [u8],
+    /// The length of the whole descriptor loop, from which the offset of each descriptor is
+    /// derived
+    loop_length: usize,
+    diagnostics: &'buf dyn Scte35Diagnostics,
 }
 impl<'buf> SpliceDescriptorIter<'buf> {
-    fn new(buf: &'buf [u8]) -> SpliceDescriptorIter<'_> {
-        SpliceDescriptorIter { buf }
+    fn new(
+        buf: &'buf [u8],
+        diagnostics: &'buf dyn Scte35Diagnostics,
+    ) -> SpliceDescriptorIter<'buf> {
+        SpliceDescriptorIter {
+            buf,
+            loop_length: buf.len(),
+            diagnostics,
+        }
     }
 }
-impl<'buf> Iterator for SpliceDescriptorIter<'buf> {
-    type Item = Result<SpliceDescriptor, SpliceDescriptorErr>;
+impl<'buf> SpliceDescriptorIter<'buf> {
+    /// Like `next()`, but additionally returns the bytes of the descriptor that was parsed (or
+    /// the remaining bytes, if the descriptor's length could not be determined)
+    fn next_with_bytes(
+        &mut self,
+    ) -> Option<(&'buf [u8], Result<SpliceDescriptor, SpliceDescriptorErr>)> {
+        let diagnostics = self.diagnostics;
+        let offset = self.offset();
+        self.next_bytes().map(|(bytes, result)| {
+            (
+                bytes,
+                result.and_then(|desc| {
+                    SpliceDescriptor::parse(desc, diagnostics)
+                        .map_err(|e| e.in_descriptor(desc[0], offset))
+                }),
+            )
+        })
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    /// The offset within the descriptor loop of the next descriptor
+    fn offset(&self) -> usize {
+        self.loop_length - self.buf.len()
+    }
+
+    /// Splits off the bytes of the next descriptor, without parsing them.  Errors are located as
+    /// for `SpliceDescriptorErr::Descriptor`.
+    fn next_bytes(&mut self) -> Option<DescriptorBytes<'buf>> {
+        let offset = self.offset();
+        let tag = *self.buf.first()?;
+        self.split_next()
+            .map(|(bytes, result)| (bytes, result.map_err(|e| e.in_descriptor(tag, offset))))
+    }
+
+    fn split_next(&mut self) -> Option<DescriptorBytes<'buf>> {
         if self.buf.is_empty() {
             return None;
         }
-        if self.buf.len() < 6 {
+        let buf = self.buf;
+        if buf.len() < 2 {
             self.buf = &self.buf[0..0];
-            return Some(Err(SpliceDescriptorErr::NotEnoughData {
-                field_name: "splice_descriptor",
-                expected: 2,
-                actual: self.buf.len(),
-            }));
+            return Some((
+                buf,
+                Err(SpliceDescriptorErr::not_enough_data(
+                    "splice_descriptor",
+                    0,
+                    2,
+                    buf.len(),
+                )),
+            ));
         }
+        // a descriptor too short to hold its identifier is split off by its descriptor_length
+        // like any other, to be reported when parsed, so that the descriptors following it are
+        // still found
         let descriptor_length = self.buf[1] as usize;
         if self.buf.len() < descriptor_length + 2 {
             self.buf = &self.buf[0..0];
-            return Some(Err(SpliceDescriptorErr::NotEnoughData {
-                field_name: "splice_descriptor",
-                expected: descriptor_length + 2,
-                actual: self.buf.len(),
-            }));
-        }
-        if descriptor_length > 254 {
-            self.buf = &self.buf[0..0];
-            return Some(Err(SpliceDescriptorErr::InvalidDescriptorLength(
-                descriptor_length,
-            )));
+            return Some((
+                buf,
+                Err(SpliceDescriptorErr::not_enough_data(
+                    "splice_descriptor",
+                    0,
+                    descriptor_length + 2,
+                    buf.len(),
+                )),
+            ));
         }
         let (desc, rest) = self.buf.split_at(2 + descriptor_length);
-        let result = SpliceDescriptor::parse(desc);
         self.buf = rest;
-        Some(result)
+        if descriptor_length > 254 {
+            return Some((
+                desc,
+                Err(SpliceDescriptorErr::InvalidDescriptorLength(
+                    descriptor_length,
+                )),
+            ));
+        }
+        Some((desc, Ok(desc)))
+    }
+}
+impl<'buf> Iterator for SpliceDescriptorIter<'buf> {
+    type Item = Result<SpliceDescriptor, SpliceDescriptorErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_bytes().map(|(_, result)| result)
     }
 }
 
-pub struct Scte35SectionProcessor<P, Ctx: demultiplex::DemuxContext>
+pub struct Scte35SectionProcessor<P, Ctx: demultiplex::DemuxContext, U = (), D = LogDiagnostics>
 where
-    P: SpliceInfoProcessor,
+    P: SpliceInfoUserProcessor<U>,
+    D: Scte35Diagnostics,
 {
     processor: P,
+    user: U,
+    diagnostics: D,
+    max_section_length: usize,
+    crc_mode: CrcMode,
+    protocol_version_handler: fn(u8) -> bool,
+    authorized_tiers: Vec<Tier>,
+    pid: Option<mpeg2ts_reader::packet::Pid>,
     phantom: marker::PhantomData<Ctx>,
 }
-impl<P, Ctx: demultiplex::DemuxContext> psi::WholeCompactSyntaxPayloadParser
-    for Scte35SectionProcessor<P, Ctx>
+impl<P, Ctx: demultiplex::DemuxContext, U, D> psi::WholeCompactSyntaxPayloadParser
+    for Scte35SectionProcessor<P, Ctx, U, D>
 where
-    P: SpliceInfoProcessor,
+    P: SpliceInfoUserProcessor<U>,
+    D: Scte35Diagnostics,
 {
     type Context = Ctx;
 
@@ -1208,359 +3381,1988 @@ where
         header: &psi::SectionCommonHeader,
         data: &[u8],
     ) {
-        if header.table_id == 0xfc {
-            // no CRC while fuzz-testing, to make it more likely to find parser bugs,
-            if !cfg!(fuzzing) {
-                let crc = mpeg2ts_reader::mpegts_crc::sum32(data);
-                if crc != 0 {
-                    error!("section CRC check failed {:#08x}", crc);
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "scte35_section",
+            pid = self.pid.map(u16::from),
+            command_type = tracing::field::Empty,
+            splice_event_id = tracing::field::Empty,
+            pts = tracing::field::Empty,
+        );
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        match parse_section(
+            header,
+            data,
+            self.max_section_length,
+            self.crc_mode,
+            self.protocol_version_handler,
+            &self.diagnostics,
+        ) {
+            Ok((splice_header, splice_command, descriptors)) => {
+                #[cfg(feature = "tracing")]
+                trace_section(&span, &splice_header, &splice_command);
+                self.diagnostics
+                    .section_parsed(&splice_header, &splice_command, &descriptors);
+                let tier = splice_header.tier();
+                if !self.authorized_tiers.is_empty()
+                    && !self.authorized_tiers.iter().any(|&a| tier.matches(a))
+                {
+                    self.diagnostics.tier_filtered(tier);
                     return;
                 }
-            }
-            let section_data = &data[psi::SectionCommonHeader::SIZE..];
-            if section_data.len() < SpliceInfoHeader::HEADER_LENGTH + 4 {
-                error!(
-                    "section data too short: {} (must be at least {})",
-                    section_data.len(),
-                    SpliceInfoHeader::HEADER_LENGTH + 4
+                self.processor.process_section_with(
+                    &self.user,
+                    data,
+                    splice_header,
+                    splice_command,
+                    descriptors,
                 );
-                return;
-            }
-            // trim off the 32-bit CRC
-            let section_data = &section_data[..section_data.len() - 4];
-            let (splice_header, rest) = SpliceInfoHeader::new(section_data);
-            if splice_header.encrypted_packet() {
-                error!("encrypted SCTE-35 data not supoprted");
-                return;
-            }
-            let command_len = splice_header.splice_command_length() as usize;
-            if command_len > rest.len() {
-                error!("splice_command_length of {} bytes is too long to fit in remaining {} bytes of section data", command_len, rest.len());
-                return;
-            }
-            let (payload, rest) = rest.split_at(command_len);
-            if rest.len() < 2 {
-                error!("end of section data while trying to read descriptor_loop_length");
-                return;
-            }
-            let descriptor_loop_length = (u16::from(rest[0]) << 8 | u16::from(rest[1])) as usize;
-            if descriptor_loop_length + 2 > rest.len() {
-                error!("descriptor_loop_length of {} bytes is too long to fit in remaining {} bytes of section data", descriptor_loop_length, rest.len());
-                return;
-            }
-            let descriptors = &rest[2..2 + descriptor_loop_length];
-            let splice_command = match splice_header.splice_command_type() {
-                SpliceCommandType::SpliceNull => Some(Self::splice_null(payload)),
-                SpliceCommandType::SpliceInsert => Some(Self::splice_insert(payload)),
-                SpliceCommandType::TimeSignal => Some(Self::time_signal(payload)),
-                SpliceCommandType::BandwidthReservation => {
-                    Some(Self::bandwidth_reservation(payload))
-                }
-                SpliceCommandType::PrivateCommand => Some(Self::private_command(payload)),
-                _ => None,
-            };
-            match splice_command {
-                Some(Ok(splice_command)) => {
-                    self.processor.process(
-                        splice_header,
-                        splice_command,
-                        SpliceDescriptors { buf: descriptors },
-                    );
-                }
-                Some(Err(e)) => {
-                    error!("parse error: {:?}", e);
-                }
-                None => {
-                    error!(
-                        "unhandled command {:?}",
-                        splice_header.splice_command_type()
-                    );
-                }
             }
-        } else {
-            error!(
-                "bad table_id for scte35: {:#x} (expected 0xfc)",
-                header.table_id
-            );
+            Err(e) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %e, "section could not be parsed");
+                self.diagnostics.error(&e)
+            }
         }
     }
 }
-impl<P, Ctx: demultiplex::DemuxContext> Scte35SectionProcessor<P, Ctx>
-where
-    P: SpliceInfoProcessor,
-{
-    pub fn new(processor: P) -> Scte35SectionProcessor<P, Ctx> {
-        Scte35SectionProcessor {
-            processor,
-            phantom: marker::PhantomData,
-        }
+
+/// Records the details of a successfully parsed section in the span created for it, and emits
+/// an event within that span
+#[cfg(feature = "tracing")]
+fn trace_section(span: &tracing::Span, header: &SpliceInfoHeader<'_>, command: &SpliceCommand) {
+    span.record(
+        "command_type",
+        tracing::field::display(header.splice_command_type()),
+    );
+    if let SpliceCommand::SpliceInsert {
+        splice_event_id, ..
+    } = command
+    {
+        span.record("splice_event_id", splice_event_id);
     }
-    fn splice_null(payload: &[u8]) -> Result<SpliceCommand, SpliceDescriptorErr> {
-        if payload.is_empty() {
-            Ok(SpliceCommand::SpliceNull {})
-        } else {
-            Err(SpliceDescriptorErr::InvalidDescriptorLength(payload.len()))
-        }
+    if let Some(pts) = command.splice_pts(header.pts_adjustment()) {
+        span.record("pts", pts);
     }
+    tracing::debug!("section parsed");
+}
 
-    fn splice_insert(payload: &[u8]) -> Result<SpliceCommand, SpliceDescriptorErr> {
-        let mut r = bitreader::BitReader::new(payload);
-
-        let splice_event_id = r.read_u32(32).named("splice_insert.splice_event_id")?;
-        let splice_event_cancel_indicator = r
-            .read_bool()
-            .named("splice_insert.splice_event_cancel_indicator")?;
-        let reserved = r.read_u8(7).named("splice_insert.reserved")?;
-        let result = SpliceCommand::SpliceInsert {
-            splice_event_id,
-            reserved,
-            splice_detail: Self::read_splice_detail(&mut r, splice_event_cancel_indicator)?,
-        };
-
-        // if we end up without reading to the end of a byte, this must indicate a bug in the
-        // parsing routine,
-        assert!(r.is_aligned(1));
+/// Non-fatal problems noticed while parsing a `splice_info_section()`, which do not prevent it
+/// from being processed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scte35Warning {
+    /// The named syntax structure was followed by bytes that the parser did not consume
+    TrailingData {
+        syntax: &'static str,
+        consumed: usize,
+        length: usize,
+    },
+    /// A descriptor with the `CUEI` identifier had a `splice_descriptor_tag` value outside of
+    /// the range the spec defines, which usually indicates a malformed descriptor or a
+    /// pre-standard encoder.  The descriptor is reported as `SpliceDescriptor::Reserved`.
+    UnexpectedDescriptorTag(u8),
+    /// The section's `CRC_32` did not match its content, but the section was processed anyway
+    /// because `CrcMode::Warn` is in effect
+    Crc(u32),
+    /// The section's `splice_command_length` had the value `0xfff`, used by older encoders to
+    /// indicate that the length is not given, so the _splice-command_ was parsed up to the point
+    /// at which its content ends
+    LegacySpliceCommandLength,
+    /// The section's `private_indicator` was set, though it should be `0` for a
+    /// `splice_info_section()`
+    PrivateIndicator,
+    /// The named reserved field held the given value, rather than having all bits set to `1`
+    ReservedBits { field_name: &'static str, value: u8 },
+    /// A UPID had a length other than that which its type requires, but was accepted because
+    /// `UpidLengthMode::Warn` is in effect.  The UPID is given as `SegmentationUpid::Reserved`,
+    /// holding its bytes undecoded (or as `SegmentationUpid::None`, if its length was `0`).
+    UpidLength {
+        upid_type: SegmentationUpidType,
+        expected: usize,
+        actual: usize,
+    },
+    /// A URI UPID held the given bytes, which are not valid UTF-8 or could not be parsed as a
+    /// URL.  The UPID is given as `SegmentationUpid::Reserved`, holding its bytes undecoded.
+    MalformedUri(Vec<u8>),
+}
 
-        if payload.len() > (r.position() / 8) as usize {
-            error!(
-                "only {} bytes consumed data in splice_insert of {} bytes",
-                r.position() / 8,
-                payload.len()
-            );
+impl Scte35Warning {
+    /// The `log` target under which the default `Scte35Diagnostics` reports this warning
+    fn log_target(&self) -> &'static str {
+        match self {
+            Scte35Warning::Crc(_) => LOG_TARGET_CRC,
+            Scte35Warning::LegacySpliceCommandLength => LOG_TARGET_COMMAND,
+            Scte35Warning::PrivateIndicator => LOG_TARGET_SECTION,
+            Scte35Warning::UnexpectedDescriptorTag(_)
+            | Scte35Warning::UpidLength { .. }
+            | Scte35Warning::MalformedUri(_) => LOG_TARGET_DESCRIPTOR,
+            Scte35Warning::TrailingData { syntax: name, .. }
+            | Scte35Warning::ReservedBits {
+                field_name: name, ..
+            } => {
+                if name.contains("_descriptor") {
+                    LOG_TARGET_DESCRIPTOR
+                } else {
+                    LOG_TARGET_COMMAND
+                }
+            }
         }
-        Ok(result)
     }
+}
 
-    fn time_signal(payload: &[u8]) -> Result<SpliceCommand, SpliceDescriptorErr> {
-        let mut r = bitreader::BitReader::new(payload);
-
-        let result = SpliceCommand::TimeSignal {
-            splice_time: SpliceTime::Timed(Self::read_splice_time(&mut r)?),
-        };
-
-        // if we end up without reading to the end of a byte, this must indicate a bug in the
-        // parsing routine,
-        assert!(r.is_aligned(1));
+const LOG_TARGET_CRC: &str = "scte35::crc";
+const LOG_TARGET_COMMAND: &str = "scte35::command";
+const LOG_TARGET_DESCRIPTOR: &str = "scte35::descriptor";
+const LOG_TARGET_SECTION: &str = "scte35::section";
 
-        if payload.len() > (r.position() / 8) as usize {
-            error!(
-                "only {} bytes consumed data in time_signal of {} bytes",
-                r.position() / 8,
-                payload.len()
-            );
+/// How a `Scte35SectionProcessor` treats sections whose `CRC_32` does not match their content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrcMode {
+    /// Report `Scte35Error::Crc` and drop the section
+    Strict,
+    /// Report `Scte35Warning::Crc`, but process the section regardless
+    Warn,
+    /// Do not check the `CRC_32` at all
+    Skip,
+}
+impl Default for CrcMode {
+    /// `Strict`, except when fuzz-testing, where `Skip` makes it more likely that the fuzzer
+    /// will find parser bugs
+    fn default() -> CrcMode {
+        if cfg!(fuzzing) {
+            CrcMode::Skip
+        } else {
+            CrcMode::Strict
         }
-        Ok(result)
+    }
+}
+
+/// How UPIDs are treated whose `segmentation_upid_length` differs from the fixed length that
+/// their `segmentation_upid_type` requires (for example, an Ad-ID which is not 12 bytes long)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpidLengthMode {
+    /// Fail the descriptor with `SpliceDescriptorErr::InvalidUpidLength`
+    #[default]
+    Strict,
+    /// Report `Scte35Warning::UpidLength`, but accept the descriptor regardless
+    Warn,
+}
+/// Receives notification of the problems found in the sections given to a
+/// `Scte35SectionProcessor`, so that applications can route them to their own logging or
+/// metrics.
+///
+/// Warnings about splice descriptors are delivered as the descriptors are iterated over by the
+/// `SpliceInfoProcessor`.  The default implementations of both methods log the problem via the
+/// `log` crate, with one of the targets `scte35::crc`, `scte35::command`, `scte35::descriptor`
+/// or `scte35::section` according to where in the section the problem lies.
+pub trait Scte35Diagnostics {
+    /// The section could not be processed, and has not been passed to the `SpliceInfoProcessor`
+    fn error(&self, error: &Scte35Error) {
+        error!(target: error.log_target(), "{:?}", error);
     }
 
-    fn bandwidth_reservation(payload: &[u8]) -> Result<SpliceCommand, SpliceDescriptorErr> {
-        if payload.is_empty() {
-            Ok(SpliceCommand::BandwidthReservation {})
+    /// The section was processed despite the given problem.  The default implementation logs
+    /// `Scte35Warning::ReservedBits` at debug level, since many encoders leave reserved bits set
+    /// to `0`, and other warnings at warn level.
+    fn warning(&self, warning: &Scte35Warning) {
+        if let Scte35Warning::ReservedBits { .. } = warning {
+            debug!(target: warning.log_target(), "{:?}", warning);
         } else {
-            Err(SpliceDescriptorErr::InvalidDescriptorLength(payload.len()))
+            warn!(target: warning.log_target(), "{:?}", warning);
         }
     }
 
-    fn private_command(payload: &[u8]) -> Result<SpliceCommand, SpliceDescriptorErr> {
-        let mut r = bitreader::BitReader::new(payload);
-        let identifier = r.read_u32(32).named("private_command.identifier")?;
-        Ok(SpliceCommand::PrivateCommand {
-            identifier,
-            private_bytes: payload[4..].to_vec(),
-        })
+    /// The section's tier did not match any of those given to
+    /// `Scte35SectionProcessor::with_authorized_tiers()`, so it has not been passed to the
+    /// `SpliceInfoProcessor`.  This is expected in multi-tenant distribution, so the default
+    /// implementation does nothing.
+    fn tier_filtered(&self, _tier: Tier) {}
+
+    /// The section was parsed without error.  This is called before the section's tier is
+    /// checked against any given to `Scte35SectionProcessor::with_authorized_tiers()`, and
+    /// before the section is passed to the `SpliceInfoProcessor`.  The default implementation
+    /// does nothing.
+    fn section_parsed(
+        &self,
+        _header: &SpliceInfoHeader<'_>,
+        _command: &SpliceCommand,
+        _descriptors: &SpliceDescriptors<'_>,
+    ) {
     }
 
-    fn read_splice_detail(
-        r: &mut bitreader::BitReader<'_>,
-        splice_event_cancel_indicator: bool,
-    ) -> Result<SpliceInsert, SpliceDescriptorErr> {
-        if splice_event_cancel_indicator {
-            Ok(SpliceInsert::Cancel)
-        } else {
-            r.relative_reader().skip(1).named("splice_insert.flags")?;
-            let network_indicator =
-                NetworkIndicator::from_flag(r.read_u8(1).named("splice_insert.network_indicator")?);
-            let program_splice_flag = r.read_bool().named("splice_insert.program_splice_flag")?;
-            let duration_flag = r.read_bool().named("splice_insert.duration_flag")?;
-            let splice_immediate_flag =
-                r.read_bool().named("splice_insert.splice_immediate_flag")?;
-            r.skip(4).named("splice_insert.reserved")?;
+    /// How UPIDs of the wrong length for their type are to be treated, for monitoring of
+    /// sources known to signal malformed UPIDs.  Defaults to `UpidLengthMode::Strict`.
+    fn upid_length_mode(&self) -> UpidLengthMode {
+        UpidLengthMode::Strict
+    }
+}
+/// Allows a single `Scte35Diagnostics` (such as `metrics::Scte35Metrics`) to be shared between
+/// several `Scte35SectionProcessor` instances, and with the rest of the application
+impl<T: Scte35Diagnostics + ?Sized> Scte35Diagnostics for std::sync::Arc<T> {
+    fn error(&self, error: &Scte35Error) {
+        (**self).error(error)
+    }
 
-            Ok(SpliceInsert::Insert {
-                network_indicator,
-                splice_mode: Self::read_splice_mode(r, program_splice_flag, splice_immediate_flag)?,
-                duration: if duration_flag {
-                    Some(Self::read_duration(r)?)
-                } else {
-                    None
-                },
-                unique_program_id: r.read_u16(16).named("unique_program_id")?,
-                avail_num: r.read_u8(8).named("avail_num")?,
-                avails_expected: r.read_u8(8).named("avails_expected")?,
-            })
-        }
+    fn warning(&self, warning: &Scte35Warning) {
+        (**self).warning(warning)
     }
 
-    fn read_splice_mode(
-        r: &mut bitreader::BitReader<'_>,
-        program_splice_flag: bool,
-        splice_immediate_flag: bool,
-    ) -> Result<SpliceMode, SpliceDescriptorErr> {
-        if program_splice_flag {
-            let time = if splice_immediate_flag {
-                SpliceTime::Immediate
-            } else {
-                SpliceTime::Timed(Self::read_splice_time(r)?)
-            };
-            Ok(SpliceMode::Program(time))
-        } else {
-            let component_count = r.read_u8(8).named("component_count")? as usize;
-            let mut components = Vec::with_capacity(component_count);
-            for _ in 0..component_count {
-                let component_tag = r.read_u8(8).named("component_tag")?;
-                let splice_time = if splice_immediate_flag {
-                    SpliceTime::Immediate
-                } else {
-                    SpliceTime::Timed(Self::read_splice_time(r)?)
-                };
-                components.push(ComponentSplice {
-                    component_tag,
-                    splice_time,
-                });
-            }
-            Ok(SpliceMode::Components(components))
-        }
+    fn tier_filtered(&self, tier: Tier) {
+        (**self).tier_filtered(tier)
     }
 
-    fn read_splice_time(
-        r: &mut bitreader::BitReader<'_>,
-    ) -> Result<Option<u64>, SpliceDescriptorErr> {
-        Ok(if r.read_bool().named("splice_time.time_specified_flag")? {
-            r.skip(6).named("splice_time.reserved")?; // reserved
-            Some(r.read_u64(33).named("splice_time.pts_time")?)
-        } else {
-            r.skip(7).named("splice_time.reserved")?; // reserved
-            None
-        })
+    fn section_parsed(
+        &self,
+        header: &SpliceInfoHeader<'_>,
+        command: &SpliceCommand,
+        descriptors: &SpliceDescriptors<'_>,
+    ) {
+        (**self).section_parsed(header, command, descriptors)
     }
 
-    fn read_duration(
-        r: &mut bitreader::BitReader<'_>,
-    ) -> Result<SpliceDuration, SpliceDescriptorErr> {
-        let return_mode = ReturnMode::from_flag(r.read_u8(1).named("break_duration.auto_return")?);
-        r.skip(6).named("break_duration.reserved")?;
-        Ok(SpliceDuration {
-            return_mode,
-            duration: r.read_u64(33).named("break_duration.duration")?,
+    fn upid_length_mode(&self) -> UpidLengthMode {
+        (**self).upid_length_mode()
+    }
+}
+
+/// The default `Scte35Diagnostics`, which logs every problem via the `log` crate
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogDiagnostics;
+impl Scte35Diagnostics for LogDiagnostics {}
+
+/// Skips the given number of reserved bits, reporting `Scte35Warning::ReservedBits` if they are
+/// not all set to `1` as the spec requires
+fn skip_reserved(
+    r: &mut bitreader::BitReader<'_>,
+    bits: u8,
+    field_name: &'static str,
+    diagnostics: &dyn Scte35Diagnostics,
+) -> Result<(), SpliceDescriptorErr> {
+    let value = r.read_u8(bits).named(field_name)?;
+    if u16::from(value) != (1 << bits) - 1 {
+        diagnostics.warning(&Scte35Warning::ReservedBits { field_name, value });
+    }
+    Ok(())
+}
+
+fn check_aligned(
+    r: &bitreader::BitReader<'_>,
+    syntax: &'static str,
+) -> Result<(), SpliceDescriptorErr> {
+    if r.is_aligned(1) {
+        Ok(())
+    } else {
+        Err(SpliceDescriptorErr::Unaligned {
+            syntax,
+            bit_position: r.position(),
         })
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use hex_literal::*;
-    use matches::*;
-    use mpeg2ts_reader::demultiplex;
-    use mpeg2ts_reader::psi;
-    use mpeg2ts_reader::psi::WholeCompactSyntaxPayloadParser;
+/// Checks that `count` entries, each occupying at least `min_entry_bits`, could fit in the data
+/// remaining to the given reader, so that a corrupt count is rejected before it is used to size
+/// an allocation
+fn check_count(
+    r: &bitreader::BitReader<'_>,
+    count: usize,
+    min_entry_bits: u64,
+    field_name: &'static str,
+) -> Result<usize, SpliceDescriptorErr> {
+    let max = (r.remaining() / min_entry_bits) as usize;
+    if count > max {
+        return Err(SpliceDescriptorErr::CountExceedsData {
+            field_name,
+            count,
+            max,
+        });
+    }
+    Ok(count)
+}
+
+fn report_trailing_data(
+    syntax: &'static str,
+    r: &bitreader::BitReader<'_>,
+    buf: &[u8],
+    diagnostics: &dyn Scte35Diagnostics,
+) {
+    report_trailing_bytes(syntax, (r.position() / 8) as usize, buf, diagnostics);
+}
+
+fn report_trailing_bytes(
+    syntax: &'static str,
+    consumed: usize,
+    buf: &[u8],
+    diagnostics: &dyn Scte35Diagnostics,
+) {
+    if buf.len() > consumed {
+        diagnostics.warning(&Scte35Warning::TrailingData {
+            syntax,
+            consumed,
+            length: buf.len(),
+        });
+    }
+}
+
+/// Problems which prevent a `splice_info_section()` from being processed
+#[derive(Debug, Clone)]
+pub enum Scte35Error {
+    /// The section's `table_id` was not the expected value, `0xfc`
+    BadTableId(u8),
+    /// The section's `section_syntax_indicator` was set, though it must be `0` for a
+    /// `splice_info_section()`
+    SectionSyntaxIndicator,
+    /// The section's `section_length` exceeded the configured maximum
+    SectionTooLong { section_length: usize, max: usize },
+    /// The section's `CRC_32` did not match its content
+    Crc(u32),
+    /// The section is too short to hold the fixed-size fields of the `splice_info_section()`
+    SectionTooShort { actual: usize, expected: usize },
+    /// The section's `protocol_version` was not `0`, the only version this library supports (see
+    /// `Scte35SectionProcessor::with_protocol_version_handler()`)
+    UnsupportedProtocolVersion(u8),
+    /// The section is encrypted, which is not supported for parsing
+    Encrypted,
+    /// The `splice_command_length` value was too long to fit in the section
+    SpliceCommandLength {
+        splice_command_length: usize,
+        available: usize,
+    },
+    /// The section ended before the `descriptor_loop_length` field
+    MissingDescriptorLoopLength,
+    /// The `descriptor_loop_length` value was too long to fit in the section
+    DescriptorLoopLength {
+        descriptor_loop_length: usize,
+        available: usize,
+    },
+    /// The _splice-command_ could not be parsed
+    Command(SpliceDescriptorErr),
+    /// One of the section's splice descriptors could not be parsed
+    Descriptor(SpliceDescriptorErr),
+}
+impl Scte35Error {
+    /// The `log` target under which the default `Scte35Diagnostics` reports this error
+    fn log_target(&self) -> &'static str {
+        match self {
+            Scte35Error::Crc(_) => LOG_TARGET_CRC,
+            Scte35Error::SpliceCommandLength { .. } | Scte35Error::Command(_) => LOG_TARGET_COMMAND,
+            Scte35Error::MissingDescriptorLoopLength
+            | Scte35Error::DescriptorLoopLength { .. }
+            | Scte35Error::Descriptor(_) => LOG_TARGET_DESCRIPTOR,
+            _ => LOG_TARGET_SECTION,
+        }
+    }
+}
+impl std::fmt::Display for Scte35Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scte35Error::BadTableId(table_id) => {
+                write!(
+                    f,
+                    "table_id {:#04x} is not that of a splice_info_section",
+                    table_id
+                )
+            }
+            Scte35Error::SectionSyntaxIndicator => {
+                f.write_str("section_syntax_indicator is set, but must be 0")
+            }
+            Scte35Error::SectionTooLong {
+                section_length,
+                max,
+            } => write!(
+                f,
+                "section_length {} exceeds the maximum of {}",
+                section_length, max
+            ),
+            Scte35Error::Crc(crc) => write!(f, "CRC_32 check failed (residue {:#010x})", crc),
+            Scte35Error::SectionTooShort { actual, expected } => write!(
+                f,
+                "section of {} bytes is too short, needing at least {}",
+                actual, expected
+            ),
+            Scte35Error::UnsupportedProtocolVersion(protocol_version) => {
+                write!(f, "unsupported protocol_version {}", protocol_version)
+            }
+            Scte35Error::Encrypted => f.write_str("encrypted sections can not be parsed"),
+            Scte35Error::SpliceCommandLength {
+                splice_command_length,
+                available,
+            } => write!(
+                f,
+                "splice_command_length {} exceeds the {} bytes available",
+                splice_command_length, available
+            ),
+            Scte35Error::MissingDescriptorLoopLength => {
+                f.write_str("section ends before descriptor_loop_length")
+            }
+            Scte35Error::DescriptorLoopLength {
+                descriptor_loop_length,
+                available,
+            } => write!(
+                f,
+                "descriptor_loop_length {} exceeds the {} bytes available",
+                descriptor_loop_length, available
+            ),
+            Scte35Error::Command(_) => f.write_str("invalid splice command"),
+            Scte35Error::Descriptor(_) => f.write_str("invalid splice descriptor"),
+        }
+    }
+}
+impl std::error::Error for Scte35Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Scte35Error::Command(e) | Scte35Error::Descriptor(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// The default handler for sections with a `protocol_version` other than `0`
+pub(crate) fn reject_protocol_version(_protocol_version: u8) -> bool {
+    false
+}
+
+/// Validates the given complete section (including the 3-byte common header and trailing
+/// `CRC_32`), and parses its splice command, returning the parts to be given to a
+/// `SpliceInfoProcessor`
+pub(crate) fn parse_section<'a>(
+    header: &psi::SectionCommonHeader,
+    data: &'a [u8],
+    max_section_length: usize,
+    crc_mode: CrcMode,
+    protocol_version_handler: fn(u8) -> bool,
+    diagnostics: &'a dyn Scte35Diagnostics,
+) -> Result<(SpliceInfoHeader<'a>, SpliceCommand, SpliceDescriptors<'a>), Scte35Error> {
+    if header.table_id != 0xfc {
+        return Err(Scte35Error::BadTableId(header.table_id));
+    }
+    if header.section_syntax_indicator {
+        return Err(Scte35Error::SectionSyntaxIndicator);
+    }
+    if header.private_indicator {
+        diagnostics.warning(&Scte35Warning::PrivateIndicator);
+    }
+    if header.section_length > max_section_length {
+        return Err(Scte35Error::SectionTooLong {
+            section_length: header.section_length,
+            max: max_section_length,
+        });
+    }
+    if crc_mode != CrcMode::Skip {
+        let crc = mpeg2ts_reader::mpegts_crc::sum32(data);
+        if crc != 0 {
+            if crc_mode == CrcMode::Strict {
+                return Err(Scte35Error::Crc(crc));
+            }
+            diagnostics.warning(&Scte35Warning::Crc(crc));
+        }
+    }
+    let section_data = &data[psi::SectionCommonHeader::SIZE..];
+    if section_data.len() < SpliceInfoHeader::HEADER_LENGTH + 4 {
+        return Err(Scte35Error::SectionTooShort {
+            actual: section_data.len(),
+            expected: SpliceInfoHeader::HEADER_LENGTH + 4,
+        });
+    }
+    // trim off the 32-bit CRC
+    let section_data = &section_data[..section_data.len() - 4];
+    let (mut splice_header, rest) = SpliceInfoHeader::try_new(section_data)?;
+    splice_header.sap_type = SapType::from_id(data[1] >> 4);
+    let protocol_version = splice_header.protocol_version();
+    if protocol_version != 0 && !protocol_version_handler(protocol_version) {
+        return Err(Scte35Error::UnsupportedProtocolVersion(protocol_version));
+    }
+    if splice_header.encrypted_packet() {
+        return Err(Scte35Error::Encrypted);
+    }
+    let command_len = splice_header.splice_command_length() as usize;
+    let command_type = splice_header.splice_command_type();
+    let (splice_command, rest) = if command_len == SpliceInfoHeader::LEGACY_SPLICE_COMMAND_LENGTH {
+        diagnostics.warning(&Scte35Warning::LegacySpliceCommandLength);
+        let (splice_command, len) = SpliceCommand::parse_legacy(command_type, rest, diagnostics)?;
+        (Ok(splice_command), &rest[len..])
+    } else {
+        if command_len > rest.len() {
+            return Err(Scte35Error::SpliceCommandLength {
+                splice_command_length: command_len,
+                available: rest.len(),
+            });
+        }
+        let (payload, rest) = rest.split_at(command_len);
+        (
+            SpliceCommand::parse(command_type, payload, diagnostics),
+            rest,
+        )
+    };
+    if rest.len() < 2 {
+        return Err(Scte35Error::MissingDescriptorLoopLength);
+    }
+    let descriptor_loop_length = (u16::from(rest[0]) << 8 | u16::from(rest[1])) as usize;
+    if descriptor_loop_length + 2 > rest.len() {
+        return Err(Scte35Error::DescriptorLoopLength {
+            descriptor_loop_length,
+            available: rest.len(),
+        });
+    }
+    let descriptors = &rest[2..2 + descriptor_loop_length];
+    match splice_command {
+        Ok(splice_command) => Ok((
+            splice_header,
+            splice_command,
+            SpliceDescriptors {
+                buf: descriptors,
+                diagnostics,
+            },
+        )),
+        Err(e) => Err(Scte35Error::Command(e)),
+    }
+}
+
+impl<P, Ctx: demultiplex::DemuxContext> Scte35SectionProcessor<P, Ctx>
+where
+    P: SpliceInfoProcessor,
+{
+    pub fn new(processor: P) -> Scte35SectionProcessor<P, Ctx> {
+        Scte35SectionProcessor::with_user(processor, ())
+    }
+}
+impl<P, Ctx: demultiplex::DemuxContext, U> Scte35SectionProcessor<P, Ctx, U>
+where
+    P: SpliceInfoUserProcessor<U>,
+{
+    /// Creates a processor which will pass a reference to the given `user` value on to each
+    /// call to `SpliceInfoUserProcessor::process_with()`.
+    pub fn with_user(processor: P, user: U) -> Scte35SectionProcessor<P, Ctx, U> {
+        Scte35SectionProcessor {
+            processor,
+            user,
+            diagnostics: LogDiagnostics,
+            max_section_length: MAX_SECTION_LENGTH,
+            crc_mode: CrcMode::default(),
+            protocol_version_handler: reject_protocol_version,
+            authorized_tiers: Vec::new(),
+            pid: None,
+            phantom: marker::PhantomData,
+        }
+    }
+}
+impl<P, Ctx: demultiplex::DemuxContext, U, D> Scte35SectionProcessor<P, Ctx, U, D>
+where
+    P: SpliceInfoUserProcessor<U>,
+    D: Scte35Diagnostics,
+{
+    /// Delivers problems found in sections to the given `Scte35Diagnostics` implementation,
+    /// rather than logging them.
+    pub fn with_diagnostics<D2: Scte35Diagnostics>(
+        self,
+        diagnostics: D2,
+    ) -> Scte35SectionProcessor<P, Ctx, U, D2> {
+        Scte35SectionProcessor {
+            processor: self.processor,
+            user: self.user,
+            diagnostics,
+            max_section_length: self.max_section_length,
+            crc_mode: self.crc_mode,
+            protocol_version_handler: self.protocol_version_handler,
+            authorized_tiers: self.authorized_tiers,
+            pid: self.pid,
+            phantom: marker::PhantomData,
+        }
+    }
+
+    /// The `Scte35Diagnostics` given to `with_diagnostics()`
+    pub fn diagnostics(&self) -> &D {
+        &self.diagnostics
+    }
+
+    /// The user value given to `with_user()`
+    pub fn user(&self) -> &U {
+        &self.user
+    }
+
+    /// Mutable access to the user value given to `with_user()`, which may be updated between
+    /// sections
+    pub fn user_mut(&mut self) -> &mut U {
+        &mut self.user
+    }
+
+    /// Sections with a `section_length` greater than the given value will be rejected before
+    /// any further processing.  Defaults to `MAX_SECTION_LENGTH`; deployments which know that
+    /// their sections are always small may choose a lower limit.
+    pub fn with_max_section_length(mut self, max_section_length: usize) -> Self {
+        self.max_section_length = max_section_length;
+        self
+    }
+
+    /// How sections with an incorrect `CRC_32` are treated.  Defaults to `CrcMode::Strict`;
+    /// `CrcMode::Warn` suits monitoring of sources known to mis-calculate the CRC.
+    pub fn with_crc_mode(mut self, crc_mode: CrcMode) -> Self {
+        self.crc_mode = crc_mode;
+        self
+    }
+
+    /// Called with the `protocol_version` of any section whose version is not `0`, the only one
+    /// this library supports.  If the handler returns `true`, the section is parsed as though it
+    /// were version `0`, allowing future versions to be handled experimentally; otherwise the
+    /// section is rejected with `Scte35Error::UnsupportedProtocolVersion`, which is the default.
+    pub fn with_protocol_version_handler(mut self, handler: fn(u8) -> bool) -> Self {
+        self.protocol_version_handler = handler;
+        self
+    }
+
+    /// Only sections whose `tier` matches one of the given tiers (in the sense of
+    /// `Tier::matches()`) will be passed to the `SpliceInfoProcessor`; others are skipped, and
+    /// reported via `Scte35Diagnostics::tier_filtered()`.  By default, no filtering is applied.
+    pub fn with_authorized_tiers<I: IntoIterator<Item = Tier>>(mut self, tiers: I) -> Self {
+        self.authorized_tiers = tiers.into_iter().collect();
+        self
+    }
+
+    /// The PID from which the sections are read, for inclusion in the `tracing` span created
+    /// for each section when this crate's `tracing` feature is enabled.  The filters of the
+    /// [`demux`](demux/index.html) module set this themselves.
+    pub fn with_pid(mut self, pid: mpeg2ts_reader::packet::Pid) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// The PID given to `with_pid()`
+    pub fn pid(&self) -> Option<mpeg2ts_reader::packet::Pid> {
+        self.pid
+    }
+
+    /// To be called when the application detects a discontinuity in the stream, or switches
+    /// to a different input, so that the `SpliceInfoProcessor` can discard stale state.
+    pub fn reset(&mut self) {
+        self.processor.reset();
+    }
+}
+impl SpliceCommand {
+    /// Parses the _splice-command_ payload for the given `splice_command_type`, producing
+    /// `SpliceCommand::Reserved` for command types that the spec reserves
+    fn parse(
+        splice_command_type: SpliceCommandType,
+        payload: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceCommand, SpliceDescriptorErr> {
+        match splice_command_type {
+            SpliceCommandType::SpliceNull => Self::splice_null(payload),
+            SpliceCommandType::SpliceSchedule => Self::splice_schedule(payload, diagnostics),
+            SpliceCommandType::SpliceInsert => Self::splice_insert(payload, diagnostics),
+            SpliceCommandType::TimeSignal => Self::time_signal(payload, diagnostics),
+            SpliceCommandType::BandwidthReservation => Self::bandwidth_reservation(payload),
+            SpliceCommandType::PrivateCommand => Self::private_command(payload),
+            SpliceCommandType::Reserved(command_type) => Ok(SpliceCommand::Reserved {
+                command_type,
+                payload: payload.to_vec(),
+            }),
+        }
+    }
+
+    fn splice_null(payload: &[u8]) -> Result<SpliceCommand, SpliceDescriptorErr> {
+        if payload.is_empty() {
+            Ok(SpliceCommand::SpliceNull {})
+        } else {
+            Err(SpliceDescriptorErr::InvalidDescriptorLength(payload.len()))
+        }
+    }
+
+    /// Parses a command of the given type from the start of `data`, for sections whose
+    /// `splice_command_length` has the legacy value `0xfff`, returning the command and the number
+    /// of bytes it occupies.  Only those command types whose length is implied by their content
+    /// can be parsed in this way.
+    fn parse_legacy(
+        splice_command_type: SpliceCommandType,
+        data: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<(SpliceCommand, usize), Scte35Error> {
+        let mut r = bitreader::BitReader::new(data);
+        let splice_command = match splice_command_type {
+            SpliceCommandType::SpliceNull => SpliceCommand::SpliceNull {},
+            SpliceCommandType::BandwidthReservation => SpliceCommand::BandwidthReservation {},
+            SpliceCommandType::SpliceSchedule => {
+                Self::read_splice_schedule(&mut r, diagnostics).map_err(Scte35Error::Command)?
+            }
+            SpliceCommandType::SpliceInsert => {
+                Self::read_splice_insert(&mut r, diagnostics).map_err(Scte35Error::Command)?
+            }
+            SpliceCommandType::TimeSignal => {
+                Self::read_time_signal(&mut r, diagnostics).map_err(Scte35Error::Command)?
+            }
+            SpliceCommandType::PrivateCommand | SpliceCommandType::Reserved(_) => {
+                return Err(Scte35Error::SpliceCommandLength {
+                    splice_command_length: SpliceInfoHeader::LEGACY_SPLICE_COMMAND_LENGTH,
+                    available: data.len(),
+                })
+            }
+        };
+        Ok((splice_command, (r.position() / 8) as usize))
+    }
+
+    fn splice_schedule(
+        payload: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceCommand, SpliceDescriptorErr> {
+        let mut r = bitreader::BitReader::new(payload);
+        let result = Self::read_splice_schedule(&mut r, diagnostics)?;
+
+        // if we end up without reading to the end of a byte, this must indicate a bug in the
+        // parsing routine
+        check_aligned(&r, "splice_schedule")?;
+
+        report_trailing_data("splice_schedule", &r, payload, diagnostics);
+        Ok(result)
+    }
+
+    fn read_splice_schedule(
+        r: &mut bitreader::BitReader<'_>,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceCommand, SpliceDescriptorErr> {
+        let splice_count = r.read_u8(8).named("splice_schedule.splice_count")?;
+        // splice_event_id, and the flags which follow it
+        let splice_count =
+            check_count(r, splice_count as usize, 40, "splice_schedule.splice_count")?;
+        let mut events = Vec::with_capacity(splice_count);
+        for _ in 0..splice_count {
+            let splice_event_id = r.read_u32(32).named("splice_schedule.splice_event_id")?;
+            let splice_event_cancel_indicator = r
+                .read_bool()
+                .named("splice_schedule.splice_event_cancel_indicator")?;
+            skip_reserved(r, 7, "splice_schedule.reserved", diagnostics)?;
+            events.push(SpliceScheduleEvent {
+                splice_event_id,
+                splice_detail: Self::read_scheduled_splice(
+                    r,
+                    splice_event_cancel_indicator,
+                    diagnostics,
+                )?,
+            });
+        }
+        Ok(SpliceCommand::SpliceSchedule { events })
+    }
+
+    fn read_scheduled_splice(
+        r: &mut bitreader::BitReader<'_>,
+        splice_event_cancel_indicator: bool,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<ScheduledSplice, SpliceDescriptorErr> {
+        if splice_event_cancel_indicator {
+            return Ok(ScheduledSplice::Cancel);
+        }
+        let network_indicator = NetworkIndicator::try_from(
+            r.read_u8(1)
+                .named("splice_schedule.out_of_network_indicator")?,
+        )?;
+        let program_splice_flag = r.read_bool().named("splice_schedule.program_splice_flag")?;
+        let duration_flag = r.read_bool().named("splice_schedule.duration_flag")?;
+        skip_reserved(r, 5, "splice_schedule.reserved", diagnostics)?;
+        let splice_mode = if program_splice_flag {
+            ScheduledSpliceMode::Program(UtcSpliceTime(
+                r.read_u32(32).named("splice_schedule.utc_splice_time")?,
+            ))
+        } else {
+            let component_count = r.read_u8(8).named("splice_schedule.component_count")? as usize;
+            let component_count =
+                check_count(r, component_count, 40, "splice_schedule.component_count")?;
+            let mut components = Vec::with_capacity(component_count);
+            for _ in 0..component_count {
+                components.push(ScheduledComponentSplice {
+                    component_tag: r.read_u8(8).named("splice_schedule.component_tag")?,
+                    utc_splice_time: UtcSpliceTime(
+                        r.read_u32(32).named("splice_schedule.utc_splice_time")?,
+                    ),
+                });
+            }
+            ScheduledSpliceMode::Components(components)
+        };
+        Ok(ScheduledSplice::Insert {
+            network_indicator,
+            splice_mode,
+            duration: if duration_flag {
+                Some(Self::read_duration(r, diagnostics)?)
+            } else {
+                None
+            },
+            unique_program_id: r.read_u16(16).named("splice_schedule.unique_program_id")?,
+            avail_num: r.read_u8(8).named("splice_schedule.avail_num")?,
+            avails_expected: r.read_u8(8).named("splice_schedule.avails_expected")?,
+        })
+    }
+
+    fn splice_insert(
+        payload: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceCommand, SpliceDescriptorErr> {
+        let mut r = bitreader::BitReader::new(payload);
+        let result = Self::read_splice_insert(&mut r, diagnostics)?;
+
+        // if we end up without reading to the end of a byte, this must indicate a bug in the
+        // parsing routine
+        check_aligned(&r, "splice_insert")?;
+
+        report_trailing_data("splice_insert", &r, payload, diagnostics);
+        Ok(result)
+    }
+
+    fn read_splice_insert(
+        r: &mut bitreader::BitReader<'_>,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceCommand, SpliceDescriptorErr> {
+        let splice_event_id = r.read_u32(32).named("splice_insert.splice_event_id")?;
+        let splice_event_cancel_indicator = r
+            .read_bool()
+            .named("splice_insert.splice_event_cancel_indicator")?;
+        let reserved = r.read_u8(7).named("splice_insert.reserved")?;
+        Ok(SpliceCommand::SpliceInsert {
+            splice_event_id,
+            reserved,
+            splice_detail: Self::read_splice_detail(r, splice_event_cancel_indicator, diagnostics)?,
+        })
+    }
+
+    fn time_signal(
+        payload: &[u8],
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceCommand, SpliceDescriptorErr> {
+        let mut r = bitreader::BitReader::new(payload);
+        let result = Self::read_time_signal(&mut r, diagnostics)?;
+
+        // if we end up without reading to the end of a byte, this must indicate a bug in the
+        // parsing routine
+        check_aligned(&r, "time_signal")?;
+
+        report_trailing_data("time_signal", &r, payload, diagnostics);
+        Ok(result)
+    }
+
+    fn read_time_signal(
+        r: &mut bitreader::BitReader<'_>,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceCommand, SpliceDescriptorErr> {
+        Ok(SpliceCommand::TimeSignal {
+            splice_time: SpliceTime::Timed(Self::read_splice_time(r, diagnostics)?),
+        })
+    }
+
+    fn bandwidth_reservation(payload: &[u8]) -> Result<SpliceCommand, SpliceDescriptorErr> {
+        if payload.is_empty() {
+            Ok(SpliceCommand::BandwidthReservation {})
+        } else {
+            Err(SpliceDescriptorErr::InvalidDescriptorLength(payload.len()))
+        }
+    }
+
+    fn private_command(payload: &[u8]) -> Result<SpliceCommand, SpliceDescriptorErr> {
+        if payload.len() < 4 {
+            return Err(SpliceDescriptorErr::not_enough_data(
+                "private_command.identifier",
+                0,
+                4,
+                payload.len(),
+            ));
+        }
+        Ok(SpliceCommand::PrivateCommand {
+            identifier: [payload[0], payload[1], payload[2], payload[3]],
+            private_bytes: payload[4..].to_vec(),
+        })
+    }
+
+    fn read_splice_detail(
+        r: &mut bitreader::BitReader<'_>,
+        splice_event_cancel_indicator: bool,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceInsert, SpliceDescriptorErr> {
+        if splice_event_cancel_indicator {
+            Ok(SpliceInsert::Cancel)
+        } else {
+            r.relative_reader().skip(1).named("splice_insert.flags")?;
+            let network_indicator =
+                NetworkIndicator::try_from(r.read_u8(1).named("splice_insert.network_indicator")?)?;
+            let program_splice_flag = r.read_bool().named("splice_insert.program_splice_flag")?;
+            let duration_flag = r.read_bool().named("splice_insert.duration_flag")?;
+            let splice_immediate_flag =
+                r.read_bool().named("splice_insert.splice_immediate_flag")?;
+            skip_reserved(r, 4, "splice_insert.reserved", diagnostics)?;
+
+            Ok(SpliceInsert::Insert {
+                network_indicator,
+                splice_mode: Self::read_splice_mode(
+                    r,
+                    program_splice_flag,
+                    splice_immediate_flag,
+                    diagnostics,
+                )?,
+                duration: if duration_flag {
+                    Some(Self::read_duration(r, diagnostics)?)
+                } else {
+                    None
+                },
+                unique_program_id: r.read_u16(16).named("unique_program_id")?,
+                avail_num: r.read_u8(8).named("avail_num")?,
+                avails_expected: r.read_u8(8).named("avails_expected")?,
+            })
+        }
+    }
+
+    fn read_splice_mode(
+        r: &mut bitreader::BitReader<'_>,
+        program_splice_flag: bool,
+        splice_immediate_flag: bool,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceMode, SpliceDescriptorErr> {
+        if program_splice_flag {
+            let time = if splice_immediate_flag {
+                SpliceTime::Immediate
+            } else {
+                SpliceTime::Timed(Self::read_splice_time(r, diagnostics)?)
+            };
+            Ok(SpliceMode::Program(time))
+        } else {
+            let component_count = r.read_u8(8).named("component_count")? as usize;
+            // component_tag, and for timed splices at least the time_specified_flag byte
+            let min_entry_bits = if splice_immediate_flag { 8 } else { 16 };
+            let component_count =
+                check_count(r, component_count, min_entry_bits, "component_count")?;
+            let mut components = Vec::with_capacity(component_count);
+            for _ in 0..component_count {
+                let component_tag = r.read_u8(8).named("component_tag")?;
+                let splice_time = if splice_immediate_flag {
+                    SpliceTime::Immediate
+                } else {
+                    SpliceTime::Timed(Self::read_splice_time(r, diagnostics)?)
+                };
+                components.push(ComponentSplice {
+                    component_tag,
+                    splice_time,
+                });
+            }
+            Ok(SpliceMode::Components(components))
+        }
+    }
+
+    fn read_splice_time(
+        r: &mut bitreader::BitReader<'_>,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<Option<u64>, SpliceDescriptorErr> {
+        Ok(if r.read_bool().named("splice_time.time_specified_flag")? {
+            skip_reserved(r, 6, "splice_time.reserved", diagnostics)?; // reserved
+            Some(r.read_u64(33).named("splice_time.pts_time")?)
+        } else {
+            skip_reserved(r, 7, "splice_time.reserved", diagnostics)?; // reserved
+            None
+        })
+    }
+
+    fn read_duration(
+        r: &mut bitreader::BitReader<'_>,
+        diagnostics: &dyn Scte35Diagnostics,
+    ) -> Result<SpliceDuration, SpliceDescriptorErr> {
+        let return_mode = ReturnMode::try_from(r.read_u8(1).named("break_duration.auto_return")?)?;
+        skip_reserved(r, 6, "break_duration.reserved", diagnostics)?;
+        Ok(SpliceDuration {
+            return_mode,
+            duration: r.read_u64(33).named("break_duration.duration")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::*;
+    use hex_literal::*;
+    use matches::*;
+    use mpeg2ts_reader::demultiplex;
+    use mpeg2ts_reader::psi;
+    use mpeg2ts_reader::psi::WholeCompactSyntaxPayloadParser;
+
+    mpeg2ts_reader::demux_context!(
+        NullDemuxContext,
+        demultiplex::NullPacketFilter<NullDemuxContext>
+    );
+    impl NullDemuxContext {
+        fn do_construct(
+            &mut self,
+            _req: demultiplex::FilterRequest<'_, '_>,
+        ) -> demultiplex::NullPacketFilter<NullDemuxContext> {
+            unimplemented!();
+        }
+    }
+
+    struct MockSpliceInsertProcessor;
+    impl SpliceInfoProcessor for MockSpliceInsertProcessor {
+        fn process(
+            &self,
+            header: SpliceInfoHeader<'_>,
+            command: SpliceCommand,
+            descriptors: SpliceDescriptors<'_>,
+        ) {
+            assert_eq!(header.encryption_algorithm(), EncryptionAlgorithm::None);
+            assert_matches!(command, SpliceCommand::SpliceInsert { .. });
+            for d in &descriptors {
+                d.unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn it_works() {
+        let data = hex!(
+            "fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49"
+        );
+        let mut parser = Scte35SectionProcessor::new(MockSpliceInsertProcessor);
+        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+        let mut ctx = NullDemuxContext::new();
+        parser.section(&mut ctx, &header, &data[..]);
+    }
+
+    #[test]
+    fn closure_processors() {
+        let header =
+            psi::SectionCommonHeader::new(&SPLICE_INSERT[..psi::SectionCommonHeader::SIZE]);
+        let mut ctx = NullDemuxContext::new();
+
+        let called = std::cell::Cell::new(false);
+        let mut parser = Scte35SectionProcessor::new(
+            |_header: SpliceInfoHeader<'_>,
+             command: SpliceCommand,
+             _descriptors: SpliceDescriptors<'_>| {
+                assert_matches!(command, SpliceCommand::SpliceInsert { .. });
+                called.set(true);
+            },
+        );
+        parser.section(&mut ctx, &header, &SPLICE_INSERT[..]);
+        assert!(called.get());
+
+        let mut commands = vec![];
+        let mut parser =
+            Scte35SectionProcessor::new(FnMutProcessor::new(|_header, command, _descriptors| {
+                commands.push(command)
+            }));
+        parser.section(&mut ctx, &header, &SPLICE_INSERT[..]);
+        parser.section(&mut ctx, &header, &SPLICE_INSERT[..]);
+        assert_eq!(commands.len(), 2);
+    }
+
+    #[derive(Default)]
+    struct ResettableProcessor {
+        count: std::cell::Cell<u32>,
+    }
+    impl SpliceInfoProcessor for ResettableProcessor {
+        fn process(
+            &self,
+            _header: SpliceInfoHeader<'_>,
+            _command: SpliceCommand,
+            _descriptors: SpliceDescriptors<'_>,
+        ) {
+            self.count.set(self.count.get() + 1);
+        }
+        fn reset(&self) {
+            self.count.set(0);
+        }
+    }
+
+    #[test]
+    fn reset() {
+        let header =
+            psi::SectionCommonHeader::new(&SPLICE_INSERT[..psi::SectionCommonHeader::SIZE]);
+        let mut ctx = NullDemuxContext::new();
+        let mut parser = Scte35SectionProcessor::new(ResettableProcessor::default());
+        parser.section(&mut ctx, &header, &SPLICE_INSERT[..]);
+        assert_eq!(parser.processor.count.get(), 1);
+        parser.reset();
+        assert_eq!(parser.processor.count.get(), 0);
+    }
+
+    #[derive(Default)]
+    struct RecordingDiagnostics {
+        errors: std::cell::RefCell<Vec<String>>,
+        warnings: std::cell::RefCell<Vec<String>>,
+    }
+    impl Scte35Diagnostics for RecordingDiagnostics {
+        fn error(&self, error: &Scte35Error) {
+            self.errors.borrow_mut().push(format!("{:?}", error));
+        }
+
+        fn warning(&self, warning: &Scte35Warning) {
+            self.warnings.borrow_mut().push(format!("{:?}", warning));
+        }
+    }
+
+    /// Records the fields of spans and events, as `name=value` strings
+    #[cfg(feature = "tracing")]
+    #[derive(Default, Clone)]
+    struct RecordingSubscriber(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for RecordingSubscriber {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{}={:?}", field.name(), value));
+        }
+    }
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for RecordingSubscriber {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            span.record(&mut self.clone());
+            tracing::span::Id::from_u64(1)
+        }
+        fn record(&self, _span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            values.record(&mut self.clone());
+        }
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+        fn event(&self, event: &tracing::Event<'_>) {
+            event.record(&mut self.clone());
+        }
+        fn enter(&self, _span: &tracing::span::Id) {}
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing() {
+        let mut bad_crc = SPLICE_INSERT;
+        bad_crc[5] ^= 1;
+        let subscriber = RecordingSubscriber::default();
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            let mut parser = Scte35SectionProcessor::new(
+                |_header: SpliceInfoHeader<'_>,
+                 _command: SpliceCommand,
+                 _descriptors: SpliceDescriptors<'_>| {},
+            )
+            .with_pid(mpeg2ts_reader::packet::Pid::new(0x101));
+            let header =
+                psi::SectionCommonHeader::new(&SPLICE_INSERT[..psi::SectionCommonHeader::SIZE]);
+            let mut ctx = NullDemuxContext::new();
+            parser.section(&mut ctx, &header, &SPLICE_INSERT[..]);
+            parser.section(&mut ctx, &header, &bad_crc[..]);
+        });
+        let recorded = subscriber.0.lock().unwrap().clone();
+        assert_eq!(
+            recorded[..5],
+            [
+                "pid=257",
+                "command_type=splice_insert",
+                "splice_event_id=1",
+                "pts=756296448",
+                "message=section parsed",
+            ]
+        );
+        assert_eq!(
+            recorded[5..7],
+            ["pid=257", "message=section could not be parsed"]
+        );
+        assert!(recorded[7].starts_with("error=CRC_32 check failed"));
+    }
+
+    #[test]
+    fn diagnostics() {
+        // a time_signal() with two trailing bytes in its splice_command_length
+        let data =
+            with_crc(hex!("fc3018 00 0000000000 00 fff007 06 fe00000000 0000 0000").to_vec());
+        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+        let mut ctx = NullDemuxContext::new();
+        let mut parser = Scte35SectionProcessor::new(
+            |_header: SpliceInfoHeader<'_>,
+             _command: SpliceCommand,
+             _descriptors: SpliceDescriptors<'_>| {},
+        )
+        .with_diagnostics(RecordingDiagnostics::default());
+        parser.section(&mut ctx, &header, &data[..]);
+        let mut bad_crc = data.clone();
+        bad_crc[5] ^= 1;
+        parser.section(&mut ctx, &header, &bad_crc[..]);
+        assert_eq!(
+            *parser.diagnostics().warnings.borrow(),
+            vec![r#"TrailingData { syntax: "time_signal", consumed: 5, length: 7 }"#]
+        );
+        assert_eq!(parser.diagnostics().errors.borrow().len(), 1);
+        assert!(parser.diagnostics().errors.borrow()[0].starts_with("Crc("));
+    }
+
+    #[test]
+    fn log_targets() {
+        assert_eq!(Scte35Error::Crc(1).log_target(), "scte35::crc");
+        assert_eq!(Scte35Error::BadTableId(0).log_target(), "scte35::section");
+        assert_eq!(
+            Scte35Error::MissingDescriptorLoopLength.log_target(),
+            "scte35::descriptor"
+        );
+        assert_eq!(
+            Scte35Warning::ReservedBits {
+                field_name: "splice_time.reserved",
+                value: 0
+            }
+            .log_target(),
+            "scte35::command"
+        );
+        assert_eq!(
+            Scte35Warning::TrailingData {
+                syntax: "avail_descriptor",
+                consumed: 4,
+                length: 6
+            }
+            .log_target(),
+            "scte35::descriptor"
+        );
+    }
+
+    #[test]
+    fn parse_report() {
+        // a time_signal() with zeroed reserved bits, and an avail_descriptor() with an extra byte
+        let data = with_crc(
+            hex!("fc3021 00 0000000000 00 fff005 06 8000000000 000b 00094355454900000135ff")
+                .to_vec(),
+        );
+        let (section, report) = SpliceInfoSection::parse_with_report(&data).unwrap();
+        assert_matches!(
+            section.splice_descriptors[..],
+            [SpliceDescriptor::AvailDescriptor {
+                provider_avail_id: 0x135
+            }]
+        );
+        assert!(!report.is_clean());
+        assert_eq!(
+            report.warnings,
+            vec![
+                Scte35Warning::ReservedBits {
+                    field_name: "splice_time.reserved",
+                    value: 0
+                },
+                Scte35Warning::TrailingData {
+                    syntax: "avail_descriptor",
+                    consumed: 4,
+                    length: 5
+                },
+            ]
+        );
+
+        let data = with_crc(hex!("fc3016 00 0000000000 00 fff005 06 fe00000000 0000").to_vec());
+        let (_, report) = SpliceInfoSection::parse_with_report(&data).unwrap();
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn legacy_splice_command_length() {
+        let data = with_crc(
+            hex!("fc3020 00 0000000000 00 ffffff 06 fe00000000 000a 000843554549 00000135")
+                .to_vec(),
+        );
+        let (section, report) = SpliceInfoSection::parse_with_report(&data).unwrap();
+        assert_matches!(
+            section.splice_command,
+            SpliceCommand::TimeSignal {
+                splice_time: SpliceTime::Timed(Some(0))
+            }
+        );
+        assert_matches!(
+            section.splice_descriptors[..],
+            [SpliceDescriptor::AvailDescriptor {
+                provider_avail_id: 0x135
+            }]
+        );
+        assert_eq!(
+            report.warnings,
+            vec![Scte35Warning::LegacySpliceCommandLength]
+        );
+
+        // the length of a private_command() can not be determined from its content
+        let data = with_crc(hex!("fc3017 00 0000000000 00 ffffff ff 41424344 0102 0000").to_vec());
+        assert_matches!(
+            SpliceInfoSection::parse(&data),
+            Err(Scte35Error::SpliceCommandLength {
+                splice_command_length: 0xfff,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn crc_mode() {
+        let mut data = SPLICE_INSERT;
+        data[39] ^= 1;
+        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+        let mut ctx = NullDemuxContext::new();
+        for (mode, processed, warnings) in [
+            (CrcMode::Strict, 0, 0),
+            (CrcMode::Warn, 1, 1),
+            (CrcMode::Skip, 1, 0),
+        ] {
+            let mut parser =
+                Scte35SectionProcessor::with_user(CountingProcessor, Default::default())
+                    .with_diagnostics(RecordingDiagnostics::default())
+                    .with_crc_mode(mode);
+            parser.section(&mut ctx, &header, &data[..]);
+            assert_eq!(parser.user().get(), processed, "{:?}", mode);
+            assert_eq!(parser.diagnostics().warnings.borrow().len(), warnings);
+            assert_eq!(
+                parser.diagnostics().errors.borrow().len(),
+                1 - processed as usize
+            );
+        }
+    }
+
+    #[derive(Default)]
+    struct TierDiagnostics(std::cell::RefCell<Vec<Tier>>);
+    impl Scte35Diagnostics for TierDiagnostics {
+        fn tier_filtered(&self, tier: Tier) {
+            self.0.borrow_mut().push(tier);
+        }
+    }
+
+    #[test]
+    fn tier_filtering() {
+        let section = |tier: u16| {
+            let mut data = hex!("fc3011 00 0000000000 00 fff0 00 00 0000").to_vec();
+            data[10] = (tier >> 4) as u8;
+            data[11] = (tier << 4) as u8;
+            with_crc(data)
+        };
+        let mut ctx = NullDemuxContext::new();
+        let mut parser = Scte35SectionProcessor::with_user(CountingProcessor, Default::default())
+            .with_diagnostics(TierDiagnostics::default())
+            .with_authorized_tiers([Tier::new(0x123).unwrap(), Tier::new(0x456).unwrap()]);
+        for tier in [0x123, 0x456, 0x789, 0xfff] {
+            let data = section(tier);
+            let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+            parser.section(&mut ctx, &header, &data[..]);
+        }
+        assert_eq!(parser.user().get(), 3);
+        assert_eq!(
+            *parser.diagnostics().0.borrow(),
+            vec![Tier::new(0x789).unwrap()]
+        );
+    }
+
+    #[test]
+    fn protocol_version() {
+        let data = with_crc(hex!("fc3011 01 0000000000 00 fff0 00 00 0000").to_vec());
+        assert_matches!(
+            SpliceInfoSection::parse(&data),
+            Err(Scte35Error::UnsupportedProtocolVersion(1))
+        );
+        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+        let mut ctx = NullDemuxContext::new();
+        let mut parser = Scte35SectionProcessor::with_user(CountingProcessor, Default::default())
+            .with_diagnostics(RecordingDiagnostics::default());
+        parser.section(&mut ctx, &header, &data[..]);
+        assert_eq!(parser.user().get(), 0);
+        assert_eq!(
+            *parser.diagnostics().errors.borrow(),
+            vec!["UnsupportedProtocolVersion(1)"]
+        );
+
+        let mut parser = parser.with_protocol_version_handler(|v| v == 1);
+        parser.section(&mut ctx, &header, &data[..]);
+        assert_eq!(parser.user().get(), 1);
+    }
+
+    #[test]
+    fn section_header_constraints() {
+        let section = |flags: u8| {
+            let mut data = hex!("fc3011 00 0000000000 00 fff0 00 00 0000").to_vec();
+            data[1] = flags;
+            with_crc(data)
+        };
+        let sap_type = std::cell::Cell::new(None);
+        let mut ctx = NullDemuxContext::new();
+        let mut parser = Scte35SectionProcessor::new(
+            |header: SpliceInfoHeader<'_>,
+             _command: SpliceCommand,
+             _descriptors: SpliceDescriptors<'_>| {
+                sap_type.set(Some(header.sap_type()));
+            },
+        )
+        .with_diagnostics(RecordingDiagnostics::default());
+        for flags in [0x10, 0x60, 0xb0] {
+            let data = section(flags);
+            let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+            parser.section(&mut ctx, &header, &data[..]);
+        }
+        // the section with section_syntax_indicator set was not processed
+        assert_eq!(sap_type.get(), Some(SapType::Type3));
+        assert_eq!(
+            *parser.diagnostics().warnings.borrow(),
+            vec!["PrivateIndicator"]
+        );
+        assert_eq!(
+            *parser.diagnostics().errors.borrow(),
+            vec!["SectionSyntaxIndicator"]
+        );
+        assert_matches!(
+            SpliceInfoSection::parse(&section(0xb0)),
+            Err(Scte35Error::SectionSyntaxIndicator)
+        );
+        for id in 0..4 {
+            assert_eq!(SapType::from_id(id).id(), id);
+        }
+    }
+
+    #[derive(Default)]
+    struct BytesProcessor(std::cell::RefCell<Vec<u8>>);
+    impl SpliceInfoProcessor for BytesProcessor {
+        fn process(
+            &self,
+            _header: SpliceInfoHeader<'_>,
+            _command: SpliceCommand,
+            _descriptors: SpliceDescriptors<'_>,
+        ) {
+            panic!("process_section() should have been called instead");
+        }
+
+        fn process_section(
+            &self,
+            section: &[u8],
+            _header: SpliceInfoHeader<'_>,
+            _command: SpliceCommand,
+            _descriptors: SpliceDescriptors<'_>,
+        ) {
+            self.0.borrow_mut().extend_from_slice(section);
+        }
+    }
+
+    #[test]
+    fn section_bytes() {
+        let header =
+            psi::SectionCommonHeader::new(&SPLICE_INSERT[..psi::SectionCommonHeader::SIZE]);
+        let mut ctx = NullDemuxContext::new();
+        let mut parser = Scte35SectionProcessor::new(BytesProcessor::default());
+        parser.section(&mut ctx, &header, &SPLICE_INSERT[..]);
+        assert_eq!(*parser.processor.0.borrow(), SPLICE_INSERT);
+    }
+
+    struct MustNotBeCalledProcessor;
+    impl SpliceInfoProcessor for MustNotBeCalledProcessor {
+        fn process(
+            &self,
+            _header: SpliceInfoHeader<'_>,
+            _command: SpliceCommand,
+            _descriptors: SpliceDescriptors<'_>,
+        ) {
+            panic!("section should have been rejected");
+        }
+    }
+
+    #[test]
+    fn max_section_length() {
+        let mut parser =
+            Scte35SectionProcessor::new(MustNotBeCalledProcessor).with_max_section_length(0x24);
+        let header =
+            psi::SectionCommonHeader::new(&SPLICE_INSERT[..psi::SectionCommonHeader::SIZE]);
+        let mut ctx = NullDemuxContext::new();
+        parser.section(&mut ctx, &header, &SPLICE_INSERT[..]);
+    }
+
+    struct CountingProcessor;
+    impl SpliceInfoUserProcessor<std::cell::Cell<u32>> for CountingProcessor {
+        fn process_with(
+            &self,
+            user: &std::cell::Cell<u32>,
+            _header: SpliceInfoHeader<'_>,
+            _command: SpliceCommand,
+            _descriptors: SpliceDescriptors<'_>,
+        ) {
+            user.set(user.get() + 1);
+        }
+    }
+
+    #[test]
+    fn user_value() {
+        let mut parser =
+            Scte35SectionProcessor::with_user(CountingProcessor, std::cell::Cell::new(0));
+        let header =
+            psi::SectionCommonHeader::new(&SPLICE_INSERT[..psi::SectionCommonHeader::SIZE]);
+        let mut ctx = NullDemuxContext::new();
+        parser.section(&mut ctx, &header, &SPLICE_INSERT[..]);
+        parser.section(&mut ctx, &header, &SPLICE_INSERT[..]);
+        assert_eq!(parser.user().get(), 2);
+    }
+
+    struct MockTimeSignalProcessor;
+    impl SpliceInfoProcessor for MockTimeSignalProcessor {
+        fn process(
+            &self,
+            header: SpliceInfoHeader<'_>,
+            command: SpliceCommand,
+            descriptors: SpliceDescriptors<'_>,
+        ) {
+            assert_eq!(header.encryption_algorithm(), EncryptionAlgorithm::None);
+            assert_matches!(command, SpliceCommand::TimeSignal { .. });
+            for d in &descriptors {
+                d.unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn it_understands_time_signal() {
+        let data = hex!(
+            "fc302700000000000000fff00506ff592d03c00011020f43554549000000017fbf000010010112ce0e6b"
+        );
+        let mut parser = Scte35SectionProcessor::new(MockTimeSignalProcessor);
+        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+        let mut ctx = NullDemuxContext::new();
+        parser.section(&mut ctx, &header, &data[..]);
+    }
+
+    #[test]
+    fn tier() {
+        assert_eq!(Tier::new(0x1000), None);
+        let tier = Tier::new(0x123).unwrap();
+        assert!(!tier.is_all());
+        assert!(tier.matches(Tier::new(0x123).unwrap()));
+        assert!(!tier.matches(Tier::new(0x124).unwrap()));
+        assert!(tier.matches(Tier::ALL));
+        assert!(Tier::ALL.matches(tier));
+        assert_eq!(serde_json::to_string(&tier).unwrap(), "291");
+    }
+
+    #[test]
+    fn summarize() {
+        use crate::builder::{SegmentationDescriptorBuilder, TimeSignalBuilder};
+        assert_eq!(
+            crate::presets::cue_in(7).summarize(),
+            "SpliceInsert IN event 7 immediate"
+        );
+        let section = TimeSignalBuilder::new()
+            .at(900_000)
+            .segmentation(
+                SegmentationDescriptorBuilder::new(
+                    3,
+                    SegmentationTypeId::ProviderPlacementOpportunityStart,
+                )
+                .duration(2_700_000)
+                .segment(1, 2),
+            )
+            .build_section()
+            .unwrap();
+        assert_eq!(
+            section.summarize(),
+            "TimeSignal at PTS 900000; Provider Placement Opportunity Start segmentation event 3 \
+             (segment 1 of 2), 30.0s"
+        );
+        let section = SpliceInfoSection::parse(&TIME_SIGNAL).unwrap();
+        assert_eq!(
+            section.summarize(),
+            "TimeSignal at PTS 5791089600; Program Start segmentation event 1"
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parse_batch() {
+        let mut sections = vec![SPLICE_INSERT.to_vec(); 100];
+        sections[42].truncate(20);
+        let results = super::parse_batch(&sections);
+        assert_eq!(results.len(), 100);
+        for (i, result) in results.iter().enumerate() {
+            if i == 42 {
+                assert_matches!(result, Err(Scte35Error::SectionTooShort { actual: 20, .. }));
+            } else {
+                assert_matches!(
+                    result,
+                    Ok(SpliceInfoSection {
+                        splice_command: SpliceCommand::SpliceInsert {
+                            splice_event_id: 1,
+                            ..
+                        },
+                        ..
+                    })
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parse_owned_section() {
+        let mut data = SPLICE_INSERT.to_vec();
+        let section = SpliceInfoSection::parse(&data).unwrap();
+        assert_matches!(
+            section.splice_command,
+            SpliceCommand::SpliceInsert {
+                splice_event_id: 1,
+                ..
+            }
+        );
+        assert_eq!(section.splice_pts(), Some(0x0_2d14_2b00));
+        let handle = std::thread::spawn(move || section.splice_descriptors.len());
+        assert_eq!(handle.join().unwrap(), 0);
+
+        // trailing stuffing is ignored
+        data.extend_from_slice(&[0xff; 4]);
+        assert!(SpliceInfoSection::parse(&data).is_ok());
+        assert_matches!(
+            SpliceInfoSection::parse(&data[..20]),
+            Err(Scte35Error::SectionTooShort {
+                actual: 20,
+                expected: 40
+            })
+        );
+        assert_matches!(
+            SpliceInfoSection::parse(&data[..2]),
+            Err(Scte35Error::SectionTooShort { .. })
+        );
+    }
+
+    #[test]
+    fn header_too_short() {
+        assert_matches!(
+            SpliceInfoHeader::try_new(&[0; 10]),
+            Err(Scte35Error::SectionTooShort {
+                actual: 10,
+                expected: 11
+            })
+        );
+        let (_, rest) = SpliceInfoHeader::try_new(&[0; 12]).unwrap();
+        assert_eq!(rest.len(), 1);
+    }
+
+    #[test]
+    fn from_hex() {
+        let section = SpliceInfoSection::from_hex(
+            " 0xFC3025 00000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49\n",
+        )
+        .unwrap();
+        assert_eq!(section.splice_pts(), Some(0x0_2d14_2b00));
+        let section: SpliceInfoSection =
+            "fc301100000000000000fff0000000007a4fbfff".parse().unwrap();
+        assert_matches!(section.splice_command, SpliceCommand::SpliceNull {});
+        assert_matches!(
+            SpliceInfoSection::from_hex("0xfc30 1g"),
+            Err(FromHexError::InvalidDigit {
+                offset: 8,
+                character: 'g'
+            })
+        );
+        assert_matches!(
+            SpliceInfoSection::from_hex("fc3"),
+            Err(FromHexError::OddNumberOfDigits(3))
+        );
+        assert_matches!(
+            SpliceInfoSection::from_hex("fc30"),
+            Err(FromHexError::Section(Scte35Error::SectionTooShort { .. }))
+        );
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn from_base64() {
+        let section = SpliceInfoSection::from_base64(
+            " /DAlAAAAAAAAAP/wFAUAAAABf+/+LRQrAP4BI9MIAAEBAQAAfxV6SQ==\n",
+        )
+        .unwrap();
+        assert_eq!(section.splice_pts(), Some(0x0_2d14_2b00));
+        assert_matches!(
+            SpliceInfoSection::from_base64("/DAl!"),
+            Err(FromBase64Error::Base64(_))
+        );
+        assert_matches!(
+            SpliceInfoSection::from_base64("/DAlAAAA"),
+            Err(FromBase64Error::Section(
+                Scte35Error::SectionTooShort { .. }
+            ))
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let header = psi::SectionCommonHeader::new(&TIME_SIGNAL[..psi::SectionCommonHeader::SIZE]);
+        let (splice_header, command, descriptors) = parse_section(
+            &header,
+            &TIME_SIGNAL,
+            MAX_SECTION_LENGTH,
+            CrcMode::default(),
+            reject_protocol_version,
+            &LogDiagnostics,
+        )
+        .unwrap();
+
+        let json = serde_json::to_value(&splice_header).unwrap();
+        let owned: OwnedSpliceInfoHeader = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(owned, OwnedSpliceInfoHeader::from(&splice_header));
+        assert_eq!(serde_json::to_value(&owned).unwrap(), json);
+
+        // the same section, but signalling a type 1 SAP
+        let mut data = TIME_SIGNAL[..TIME_SIGNAL.len() - 4].to_vec();
+        data[1] &= !0x30;
+        let data = with_crc(data);
+        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+        let (splice_header, _, _) = parse_section(
+            &header,
+            &data,
+            MAX_SECTION_LENGTH,
+            CrcMode::default(),
+            reject_protocol_version,
+            &LogDiagnostics,
+        )
+        .unwrap();
+        assert!(format!("{:?}", splice_header).contains("sap_type: Type1"));
+        let json = serde_json::to_value(&splice_header).unwrap();
+        let owned: OwnedSpliceInfoHeader = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(owned.sap_type, SapType::Type1);
+        assert_eq!(owned, OwnedSpliceInfoHeader::from(&splice_header));
+
+        let mut section =
+            SpliceInfoSection::from_parts(&splice_header, command, &descriptors).unwrap();
+        section.splice_descriptors.push(
+            SpliceDescriptor::parse(
+                &hex!("0212 43554549 00000002 7f bf 0103616263 30 00 00"),
+                &LogDiagnostics,
+            )
+            .unwrap(),
+        );
+        let json = serde_json::to_value(&section).unwrap();
+        let section: SpliceInfoSection = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&section).unwrap(), json);
+
+        assert!(serde_json::from_str::<Tier>("4096").is_err());
+    }
+
+    #[test]
+    fn splice_pts_ordering() {
+        let timed = |pts| SpliceCommand::TimeSignal {
+            splice_time: SpliceTime::Timed(Some(pts)),
+        };
+        let immediate = SpliceCommand::TimeSignal {
+            splice_time: SpliceTime::Immediate,
+        };
+        assert_eq!(timed(100).splice_pts(50), Some(150));
+        assert_eq!(immediate.splice_pts(0), None);
+        let mut commands = [timed(100), immediate, timed(time::PTS_MODULUS - 100)];
+        commands.sort_by(SpliceCommand::cmp_by_pts);
+        let pts: Vec<_> = commands.iter().map(|c| c.splice_pts(0)).collect();
+        assert_eq!(pts, [None, Some(time::PTS_MODULUS - 100), Some(100)]);
+    }
+
+    #[test]
+    fn adjusted_times() {
+        let command = SpliceCommand::SpliceInsert {
+            splice_event_id: 1,
+            reserved: 0x7f,
+            splice_detail: SpliceInsert::Insert {
+                network_indicator: NetworkIndicator::Out,
+                splice_mode: SpliceMode::Components(vec![
+                    ComponentSplice {
+                        component_tag: 1,
+                        splice_time: SpliceTime::Timed(Some(time::PTS_MODULUS - 10)),
+                    },
+                    ComponentSplice {
+                        component_tag: 2,
+                        splice_time: SpliceTime::Timed(None),
+                    },
+                ]),
+                duration: None,
+                unique_program_id: 0,
+                avail_num: 0,
+                avails_expected: 0,
+            },
+        };
+        assert_eq!(
+            command.adjusted_times(20),
+            [AdjustedTime {
+                component_tag: Some(1),
+                pts: time::SpliceTimestamp::new(10),
+            }]
+        );
+        let descriptor = SegmentationDescriptor::Insert {
+            program_segmentation_flag: false,
+            segmentation_duration_flag: false,
+            delivery_not_restricted_flag: true,
+            delivery_restrictions: DeliveryRestrictionFlags::None,
+            segmentation_mode: SegmentationMode::Component {
+                components: vec![SegmentationModeComponent {
+                    component_tag: 3,
+                    pts_offset: 100,
+                }],
+            },
+            segmentation_duration: None,
+            segmentation_upid: SegmentationUpid::None,
+            segmentation_type_id: SegmentationTypeId::ProgramStart,
+            segment_num: 0,
+            segments_expected: 0,
+            sub_segments: None,
+        };
+        assert_eq!(
+            descriptor.component_times(time::SpliceTimestamp::new(time::PTS_MODULUS - 50)),
+            [AdjustedTime {
+                component_tag: Some(3),
+                pts: time::SpliceTimestamp::new(50),
+            }]
+        );
+    }
 
-    mpeg2ts_reader::demux_context!(
-        NullDemuxContext,
-        demultiplex::NullPacketFilter<NullDemuxContext>
-    );
-    impl NullDemuxContext {
-        fn do_construct(
-            &mut self,
-            _req: demultiplex::FilterRequest<'_, '_>,
-        ) -> demultiplex::NullPacketFilter<NullDemuxContext> {
-            unimplemented!();
+    #[test]
+    fn splice_schedule() {
+        let data = hex!(
+            "02"               // splice_count
+            "00000001" "7f"    // event 1, not cancelled
+            "ff" "4d7c6d00"    // out, program splice, with duration, utc_splice_time
+            "fe" "0052f500"    // break_duration
+            "0001" "01" "02"   // unique_program_id, avail_num, avails_expected
+            "00000002" "ff"    // event 2, cancelled
+        );
+        let cmd = SpliceCommand::splice_schedule(&data[..], &LogDiagnostics).unwrap();
+        match cmd {
+            SpliceCommand::SpliceSchedule { events } => {
+                assert_eq!(events.len(), 2);
+                assert_eq!(events[0].splice_event_id, 1);
+                assert_matches!(
+                    events[0].splice_detail,
+                    ScheduledSplice::Insert {
+                        network_indicator: NetworkIndicator::Out,
+                        splice_mode: ScheduledSpliceMode::Program(UtcSpliceTime(1_300_000_000)),
+                        duration: Some(_),
+                        unique_program_id: 1,
+                        avail_num: 1,
+                        avails_expected: 2,
+                    }
+                );
+                assert_eq!(events[1].splice_event_id, 2);
+                assert_matches!(events[1].splice_detail, ScheduledSplice::Cancel);
+            }
+            _ => panic!("unexpected {:?}", cmd),
         }
     }
 
-    struct MockSpliceInsertProcessor;
-    impl SpliceInfoProcessor for MockSpliceInsertProcessor {
-        fn process(
-            &self,
-            header: SpliceInfoHeader<'_>,
-            command: SpliceCommand,
-            descriptors: SpliceDescriptors<'_>,
-        ) {
-            assert_eq!(header.encryption_algorithm(), EncryptionAlgorithm::None);
-            assert_matches!(command, SpliceCommand::SpliceInsert { .. });
-            for d in &descriptors {
-                d.unwrap();
+    #[test]
+    fn reserved_command() {
+        let data = with_crc(hex!("fc3013 00 0000000000 00 fff002 02 abcd 0000").to_vec());
+        let section = SpliceInfoSection::parse(&data).unwrap();
+        assert_eq!(
+            section.splice_command,
+            SpliceCommand::Reserved {
+                command_type: 0x02,
+                payload: vec![0xab, 0xcd],
             }
-        }
+        );
+        assert_eq!(section.encode().unwrap(), data);
     }
 
     #[test]
-    fn it_works() {
-        let data = hex!(
-            "fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49"
+    fn invalid_field_values() {
+        assert_matches!(
+            NetworkIndicator::try_from(2),
+            Err(SpliceDescriptorErr::InvalidFieldValue {
+                field_name: "out_of_network_indicator",
+                value: 2
+            })
+        );
+        assert_matches!(ReturnMode::try_from(1), Ok(ReturnMode::Automatic));
+        assert_matches!(
+            ReturnMode::try_from(0xff),
+            Err(SpliceDescriptorErr::InvalidFieldValue { .. })
+        );
+        assert_matches!(
+            DeviceRestrictions::try_from(3),
+            Ok(DeviceRestrictions::None)
+        );
+        assert_matches!(
+            DeviceRestrictions::try_from(4),
+            Err(SpliceDescriptorErr::InvalidFieldValue { .. })
         );
-        let mut parser = Scte35SectionProcessor::new(MockSpliceInsertProcessor);
-        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
-        let mut ctx = NullDemuxContext::new();
-        parser.section(&mut ctx, &header, &data[..]);
     }
 
-    struct MockTimeSignalProcessor;
-    impl SpliceInfoProcessor for MockTimeSignalProcessor {
-        fn process(
-            &self,
-            header: SpliceInfoHeader<'_>,
-            command: SpliceCommand,
-            descriptors: SpliceDescriptors<'_>,
-        ) {
-            assert_eq!(header.encryption_algorithm(), EncryptionAlgorithm::None);
-            assert_matches!(command, SpliceCommand::TimeSignal { .. });
-            for d in &descriptors {
-                d.unwrap();
+    #[test]
+    fn private_command() {
+        let data = with_crc(hex!("fc3017 00 0000000000 00 fff006 ff 41424344 0102 0000").to_vec());
+        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+        let (_, command, _) = parse_section(
+            &header,
+            &data,
+            MAX_SECTION_LENGTH,
+            CrcMode::default(),
+            reject_protocol_version,
+            &LogDiagnostics,
+        )
+        .unwrap();
+        match command {
+            SpliceCommand::PrivateCommand {
+                identifier,
+                private_bytes,
+            } => {
+                assert_eq!(&identifier, b"ABCD");
+                assert_eq!(private_bytes, vec![1, 2]);
             }
+            _ => panic!("unexpected {:?}", command),
         }
+        assert_matches!(
+            SpliceCommand::private_command(&hex!("414243")[..]),
+            Err(SpliceDescriptorErr::NotEnoughData { .. })
+        );
     }
 
     #[test]
-    fn it_understands_time_signal() {
-        let data = hex!(
-            "fc302700000000000000fff00506ff592d03c00011020f43554549000000017fbf000010010112ce0e6b"
+    fn utc_splice_time() {
+        // as signalled by a conforming encoder, the value converts directly to UTC
+        assert_eq!(
+            "2021-03-17T07:06:40Z",
+            UtcSpliceTime(1_300_000_000).to_rfc3339(0)
+        );
+        // raw GPS time from a non-conforming source is 18 leap seconds ahead of UTC
+        assert_eq!(
+            "2021-03-17T07:06:22Z",
+            UtcSpliceTime(1_300_000_000).to_rfc3339(time::CURRENT_GPS_UTC_OFFSET)
         );
-        let mut parser = Scte35SectionProcessor::new(MockTimeSignalProcessor);
-        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
-        let mut ctx = NullDemuxContext::new();
-        parser.section(&mut ctx, &header, &data[..]);
     }
 
     #[test]
     fn splice_descriptor() {
         let data = [];
         assert_matches!(
-            SpliceDescriptor::parse(&data[..]),
+            SpliceDescriptor::parse(&data[..], &LogDiagnostics),
             Err(SpliceDescriptorErr::NotEnoughData { .. })
         );
         let data = hex!("01084D5949440000"); // descriptor payload too short
         assert_matches!(
-            SpliceDescriptor::parse(&data[..]),
+            SpliceDescriptor::parse(&data[..], &LogDiagnostics),
             Err(SpliceDescriptorErr::NotEnoughData { .. })
         );
         let data = hex!("01034D59494400000003");
         assert_matches!(
-            SpliceDescriptor::parse(&data[..]),
+            SpliceDescriptor::parse(&data[..], &LogDiagnostics),
             Err(SpliceDescriptorErr::InvalidDescriptorLength { .. })
         );
         let data = hex!("01084D59494400000003");
         assert_matches!(
-            SpliceDescriptor::parse(&data[..]),
+            SpliceDescriptor::parse(&data[..], &LogDiagnostics),
             Ok(SpliceDescriptor::Reserved {
                 tag: 01,
                 identifier: [0x4D, 0x59, 0x49, 0x44],
                 private_bytes: _,
             })
         );
+        let data = hex!("040f435545492f65656e67a7216672610e");
+        match SpliceDescriptor::parse(&data[..], &LogDiagnostics).unwrap() {
+            SpliceDescriptor::AudioDescriptor { components } => {
+                assert_eq!(components.len(), 2);
+                assert_eq!(components[0].component_tag, 0x65);
+                assert_eq!(&components[0].iso_code, b"eng");
+                assert_eq!(components[0].bit_stream_mode, 5);
+                assert_eq!(components[0].num_channels, 3);
+                assert!(components[0].full_srvc_audio);
+                assert_eq!(&components[1].iso_code, b"fra");
+                assert!(!components[1].full_srvc_audio);
+            }
+            desc => panic!("unexpected {:?}", desc),
+        }
+        // a tag not defined for CUEI is tolerated (with a warning)
+        let data = hex!("0a054355454901");
+        assert_matches!(
+            SpliceDescriptor::parse(&data[..], &LogDiagnostics),
+            Ok(SpliceDescriptor::Reserved {
+                tag: 0x0a,
+                identifier: [0x43, 0x55, 0x45, 0x49],
+                private_bytes: _,
+            })
+        );
 
         let data = hex!("020f43554549000000017fbf0000100101");
         assert_matches!(
-            SpliceDescriptor::parse(&data[..]),
+            SpliceDescriptor::parse(&data[..], &LogDiagnostics),
             Ok(SpliceDescriptor::SegmentationDescriptor {
                 segmentation_event_id: 1,
                 descriptor_detail: SegmentationDescriptor::Insert {
@@ -1580,10 +5382,127 @@ mod tests {
         );
     }
 
+    #[test]
+    fn error_display() {
+        use std::error::Error;
+        // an avail_descriptor(), followed by a truncated segmentation_descriptor()
+        let data = hex!("000843554549 00000001 020543554549 00");
+        let descriptors = SpliceDescriptors {
+            buf: &data[..],
+            diagnostics: &LogDiagnostics,
+        };
+        let err = descriptors.into_iter().nth(1).unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid splice_descriptor with tag 0x02 at byte 10 of the descriptor loop"
+        );
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            "not enough data for segmentation_descriptor.id: 4 bytes required at bit offset 0, \
+             but only 1 remain"
+        );
+        assert_matches!(
+            err.detail(),
+            SpliceDescriptorErr::NotEnoughData {
+                field_name: "segmentation_descriptor.id",
+                ..
+            }
+        );
+        let err = Scte35Error::Descriptor(err);
+        assert_eq!(err.to_string(), "invalid splice descriptor");
+        assert!(err.source().unwrap().source().is_some());
+    }
+
+    #[test]
+    fn descriptor_recovery() {
+        // an avail_descriptor(), one too short for its identifier, a truncated
+        // segmentation_descriptor(), and another avail_descriptor()
+        let data = with_crc(
+            hex!(
+                "fc3030 00 0000000000 00 fff000 00 001f
+                000843554549 00000001 03024355 020543554549 00 000843554549 00000002"
+            )
+            .to_vec(),
+        );
+        let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+        let (splice_header, command, descriptors) = parse_section(
+            &header,
+            &data,
+            MAX_SECTION_LENGTH,
+            CrcMode::default(),
+            reject_protocol_version,
+            &LogDiagnostics,
+        )
+        .unwrap();
+        assert_eq!(descriptors.into_iter().count(), 4);
+        assert_eq!(descriptors.views().count(), 4);
+        let (section, errors) =
+            SpliceInfoSection::from_parts_recovering(&splice_header, command, &descriptors);
+        assert_eq!(
+            section.splice_descriptors,
+            vec![
+                SpliceDescriptor::AvailDescriptor {
+                    provider_avail_id: 1
+                },
+                SpliceDescriptor::AvailDescriptor {
+                    provider_avail_id: 2
+                },
+            ]
+        );
+        assert_matches!(
+            &errors[..],
+            [
+                SpliceDescriptorErr::Descriptor {
+                    splice_descriptor_tag: 0x03,
+                    offset: 10,
+                    ..
+                },
+                SpliceDescriptorErr::Descriptor {
+                    splice_descriptor_tag: 0x02,
+                    offset: 14,
+                    ..
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn serialize_descriptor_errors() {
+        // an avail_descriptor(), followed by a truncated segmentation_descriptor()
+        let data = hex!("000843554549 00000001 020543554549 00");
+        let descriptors = SpliceDescriptors {
+            buf: &data[..],
+            diagnostics: &LogDiagnostics,
+        };
+        let json = serde_json::to_value(&descriptors).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                { "AvailDescriptor": { "provider_avail_id": 1 } },
+                {
+                    "Error": {
+                        "error": { "Descriptor": {
+                            "splice_descriptor_tag": 2,
+                            "offset": 10,
+                            "error": { "NotEnoughData": {
+                                "field_name": "segmentation_descriptor.id",
+                                "expected": 4,
+                                "actual": 1,
+                                "bit_offset": 0,
+                            } },
+                        } },
+                        "bytes": [0x02, 0x05, 0x43, 0x55, 0x45, 0x49, 0x00],
+                    }
+                },
+            ])
+        );
+    }
+
     #[test]
     fn segmentation_descriptor() {
         let data = hex!("480000ad7f9f0808000000002cb2d79d350200");
-        let desc = SpliceDescriptor::parse_segmentation_descriptor(&data[..]).unwrap();
+        let desc =
+            SpliceDescriptor::parse_segmentation_descriptor(&data[..], &LogDiagnostics).unwrap();
         match desc {
             SpliceDescriptor::SegmentationDescriptor {
                 descriptor_detail:
@@ -1600,18 +5519,552 @@ mod tests {
         };
     }
 
+    #[test]
+    fn isan_upids() {
+        fn upid(data: &[u8]) -> SegmentationUpid {
+            match SpliceDescriptor::parse_segmentation_descriptor(data, &LogDiagnostics).unwrap() {
+                SpliceDescriptor::SegmentationDescriptor {
+                    descriptor_detail:
+                        SegmentationDescriptor::Insert {
+                            segmentation_upid, ..
+                        },
+                    ..
+                } => segmentation_upid,
+                desc => panic!("unexpected {:?}", desc),
+            }
+        }
+        let deprecated = upid(&hex!("480000ad7f9f05080000000100020003350200"));
+        assert_matches!(deprecated, SegmentationUpid::IsanDeprecated(_));
+        assert_eq!(
+            deprecated.segmentation_upid_type(),
+            SegmentationUpidType::ISANDeprecated
+        );
+        let versioned = upid(&hex!("480000ad7f9f060c00000001000200030004ffff350200"));
+        assert_eq!(
+            "Isan(Isan(00000001000200030004ffff))",
+            format!("{:?}", versioned)
+        );
+        assert_eq!(
+            versioned.segmentation_upid_type(),
+            SegmentationUpidType::ISAN
+        );
+        assert_eq!(versioned.segmentation_upid_length(), 12);
+    }
+
+    #[test]
+    fn segmentation_type_ids() {
+        assert_eq!(
+            SegmentationTypeId::from_id(0x38),
+            SegmentationTypeId::ProviderOverlayPlacementOpportunityStart
+        );
+        assert_eq!(
+            SegmentationTypeId::from_id(0x3f),
+            SegmentationTypeId::DistributorPromoEnd
+        );
+        assert_eq!(
+            SegmentationTypeId::from_id(0x47),
+            SegmentationTypeId::DistributorAdBlockEnd
+        );
+        assert_eq!(
+            SegmentationTypeId::from_id(0x48),
+            SegmentationTypeId::Reserved(0x48)
+        );
+        for id in 0..=0xff {
+            assert_eq!(SegmentationTypeId::from_id(id).id(), id);
+        }
+    }
+
+    #[test]
+    fn model_equality() {
+        let section = SpliceInfoSection::parse(&TIME_SIGNAL).unwrap();
+        let copy = section.clone();
+        assert_eq!(section, SpliceInfoSection::parse(&TIME_SIGNAL).unwrap());
+        let mut other = copy.clone();
+        other.pts_adjustment = 1;
+        assert_ne!(section, other);
+        let set: std::collections::HashSet<_> = vec![section, copy, other].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn dtmf_descriptor() {
+        let descriptor =
+            SpliceDescriptor::parse(&hex!("010943554549 64 7f 31322a"), &LogDiagnostics).unwrap();
+        assert_eq!(descriptor.dtmf_str().unwrap().unwrap(), "12*");
+        let descriptor =
+            SpliceDescriptor::parse(&hex!("010943554549 64 7f 314123"), &LogDiagnostics).unwrap();
+        assert_matches!(
+            descriptor.dtmf_str(),
+            Some(Err(SpliceDescriptorErr::InvalidFieldValue {
+                field_name: "DTMF_char",
+                value: b'A'
+            }))
+        );
+        let descriptor =
+            SpliceDescriptor::parse(&hex!("000843554549 00000001"), &LogDiagnostics).unwrap();
+        assert!(descriptor.dtmf_str().is_none());
+    }
+
+    #[test]
+    fn segmentation_type_semantics() {
+        let t = SegmentationTypeId::from_id;
+        assert_eq!(
+            SegmentationTypeId::BreakStart.corresponding_end_type(),
+            Some(SegmentationTypeId::BreakEnd)
+        );
+        assert_eq!(
+            SegmentationTypeId::DistributorAdBlockEnd.corresponding_start_type(),
+            Some(SegmentationTypeId::DistributorAdBlockStart)
+        );
+        assert_eq!(t(0x24).corresponding_end_type(), Some(t(0x25)));
+        for start in [0x10, 0x20, 0x22, 0x30, 0x3c, 0x46, 0x50] {
+            assert!(t(start).is_start(), "{:#x}", start);
+            assert!(t(start + 1).is_end(), "{:#x}", start + 1);
+            assert!(!t(start + 1).is_start(), "{:#x}", start + 1);
+        }
+        for other in [0x00, 0x01, 0x12, 0x19, 0x28, 0x48, 0x52] {
+            assert!(!t(other).is_start() && !t(other).is_end(), "{:#x}", other);
+        }
+        assert!(SegmentationTypeId::ProviderPlacementOpportunityStart.is_out());
+        assert!(SegmentationTypeId::ProviderPlacementOpportunityEnd.is_in());
+        assert!(!SegmentationTypeId::ChapterStart.is_out());
+        assert!(!SegmentationTypeId::ChapterEnd.is_in());
+        assert!(!SegmentationTypeId::ProviderOverlayPlacementOpportunityStart.is_out());
+
+        let descriptor = SegmentationDescriptor::Insert {
+            program_segmentation_flag: true,
+            segmentation_duration_flag: true,
+            delivery_not_restricted_flag: true,
+            delivery_restrictions: DeliveryRestrictionFlags::None,
+            segmentation_mode: SegmentationMode::Program,
+            segmentation_duration: Some(2_700_000),
+            segmentation_upid: SegmentationUpid::None,
+            segmentation_type_id: SegmentationTypeId::BreakStart,
+            segment_num: 0,
+            segments_expected: 0,
+            sub_segments: None,
+        };
+        assert!(descriptor.is_out());
+        assert_eq!(
+            descriptor.expected_end_pts(time::SpliceTimestamp::new(time::PTS_MODULUS - 100)),
+            Some(time::SpliceTimestamp::new(2_700_000 - 100))
+        );
+        assert!(!SegmentationDescriptor::Cancel.is_out());
+        assert_eq!(
+            SegmentationDescriptor::Cancel.expected_end_pts(time::SpliceTimestamp::new(0)),
+            None
+        );
+    }
+
+    #[test]
+    fn display_names() {
+        assert_eq!(
+            SegmentationTypeId::from_id(0x34).to_string(),
+            "Provider Placement Opportunity Start"
+        );
+        assert_eq!(
+            SegmentationTypeId::from_id(0x24).to_string(),
+            "Reserved (0x24)"
+        );
+        assert_eq!(
+            SegmentationTypeId::from_id(0x48).to_string(),
+            "Reserved (0x48)"
+        );
+        assert_eq!(SegmentationUpidType::AdID.to_string(), "Ad-ID");
+        assert_eq!(
+            SegmentationUpidType::from_type(0x12).to_string(),
+            "Reserved (0x12)"
+        );
+        assert_eq!(SpliceCommandType::TimeSignal.to_string(), "time_signal");
+        assert_eq!(
+            SpliceCommandType::from_id(0x01).to_string(),
+            "Reserved (0x01)"
+        );
+        assert_eq!(EncryptionAlgorithm::DesCbc.to_string(), "DES - CBC mode");
+        assert_eq!(
+            EncryptionAlgorithm::from_id(0x20).to_string(),
+            "User private (0x20)"
+        );
+    }
+
+    #[test]
+    fn mid_upid() {
+        // MID() holding an Ad-ID and a TI
+        let upid = SegmentationUpid::parse_payload(
+            SegmentationUpidType::MID,
+            hex!("030c 414243443030303130303048 0808 000000002cb2d79d").to_vec(),
+        )
+        .unwrap();
+        match upid {
+            SegmentationUpid::MID(ref upids) => {
+                assert_eq!(upids.len(), 2);
+                assert_matches!(upids[0], SegmentationUpid::AdID(ref a) if a.0 == "ABCD0001000H");
+                assert_matches!(upids[1], SegmentationUpid::TI(ref t) if t.0 == hex!("000000002cb2d79d"));
+            }
+            _ => panic!("unexpected {:?}", upid),
+        }
+        assert_eq!(upid.segmentation_upid_type(), SegmentationUpidType::MID);
+        assert_eq!(upid.segmentation_upid_length(), 24);
+
+        // an entry whose length runs beyond the end of the MID() payload
+        assert_matches!(
+            SegmentationUpid::parse_payload(
+                SegmentationUpidType::MID,
+                hex!("0808 000000002c").to_vec()
+            ),
+            Err(SpliceDescriptorErr::NotEnoughData {
+                field_name: "MID.segmentation_upid",
+                expected: 8,
+                actual: 5,
+                bit_offset: 16,
+            })
+        );
+        // an entry within the MID() that is itself invalid
+        assert_matches!(
+            SegmentationUpid::parse_payload(SegmentationUpidType::MID, hex!("0802 0000").to_vec()),
+            Err(SpliceDescriptorErr::InvalidUpidLength { .. })
+        );
+    }
+
+    #[test]
+    fn atsc_upid() {
+        let upid = SegmentationUpid::parse_payload(
+            SegmentationUpidType::ATSC,
+            hex!("1234 eb1e 6162").to_vec(),
+        )
+        .unwrap();
+        assert_eq!(
+            "ATSC(ATSC { tsid: 4660, end_of_day: 21, unique_for: 286, content_id: 6162 })",
+            format!("{:?}", upid)
+        );
+        assert_eq!(upid.segmentation_upid_length(), 6);
+        assert_matches!(
+            SegmentationUpid::parse_payload(SegmentationUpidType::ATSC, hex!("1234eb").to_vec()),
+            Err(SpliceDescriptorErr::NotEnoughData {
+                field_name: "ATSC_content_identifier.unique_for",
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn uuid_and_scr_upids() {
+        let upid = SegmentationUpid::parse_payload(
+            SegmentationUpidType::from_type(0x10),
+            hex!("f81d4fae7dec11d0a76500a0c91e6bf6").to_vec(),
+        )
+        .unwrap();
+        assert_eq!(
+            "UUID(Uuid(f81d4fae-7dec-11d0-a765-00a0c91e6bf6))",
+            format!("{:?}", upid)
+        );
+        assert_eq!(upid.segmentation_upid_type().id(), 0x10);
+        assert_matches!(
+            SegmentationUpid::parse_payload(SegmentationUpidType::UUID, vec![0; 15]),
+            Err(SpliceDescriptorErr::InvalidUpidLength { .. })
+        );
+        let upid =
+            SegmentationUpid::parse_payload(SegmentationUpidType::from_type(0x11), vec![1, 2])
+                .unwrap();
+        assert_eq!("SCR(SCR(0102))", format!("{:?}", upid));
+        assert_eq!(
+            SegmentationUpidType::from_type(0x12),
+            SegmentationUpidType::Reserved(0x12)
+        );
+    }
+
+    #[test]
+    fn upid_length_mode() {
+        struct Warn(WarningCollector);
+        impl Scte35Diagnostics for Warn {
+            fn warning(&self, warning: &Scte35Warning) {
+                self.0.warning(warning)
+            }
+
+            fn upid_length_mode(&self) -> UpidLengthMode {
+                UpidLengthMode::Warn
+            }
+        }
+        // segmentation descriptors with an 11 byte Ad-ID, a zero-length Ad-ID, and a MID holding
+        // a 7 byte TI
+        let short = hex!("480000017fbf 030b 4142434430303031303030 10 0000");
+        let empty = hex!("480000017fbf 0300 10 0000");
+        let mid = hex!("480000017fbf 0d09 0807 00000000000001 10 0000");
+
+        for data in [&short[..], &empty[..], &mid[..]] {
+            assert_matches!(
+                SpliceDescriptor::parse_segmentation_descriptor(data, &LogDiagnostics),
+                Err(SpliceDescriptorErr::InvalidUpidLength { .. })
+            );
+        }
+
+        let upid = |data: &[u8]| {
+            let diagnostics = Warn(WarningCollector::default());
+            match SpliceDescriptor::parse_segmentation_descriptor(data, &diagnostics).unwrap() {
+                SpliceDescriptor::SegmentationDescriptor {
+                    descriptor_detail:
+                        SegmentationDescriptor::Insert {
+                            segmentation_upid, ..
+                        },
+                    ..
+                } => (segmentation_upid, diagnostics.0 .0.into_inner()),
+                other => panic!("unexpected {:?}", other),
+            }
+        };
+        assert_eq!(
+            upid(&short),
+            (
+                SegmentationUpid::Reserved(SegmentationUpidType::AdID, b"ABCD0001000".to_vec()),
+                vec![Scte35Warning::UpidLength {
+                    upid_type: SegmentationUpidType::AdID,
+                    expected: 12,
+                    actual: 11
+                }]
+            )
+        );
+        assert_eq!(upid(&empty).0, SegmentationUpid::None);
+        assert_matches!(
+            upid(&mid),
+            (SegmentationUpid::MID(ref upids), ref warnings)
+                if std::matches!(upids[..], [SegmentationUpid::Reserved(SegmentationUpidType::TI, _)])
+                    && warnings.len() == 1
+        );
+    }
+
+    #[test]
+    fn uri_upid() {
+        let diagnostics = WarningCollector::default();
+        let uri = |data: &[u8]| {
+            SegmentationUpid::parse_payload_with(
+                SegmentationUpidType::URI,
+                data.to_vec(),
+                &diagnostics,
+            )
+            .unwrap()
+        };
+        assert_matches!(
+            uri(b"urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6"),
+            SegmentationUpid::URI(ref u) if u.0.scheme() == "urn"
+        );
+        assert!(diagnostics.0.borrow().is_empty());
+        assert_eq!(
+            uri(b"no scheme"),
+            SegmentationUpid::Reserved(SegmentationUpidType::URI, b"no scheme".to_vec())
+        );
+        assert_eq!(
+            uri(b"urn:\xff"),
+            SegmentationUpid::Reserved(SegmentationUpidType::URI, b"urn:\xff".to_vec())
+        );
+        assert_eq!(
+            diagnostics.0.into_inner(),
+            [
+                Scte35Warning::MalformedUri(b"no scheme".to_vec()),
+                Scte35Warning::MalformedUri(b"urn:\xff".to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn mpu_upid() {
+        let upid =
+            SegmentationUpid::parse_payload(SegmentationUpidType::MPU, b"ADFR\x01\x02".to_vec())
+                .unwrap();
+        match upid {
+            SegmentationUpid::MPU(ref mpu) => {
+                assert_eq!(mpu.format_identifier(), Some(*b"ADFR"));
+                assert_eq!(mpu.private_data(), &[1, 2]);
+            }
+            _ => panic!("unexpected {:?}", upid),
+        }
+        assert_matches!(
+            SegmentationUpid::parse_payload(SegmentationUpidType::MPU, b"ADF".to_vec()),
+            Err(SpliceDescriptorErr::NotEnoughData {
+                field_name: "MPU.format_identifier",
+                expected: 4,
+                actual: 3,
+                bit_offset: 0,
+            })
+        );
+    }
+
     #[test]
     fn no_sub_segment_num() {
         // This segmentation_descriptor() does not include sub_segment_num or
         // sub_segments_expected fields.  Their absence should not cause parsing problems.
         let data = hex!("480000bf7fcf0000f8fa630d110e054c413330390808000000002e538481340000");
-        SpliceDescriptor::parse_segmentation_descriptor(&data[..]).unwrap();
+        SpliceDescriptor::parse_segmentation_descriptor(&data[..], &LogDiagnostics).unwrap();
     }
 
     #[test]
     fn too_large_segment_descriptor() {
         // there are more bytes than expected; this should not panic
         let data = hex!("480000ad7f9f0808000000002cb2d79d350200000000");
-        SpliceDescriptor::parse_segmentation_descriptor(&data[..]).unwrap();
+        SpliceDescriptor::parse_segmentation_descriptor(&data[..], &LogDiagnostics).unwrap();
+    }
+
+    #[test]
+    fn count_exceeds_data() {
+        // dtmf_count of 7, but only one DTMF_char
+        let data = hex!("0107435545490aff31");
+        assert_matches!(
+            SpliceDescriptor::parse(&data[..], &LogDiagnostics),
+            Err(SpliceDescriptorErr::CountExceedsData {
+                field_name: "dtmf_descriptor.dtmf_count",
+                count: 7,
+                max: 1,
+            })
+        );
+        // component-mode splice_insert() claiming 255 components
+        let data = hex!("00000001 7f 8f ff 01 fe00000000");
+        assert_matches!(
+            SpliceCommand::parse(SpliceCommandType::SpliceInsert, &data[..], &LogDiagnostics),
+            Err(SpliceDescriptorErr::CountExceedsData {
+                field_name: "component_count",
+                count: 255,
+                max: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn internal_errors() {
+        let data = [0xff, 0xff];
+        let mut r = bitreader::BitReader::new(&data[..]);
+        assert_matches!(
+            r.read_u8(9).named("too_wide"),
+            Err(SpliceDescriptorErr::ReaderError {
+                field_name: "too_wide",
+                ..
+            })
+        );
+        r.read_bool().unwrap();
+        assert_matches!(
+            check_aligned(&r, "partial"),
+            Err(SpliceDescriptorErr::Unaligned {
+                syntax: "partial",
+                bit_position: 1
+            })
+        );
+    }
+
+    #[test]
+    fn cue_identifier_descriptor() {
+        // PMT body with no program-level descriptors, and a single stream_type=0x86 stream
+        // carrying cue_identifier_descriptor(cue_stream_type=0x01)
+        let data = hex!("e100f00086e1f4f0038a0101");
+        let pmt = psi::pmt::PmtSection::from_bytes(&data[..]).unwrap();
+        assert!(is_scte35(&pmt));
+        let stream_info = pmt.streams().next().unwrap();
+        assert_eq!(
+            cue_stream_type(&stream_info),
+            Some(CueStreamType::AllCommands)
+        );
+
+        // same, but without the descriptor
+        let data = hex!("e100f00086e1f4f000");
+        let pmt = psi::pmt::PmtSection::from_bytes(&data[..]).unwrap();
+        assert!(!is_scte35(&pmt));
+        let stream_info = pmt.streams().next().unwrap();
+        assert_eq!(cue_stream_type(&stream_info), None);
+    }
+
+    #[test]
+    fn section_cmp_by_pts() {
+        let parse = |data: &[u8]| {
+            let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+            let (header, command, descriptors) = parse_section(
+                &header,
+                data,
+                MAX_SECTION_LENGTH,
+                CrcMode::default(),
+                reject_protocol_version,
+                &LogDiagnostics,
+            )
+            .unwrap();
+            SpliceInfoSection::from_parts(&header, command, &descriptors).unwrap()
+        };
+        let mut immediate = parse(&SPLICE_INSERT);
+        if let SpliceCommand::SpliceInsert {
+            splice_detail: SpliceInsert::Insert { splice_mode, .. },
+            ..
+        } = &mut immediate.splice_command
+        {
+            *splice_mode = SpliceMode::Program(SpliceTime::Immediate);
+        }
+        // the time_signal() PTS is more than half the 33-bit range beyond that of the
+        // splice_insert(), so is taken to precede it
+        let mut adjusted = parse(&SPLICE_INSERT);
+        adjusted.pts_adjustment = 1000;
+        let mut sections = [
+            adjusted,
+            parse(&SPLICE_INSERT),
+            immediate,
+            parse(&TIME_SIGNAL),
+        ];
+        sections.sort_by(SpliceInfoSection::cmp_by_pts);
+        let pts: Vec<_> = sections.iter().map(|s| s.splice_pts()).collect();
+        assert_eq!(
+            pts,
+            [
+                None,
+                Some(0x1_592d_03c0),
+                Some(0x2d14_2b00),
+                Some(0x2d14_2b00 + 1000)
+            ]
+        );
+    }
+
+    #[test]
+    fn scte35_pids() {
+        // PMT body with the CUEI registration descriptor, a video stream on PID 0x100 and
+        // SCTE-35 streams on PIDs 0x101 and 0x102
+        let data = hex!("e100f006 0504 43554549 1be100f000 86e101f000 86e102f000");
+        let pmt = psi::pmt::PmtSection::from_bytes(&data[..]).unwrap();
+        assert_eq!(
+            super::scte35_pids(&pmt),
+            vec![
+                mpeg2ts_reader::packet::Pid::new(0x101),
+                mpeg2ts_reader::packet::Pid::new(0x102)
+            ]
+        );
+
+        // same, but without the registration descriptor
+        let data = hex!("e100f000 1be100f000 86e101f000 86e102f000");
+        let pmt = psi::pmt::PmtSection::from_bytes(&data[..]).unwrap();
+        assert!(super::scte35_pids(&pmt).is_empty());
+    }
+
+    #[test]
+    fn component_tag_map() {
+        // PMT body with a video stream on PID 0x101 tagged 0x0a, and an audio stream on PID
+        // 0x102 tagged 0x0b, via stream_identifier_descriptor()
+        let data = hex!("e100f000 1be101f00352010a 0fe102f00352010b");
+        let pmt = psi::pmt::PmtSection::from_bytes(&data[..]).unwrap();
+        let map = ComponentTagMap::from_pmt(&pmt);
+        assert_eq!(map.pid(0x0a), Some(mpeg2ts_reader::packet::Pid::new(0x101)));
+        assert_eq!(map.pid(0x0b), Some(mpeg2ts_reader::packet::Pid::new(0x102)));
+        assert_eq!(map.pid(0x0c), None);
+
+        let components = vec![
+            ComponentSplice {
+                component_tag: 0x0b,
+                splice_time: SpliceTime::Timed(Some(1000)),
+            },
+            ComponentSplice {
+                component_tag: 0x0c,
+                splice_time: SpliceTime::Timed(None),
+            },
+        ];
+        let pids: Vec<_> = map
+            .splice_components(&components)
+            .map(|(c, pid)| (c.component_tag(), pid))
+            .collect();
+        assert_eq!(
+            pids,
+            vec![
+                (0x0b, Some(mpeg2ts_reader::packet::Pid::new(0x102))),
+                (0x0c, None)
+            ]
+        );
     }
 }
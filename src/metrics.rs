@@ -0,0 +1,313 @@
+//! Counters of the sections seen by a `Scte35SectionProcessor`, for monitoring probes which
+//! need to export statistics (for example to Prometheus) without wrapping every
+//! `SpliceInfoProcessor` callback.
+//!
+//! [`Scte35Metrics`](struct.Scte35Metrics.html) is a `Scte35Diagnostics` implementation which
+//! counts the sections, commands, descriptors and problems reported to it, before passing each
+//! notification on to another `Scte35Diagnostics` (by default, `LogDiagnostics`).  Since a
+//! `Scte35SectionProcessor` owns its diagnostics, and is usually owned in turn by the demux, the
+//! metrics are normally shared via an `Arc`, so that the application can read them while the
+//! stream is being processed.
+//!
+//! ## Example
+//!
+//! ```
+//! # use scte35_reader::metrics::Scte35Metrics;
+//! # use scte35_reader::{Scte35SectionProcessor, SpliceCommand, SpliceDescriptors, SpliceInfoHeader};
+//! # use std::sync::Arc;
+//! # mpeg2ts_reader::demux_context!(
+//! #     DumpDemuxContext,
+//! #     mpeg2ts_reader::demultiplex::NullPacketFilter<DumpDemuxContext>
+//! # );
+//! # impl DumpDemuxContext {
+//! #     fn do_construct(
+//! #         &mut self,
+//! #         _req: mpeg2ts_reader::demultiplex::FilterRequest<'_, '_>,
+//! #     ) -> mpeg2ts_reader::demultiplex::NullPacketFilter<DumpDemuxContext> {
+//! #         unimplemented!()
+//! #     }
+//! # }
+//! let metrics = Arc::new(Scte35Metrics::new());
+//! let parser: Scte35SectionProcessor<_, DumpDemuxContext, _, _> = Scte35SectionProcessor::new(
+//!     |_: SpliceInfoHeader<'_>, _: SpliceCommand, _: SpliceDescriptors<'_>| {},
+//! )
+//! .with_diagnostics(metrics.clone());
+//! // ...hand the parser to the demux, and then periodically,
+//! let exposition = metrics.snapshot().to_prometheus();
+//! ```
+
+use crate::{
+    LogDiagnostics, Scte35Diagnostics, Scte35Error, Scte35Warning, SpliceCommand,
+    SpliceCommandType, SpliceDescriptors, SpliceInfoHeader, Tier, UpidLengthMode,
+};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counts the notifications given to it as a `Scte35Diagnostics`, passing each on to the
+/// wrapped `Scte35Diagnostics`.
+///
+/// A CRC failure is counted only in `crc_failures`, whether it is reported as an error (under
+/// `CrcMode::Strict`) or as a warning (under `CrcMode::Warn`), rather than also being counted
+/// among the parse errors or warnings.
+#[derive(Debug)]
+pub struct Scte35Metrics<D = LogDiagnostics> {
+    inner: D,
+    sections: AtomicU64,
+    /// indexed by `splice_command_type`
+    commands: [AtomicU64; 256],
+    /// indexed by `splice_descriptor_tag`
+    descriptors: [AtomicU64; 256],
+    crc_failures: AtomicU64,
+    parse_errors: AtomicU64,
+    warnings: AtomicU64,
+    tier_filtered: AtomicU64,
+}
+impl Default for Scte35Metrics {
+    fn default() -> Self {
+        Scte35Metrics::wrapping(LogDiagnostics)
+    }
+}
+impl Scte35Metrics {
+    /// Counts sections, and logs any problems with them
+    pub fn new() -> Scte35Metrics {
+        Self::default()
+    }
+}
+impl<D: Scte35Diagnostics> Scte35Metrics<D> {
+    /// Counts sections, passing every notification on to the given `Scte35Diagnostics`
+    pub fn wrapping(inner: D) -> Scte35Metrics<D> {
+        Scte35Metrics {
+            inner,
+            sections: AtomicU64::new(0),
+            commands: [(); 256].map(|_| AtomicU64::new(0)),
+            descriptors: [(); 256].map(|_| AtomicU64::new(0)),
+            crc_failures: AtomicU64::new(0),
+            parse_errors: AtomicU64::new(0),
+            warnings: AtomicU64::new(0),
+            tier_filtered: AtomicU64::new(0),
+        }
+    }
+
+    /// The `Scte35Diagnostics` given to `wrapping()`
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    /// The current values of the counters
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let nonzero = |counters: &[AtomicU64; 256]| {
+            counters
+                .iter()
+                .enumerate()
+                .map(|(id, count)| (id as u8, count.load(Ordering::Relaxed)))
+                .filter(|(_, count)| *count > 0)
+                .collect::<Vec<_>>()
+        };
+        MetricsSnapshot {
+            sections: self.sections.load(Ordering::Relaxed),
+            commands: nonzero(&self.commands)
+                .into_iter()
+                .map(|(id, count)| (SpliceCommandType::from_id(id), count))
+                .collect(),
+            descriptors: nonzero(&self.descriptors),
+            crc_failures: self.crc_failures.load(Ordering::Relaxed),
+            parse_errors: self.parse_errors.load(Ordering::Relaxed),
+            warnings: self.warnings.load(Ordering::Relaxed),
+            tier_filtered: self.tier_filtered.load(Ordering::Relaxed),
+        }
+    }
+}
+impl<D: Scte35Diagnostics> Scte35Diagnostics for Scte35Metrics<D> {
+    fn error(&self, error: &Scte35Error) {
+        match error {
+            Scte35Error::Crc(_) => &self.crc_failures,
+            _ => &self.parse_errors,
+        }
+        .fetch_add(1, Ordering::Relaxed);
+        self.inner.error(error);
+    }
+
+    fn warning(&self, warning: &Scte35Warning) {
+        match warning {
+            Scte35Warning::Crc(_) => &self.crc_failures,
+            _ => &self.warnings,
+        }
+        .fetch_add(1, Ordering::Relaxed);
+        self.inner.warning(warning);
+    }
+
+    fn tier_filtered(&self, tier: Tier) {
+        self.tier_filtered.fetch_add(1, Ordering::Relaxed);
+        self.inner.tier_filtered(tier);
+    }
+
+    fn section_parsed(
+        &self,
+        header: &SpliceInfoHeader<'_>,
+        command: &SpliceCommand,
+        descriptors: &SpliceDescriptors<'_>,
+    ) {
+        self.sections.fetch_add(1, Ordering::Relaxed);
+        self.commands[usize::from(header.splice_command_type().id())]
+            .fetch_add(1, Ordering::Relaxed);
+        for tag in descriptors.tags() {
+            self.descriptors[usize::from(tag)].fetch_add(1, Ordering::Relaxed);
+        }
+        self.inner.section_parsed(header, command, descriptors);
+    }
+
+    fn upid_length_mode(&self) -> UpidLengthMode {
+        self.inner.upid_length_mode()
+    }
+}
+
+/// The values of the counters of a `Scte35Metrics` at a point in time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Sections parsed without error
+    pub sections: u64,
+    /// The number of parsed sections carrying each type of command, for those types seen
+    pub commands: Vec<(SpliceCommandType, u64)>,
+    /// The number of descriptors with each `splice_descriptor_tag` in the parsed sections, for
+    /// those tags seen
+    pub descriptors: Vec<(u8, u64)>,
+    /// Sections whose `CRC_32` did not match their content
+    pub crc_failures: u64,
+    /// Sections which could not be parsed, for reasons other than a CRC failure
+    pub parse_errors: u64,
+    /// Non-fatal problems found within sections, other than CRC failures
+    pub warnings: u64,
+    /// Sections dropped because of their tier
+    pub tier_filtered: u64,
+}
+impl MetricsSnapshot {
+    /// Formats the counters in the Prometheus text exposition format, with metric names
+    /// prefixed `scte35_`
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let mut counter = |name: &str, help: &str, samples: &[(String, u64)]| {
+            // writing to a String can not fail
+            let _ = writeln!(out, "# HELP scte35_{} {}", name, help);
+            let _ = writeln!(out, "# TYPE scte35_{} counter", name);
+            for (labels, value) in samples {
+                let _ = writeln!(out, "scte35_{}{} {}", name, labels, value);
+            }
+        };
+        let single = |value| [(String::new(), value)];
+        counter(
+            "sections_total",
+            "Sections parsed without error",
+            &single(self.sections),
+        );
+        let commands: Vec<_> = self
+            .commands
+            .iter()
+            .map(|(command_type, count)| {
+                (
+                    format!("{{command_type=\"{:#04x}\"}}", command_type.id()),
+                    *count,
+                )
+            })
+            .collect();
+        counter(
+            "commands_total",
+            "Parsed sections by splice_command_type",
+            &commands,
+        );
+        let descriptors: Vec<_> = self
+            .descriptors
+            .iter()
+            .map(|(tag, count)| (format!("{{tag=\"{:#04x}\"}}", tag), *count))
+            .collect();
+        counter(
+            "descriptors_total",
+            "Descriptors in parsed sections by splice_descriptor_tag",
+            &descriptors,
+        );
+        counter(
+            "crc_failures_total",
+            "Sections failing the CRC_32 check",
+            &single(self.crc_failures),
+        );
+        counter(
+            "parse_errors_total",
+            "Sections which could not be parsed",
+            &single(self.parse_errors),
+        );
+        counter(
+            "warnings_total",
+            "Non-fatal problems found in sections",
+            &single(self.warnings),
+        );
+        counter(
+            "tier_filtered_total",
+            "Sections dropped because of their tier",
+            &single(self.tier_filtered),
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use crate::tests::NullDemuxContext;
+    use crate::Scte35SectionProcessor;
+    use hex_literal::*;
+    use mpeg2ts_reader::psi;
+    use mpeg2ts_reader::psi::WholeCompactSyntaxPayloadParser;
+    use std::sync::Arc;
+
+    #[test]
+    fn counts() {
+        let metrics = Arc::new(Scte35Metrics::new());
+        let mut parser = Scte35SectionProcessor::new(
+            |_: SpliceInfoHeader<'_>, _: SpliceCommand, _: SpliceDescriptors<'_>| {},
+        )
+        .with_diagnostics(metrics.clone());
+        let mut ctx = NullDemuxContext::new();
+        let mut bad_crc = TIME_SIGNAL;
+        bad_crc[5] ^= 1;
+        let bad_table_id = hex!("fd3011000000");
+        for data in [
+            &SPLICE_INSERT[..],
+            &SPLICE_INSERT[..],
+            &TIME_SIGNAL[..],
+            &bad_crc[..],
+            &bad_table_id[..],
+        ] {
+            let header = psi::SectionCommonHeader::new(&data[..psi::SectionCommonHeader::SIZE]);
+            parser.section(&mut ctx, &header, data);
+        }
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(
+            snapshot,
+            MetricsSnapshot {
+                sections: 3,
+                commands: vec![
+                    (SpliceCommandType::SpliceInsert, 2),
+                    (SpliceCommandType::TimeSignal, 1)
+                ],
+                descriptors: vec![(0x02, 1)],
+                crc_failures: 1,
+                parse_errors: 1,
+                warnings: 0,
+                tier_filtered: 0,
+            }
+        );
+        let text = snapshot.to_prometheus();
+        assert!(text.contains("\nscte35_sections_total 3\n"), "{}", text);
+        assert!(
+            text.contains("\nscte35_commands_total{command_type=\"0x05\"} 2\n"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("\nscte35_descriptors_total{tag=\"0x02\"} 1\n"),
+            "{}",
+            text
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Division of complete sections into 188-byte Transport Stream packets, ready to be muxed
+//! alongside the other elementary streams of a program.
+//!
+//! Each section begins a new packet (with `payload_unit_start_indicator` set, and a
+//! `pointer_field` of `0`), and the last packet of each section is padded with `0xff` stuffing
+//! bytes, so that no packet carries the data of more than one section.  A
+//! [`Packetizer`](struct.Packetizer.html) maintains a separate `continuity_counter` for each PID
+//! it is given sections for.
+//!
+//! ## Example
+//!
+//! ```
+//! # use hex_literal::*;
+//! # use mpeg2ts_reader::packet::Pid;
+//! # use scte35_reader::packetize::Packetizer;
+//! let section = hex!("fc301100000000000000fff0000000007a4fbfff");
+//! let mut packetizer = Packetizer::new();
+//! let packets = packetizer.packetize(Pid::new(0x101), &section);
+//! assert_eq!(packets.len(), 1);
+//! assert_eq!(&packets[0][..4], &[0x47, 0x41, 0x01, 0x10]);
+//! ```
+//!
+//! A [`PacketizingSink`](struct.PacketizingSink.html) adapts a `Packetizer` for use with the
+//! [`inject`](../inject/index.html) module:
+//!
+//! ```
+//! # use mpeg2ts_reader::packet::Pid;
+//! # use scte35_reader::inject::InjectionScheduler;
+//! # use scte35_reader::packetize::PacketizingSink;
+//! let mut muxed = vec![];
+//! let sink = PacketizingSink::new(Pid::new(0x101), |pk: &[u8]| muxed.extend_from_slice(pk));
+//! let mut scheduler = InjectionScheduler::new(sink);
+//! ```
+
+use crate::inject::SectionSink;
+use mpeg2ts_reader::packet::{Packet, Pid};
+use std::collections::HashMap;
+
+/// The bytes of a single TS packet
+pub type PacketBytes = [u8; Packet::SIZE];
+
+/// the sync byte, PID, flags and continuity_counter
+const HEADER_SIZE: usize = 4;
+/// Marks the packet as carrying only payload, with no adaptation field
+const PAYLOAD_ONLY: u8 = 0b0001_0000;
+const STUFFING_BYTE: u8 = 0xff;
+
+/// Divides sections into TS packets, keeping track of the `continuity_counter` of each PID.
+#[derive(Debug, Default)]
+pub struct Packetizer {
+    /// the `continuity_counter` value for the next packet on each PID
+    continuity_counters: HashMap<Pid, u8>,
+}
+impl Packetizer {
+    /// Creates a packetizer for which the first packet on every PID will have a
+    /// `continuity_counter` of `0`.
+    pub fn new() -> Packetizer {
+        Self::default()
+    }
+
+    /// Produces the packets carrying the given section on the given PID.
+    ///
+    /// The section is not checked, and should be complete, from `table_id` through to
+    /// `CRC_32`.
+    pub fn packetize(&mut self, pid: Pid, section: &[u8]) -> Vec<PacketBytes> {
+        let counter = self.continuity_counters.entry(pid).or_insert(0);
+        let pid = u16::from(pid);
+        let mut packets = vec![];
+        let mut payload = section;
+        let mut first = true;
+        while first || !payload.is_empty() {
+            let mut pk = [STUFFING_BYTE; Packet::SIZE];
+            pk[0] = Packet::SYNC_BYTE;
+            pk[1] = if first { 0x40 } else { 0 } | (pid >> 8) as u8;
+            pk[2] = pid as u8;
+            pk[3] = PAYLOAD_ONLY | *counter;
+            let mut pos = HEADER_SIZE;
+            if first {
+                // pointer_field
+                pk[pos] = 0;
+                pos += 1;
+            }
+            let len = payload.len().min(Packet::SIZE - pos);
+            pk[pos..pos + len].copy_from_slice(&payload[..len]);
+            payload = &payload[len..];
+            packets.push(pk);
+            *counter = (*counter + 1) & 0xf;
+            first = false;
+        }
+        packets
+    }
+}
+
+/// A `SectionSink` which packetizes each section onto a single PID, passing each resulting
+/// packet in turn to the given function.
+///
+/// ```
+/// # use mpeg2ts_reader::packet::Pid;
+/// # use scte35_reader::inject::SectionSink;
+/// # use scte35_reader::packetize::PacketizingSink;
+/// # let section = vec![0xfc; 200];
+/// let mut packets = 0;
+/// let mut sink = PacketizingSink::new(Pid::new(0x101), |_pk: &[u8]| packets += 1);
+/// sink.emit(&section);
+/// drop(sink);
+/// assert_eq!(packets, 2);
+/// ```
+pub struct PacketizingSink<F: FnMut(&[u8])> {
+    packetizer: Packetizer,
+    pid: Pid,
+    out: F,
+}
+impl<F: FnMut(&[u8])> PacketizingSink<F> {
+    pub fn new(pid: Pid, out: F) -> PacketizingSink<F> {
+        PacketizingSink {
+            packetizer: Packetizer::new(),
+            pid,
+            out,
+        }
+    }
+}
+impl<F: FnMut(&[u8])> SectionSink for PacketizingSink<F> {
+    fn emit(&mut self, section: &[u8]) {
+        for pk in self.packetizer.packetize(self.pid, section) {
+            (self.out)(&pk);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cue;
+    use crate::test_util::*;
+    use hex_literal::*;
+
+    #[test]
+    fn round_trip() {
+        let pat = with_crc(hex!("00b00d 0001 c1 00 00 0001e100").to_vec());
+        let pmt = with_crc(hex!("02b018 0001 c1 00 00 e1fff006 0504 43554549 86e101f000").to_vec());
+        // a time_signal() with twenty avail_descriptors, needing two packets
+        let mut long = hex!("fc30de 00 0000000000 00 fff005 06 fe00000000 00c8").to_vec();
+        for id in 0..20u32 {
+            long.extend_from_slice(&hex!("0008 43554549"));
+            long.extend_from_slice(&id.to_be_bytes());
+        }
+        let long = with_crc(long);
+
+        let mut packetizer = Packetizer::new();
+        let mut packets = vec![];
+        packets.extend(packetizer.packetize(Pid::PAT, &pat));
+        packets.extend(packetizer.packetize(Pid::new(0x100), &pmt));
+        packets.extend(packetizer.packetize(Pid::new(0x101), &long));
+        packets.extend(packetizer.packetize(Pid::new(0x101), &SPLICE_NULL));
+        assert_eq!(packets.len(), 5);
+        let pid_101: Vec<_> = packets[2..]
+            .iter()
+            .map(|pk| Packet::new(pk))
+            .map(|pk| {
+                (
+                    pk.payload_unit_start_indicator(),
+                    pk.continuity_counter().count(),
+                )
+            })
+            .collect();
+        assert_eq!(pid_101, [(true, 0), (false, 1), (true, 2)]);
+        // stuffing follows the 42 bytes of the section which did not fit in the first packet
+        assert!(packets[3][HEADER_SIZE + 42..]
+            .iter()
+            .all(|b| *b == STUFFING_BYTE));
+
+        let cues: Vec<_> = cue::cues(&packets).map(Result::unwrap).collect();
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].bytes, long);
+        assert_eq!(cues[0].section.splice_descriptors.len(), 20);
+        assert_eq!(cues[1].bytes, SPLICE_NULL);
+    }
+
+    #[test]
+    fn continuity_counter_wraps() {
+        let mut packetizer = Packetizer::new();
+        let section = [0xfc; 10];
+        let counters: Vec<_> = (0..17)
+            .map(|_| packetizer.packetize(Pid::new(0x101), &section)[0][3] & 0xf)
+            .collect();
+        assert_eq!(counters[15..], [15, 0]);
+        assert_eq!(
+            packetizer.packetize(Pid::new(0x102), &section)[0][3] & 0xf,
+            0
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! One-call constructors of complete sections for the most common ad-insertion and
+//! content-identification patterns, built on the types of the [`builder`](../builder/index.html)
+//! module.
+//!
+//! Each produces an immediate cue, with no PTS adjustment, no encryption and the 'all tiers'
+//! tier value; where more control is needed, use the builders directly.
+//!
+//! ## Example
+//!
+//! ```
+//! # use scte35_reader::presets;
+//! # use std::time::Duration;
+//! let out = presets::cue_out(Duration::from_secs(30), 1).unwrap();
+//! let data = out.encode().unwrap();
+//! // ...and when the break is over,
+//! let data = presets::cue_in(1).encode().unwrap();
+//! ```
+
+use crate::builder::{
+    BuildError, SegmentationDescriptorBuilder, SpliceInsertBuilder, TimeSignalBuilder,
+};
+use crate::{time, ReturnMode, SegmentationTypeId, SegmentationUpid, SpliceInfoSection};
+use std::time::Duration;
+
+/// An immediate `splice_insert()` out of the network, to the start of a break of the given
+/// duration at whose end the splicer returns to the network automatically.  Fails if the
+/// duration is too long for the 33-bit `break_duration` field.
+pub fn cue_out(duration: Duration, splice_event_id: u32) -> Result<SpliceInfoSection, BuildError> {
+    let ticks = time::from_duration(duration, time::TickField::Bits33, time::Rounding::Nearest)
+        .ok_or(BuildError::FieldOutOfRange {
+            field_name: "break_duration.duration",
+            value: duration.as_secs().saturating_mul(90_000),
+        })?;
+    SpliceInsertBuilder::new(splice_event_id)
+        .out_of_network()
+        .break_duration(ReturnMode::Automatic, ticks)
+        .build_section()
+}
+
+/// An immediate `splice_insert()` back into the network, ending the break signalled by a
+/// `cue_out()` with the same `splice_event_id`.
+pub fn cue_in(splice_event_id: u32) -> SpliceInfoSection {
+    SpliceInsertBuilder::new(splice_event_id)
+        .return_to_network()
+        .build_section()
+        .expect("immediate splice_insert() without break_duration is always valid")
+}
+
+/// An immediate `time_signal()` with a _Chapter Start_ segmentation descriptor identifying the
+/// chapter by the given UPID.  Fails if the UPID is too long for its length field.
+pub fn chapter_start(
+    upid: SegmentationUpid,
+    segmentation_event_id: u32,
+) -> Result<SpliceInfoSection, BuildError> {
+    segmentation(
+        SegmentationTypeId::ChapterStart,
+        upid,
+        segmentation_event_id,
+    )
+}
+
+/// An immediate `time_signal()` with a _Chapter End_ segmentation descriptor, ending the chapter
+/// signalled by a `chapter_start()` with the same UPID and `segmentation_event_id`.
+pub fn chapter_end(
+    upid: SegmentationUpid,
+    segmentation_event_id: u32,
+) -> Result<SpliceInfoSection, BuildError> {
+    segmentation(SegmentationTypeId::ChapterEnd, upid, segmentation_event_id)
+}
+
+fn segmentation(
+    segmentation_type_id: SegmentationTypeId,
+    upid: SegmentationUpid,
+    segmentation_event_id: u32,
+) -> Result<SpliceInfoSection, BuildError> {
+    TimeSignalBuilder::new()
+        .segmentation(
+            SegmentationDescriptorBuilder::new(segmentation_event_id, segmentation_type_id)
+                .upid(upid),
+        )
+        .build_section()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        upid, NetworkIndicator, SegmentationDescriptor, SpliceCommand, SpliceDescriptor,
+        SpliceInsert, SpliceMode, SpliceTime,
+    };
+    use matches::*;
+
+    #[test]
+    fn cue_out_in() {
+        let data = cue_out(Duration::from_secs(30), 7)
+            .unwrap()
+            .encode()
+            .unwrap();
+        let section = SpliceInfoSection::parse(&data).unwrap();
+        assert_matches!(
+            section.splice_command,
+            SpliceCommand::SpliceInsert {
+                splice_event_id: 7,
+                splice_detail: SpliceInsert::Insert {
+                    network_indicator: NetworkIndicator::Out,
+                    splice_mode: SpliceMode::Program(SpliceTime::Immediate),
+                    duration: Some(ref d),
+                    ..
+                },
+                ..
+            } if d.duration() == 2_700_000 && std::matches!(d.return_mode(), ReturnMode::Automatic)
+        );
+        assert!(cue_out(Duration::from_secs(100_000), 7).is_err());
+
+        let data = cue_in(7).encode().unwrap();
+        let section = SpliceInfoSection::parse(&data).unwrap();
+        assert_matches!(
+            section.splice_command,
+            SpliceCommand::SpliceInsert {
+                splice_event_id: 7,
+                splice_detail: SpliceInsert::Insert {
+                    network_indicator: NetworkIndicator::In,
+                    duration: None,
+                    ..
+                },
+                ..
+            }
+        );
+    }
+
+    #[test]
+    fn chapters() {
+        let id = || SegmentationUpid::AdID(upid::AdID("ABCD01234567".to_string()));
+        for (section, type_id) in [
+            (chapter_start(id(), 3).unwrap(), 0x20),
+            (chapter_end(id(), 3).unwrap(), 0x21),
+        ] {
+            let data = section.encode().unwrap();
+            let section = SpliceInfoSection::parse(&data).unwrap();
+            assert_matches!(
+                section.splice_command,
+                SpliceCommand::TimeSignal {
+                    splice_time: SpliceTime::Timed(None)
+                }
+            );
+            assert_matches!(
+                &section.splice_descriptors[..],
+                [SpliceDescriptor::SegmentationDescriptor {
+                    segmentation_event_id: 3,
+                    descriptor_detail: SegmentationDescriptor::Insert {
+                        segmentation_upid: SegmentationUpid::AdID(_),
+                        segmentation_type_id,
+                        ..
+                    },
+                }] if segmentation_type_id.id() == type_id
+            );
+        }
+    }
+}
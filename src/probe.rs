@@ -0,0 +1,147 @@
+//! Summarises the SCTE-35 content of a whole Transport Stream, in the manner of a 'mediainfo for
+//! cues'.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! let file = std::fs::File::open("capture.ts").unwrap();
+//! let summary = scte35_reader::probe::probe(std::io::BufReader::new(file)).unwrap();
+//! println!("{} SCTE-35 PIDs", summary.pids.len());
+//! for (command, count) in &summary.command_counts {
+//!     println!("{}: {}", command, count);
+//! }
+//! ```
+
+use crate::cue::{scan_reader, Cue, ScanError};
+use crate::{time, Scte35Error, SegmentationDescriptor, SpliceCommand, SpliceDescriptor};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+
+/// The result of `probe()`
+#[derive(Debug, Default, serde_derive::Serialize)]
+pub struct ProbeSummary {
+    /// The PIDs on which cues were found
+    pub pids: BTreeSet<u16>,
+    /// The number of cues seen, keyed by _splice-command_ name (e.g. `"TimeSignal"`)
+    pub command_counts: BTreeMap<&'static str, usize>,
+    /// The number of `segmentation_descriptor()` instances seen, keyed by the name of their
+    /// `segmentation_type_id` (e.g. `"ProgramStart"`)
+    pub segmentation_type_counts: BTreeMap<String, usize>,
+    /// The earliest splice PTS signalled by any cue (with `pts_adjustment` applied)
+    pub first_splice_pts: Option<u64>,
+    /// The latest splice PTS signalled by any cue (with `pts_adjustment` applied)
+    pub last_splice_pts: Option<u64>,
+    /// The number of sections that could not be decoded because they are encrypted
+    pub encrypted_sections: usize,
+    /// Descriptions of the problems encountered with any sections which failed to parse
+    pub warnings: Vec<String>,
+}
+impl ProbeSummary {
+    fn add(&mut self, cue: Cue) {
+        self.pids.insert(u16::from(cue.stream.elementary_pid));
+        *self
+            .command_counts
+            .entry(command_name(&cue.section.splice_command))
+            .or_insert(0) += 1;
+        for desc in &cue.section.splice_descriptors {
+            if let SpliceDescriptor::SegmentationDescriptor {
+                descriptor_detail:
+                    SegmentationDescriptor::Insert {
+                        segmentation_type_id,
+                        ..
+                    },
+                ..
+            } = desc
+            {
+                *self
+                    .segmentation_type_counts
+                    .entry(format!("{:?}", segmentation_type_id))
+                    .or_insert(0) += 1;
+            }
+        }
+        if let Some(pts) = cue.section.splice_pts() {
+            self.first_splice_pts = match self.first_splice_pts {
+                Some(first) if time::cmp_pts(first, pts).is_le() => Some(first),
+                _ => Some(pts),
+            };
+            self.last_splice_pts = match self.last_splice_pts {
+                Some(last) if time::cmp_pts(last, pts).is_ge() => Some(last),
+                _ => Some(pts),
+            };
+        }
+    }
+}
+
+fn command_name(command: &SpliceCommand) -> &'static str {
+    match command {
+        SpliceCommand::SpliceNull { .. } => "SpliceNull",
+        SpliceCommand::SpliceSchedule { .. } => "SpliceSchedule",
+        SpliceCommand::SpliceInsert { .. } => "SpliceInsert",
+        SpliceCommand::TimeSignal { .. } => "TimeSignal",
+        SpliceCommand::BandwidthReservation { .. } => "BandwidthReservation",
+        SpliceCommand::PrivateCommand { .. } => "PrivateCommand",
+        SpliceCommand::Reserved { .. } => "Reserved",
+    }
+}
+
+/// Reads a Transport Stream from the given reader through to its end, and summarises the cues
+/// found within it.
+///
+/// Any trailing partial packet at the end of the input is ignored.
+pub fn probe<R: io::Read>(reader: R) -> io::Result<ProbeSummary> {
+    let mut summary = ProbeSummary::default();
+    for cue in scan_reader(reader) {
+        match cue {
+            Ok(cue) => summary.add(cue),
+            Err(ScanError::Section(Scte35Error::Encrypted)) => summary.encrypted_sections += 1,
+            Err(ScanError::Section(e)) => summary.warnings.push(format!("{:?}", e)),
+            Err(ScanError::Io(e)) => return Err(e),
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use hex_literal::*;
+
+    /// wraps a complete section in a single TS packet, after a `pointer_field` of `0`
+    fn section_packet(pid: u16, cc: u8, section: &[u8]) -> Vec<u8> {
+        let mut pk = vec![0x47, 0x40 | (pid >> 8) as u8, pid as u8, 0x10 | cc, 0x00];
+        pk.extend_from_slice(section);
+        pk.resize(188, 0xff);
+        pk
+    }
+
+    #[test]
+    fn summary() {
+        let pat = with_crc(hex!("00b00d 0001 c1 00 00 0001e100").to_vec());
+        let pmt = with_crc(hex!("02b018 0001 c1 00 00 e1fff006 0504 43554549 86e101f000").to_vec());
+        let mut encrypted = SPLICE_NULL.to_vec();
+        encrypted[4] |= 0x80;
+        let encrypted = with_crc(encrypted[..encrypted.len() - 4].to_vec());
+
+        let mut ts = vec![];
+        ts.extend(section_packet(0, 0, &pat));
+        ts.extend(section_packet(0x100, 0, &pmt));
+        ts.extend(section_packet(0x101, 0, &SPLICE_INSERT));
+        ts.extend(section_packet(0x101, 1, &SPLICE_NULL));
+        ts.extend(section_packet(0x101, 2, &encrypted));
+        // partial trailing packet
+        ts.extend_from_slice(&[0x47, 0x00]);
+
+        let summary = probe(&ts[..]).unwrap();
+        assert_eq!(
+            summary.pids.iter().copied().collect::<Vec<_>>(),
+            vec![0x101]
+        );
+        assert_eq!(summary.command_counts["SpliceInsert"], 1);
+        assert_eq!(summary.command_counts["SpliceNull"], 1);
+        assert_eq!(summary.first_splice_pts, Some(0x0_2d14_2b00));
+        assert_eq!(summary.last_splice_pts, Some(0x0_2d14_2b00));
+        assert_eq!(summary.encrypted_sections, 1);
+        assert!(summary.warnings.is_empty());
+    }
+}
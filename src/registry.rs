@@ -0,0 +1,265 @@
+//! Application-supplied parsers for proprietary splice descriptors, which would otherwise be
+//! reported as `SpliceDescriptor::Reserved`.
+//!
+//! Parsers are registered in a `DescriptorRegistry` against the `identifier` and
+//! `splice_descriptor_tag` of the descriptors they handle, and the registry is then given to
+//! `SpliceDescriptors::parse_with()`.  Descriptors for which no parser is registered are parsed
+//! by the built-in parsers as usual, except that descriptors with an `identifier` other than
+//! `CUEI` are first offered to any fallback given to `DescriptorRegistry::set_fallback()`,
+//! before being reported as `SpliceDescriptor::Reserved`.
+//!
+//! ## Example
+//!
+//! ```
+//! # use scte35_reader::registry::{Descriptor, DescriptorRegistry};
+//! # use scte35_reader::{SpliceDescriptorErr, SpliceDescriptors};
+//! #[derive(Debug)]
+//! struct AcmeRating(u8);
+//!
+//! let mut registry = DescriptorRegistry::new();
+//! registry.register(*b"ACME", 0x01, |payload: &[u8]| match payload {
+//!     [rating] => Ok(AcmeRating(*rating)),
+//!     _ => Err(SpliceDescriptorErr::InvalidDescriptorLength(payload.len() + 4)),
+//! });
+//!
+//! fn ratings(descriptors: &SpliceDescriptors<'_>, registry: &DescriptorRegistry<AcmeRating>) {
+//!     for desc in descriptors.parse_with(registry) {
+//!         match desc {
+//!             Ok(Descriptor::Custom(rating)) => println!("{:?}", rating),
+//!             Ok(Descriptor::Standard(desc)) => println!("{:?}", desc),
+//!             Err(e) => println!("{:?}", e),
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::{SpliceDescriptor, SpliceDescriptorErr, SpliceDescriptorIter};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Parses the bytes following the `identifier` of a descriptor into an application type
+pub type DescriptorParser<T> = dyn Fn(&[u8]) -> Result<T, SpliceDescriptorErr> + Send + Sync;
+
+/// Given the `identifier`, `splice_descriptor_tag` and the bytes following the `identifier` of a
+/// descriptor, returns `None` if the descriptor is not one the application handles
+pub type FallbackParser<T> =
+    dyn Fn([u8; 4], u8, &[u8]) -> Option<Result<T, SpliceDescriptorErr>> + Send + Sync;
+
+/// A descriptor as parsed with the help of a `DescriptorRegistry`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Descriptor<T> {
+    /// A descriptor which no registered parser (or fallback) handled, parsed by the built-in
+    /// parsers
+    Standard(SpliceDescriptor),
+    /// The value produced by the parser registered for the descriptor's `identifier` and tag
+    Custom(T),
+}
+
+/// Parsers for descriptors with particular `identifier` and `splice_descriptor_tag` values,
+/// producing values of the application's type `T`.
+pub struct DescriptorRegistry<T> {
+    parsers: HashMap<([u8; 4], u8), Box<DescriptorParser<T>>>,
+    fallback: Option<Box<FallbackParser<T>>>,
+}
+impl<T> Default for DescriptorRegistry<T> {
+    fn default() -> Self {
+        DescriptorRegistry {
+            parsers: HashMap::new(),
+            fallback: None,
+        }
+    }
+}
+impl<T> DescriptorRegistry<T> {
+    pub fn new() -> DescriptorRegistry<T> {
+        Self::default()
+    }
+
+    /// Adds or replaces the parser for descriptors with the given `identifier` and
+    /// `splice_descriptor_tag`.  A parser registered for a `CUEI` tag takes the place of the
+    /// built-in parser for that tag.
+    pub fn register<F>(&mut self, identifier: [u8; 4], splice_descriptor_tag: u8, parser: F)
+    where
+        F: Fn(&[u8]) -> Result<T, SpliceDescriptorErr> + Send + Sync + 'static,
+    {
+        self.parsers
+            .insert((identifier, splice_descriptor_tag), Box::new(parser));
+    }
+
+    /// Sets the parser consulted for descriptors whose `identifier` is not `CUEI`, and for
+    /// which no parser is registered, replacing any previous fallback.  Descriptors for which
+    /// the fallback returns `None` are reported as `SpliceDescriptor::Reserved`.
+    pub fn set_fallback<F>(&mut self, fallback: F)
+    where
+        F: Fn([u8; 4], u8, &[u8]) -> Option<Result<T, SpliceDescriptorErr>> + Send + Sync + 'static,
+    {
+        self.fallback = Some(Box::new(fallback));
+    }
+
+    pub fn is_registered(&self, identifier: [u8; 4], splice_descriptor_tag: u8) -> bool {
+        self.parsers
+            .contains_key(&(identifier, splice_descriptor_tag))
+    }
+
+    /// Parses a single descriptor, including its tag and length
+    fn parse(
+        &self,
+        data: &[u8],
+        diagnostics: &dyn crate::Scte35Diagnostics,
+    ) -> Result<Descriptor<T>, SpliceDescriptorErr> {
+        // leave descriptors too short to hold an identifier for the built-in parser to report
+        if data.len() >= 6 && data[1] >= 4 {
+            let identifier = [data[2], data[3], data[4], data[5]];
+            if let Some(parser) = self.parsers.get(&(identifier, data[0])) {
+                return parser(&data[6..]).map(Descriptor::Custom);
+            }
+            if &identifier != b"CUEI" {
+                if let Some(result) = self
+                    .fallback
+                    .as_ref()
+                    .and_then(|fallback| fallback(identifier, data[0], &data[6..]))
+                {
+                    return result.map(Descriptor::Custom);
+                }
+            }
+        }
+        SpliceDescriptor::parse(data, diagnostics).map(Descriptor::Standard)
+    }
+}
+impl<T> fmt::Debug for DescriptorRegistry<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parsers: Vec<_> = self
+            .parsers
+            .keys()
+            .map(|(id, tag)| (String::from_utf8_lossy(id), tag))
+            .collect();
+        f.debug_struct("DescriptorRegistry")
+            .field("parsers", &parsers)
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+/// Iterator over the descriptors of a loop, returned by `SpliceDescriptors::parse_with()`
+pub struct RegisteredDescriptors<'buf, 'reg, T> {
+    pub(crate) iter: SpliceDescriptorIter<'buf>,
+    pub(crate) registry: &'reg DescriptorRegistry<T>,
+}
+impl<'buf, 'reg, T> Iterator for RegisteredDescriptors<'buf, 'reg, T> {
+    type Item = Result<Descriptor<T>, SpliceDescriptorErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let diagnostics = self.iter.diagnostics;
+        let offset = self.iter.offset();
+        let (_, result) = self.iter.next_bytes()?;
+        Some(result.and_then(|desc| {
+            self.registry
+                .parse(desc, diagnostics)
+                .map_err(|e| e.in_descriptor(desc[0], offset))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{LogDiagnostics, SpliceDescriptors};
+    use hex_literal::*;
+    use matches::*;
+
+    #[derive(Debug)]
+    struct Acme(u16);
+
+    fn registry() -> DescriptorRegistry<Acme> {
+        let mut registry = DescriptorRegistry::new();
+        registry.register(*b"ACME", 0x01, |payload: &[u8]| match payload {
+            [a, b] => Ok(Acme(u16::from_be_bytes([*a, *b]))),
+            _ => Err(SpliceDescriptorErr::InvalidDescriptorLength(
+                payload.len() + 4,
+            )),
+        });
+        registry
+    }
+
+    #[test]
+    fn custom_descriptors() {
+        let data = hex!(
+            "00084355454900000135"
+            "010641434d451234"
+            "020541434d45ff"
+            "010741434d45123456"
+        );
+        let registry = registry();
+        assert!(registry.is_registered(*b"ACME", 0x01));
+        assert!(!registry.is_registered(*b"ACME", 0x02));
+        let descriptors = SpliceDescriptors {
+            buf: &data[..],
+            diagnostics: &LogDiagnostics,
+        };
+        let mut iter = descriptors.parse_with(&registry);
+        assert_matches!(
+            iter.next(),
+            Some(Ok(Descriptor::Standard(
+                SpliceDescriptor::AvailDescriptor {
+                    provider_avail_id: 0x135
+                }
+            )))
+        );
+        assert_matches!(iter.next(), Some(Ok(Descriptor::Custom(Acme(0x1234)))));
+        assert_matches!(
+            iter.next(),
+            Some(Ok(Descriptor::Standard(SpliceDescriptor::Reserved {
+                tag: 0x02,
+                identifier: [b'A', b'C', b'M', b'E'],
+                ..
+            })))
+        );
+        assert_matches!(
+            iter.next().unwrap().unwrap_err(),
+            SpliceDescriptorErr::Descriptor {
+                splice_descriptor_tag: 0x01,
+                offset: 25,
+                ref error,
+            } if std::matches!(**error, SpliceDescriptorErr::InvalidDescriptorLength(7))
+        );
+        assert_matches!(iter.next(), None);
+    }
+
+    #[test]
+    fn fallback() {
+        let mut registry = registry();
+        registry.set_fallback(|identifier, tag, payload| match &identifier {
+            b"ACME" | b"CUEI" => Some(Ok(Acme(u16::from(tag) << 8 | payload.len() as u16))),
+            _ => None,
+        });
+        let data = hex!(
+            "010641434d451234"
+            "020541434d45ff"
+            "0205454d4341ff"
+            "07054355454900"
+        );
+        let descriptors = SpliceDescriptors {
+            buf: &data[..],
+            diagnostics: &LogDiagnostics,
+        };
+        let mut iter = descriptors.parse_with(&registry);
+        // the registered parser takes precedence over the fallback
+        assert_matches!(iter.next(), Some(Ok(Descriptor::Custom(Acme(0x1234)))));
+        assert_matches!(iter.next(), Some(Ok(Descriptor::Custom(Acme(0x0201)))));
+        assert_matches!(
+            iter.next(),
+            Some(Ok(Descriptor::Standard(SpliceDescriptor::Reserved {
+                tag: 0x02,
+                ..
+            })))
+        );
+        // CUEI descriptors are never offered to the fallback
+        assert_matches!(
+            iter.next(),
+            Some(Ok(Descriptor::Standard(SpliceDescriptor::Reserved {
+                tag: 0x07,
+                ..
+            })))
+        );
+        assert_matches!(iter.next(), None);
+    }
+}
@@ -0,0 +1,151 @@
+//! Re-emits previously captured cues with their original relative timing, for soak-testing
+//! downstream equipment without access to the original live feed.
+//!
+//! Available when this crate's `replay` feature is enabled.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! # use mpeg2ts_reader::packet::Pid;
+//! # use scte35_reader::replay::{replay, CapturedCue, UdpSink};
+//! # use std::time::Duration;
+//! # let section = vec![];
+//! let captured = vec![
+//!     CapturedCue { arrival: Duration::from_secs(0), section: section.clone() },
+//!     CapturedCue { arrival: Duration::from_secs(30), section },
+//! ];
+//! let mut sink = UdpSink::connect("127.0.0.1:5000", Pid::new(0x101)).unwrap();
+//! replay(captured, &mut sink);
+//! ```
+
+use crate::inject::SectionSink;
+use crate::packetize::Packetizer;
+use mpeg2ts_reader::packet::Pid;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A section captured from a stream, together with the time at which it arrived relative to the
+/// start of the capture (derived, for example, from the difference between the PCR at the time
+/// of arrival and the first PCR of the capture).
+#[derive(Debug, Clone)]
+pub struct CapturedCue {
+    pub arrival: Duration,
+    pub section: Vec<u8>,
+}
+
+/// Passes each of the given cues to the sink in turn, blocking the calling thread so that the
+/// intervals between them match the intervals between their original `arrival` times.
+///
+/// Cues are expected in order of `arrival`; any cue whose time has already passed is emitted
+/// immediately.
+pub fn replay<I, S>(cues: I, sink: &mut S)
+where
+    I: IntoIterator<Item = CapturedCue>,
+    S: SectionSink,
+{
+    let start = Instant::now();
+    for cue in cues {
+        let due = start + cue.arrival;
+        let now = Instant::now();
+        if due > now {
+            thread::sleep(due - now);
+        }
+        sink.emit(&cue.section);
+    }
+}
+
+/// The number of TS packets sent in each UDP datagram, as is usual for Transport Stream over
+/// UDP
+const PACKETS_PER_DATAGRAM: usize = 7;
+
+/// A `SectionSink` which divides each section into TS packets on a single PID (using a
+/// `packetize::Packetizer`), and sends those packets over UDP, up to seven to a datagram.
+///
+/// Send failures are logged, and do not interrupt the replay.
+pub struct UdpSink {
+    socket: UdpSocket,
+    packetizer: Packetizer,
+    pid: Pid,
+}
+impl UdpSink {
+    /// Creates a sink sending the packets of the given PID to the given address, from an
+    /// ephemeral local port on the unspecified address of the same family (IPv4 or IPv6).
+    pub fn connect<A: ToSocketAddrs>(addr: A, pid: Pid) -> io::Result<UdpSink> {
+        let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no address to connect to")
+        })?;
+        let local = match addr {
+            SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+            SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(addr)?;
+        Ok(UdpSink::from_socket(socket, pid))
+    }
+
+    /// Creates a sink sending the packets of the given PID using an already-connected socket
+    pub fn from_socket(socket: UdpSocket, pid: Pid) -> UdpSink {
+        UdpSink {
+            socket,
+            packetizer: Packetizer::new(),
+            pid,
+        }
+    }
+}
+impl SectionSink for UdpSink {
+    fn emit(&mut self, section: &[u8]) {
+        let packets = self.packetizer.packetize(self.pid, section);
+        for chunk in packets.chunks(PACKETS_PER_DATAGRAM) {
+            let datagram: Vec<u8> = chunk.iter().flatten().copied().collect();
+            if let Err(e) = self.socket.send(&datagram) {
+                error!("failed to send {} bytes: {}", datagram.len(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use mpeg2ts_reader::packet::Packet;
+
+    #[test]
+    fn preserves_timing() {
+        let cues = vec![
+            CapturedCue {
+                arrival: Duration::from_millis(0),
+                section: vec![1],
+            },
+            CapturedCue {
+                arrival: Duration::from_millis(50),
+                section: vec![2],
+            },
+        ];
+        let start = Instant::now();
+        let mut emitted = vec![];
+        replay(cues, &mut |section: &[u8]| {
+            emitted.push((section[0], start.elapsed()))
+        });
+        assert_eq!(emitted.len(), 2);
+        assert!(emitted[1].1 >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn udp() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut sink = UdpSink::connect(receiver.local_addr().unwrap(), Pid::new(0x101)).unwrap();
+        replay(
+            vec![CapturedCue {
+                arrival: Duration::ZERO,
+                section: vec![0xfc, 0x30],
+            }],
+            &mut sink,
+        );
+        let mut buf = [0; PACKETS_PER_DATAGRAM * Packet::SIZE];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(len, Packet::SIZE);
+        assert_eq!(&buf[..7], &[0x47, 0x41, 0x01, 0x10, 0x00, 0xfc, 0x30]);
+    }
+}
@@ -0,0 +1,462 @@
+//! [proptest](https://docs.rs/proptest) strategies generating valid cues, for property tests
+//! within this crate and in downstream crates.
+//!
+//! Available when this crate's `proptest` feature is enabled.
+//!
+//! As with the `arbitrary::Arbitrary` implementations of the `arbitrary` feature, every value
+//! generated is one that `SpliceInfoSection::encode()` accepts and that parses back to an
+//! equivalent value, so that properties such as encode→parse→encode stability can be asserted
+//! of any value produced.  Lengths are bounded so that a generated section always fits within
+//! `MAX_SECTION_LENGTH`.
+//!
+//! ## Example
+//!
+//! ```
+//! # use proptest::prelude::*;
+//! # use scte35_reader::SpliceInfoSection;
+//! proptest! {
+//!     fn round_trip(section in scte35_reader::strategy::splice_info_section()) {
+//!         let encoded = section.encode().unwrap();
+//!         prop_assert_eq!(SpliceInfoSection::parse(&encoded).unwrap().encode().unwrap(), encoded);
+//!     }
+//! }
+//! # round_trip();
+//! ```
+
+use crate::{
+    time, upid, AudioComponent, ComponentSplice, DeliveryRestrictionFlags, DeviceRestrictions,
+    EncryptionAlgorithm, NetworkIndicator, ReturnMode, SapType, ScheduledComponentSplice,
+    ScheduledSplice, ScheduledSpliceMode, SegmentationDescriptor, SegmentationMode,
+    SegmentationModeComponent, SegmentationTypeId, SegmentationUpid, SegmentationUpidType,
+    SpliceCommand, SpliceDescriptor, SpliceDuration, SpliceInfoSection, SpliceInsert, SpliceMode,
+    SpliceScheduleEvent, SpliceTime, SubSegments, Tier, UtcSpliceTime,
+};
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+/// The most bytes generated for any variable-length field
+const MAX_BYTES: usize = 32;
+/// The most entries generated for any loop (of events, components or descriptors)
+const MAX_ENTRIES: usize = 8;
+
+/// Un-encrypted sections, since encrypted sections can not be encoded directly
+pub fn splice_info_section() -> impl Strategy<Value = SpliceInfoSection> {
+    (
+        (0..4u8).prop_map(SapType::from_id),
+        bits33(),
+        any::<u8>(),
+        tier(),
+        splice_command(),
+        entries(splice_descriptor()),
+    )
+        .prop_map(
+            |(sap_type, pts_adjustment, cw_index, tier, splice_command, splice_descriptors)| {
+                SpliceInfoSection {
+                    sap_type,
+                    protocol_version: 0,
+                    encrypted_packet: false,
+                    encryption_algorithm: EncryptionAlgorithm::None,
+                    pts_adjustment,
+                    cw_index,
+                    tier,
+                    splice_command,
+                    splice_descriptors,
+                }
+            },
+        )
+}
+
+/// Any `tier` value
+pub fn tier() -> impl Strategy<Value = Tier> {
+    (0..=0xfffu16).prop_map(Tier)
+}
+
+/// Commands of every type, including reserved `splice_command_type` values
+pub fn splice_command() -> impl Strategy<Value = SpliceCommand> {
+    prop_oneof![
+        Just(SpliceCommand::SpliceNull {}),
+        entries(
+            (any::<u32>(), scheduled_splice()).prop_map(|(splice_event_id, splice_detail)| {
+                SpliceScheduleEvent {
+                    splice_event_id,
+                    splice_detail,
+                }
+            })
+        )
+        .prop_map(|events| SpliceCommand::SpliceSchedule { events }),
+        (any::<u32>(), 0..=0x7fu8, splice_insert()).prop_map(
+            |(splice_event_id, reserved, splice_detail)| SpliceCommand::SpliceInsert {
+                splice_event_id,
+                reserved,
+                splice_detail,
+            }
+        ),
+        option::of(bits33()).prop_map(|pts| SpliceCommand::TimeSignal {
+            splice_time: SpliceTime::Timed(pts),
+        }),
+        Just(SpliceCommand::BandwidthReservation {}),
+        (any::<[u8; 4]>(), bytes(0)).prop_map(|(identifier, private_bytes)| {
+            SpliceCommand::PrivateCommand {
+                identifier,
+                private_bytes,
+            }
+        }),
+        (
+            select(&[0x01u8, 0x02, 0x03, 0x08, 0x80, 0xfe][..]),
+            bytes(0)
+        )
+            .prop_map(|(command_type, payload)| SpliceCommand::Reserved {
+                command_type,
+                payload,
+            }),
+    ]
+}
+
+fn scheduled_splice() -> impl Strategy<Value = ScheduledSplice> {
+    let splice_mode = prop_oneof![
+        any::<u32>().prop_map(|t| ScheduledSpliceMode::Program(UtcSpliceTime(t))),
+        entries((any::<u8>(), any::<u32>()).prop_map(|(component_tag, t)| {
+            ScheduledComponentSplice {
+                component_tag,
+                utc_splice_time: UtcSpliceTime(t),
+            }
+        }))
+        .prop_map(ScheduledSpliceMode::Components),
+    ];
+    prop_oneof![
+        1 => Just(ScheduledSplice::Cancel),
+        7 => (
+            network_indicator(),
+            splice_mode,
+            option::of(splice_duration()),
+            any::<(u16, u8, u8)>(),
+        )
+            .prop_map(
+                |(
+                    network_indicator,
+                    splice_mode,
+                    duration,
+                    (unique_program_id, avail_num, avails_expected),
+                )| ScheduledSplice::Insert {
+                    network_indicator,
+                    splice_mode,
+                    duration,
+                    unique_program_id,
+                    avail_num,
+                    avails_expected,
+                }
+            ),
+    ]
+}
+
+fn splice_insert() -> impl Strategy<Value = SpliceInsert> {
+    // splice_immediate_flag applies to every component together
+    let splice_mode = any::<bool>().prop_flat_map(|immediate| {
+        let splice_time = move || {
+            if immediate {
+                Just(SpliceTime::Immediate).boxed()
+            } else {
+                option::of(bits33()).prop_map(SpliceTime::Timed).boxed()
+            }
+        };
+        prop_oneof![
+            splice_time().prop_map(SpliceMode::Program),
+            entries(
+                (any::<u8>(), splice_time()).prop_map(|(component_tag, splice_time)| {
+                    ComponentSplice {
+                        component_tag,
+                        splice_time,
+                    }
+                })
+            )
+            .prop_map(SpliceMode::Components),
+        ]
+    });
+    prop_oneof![
+        1 => Just(SpliceInsert::Cancel),
+        7 => (
+            network_indicator(),
+            splice_mode,
+            option::of(splice_duration()),
+            any::<(u16, u8, u8)>(),
+        )
+            .prop_map(
+                |(
+                    network_indicator,
+                    splice_mode,
+                    duration,
+                    (unique_program_id, avail_num, avails_expected),
+                )| SpliceInsert::Insert {
+                    network_indicator,
+                    splice_mode,
+                    duration,
+                    unique_program_id,
+                    avail_num,
+                    avails_expected,
+                }
+            ),
+    ]
+}
+
+fn network_indicator() -> impl Strategy<Value = NetworkIndicator> {
+    prop_oneof![Just(NetworkIndicator::Out), Just(NetworkIndicator::In)]
+}
+
+fn splice_duration() -> impl Strategy<Value = SpliceDuration> {
+    (
+        prop_oneof![Just(ReturnMode::Automatic), Just(ReturnMode::Manual)],
+        bits33(),
+    )
+        .prop_map(|(return_mode, duration)| SpliceDuration {
+            return_mode,
+            duration,
+        })
+}
+
+/// Descriptors of every kind, including private descriptors and CUEI descriptors with
+/// undefined tags
+pub fn splice_descriptor() -> impl Strategy<Value = SpliceDescriptor> {
+    prop_oneof![
+        any::<u32>()
+            .prop_map(|provider_avail_id| SpliceDescriptor::AvailDescriptor { provider_avail_id }),
+        (any::<u8>(), vec(select(&b"0123456789*#"[..]), 0..=7)).prop_map(
+            |(preroll, dtmf_chars)| SpliceDescriptor::DTMFDescriptor {
+                preroll,
+                dtmf_chars,
+            }
+        ),
+        (any::<u32>(), segmentation_descriptor()).prop_map(
+            |(segmentation_event_id, descriptor_detail)| {
+                SpliceDescriptor::SegmentationDescriptor {
+                    segmentation_event_id,
+                    descriptor_detail,
+                }
+            }
+        ),
+        (0..=(1u64 << 48) - 1, 0..=999_999_999u32, any::<u16>()).prop_map(
+            |(tai_seconds, tai_nanoseconds, utc_offset)| SpliceDescriptor::TimeDescriptor {
+                tai_seconds,
+                tai_nanoseconds,
+                utc_offset,
+            }
+        ),
+        vec(audio_component(), 0..=15)
+            .prop_map(|components| SpliceDescriptor::AudioDescriptor { components }),
+        // CUEI descriptors with tags above 0x04 are not defined, and any other identifier is
+        // for a private descriptor
+        (any::<[u8; 4]>(), any::<u8>(), bytes(0)).prop_map(|(identifier, tag, private_bytes)| {
+            let tag = if &identifier == b"CUEI" {
+                tag.max(0x05)
+            } else {
+                tag
+            };
+            SpliceDescriptor::Reserved {
+                tag,
+                identifier,
+                private_bytes,
+            }
+        }),
+    ]
+}
+
+fn audio_component() -> impl Strategy<Value = AudioComponent> {
+    (
+        any::<u8>(),
+        [b'a'..=b'z', b'a'..=b'z', b'a'..=b'z'],
+        0..=7u8,
+        0..=15u8,
+        any::<bool>(),
+    )
+        .prop_map(
+            |(component_tag, iso_code, bit_stream_mode, num_channels, full_srvc_audio)| {
+                AudioComponent {
+                    component_tag,
+                    iso_code,
+                    bit_stream_mode,
+                    num_channels,
+                    full_srvc_audio,
+                }
+            },
+        )
+}
+
+fn segmentation_descriptor() -> impl Strategy<Value = SegmentationDescriptor> {
+    let delivery_restrictions = prop_oneof![
+        Just(DeliveryRestrictionFlags::None),
+        (
+            any::<(bool, bool, bool)>(),
+            prop_oneof![
+                Just(DeviceRestrictions::RestrictGroup0),
+                Just(DeviceRestrictions::RestrictGroup1),
+                Just(DeviceRestrictions::RestrictGroup2),
+                Just(DeviceRestrictions::None),
+            ],
+        )
+            .prop_map(
+                |(
+                    (web_delivery_allowed_flag, no_regional_blackout_flag, archive_allowed_flag),
+                    device_restrictions,
+                )| DeliveryRestrictionFlags::DeliveryRestrictions {
+                    web_delivery_allowed_flag,
+                    no_regional_blackout_flag,
+                    archive_allowed_flag,
+                    device_restrictions,
+                }
+            ),
+    ];
+    let segmentation_mode = prop_oneof![
+        Just(SegmentationMode::Program),
+        entries(
+            (any::<u8>(), bits33()).prop_map(|(component_tag, pts_offset)| {
+                SegmentationModeComponent {
+                    component_tag,
+                    pts_offset,
+                }
+            })
+        )
+        .prop_map(|components| SegmentationMode::Component { components }),
+    ];
+    let insert = (
+        delivery_restrictions,
+        segmentation_mode,
+        option::of(0..=time::TickField::Bits40.max()),
+        segmentation_upid(),
+        any::<u8>().prop_map(SegmentationTypeId::from_id),
+        any::<(u8, u8)>(),
+        option::of(
+            any::<(u8, u8)>().prop_map(|(sub_segment_num, sub_segments_expected)| SubSegments {
+                sub_segment_num,
+                sub_segments_expected,
+            }),
+        ),
+    )
+        .prop_map(
+            |(
+                delivery_restrictions,
+                segmentation_mode,
+                segmentation_duration,
+                segmentation_upid,
+                segmentation_type_id,
+                (segment_num, segments_expected),
+                sub_segments,
+            )| SegmentationDescriptor::Insert {
+                // only some segmentation types carry the sub-segment fields
+                sub_segments: sub_segments.filter(|_| segmentation_type_id.has_sub_segments()),
+                program_segmentation_flag: matches!(segmentation_mode, SegmentationMode::Program),
+                segmentation_duration_flag: segmentation_duration.is_some(),
+                delivery_not_restricted_flag: matches!(
+                    delivery_restrictions,
+                    DeliveryRestrictionFlags::None
+                ),
+                delivery_restrictions,
+                segmentation_mode,
+                segmentation_duration,
+                segmentation_upid,
+                segmentation_type_id,
+                segment_num,
+                segments_expected,
+            },
+        );
+    prop_oneof![
+        1 => Just(SegmentationDescriptor::Cancel),
+        7 => insert,
+    ]
+}
+
+/// UPIDs of every type, including `MID` and reserved `segmentation_upid_type` values
+pub fn segmentation_upid() -> impl Strategy<Value = SegmentationUpid> {
+    // the UPIDs within a MID can not themselves be empty, and are not nested further; a handful
+    // keeps the whole within the 8-bit segmentation_upid_length
+    prop_oneof![
+        1 => Just(SegmentationUpid::None),
+        1 => vec(single_upid(), 1..=4).prop_map(SegmentationUpid::MID),
+        6 => single_upid(),
+    ]
+}
+
+/// Any single UPID other than `None` and `MID`
+fn single_upid() -> impl Strategy<Value = SegmentationUpid> {
+    prop_oneof![
+        bytes(1).prop_map(|b| SegmentationUpid::UserDefined(upid::UserDefinedDeprecated(b))),
+        patterned("AAAA9999").prop_map(|s| SegmentationUpid::Isci(upid::IsciDeprecated(s))),
+        patterned("AAAAXXXXXXXX").prop_map(|s| SegmentationUpid::AdID(upid::AdID(s))),
+        any::<[u8; 32]>().prop_map(|b| SegmentationUpid::Umid(upid::Umid(b.to_vec()))),
+        any::<[u8; 8]>().prop_map(|b| SegmentationUpid::IsanDeprecated(upid::IsanDeprecated(b))),
+        any::<[u8; 12]>().prop_map(|b| SegmentationUpid::Isan(upid::Isan(b))),
+        patterned("AA9999999999").prop_map(|s| SegmentationUpid::TID(upid::TID(s))),
+        any::<[u8; 8]>().prop_map(|b| SegmentationUpid::TI(upid::TI(b.to_vec()))),
+        alphanumeric(1..=MAX_BYTES).prop_map(|s| SegmentationUpid::ADI(upid::ADI(s))),
+        any::<[u8; 12]>().prop_map(|b| SegmentationUpid::EIDR(upid::EIDR(b))),
+        (any::<u16>(), 0..=23u8, 1..=511u16, bytes(0)).prop_map(
+            |(tsid, end_of_day, unique_for, content_id)| SegmentationUpid::ATSC(upid::ATSC {
+                tsid,
+                end_of_day,
+                unique_for,
+                content_id,
+            })
+        ),
+        // a format_identifier followed by the private_data
+        bytes(4).prop_map(|b| SegmentationUpid::MPU(upid::MPU(b))),
+        bytes(1).prop_map(|b| SegmentationUpid::ADS(upid::ADSInformation(b))),
+        alphanumeric(1..=MAX_BYTES).prop_map(|s| {
+            let url = format!("urn:example:{}", s);
+            SegmentationUpid::URI(upid::Url(url::Url::parse(&url).expect("valid URN")))
+        }),
+        any::<[u8; 16]>().prop_map(|b| SegmentationUpid::UUID(upid::Uuid(b))),
+        bytes(1).prop_map(|b| SegmentationUpid::SCR(upid::SCR(b))),
+        (18..=0xffu8, bytes(1))
+            .prop_map(|(t, b)| SegmentationUpid::Reserved(SegmentationUpidType::Reserved(t), b)),
+    ]
+}
+
+fn bits33() -> impl Strategy<Value = u64> {
+    0..=time::TickField::Bits33.max()
+}
+
+/// Between zero and `MAX_ENTRIES` values produced by the given strategy
+fn entries<S: Strategy>(s: S) -> impl Strategy<Value = Vec<S::Value>> {
+    vec(s, 0..=MAX_ENTRIES)
+}
+
+/// Between `min` and `MAX_BYTES` bytes
+fn bytes(min: usize) -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), min..=MAX_BYTES)
+}
+
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+fn alphanumeric(len: std::ops::RangeInclusive<usize>) -> impl Strategy<Value = String> {
+    vec(select(ALPHANUMERIC), len).prop_map(|chars| chars.into_iter().map(char::from).collect())
+}
+
+/// Strings following the given pattern, where `A` stands for a letter, `9` for a digit and `X`
+/// for either (as in the formats the spec gives for ISCI, Ad-ID and TID)
+fn patterned(pattern: &str) -> impl Strategy<Value = String> {
+    pattern
+        .chars()
+        .map(|p| {
+            select(match p {
+                'A' => &ALPHANUMERIC[10..],
+                '9' => &ALPHANUMERIC[..10],
+                _ => ALPHANUMERIC,
+            })
+        })
+        .collect::<Vec<_>>()
+        .prop_map(|chars| chars.into_iter().map(char::from).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn round_trip(section in splice_info_section()) {
+            let encoded = section.encode().unwrap();
+            let parsed = SpliceInfoSection::parse(&encoded).unwrap();
+            prop_assert_eq!(parsed.encode().unwrap(), encoded);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! An asynchronous [`Stream`](https://docs.rs/futures-core/*/futures_core/stream/trait.Stream.html)
+//! of the cues within a Transport Stream read from a tokio `AsyncRead`, such as a socket, so that
+//! async services need not bridge the callback-based `SpliceInfoProcessor` themselves.
+//!
+//! Available when this crate's `tokio` feature is enabled.
+//!
+//! ## Example
+//!
+//! Applications will usually consume the stream with the combinators of `futures::StreamExt`;
+//! the example below uses only `futures-core`.
+//!
+//! ```
+//! # use futures_core::Stream;
+//! # use std::pin::Pin;
+//! async fn print_cues(reader: impl tokio::io::AsyncRead + Unpin) {
+//!     let mut cues = scte35_reader::stream::cue_stream(reader);
+//!     while let Some(cue) = std::future::poll_fn(|cx| Pin::new(&mut cues).poll_next(cx)).await {
+//!         println!("{:?}", cue.map(|c| c.section.splice_command));
+//!     }
+//! }
+//! ```
+
+use crate::cue::{Cue, CueDemux, ScanError};
+use crate::CrcMode;
+use mpeg2ts_reader::packet;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Creates a `Stream` of the cues found within the Transport Stream read from the given reader,
+/// through to its end, in the manner of `cue::scan_reader()`.
+///
+/// Any trailing partial packet at the end of the input is ignored.
+pub fn cue_stream<R: AsyncRead + Unpin>(reader: R) -> CueStream<R> {
+    CueStream {
+        reader: Some(reader),
+        demux: CueDemux::new(CrcMode::default()),
+        pk: [0; packet::Packet::SIZE],
+        filled: 0,
+    }
+}
+
+/// The `Stream` of cues returned by [`cue_stream()`](fn.cue_stream.html).
+///
+/// Sections which fail to parse are produced as `ScanError::Section` items, and the stream
+/// continues with the next section.  A read error is produced as `ScanError::Io`, after which
+/// the stream ends.
+pub struct CueStream<R> {
+    /// `None` once the end of the input has been reached, or reading has failed
+    reader: Option<R>,
+    demux: CueDemux,
+    /// the packet being read, of which the first `filled` bytes have been received
+    pk: [u8; packet::Packet::SIZE],
+    filled: usize,
+}
+impl<R> CueStream<R> {
+    /// How sections with an incorrect `CRC_32` are treated, as for
+    /// `Scte35SectionProcessor::with_crc_mode()`.  Defaults to `CrcMode::Strict`.
+    ///
+    /// The demultiplexer is restarted, so this is to be used before iteration begins.
+    pub fn with_crc_mode(mut self, crc_mode: CrcMode) -> Self {
+        self.demux = CueDemux::new(crc_mode);
+        self
+    }
+}
+impl<R: AsyncRead + Unpin> futures_core::Stream for CueStream<R> {
+    type Item = Result<Cue, ScanError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(cue) = this.demux.next_cue() {
+                return Poll::Ready(Some(cue.map_err(ScanError::Section)));
+            }
+            let reader = match this.reader.as_mut() {
+                Some(reader) => reader,
+                None => return Poll::Ready(None),
+            };
+            let mut buf = ReadBuf::new(&mut this.pk[this.filled..]);
+            match Pin::new(reader).poll_read(cx, &mut buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) => {
+                    this.reader = None;
+                    return Poll::Ready(Some(Err(ScanError::Io(e))));
+                }
+                Poll::Ready(Ok(())) => {
+                    let len = buf.filled().len();
+                    if len == 0 {
+                        this.reader = None;
+                        continue;
+                    }
+                    this.filled += len;
+                    if this.filled == packet::Packet::SIZE {
+                        this.demux.push(&this.pk);
+                        this.filled = 0;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+    use crate::SpliceCommand;
+    use futures_core::Stream;
+    use hex_literal::*;
+    use matches::*;
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+
+    /// A waker that does nothing, since the test polls in a loop anyway
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    /// Delivers the data a few bytes at a time, returning `Pending` before each delivery
+    struct Trickle<'a> {
+        data: &'a [u8],
+        ready: bool,
+    }
+    impl AsyncRead for Trickle<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            if !self.ready {
+                self.ready = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.ready = false;
+            let len = self.data.len().min(buf.remaining()).min(50);
+            buf.put_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn cues_from_reader() {
+        let pat = with_crc(hex!("00b00d 0001 c1 00 00 0001e100").to_vec());
+        let pmt = with_crc(hex!("02b018 0001 c1 00 00 e1fff006 0504 43554549 86e101f000").to_vec());
+        let mut ts = vec![];
+        ts.extend(section_packet(0, &pat));
+        ts.extend(section_packet(0x100, &pmt));
+        ts.extend(section_packet(0x101, &SPLICE_NULL));
+
+        let mut stream = cue_stream(Trickle {
+            data: &ts,
+            ready: false,
+        });
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        let mut cues = vec![];
+        loop {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Pending => continue,
+                Poll::Ready(Some(cue)) => cues.push(cue),
+                Poll::Ready(None) => break,
+            }
+        }
+        assert_eq!(cues.len(), 1);
+        let cue = cues[0].as_ref().unwrap();
+        assert_eq!(cue.stream.elementary_pid, packet::Pid::new(0x101));
+        assert_matches!(cue.section.splice_command, SpliceCommand::SpliceNull {});
+    }
+}
@@ -0,0 +1,37 @@
+//! Fixtures and helpers shared by the unit tests of several modules
+
+use hex_literal::*;
+use mpeg2ts_reader::packet;
+
+/// A `splice_insert()` for `splice_event_id` 1, 'out of network' at PTS `0x2d142b00` with an
+/// auto-return `break_duration` of 19125000, and no descriptors
+pub(crate) const SPLICE_INSERT: [u8; 40] =
+    hex!("fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49");
+
+/// A `splice_null()`, with no descriptors
+pub(crate) const SPLICE_NULL: [u8; 20] = hex!("fc301100000000000000fff0000000007a4fbfff");
+
+/// A `time_signal()` at PTS `0x1592d03c0`, with a `segmentation_descriptor()` for
+/// `segmentation_event_id` 1 having no UPID
+pub(crate) const TIME_SIGNAL: [u8; 42] =
+    hex!("fc302700000000000000fff00506ff592d03c00011020f43554549000000017fbf000010010112ce0e6b");
+
+/// Appends the `CRC_32` of the given section, which should be complete but for the CRC
+pub(crate) fn with_crc(mut section: Vec<u8>) -> Vec<u8> {
+    let crc = mpeg2ts_reader::mpegts_crc::sum32(&section);
+    section.extend_from_slice(&crc.to_be_bytes());
+    section
+}
+
+/// wraps a complete section in a single TS packet, after a `pointer_field` of `0`
+pub(crate) fn section_packet(pid: u16, section: &[u8]) -> Vec<u8> {
+    section_packet_cc(pid, 0, section)
+}
+
+/// As for `section_packet()`, but with the given `continuity_counter`
+pub(crate) fn section_packet_cc(pid: u16, cc: u8, section: &[u8]) -> Vec<u8> {
+    let mut pk = vec![0x47, 0x40 | (pid >> 8) as u8, pid as u8, 0x10 | cc, 0x00];
+    pk.extend_from_slice(section);
+    pk.resize(packet::Packet::SIZE, 0xff);
+    pk
+}
@@ -0,0 +1,451 @@
+//! Utilities for converting the time values carried in _SCTE-35_ messages into other
+//! representations.
+
+use std::cmp::Ordering;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Number of seconds between the Unix epoch and the GPS epoch, `1980-01-06T00:00:00Z`, from
+/// which `utc_splice_time` values are counted.
+pub const GPS_EPOCH_UNIX_SECONDS: u64 = 315_964_800;
+
+/// The difference in seconds between GPS time and UTC (i.e. the number of leap seconds inserted
+/// since the GPS epoch) in effect since `2017-01-01`.
+///
+/// Applications with access to the `GPS_UTC_offset` of an ATSC _System Time Table_ should prefer
+/// that value.
+pub const CURRENT_GPS_UTC_OFFSET: u32 = 18;
+
+/// PTS values are 33-bit counters of a 90kHz clock, and so wrap back to zero at this value
+/// (a little over 26.5 hours).
+pub const PTS_MODULUS: u64 = 1 << 33;
+
+/// Adds the given `pts_adjustment` (from the `splice_info_section()` header) to the given PTS
+/// value, wrapping at 33 bits.  Neither value need already be within the 33-bit range.
+pub fn adjust_pts(pts: u64, pts_adjustment: u64) -> u64 {
+    SpliceTimestamp::new(pts).adjust(pts_adjustment).value()
+}
+
+/// Orders two 33-bit PTS values, allowing for the wrap of the PTS counter: `a` is considered
+/// less than `b` if `b` follows it by less than half of the 33-bit range.
+///
+/// The result is only a consistent ordering for sets of values that all fall within about 13
+/// hours of one another, which is ample for sorting or merging cues taken from the same
+/// timeline.
+pub fn cmp_pts(a: u64, b: u64) -> Ordering {
+    let diff = b.wrapping_sub(a) % PTS_MODULUS;
+    if diff == 0 {
+        Ordering::Equal
+    } else if diff < PTS_MODULUS / 2 {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+/// A 33-bit PTS value, whose arithmetic wraps at `PTS_MODULUS` in the same way as the PTS
+/// counter itself.
+///
+/// Ordering is only meaningful between values near one another, and so is given by
+/// `cmp_wrapping()` (see `cmp_pts()`) rather than by an `Ord` implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SpliceTimestamp(u64);
+impl SpliceTimestamp {
+    /// Wraps the given value into the 33-bit range
+    pub fn new(pts: u64) -> SpliceTimestamp {
+        SpliceTimestamp(pts % PTS_MODULUS)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Applies the given `pts_adjustment` (see `adjust_pts()`)
+    pub fn adjust(self, pts_adjustment: u64) -> SpliceTimestamp {
+        self + pts_adjustment
+    }
+
+    /// The number of 90kHz ticks from `earlier` forward to this value, allowing for the counter
+    /// having wrapped in between.
+    pub fn ticks_since(self, earlier: SpliceTimestamp) -> u64 {
+        (self.0 + PTS_MODULUS - earlier.0) % PTS_MODULUS
+    }
+
+    /// Orders this value relative to `other`, as for `cmp_pts()`
+    pub fn cmp_wrapping(self, other: SpliceTimestamp) -> Ordering {
+        cmp_pts(self.0, other.0)
+    }
+}
+impl From<SpliceTimestamp> for u64 {
+    fn from(t: SpliceTimestamp) -> u64 {
+        t.0
+    }
+}
+impl std::ops::Add<u64> for SpliceTimestamp {
+    type Output = SpliceTimestamp;
+
+    /// Adds the given number of 90kHz ticks, wrapping at 33 bits
+    fn add(self, ticks: u64) -> SpliceTimestamp {
+        SpliceTimestamp((self.0 + ticks % PTS_MODULUS) % PTS_MODULUS)
+    }
+}
+impl std::ops::Sub<u64> for SpliceTimestamp {
+    type Output = SpliceTimestamp;
+
+    /// Subtracts the given number of 90kHz ticks, wrapping at 33 bits
+    fn sub(self, ticks: u64) -> SpliceTimestamp {
+        SpliceTimestamp((self.0 + PTS_MODULUS - ticks % PTS_MODULUS) % PTS_MODULUS)
+    }
+}
+impl std::fmt::Display for SpliceTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The rate of the 90kHz clock in which PTS values, `pts_adjustment` and durations within
+/// _SCTE-35_ messages are expressed.
+pub const PTS_TIMESCALE: u64 = 90_000;
+
+/// The rate of the 27MHz MPEG-2 system clock, from which the 90kHz clock is derived (one 90kHz
+/// tick being 300 ticks of the system clock).
+pub const SYSTEM_CLOCK_TIMESCALE: u64 = 27_000_000;
+
+/// How to treat a value that falls between two ticks of the target timescale, when converting
+/// to a coarser timescale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round towards zero, to the tick at or before the exact value
+    Down,
+    /// Round away from zero, to the tick at or after the exact value
+    Up,
+    /// Round to the closest tick, with values exactly half-way between two ticks rounded up
+    Nearest,
+}
+
+/// Converts a count of ticks at `from_timescale` Hz into a count of ticks at `to_timescale` Hz,
+/// applying the given rounding when the result is not a whole number of ticks.
+///
+/// The calculation is carried out without intermediate overflow; a result too large for `u64`
+/// saturates at `u64::MAX`.
+///
+/// # Panics
+///
+/// If `from_timescale` is zero.
+pub fn rescale(value: u64, from_timescale: u64, to_timescale: u64, rounding: Rounding) -> u64 {
+    let num = u128::from(value) * u128::from(to_timescale);
+    let den = u128::from(from_timescale);
+    let quotient = num / den;
+    let remainder = num % den;
+    let result = match rounding {
+        Rounding::Down => quotient,
+        Rounding::Up if remainder != 0 => quotient + 1,
+        Rounding::Up => quotient,
+        Rounding::Nearest if remainder >= den - remainder => quotient + 1,
+        Rounding::Nearest => quotient,
+    };
+    u64::try_from(result).unwrap_or(u64::MAX)
+}
+
+/// Converts a 90kHz value (such as a PTS or `break_duration`) into the given timescale, as used
+/// for example by the `timescale` of a DASH `EventStream` or a CMAF track.
+pub fn pts_to_timescale(pts: u64, timescale: u64, rounding: Rounding) -> u64 {
+    rescale(pts, PTS_TIMESCALE, timescale, rounding)
+}
+
+/// Converts a value in the given timescale into a 90kHz value.
+///
+/// The result is not wrapped to 33 bits; use `% PTS_MODULUS` if a PTS value is needed.
+///
+/// # Panics
+///
+/// If `timescale` is zero.
+pub fn timescale_to_pts(value: u64, timescale: u64, rounding: Rounding) -> u64 {
+    rescale(value, timescale, PTS_TIMESCALE, rounding)
+}
+
+/// Converts a 33-bit PTS value into ticks of the 27MHz system clock.  Any bits above the 33 a
+/// PTS can hold are discarded, so that the multiplication can not overflow; otherwise the
+/// conversion is exact.
+pub fn pts_to_system_clock(pts: u64) -> u64 {
+    (pts % PTS_MODULUS) * (SYSTEM_CLOCK_TIMESCALE / PTS_TIMESCALE)
+}
+
+/// Converts ticks of the 27MHz system clock (e.g. a PCR value, with its base and extension
+/// combined) into a 90kHz value.
+pub fn system_clock_to_pts(ticks: u64, rounding: Rounding) -> u64 {
+    rescale(ticks, SYSTEM_CLOCK_TIMESCALE, PTS_TIMESCALE, rounding)
+}
+
+/// The fields in which 90kHz values are carried, whose widths limit the durations they can
+/// represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickField {
+    /// The 33-bit `pts_time`, `break_duration` and `pts_adjustment` fields, and so also the
+    /// difference between two PTS values (up to about 26.5 hours)
+    Bits33,
+    /// The 40-bit `segmentation_duration` field (up to about 141 days)
+    Bits40,
+}
+impl TickField {
+    /// The largest value the field can hold
+    pub fn max(self) -> u64 {
+        match self {
+            TickField::Bits33 => PTS_MODULUS - 1,
+            TickField::Bits40 => (1 << 40) - 1,
+        }
+    }
+}
+
+/// Converts a 90kHz value (such as a `break_duration`, a `segmentation_duration` or the
+/// difference between two PTS values) into a `Duration`.  One tick is not a whole number of
+/// nanoseconds, so the given rounding is applied to the fractional part.
+pub fn as_duration(ticks: u64, rounding: Rounding) -> Duration {
+    let nanos = rescale(
+        ticks % PTS_TIMESCALE,
+        PTS_TIMESCALE,
+        1_000_000_000,
+        rounding,
+    );
+    Duration::new(ticks / PTS_TIMESCALE, nanos as u32)
+}
+
+/// Converts a `Duration` into a 90kHz value for the given field, rounding to a whole number of
+/// ticks as directed.  Fails if the result would be too large for the field.
+pub fn from_duration(duration: Duration, field: TickField, rounding: Rounding) -> Option<u64> {
+    let ticks = duration
+        .as_secs()
+        .checked_mul(PTS_TIMESCALE)?
+        .checked_add(rescale(
+            u64::from(duration.subsec_nanos()),
+            1_000_000_000,
+            PTS_TIMESCALE,
+            rounding,
+        ))?;
+    Some(ticks).filter(|t| *t <= field.max())
+}
+
+/// Converts a count of seconds since the GPS epoch into a `SystemTime`, subtracting the given
+/// `gps_utc_offset` leap seconds.
+pub fn gps_seconds_to_system_time(gps_seconds: u32, gps_utc_offset: u32) -> SystemTime {
+    let unix_seconds =
+        (GPS_EPOCH_UNIX_SECONDS + u64::from(gps_seconds)).saturating_sub(u64::from(gps_utc_offset));
+    UNIX_EPOCH + Duration::from_secs(unix_seconds)
+}
+
+/// Converts the TAI time carried by a `time_descriptor()` (seconds and nanoseconds since the PTP
+/// epoch of `1970-01-01T00:00:00 TAI`) into a `SystemTime`, subtracting the descriptor's
+/// `UTC_offset` leap seconds.
+///
+/// Fails if the time is beyond the range `SystemTime` can represent on this platform.
+pub fn tai_to_system_time(
+    tai_seconds: u64,
+    tai_nanoseconds: u32,
+    utc_offset: u16,
+) -> Option<SystemTime> {
+    UNIX_EPOCH
+        .checked_add(Duration::from_secs(
+            tai_seconds.saturating_sub(u64::from(utc_offset)),
+        ))?
+        .checked_add(Duration::from_nanos(u64::from(tai_nanoseconds)))
+}
+
+/// As `tai_to_system_time()`, but producing a `chrono::DateTime`.  Available when this crate's
+/// `chrono` feature is enabled.
+///
+/// Fails if the time is beyond the range `chrono` can represent.
+#[cfg(feature = "chrono")]
+pub fn tai_to_utc(
+    tai_seconds: u64,
+    tai_nanoseconds: u32,
+    utc_offset: u16,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+    let seconds = i64::try_from(tai_seconds.saturating_sub(u64::from(utc_offset))).ok()?;
+    chrono::DateTime::from_timestamp(seconds, 0)?
+        .checked_add_signed(chrono::TimeDelta::nanoseconds(i64::from(tai_nanoseconds)))
+}
+
+/// Formats the given time as an [RFC 3339](https://tools.ietf.org/html/rfc3339) UTC timestamp,
+/// e.g. `2021-03-17T07:06:22Z`.  A fractional seconds part is only included if the given time
+/// is not a whole number of seconds.
+pub fn format_rfc3339(time: SystemTime) -> String {
+    let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() as i64, d.subsec_nanos()),
+        Err(e) => {
+            let d = e.duration();
+            if d.subsec_nanos() == 0 {
+                (-(d.as_secs() as i64), 0)
+            } else {
+                (-(d.as_secs() as i64) - 1, 1_000_000_000 - d.subsec_nanos())
+            }
+        }
+    };
+    let days = secs.div_euclid(86_400);
+    let secs_of_day = secs.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    let mut result = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    );
+    if nanos != 0 {
+        let frac = format!("{:09}", nanos);
+        result.push('.');
+        result.push_str(frac.trim_end_matches('0'));
+    }
+    result.push('Z');
+    result
+}
+
+/// Converts a count of days since `1970-01-01` into a proleptic Gregorian `(year, month, day)`
+/// (see Howard Hinnant's `civil_from_days()` algorithm).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gps_epoch() {
+        assert_eq!(
+            "1980-01-06T00:00:00Z",
+            format_rfc3339(gps_seconds_to_system_time(0, 0))
+        );
+    }
+
+    #[test]
+    fn gps_utc_offset() {
+        assert_eq!(
+            "2021-03-17T07:06:22Z",
+            format_rfc3339(gps_seconds_to_system_time(
+                1_300_000_000,
+                CURRENT_GPS_UTC_OFFSET
+            ))
+        );
+    }
+
+    #[test]
+    fn pts_wrap() {
+        assert_eq!(cmp_pts(1, 2), Ordering::Less);
+        assert_eq!(cmp_pts(2, 1), Ordering::Greater);
+        assert_eq!(cmp_pts(5, 5), Ordering::Equal);
+        // a value just before the wrap precedes one just after it
+        assert_eq!(cmp_pts(PTS_MODULUS - 10, 10), Ordering::Less);
+        assert_eq!(cmp_pts(10, PTS_MODULUS - 10), Ordering::Greater);
+        assert_eq!(adjust_pts(PTS_MODULUS - 10, 20), 10);
+        assert_eq!(
+            adjust_pts(u64::MAX, u64::MAX),
+            (2 * (PTS_MODULUS - 1)) % PTS_MODULUS
+        );
+    }
+
+    #[test]
+    fn splice_timestamp() {
+        let t = SpliceTimestamp::new(PTS_MODULUS + 5);
+        assert_eq!(t.value(), 5);
+        assert_eq!((t - 10).value(), PTS_MODULUS - 5);
+        assert_eq!((t - 10 + 10), t);
+        assert_eq!(SpliceTimestamp::new(PTS_MODULUS - 1).adjust(2).value(), 1);
+        assert_eq!(
+            (t + u64::MAX).value(),
+            (5 + u64::MAX % PTS_MODULUS) % PTS_MODULUS
+        );
+        assert_eq!(t.ticks_since(t - 10), 10);
+        assert_eq!((t - 10).ticks_since(t), PTS_MODULUS - 10);
+        assert_eq!((t - 10).cmp_wrapping(t), Ordering::Less);
+    }
+
+    #[test]
+    fn timescales() {
+        assert_eq!(pts_to_system_clock(90_000), 27_000_000);
+        assert_eq!(pts_to_system_clock(PTS_MODULUS + 1), 300);
+        assert_eq!(pts_to_system_clock(u64::MAX), (PTS_MODULUS - 1) * 300);
+        assert_eq!(system_clock_to_pts(27_000_299, Rounding::Down), 90_000);
+        assert_eq!(system_clock_to_pts(27_000_001, Rounding::Up), 90_001);
+        assert_eq!(system_clock_to_pts(27_000_149, Rounding::Nearest), 90_000);
+        assert_eq!(system_clock_to_pts(27_000_150, Rounding::Nearest), 90_001);
+        // 30 seconds at 90kHz into a 1kHz timescale, and back
+        assert_eq!(pts_to_timescale(2_700_000, 1000, Rounding::Down), 30_000);
+        assert_eq!(timescale_to_pts(30_000, 1000, Rounding::Down), 2_700_000);
+        // one 90kHz tick is a fraction of a 48kHz tick
+        assert_eq!(pts_to_timescale(1, 48_000, Rounding::Down), 0);
+        assert_eq!(pts_to_timescale(1, 48_000, Rounding::Up), 1);
+        assert_eq!(pts_to_timescale(1, 48_000, Rounding::Nearest), 1);
+        assert_eq!(rescale(u64::MAX, 1, 2, Rounding::Down), u64::MAX);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(
+            as_duration(2_700_000, Rounding::Down),
+            Duration::from_secs(30)
+        );
+        // one tick is 11111.1 nanoseconds
+        assert_eq!(as_duration(1, Rounding::Down), Duration::from_nanos(11_111));
+        assert_eq!(as_duration(1, Rounding::Up), Duration::from_nanos(11_112));
+        assert_eq!(
+            as_duration(90_001, Rounding::Nearest),
+            Duration::from_nanos(1_000_011_111)
+        );
+        let max = as_duration(TickField::Bits40.max(), Rounding::Down);
+        assert_eq!(max.as_secs(), 12_216_795);
+        assert_eq!(
+            from_duration(max, TickField::Bits40, Rounding::Up),
+            Some(TickField::Bits40.max())
+        );
+        assert_eq!(from_duration(max, TickField::Bits33, Rounding::Up), None);
+        assert_eq!(
+            from_duration(
+                Duration::from_nanos(11_112),
+                TickField::Bits33,
+                Rounding::Down
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            from_duration(
+                Duration::from_nanos(11_112),
+                TickField::Bits33,
+                Rounding::Up
+            ),
+            Some(2)
+        );
+        assert_eq!(
+            from_duration(Duration::MAX, TickField::Bits40, Rounding::Down),
+            None
+        );
+    }
+
+    #[test]
+    fn tai() {
+        // 2021-03-17T07:06:59.5 TAI, being 37 seconds ahead of UTC
+        let t = tai_to_system_time(1_615_964_819, 500_000_000, 37).unwrap();
+        assert_eq!(format_rfc3339(t), "2021-03-17T07:06:22.5Z");
+        #[cfg(feature = "chrono")]
+        assert_eq!(
+            tai_to_utc(1_615_964_819, 500_000_000, 37).map(SystemTime::from),
+            Some(t)
+        );
+        assert_eq!(tai_to_system_time(u64::MAX, 0, 0), None);
+    }
+
+    #[test]
+    fn fractional_seconds() {
+        let t = UNIX_EPOCH + Duration::from_millis(951_782_400_250);
+        assert_eq!("2000-02-29T00:00:00.25Z", format_rfc3339(t));
+    }
+}
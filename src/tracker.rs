@@ -0,0 +1,623 @@
+//! Tracking of the avails and segments signalled by a sequence of sections, so that monitoring
+//! applications can be told when breaks actually start and end, rather than having to interpret
+//! each `splice_insert()` and segmentation descriptor themselves.
+//!
+//! A [`Scte35Tracker`](struct.Scte35Tracker.html) is given each section as it is received, and is
+//! told of the progress of the stream clock.  Splices which are signalled ahead of time are held
+//! as _pending_ until the clock reaches their splice point, at which time a
+//! [`Notification`](enum.Notification.html) is passed to the
+//! [`TrackerListener`](trait.TrackerListener.html).
+//!
+//! - `splice_insert()` commands are matched by `splice_event_id`, the 'out' starting a break and
+//!   the 'in' ending it.  Where the 'out' has `auto_return` set, the break also ends once its
+//!   `break_duration` has elapsed.
+//! - Segmentation descriptors are matched by `segmentation_event_id` and type, each 'start' type
+//!   (e.g. _Provider Advertisement Start_) being ended by the corresponding 'end' type.
+//! - A later section for the same event may revise its splice time or duration, in which case
+//!   the revised values are used from then on.
+//! - Repetitions of the start of an event which has recently ended are ignored, rather than
+//!   starting the event again.
+//!
+//! ## Example
+//!
+//! ```
+//! # use hex_literal::*;
+//! # use scte35_reader::SpliceInfoSection;
+//! # use scte35_reader::tracker::{EventId, Notification, Scte35Tracker};
+//! let out = SpliceInfoSection::parse(&hex!(
+//!     "fc302500000000000000fff01405000000017feffe2d142b00fe0123d3080001010100007f157a49"
+//! ))
+//! .unwrap();
+//! let mut notifications = vec![];
+//! let mut tracker = Scte35Tracker::new(|n| notifications.push(n));
+//! tracker.process(&out);
+//! tracker.advance(0x2d142b00);
+//! drop(tracker);
+//! assert_eq!(
+//!     notifications,
+//!     vec![Notification::BreakStarted {
+//!         id: EventId::Splice(1),
+//!         pts: Some(0x2d142b00),
+//!         duration: Some(19_125_000),
+//!     }]
+//! );
+//! ```
+
+use crate::time::{adjust_pts, cmp_pts};
+use crate::{
+    NetworkIndicator, ReturnMode, SegmentationDescriptor, SpliceCommand, SpliceDescriptor,
+    SpliceInfoSection, SpliceInsert,
+};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+/// The number of recently ended events whose starts are remembered, so that repetitions of
+/// those starts can be ignored
+const ENDED_MEMORY: usize = 32;
+
+/// Identifies a break or segment across the sections which signal it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventId {
+    /// A break signalled by `splice_insert()` commands with the given `splice_event_id`
+    Splice(u32),
+    /// A segment signalled by segmentation descriptors with the given `segmentation_event_id`,
+    /// identified by the `segmentation_type_id` of its start
+    Segmentation {
+        segmentation_event_id: u32,
+        segmentation_type_id: u8,
+    },
+}
+
+impl EventId {
+    /// The `segmentation_event_id` and start `segmentation_type_id` of a segment
+    fn segmentation(self) -> Option<(u32, u8)> {
+        match self {
+            EventId::Splice(_) => None,
+            EventId::Segmentation {
+                segmentation_event_id,
+                segmentation_type_id,
+            } => Some((segmentation_event_id, segmentation_type_id)),
+        }
+    }
+}
+
+/// A change in the state of a tracked event
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notification {
+    /// The splice point at the start of a break or segment has been reached
+    BreakStarted {
+        id: EventId,
+        /// The PTS of the splice point, or for immediate splices, the most recent clock value
+        /// given to `advance()` (`None` if there has been none)
+        pts: Option<u64>,
+        /// The signalled duration, in 90kHz units
+        duration: Option<u64>,
+    },
+    /// The splice point at the end of a break or segment has been reached
+    BreakEnded {
+        id: EventId,
+        /// The PTS of the splice point, with `pts` as for `BreakStarted`
+        pts: Option<u64>,
+    },
+    /// A pending or ongoing event was cancelled before its end was reached
+    EventCancelled { id: EventId },
+    /// A later section for a pending or ongoing event signalled a different splice time or
+    /// duration, which replace those signalled before
+    EventUpdated {
+        id: EventId,
+        old: EventTiming,
+        new: EventTiming,
+    },
+}
+
+/// The timing of an event, as signalled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventTiming {
+    /// The PTS of the splice point, or `None` for an immediate splice
+    pub pts: Option<u64>,
+    /// The duration, in 90kHz units
+    pub duration: Option<u64>,
+}
+
+/// Receives the notifications produced by a `Scte35Tracker`.
+///
+/// Implemented for any `FnMut(Notification)` closure.
+pub trait TrackerListener {
+    fn notify(&mut self, notification: Notification);
+}
+impl<F: FnMut(Notification)> TrackerListener for F {
+    fn notify(&mut self, notification: Notification) {
+        self(notification)
+    }
+}
+
+struct Event {
+    id: EventId,
+    timing: EventTiming,
+    /// The PTS at which the event starts, `None` for immediate splices whose time is unknown
+    start_pts: Option<u64>,
+    duration: Option<u64>,
+    auto_return: bool,
+    started: bool,
+    /// Once the end of the event has been signalled, the PTS at which it takes effect
+    end_pts: Option<Option<u64>>,
+}
+impl Event {
+    /// The PTS at which the event ends, if signalled explicitly or by `auto_return`
+    fn end(&self) -> Option<Option<u64>> {
+        match (self.end_pts, self.start_pts, self.duration) {
+            (None, Some(start), Some(duration)) if self.auto_return => {
+                Some(Some(adjust_pts(start, duration)))
+            }
+            (end, _, _) => end,
+        }
+    }
+}
+
+/// Maintains the state of the events signalled by a sequence of sections, notifying a
+/// `TrackerListener` as breaks start and end.
+pub struct Scte35Tracker<L: TrackerListener> {
+    listener: L,
+    /// The most recent value given to `advance()`
+    clock: Option<u64>,
+    /// Events not yet ended, in the order they were first signalled
+    events: Vec<Event>,
+    /// The identity and signalled timing of the most recently ended events, oldest first
+    ended: VecDeque<(EventId, EventTiming)>,
+}
+impl<L: TrackerListener> Scte35Tracker<L> {
+    pub fn new(listener: L) -> Scte35Tracker<L> {
+        Scte35Tracker {
+            listener,
+            clock: None,
+            events: vec![],
+            ended: VecDeque::new(),
+        }
+    }
+
+    /// Updates the tracked state from the given section.  Immediate splices take effect (and
+    /// are notified) straight away, while those with a splice time wait for `advance()`.
+    ///
+    /// Repetitions of a section already seen are ignored (including those repeating the start
+    /// of a recently ended event), but where a repetition changes the splice time or duration
+    /// of an event not yet ended, the new values take effect and `EventUpdated` is notified.
+    pub fn process(&mut self, section: &SpliceInfoSection) {
+        let pts = section.splice_pts();
+        if let SpliceCommand::SpliceInsert {
+            splice_event_id,
+            splice_detail,
+            ..
+        } = &section.splice_command
+        {
+            let id = EventId::Splice(*splice_event_id);
+            match splice_detail {
+                SpliceInsert::Cancel => self.cancel(|e| e == id),
+                SpliceInsert::Insert {
+                    network_indicator: NetworkIndicator::Out,
+                    duration,
+                    ..
+                } => self.start(
+                    id,
+                    pts,
+                    duration.as_ref().map(|d| d.duration),
+                    matches!(
+                        duration,
+                        Some(crate::SpliceDuration {
+                            return_mode: ReturnMode::Automatic,
+                            ..
+                        })
+                    ),
+                ),
+                SpliceInsert::Insert {
+                    network_indicator: NetworkIndicator::In,
+                    ..
+                } => {
+                    // the 'in' should repeat the 'out's splice_event_id, but failing that is
+                    // taken to end the break still open, if there is only one
+                    let index = self
+                        .open(|e| e == id)
+                        .or_else(|| self.only_open(|e| matches!(e, EventId::Splice(_))));
+                    self.end(index, pts);
+                }
+            }
+        }
+        for descriptor in &section.splice_descriptors {
+            if let SpliceDescriptor::SegmentationDescriptor {
+                segmentation_event_id,
+                descriptor_detail,
+            } = descriptor
+            {
+                let event_id = *segmentation_event_id;
+                match descriptor_detail {
+                    SegmentationDescriptor::Cancel => {
+                        self.cancel(|e| matches!(e.segmentation(), Some((id, _)) if id == event_id))
+                    }
+                    SegmentationDescriptor::Insert {
+                        segmentation_type_id,
+                        segmentation_duration,
+                        ..
+                    } => {
+                        if segmentation_type_id.is_start() {
+                            let id = EventId::Segmentation {
+                                segmentation_event_id: event_id,
+                                segmentation_type_id: segmentation_type_id.id(),
+                            };
+                            self.start(id, pts, *segmentation_duration, false);
+                        } else if let Some(start_type) =
+                            segmentation_type_id.corresponding_start_type()
+                        {
+                            // the end usually repeats the start's segmentation_event_id, but
+                            // failing that ends the earliest open segment of the same type
+                            let start_type = start_type.id();
+                            let index = self
+                                .open(|e| {
+                                    e == EventId::Segmentation {
+                                        segmentation_event_id: event_id,
+                                        segmentation_type_id: start_type,
+                                    }
+                                })
+                                .or_else(|| {
+                                    self.open(|e| {
+                                        matches!(e.segmentation(), Some((_, t)) if t == start_type)
+                                    })
+                                });
+                            self.end(index, pts);
+                        }
+                    }
+                }
+            }
+        }
+        self.fire();
+    }
+
+    /// Informs the tracker of the current position of the stream clock (in 90kHz units, on the
+    /// same timeline as the splice times of processed sections), notifying any starts and ends
+    /// which have become due.
+    pub fn advance(&mut self, pts: u64) {
+        self.clock = Some(pts);
+        self.fire();
+    }
+
+    /// Discards all tracked state without notification, for use when the stream clock becomes
+    /// discontinuous or the input is switched to a different source.
+    pub fn reset(&mut self) {
+        self.clock = None;
+        self.events.clear();
+        self.ended.clear();
+    }
+
+    /// The events whose start has been reached, but whose end has not.
+    pub fn active(&self) -> impl Iterator<Item = EventId> + '_ {
+        self.events.iter().filter(|e| e.started).map(|e| e.id)
+    }
+
+    /// The number of events whose start has not yet been reached.
+    pub fn pending(&self) -> usize {
+        self.events.iter().filter(|e| !e.started).count()
+    }
+
+    /// The index of the earliest event matching the given predicate whose end is not yet
+    /// signalled
+    fn open(&self, pred: impl Fn(EventId) -> bool) -> Option<usize> {
+        self.events
+            .iter()
+            .position(|e| e.end_pts.is_none() && pred(e.id))
+    }
+
+    /// The index of the event matching the given predicate whose end is not yet signalled, if
+    /// there is exactly one such event
+    fn only_open(&self, pred: impl Fn(EventId) -> bool) -> Option<usize> {
+        let mut open = self
+            .events
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.end_pts.is_none() && pred(e.id));
+        match (open.next(), open.next()) {
+            (Some((index, _)), None) => Some(index),
+            _ => None,
+        }
+    }
+
+    fn start(&mut self, id: EventId, pts: Option<u64>, duration: Option<u64>, auto_return: bool) {
+        let timing = EventTiming { pts, duration };
+        if let Some(event) = self.events.iter_mut().find(|e| e.id == id) {
+            event.auto_return = auto_return;
+            if event.timing != timing {
+                let old = event.timing;
+                event.timing = timing;
+                if !event.started {
+                    event.start_pts = pts.or(self.clock);
+                }
+                event.duration = duration;
+                self.listener.notify(Notification::EventUpdated {
+                    id,
+                    old,
+                    new: timing,
+                });
+            }
+            return;
+        }
+        if self.ended.contains(&(id, timing)) {
+            return;
+        }
+        self.events.push(Event {
+            id,
+            timing,
+            start_pts: pts.or(self.clock),
+            duration,
+            auto_return,
+            started: false,
+            end_pts: None,
+        });
+    }
+
+    fn end(&mut self, index: Option<usize>, pts: Option<u64>) {
+        if let Some(event) = index.map(|i| &mut self.events[i]) {
+            event.end_pts = Some(pts.or(self.clock));
+        }
+    }
+
+    fn cancel(&mut self, pred: impl Fn(EventId) -> bool) {
+        let listener = &mut self.listener;
+        self.events.retain(|e| {
+            if pred(e.id) {
+                listener.notify(Notification::EventCancelled { id: e.id });
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Notifies, and updates the state of, those events whose start or end has been reached
+    fn fire(&mut self) {
+        let clock = self.clock;
+        let listener = &mut self.listener;
+        let ended = &mut self.ended;
+        // an absent time means the splice is immediate
+        let due = |pts: Option<u64>| match (pts, clock) {
+            (Some(pts), Some(clock)) => cmp_pts(pts, clock) != Ordering::Greater,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        self.events.retain_mut(|e| {
+            if !e.started && due(e.start_pts) {
+                e.started = true;
+                listener.notify(Notification::BreakStarted {
+                    id: e.id,
+                    pts: e.start_pts,
+                    duration: e.duration,
+                });
+            }
+            match e.end() {
+                Some(end) if e.started && due(end) => {
+                    listener.notify(Notification::BreakEnded { id: e.id, pts: end });
+                    if ended.len() == ENDED_MEMORY {
+                        ended.pop_front();
+                    }
+                    ended.push_back((e.id, e.timing));
+                    false
+                }
+                _ => true,
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+
+    fn section(data: &[u8]) -> SpliceInfoSection {
+        SpliceInfoSection::parse(data).unwrap()
+    }
+
+    #[test]
+    fn splice_insert_auto_return() {
+        // splice_event_id 1, out at 0x2d142b00 with an auto-return duration of 19125000
+        let out = section(&SPLICE_INSERT);
+        let mut notifications = vec![];
+        let mut tracker = Scte35Tracker::new(|n| notifications.push(n));
+        tracker.process(&out);
+        tracker.process(&out);
+        assert_eq!(tracker.pending(), 1);
+        tracker.advance(0x2d14_2aff);
+        assert_eq!(tracker.active().count(), 0);
+        tracker.advance(0x2d14_2b00);
+        assert_eq!(tracker.active().collect::<Vec<_>>(), [EventId::Splice(1)]);
+        tracker.advance(0x2d14_2b00 + 19_125_000);
+        assert_eq!(tracker.active().count(), 0);
+        // a repetition of the 'out' after the break has ended does not start it again
+        tracker.process(&out);
+        assert_eq!(tracker.pending(), 0);
+        drop(tracker);
+        assert_eq!(
+            notifications,
+            [
+                Notification::BreakStarted {
+                    id: EventId::Splice(1),
+                    pts: Some(0x2d14_2b00),
+                    duration: Some(19_125_000),
+                },
+                Notification::BreakEnded {
+                    id: EventId::Splice(1),
+                    pts: Some(0x2d14_2b00 + 19_125_000),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn resignalled_update() {
+        let out = section(&SPLICE_INSERT);
+        // the same event, but now with a duration of 15 seconds
+        let mut updated = section(&SPLICE_INSERT);
+        if let SpliceCommand::SpliceInsert {
+            splice_detail:
+                SpliceInsert::Insert {
+                    duration: Some(duration),
+                    ..
+                },
+            ..
+        } = &mut updated.splice_command
+        {
+            duration.duration = 15 * 90_000;
+        }
+        let mut notifications = vec![];
+        let mut tracker = Scte35Tracker::new(|n| notifications.push(n));
+        tracker.process(&out);
+        tracker.process(&updated);
+        tracker.process(&updated);
+        tracker.advance(0x2d14_2b00 + 15 * 90_000);
+        drop(tracker);
+        let id = EventId::Splice(1);
+        assert_eq!(
+            notifications,
+            [
+                Notification::EventUpdated {
+                    id,
+                    old: EventTiming {
+                        pts: Some(0x2d14_2b00),
+                        duration: Some(19_125_000),
+                    },
+                    new: EventTiming {
+                        pts: Some(0x2d14_2b00),
+                        duration: Some(15 * 90_000),
+                    },
+                },
+                Notification::BreakStarted {
+                    id,
+                    pts: Some(0x2d14_2b00),
+                    duration: Some(15 * 90_000),
+                },
+                Notification::BreakEnded {
+                    id,
+                    pts: Some(0x2d14_2b00 + 15 * 90_000),
+                },
+            ]
+        );
+    }
+
+    /// The `splice_insert()` of the examples above, but with the given `splice_event_id` and
+    /// `network_indicator`, and without a `break_duration`
+    fn splice_insert(id: u32, indicator: NetworkIndicator) -> SpliceInfoSection {
+        let mut s = section(&SPLICE_INSERT);
+        if let SpliceCommand::SpliceInsert {
+            splice_event_id,
+            splice_detail:
+                SpliceInsert::Insert {
+                    network_indicator,
+                    duration,
+                    ..
+                },
+            ..
+        } = &mut s.splice_command
+        {
+            *splice_event_id = id;
+            *network_indicator = indicator;
+            *duration = None;
+        }
+        s
+    }
+
+    #[test]
+    fn unmatched_in() {
+        let mut notifications = vec![];
+        let mut tracker = Scte35Tracker::new(|n| notifications.push(n));
+        tracker.process(&splice_insert(1, NetworkIndicator::Out));
+        tracker.process(&splice_insert(2, NetworkIndicator::Out));
+        tracker.advance(0x2d14_2b00);
+        // with two breaks open, an 'in' of neither's splice_event_id ends neither
+        tracker.process(&splice_insert(3, NetworkIndicator::In));
+        assert_eq!(tracker.active().count(), 2);
+        tracker.process(&splice_insert(1, NetworkIndicator::In));
+        // but with only one open, that one is ended
+        tracker.process(&splice_insert(3, NetworkIndicator::In));
+        assert_eq!(tracker.active().count(), 0);
+        // the 'out' of an ended break is not acted on again
+        tracker.process(&splice_insert(1, NetworkIndicator::Out));
+        assert_eq!(tracker.pending(), 0);
+        drop(tracker);
+        let started = |id| Notification::BreakStarted {
+            id: EventId::Splice(id),
+            pts: Some(0x2d14_2b00),
+            duration: None,
+        };
+        let ended = |id| Notification::BreakEnded {
+            id: EventId::Splice(id),
+            pts: Some(0x2d14_2b00),
+        };
+        assert_eq!(notifications, [started(1), started(2), ended(1), ended(2)]);
+    }
+
+    #[test]
+    fn splice_insert_cancel() {
+        let out = section(&SPLICE_INSERT);
+        let mut cancel = section(&SPLICE_INSERT);
+        cancel.splice_command = SpliceCommand::SpliceInsert {
+            splice_event_id: 1,
+            reserved: 0x7f,
+            splice_detail: SpliceInsert::Cancel,
+        };
+        let mut notifications = vec![];
+        let mut tracker = Scte35Tracker::new(|n| notifications.push(n));
+        tracker.process(&out);
+        tracker.process(&cancel);
+        tracker.advance(0x2d14_2b00);
+        assert_eq!(tracker.pending(), 0);
+        drop(tracker);
+        assert_eq!(
+            notifications,
+            [Notification::EventCancelled {
+                id: EventId::Splice(1)
+            }]
+        );
+    }
+
+    #[test]
+    fn segmentation_start_end() {
+        // time_signal at 0x1592d03c0 with a Program Start for segmentation_event_id 1
+        let start = section(&TIME_SIGNAL);
+        let mut notifications = vec![];
+        let mut tracker = Scte35Tracker::new(|n| notifications.push(n));
+        tracker.advance(0x1_592d_03c0);
+        tracker.process(&start);
+        let id = EventId::Segmentation {
+            segmentation_event_id: 1,
+            segmentation_type_id: 0x10,
+        };
+        assert_eq!(tracker.active().collect::<Vec<_>>(), [id]);
+        // the same, but with a Program End
+        let mut end = start;
+        if let SpliceDescriptor::SegmentationDescriptor {
+            descriptor_detail:
+                SegmentationDescriptor::Insert {
+                    segmentation_type_id,
+                    ..
+                },
+            ..
+        } = &mut end.splice_descriptors[0]
+        {
+            *segmentation_type_id = crate::SegmentationTypeId::ProgramEnd;
+        }
+        tracker.process(&end);
+        assert_eq!(tracker.active().count(), 0);
+        drop(tracker);
+        assert_eq!(
+            notifications,
+            [
+                Notification::BreakStarted {
+                    id,
+                    pts: Some(0x1_592d_03c0),
+                    duration: None,
+                },
+                Notification::BreakEnded {
+                    id,
+                    pts: Some(0x1_592d_03c0),
+                },
+            ]
+        );
+    }
+}
@@ -26,18 +26,21 @@
 //! | `0x0D` | _variable_ | `MID()` | Multiple UPID types structure |
 //! | `0x0E` | _variable_ | ADS Information | Advertising information. The specific usage is out of scope of this standard. |
 //! | `0x0F` | _variable_ | URI | Universal Resource Identifier (see [RFC 3986](https://tools.ietf.org/html/rfc3986)). <br><br> e.g. `urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6` |
-//! | `0x10` - `0xFF` | _variable_ | _Reserved_ | Reserved for future standardization. |
+//! | `0x10` | `16` | UUID | Universally Unique Identifier (see [RFC 4122](https://tools.ietf.org/html/rfc4122)), in its 128-bit binary form. <br><br> e.g. `f81d4fae-7dec-11d0-a765-00a0c91e6bf6` |
+//! | `0x11` | _variable_ | SCR | Subscriber Company Reporting parameters |
+//! | `0x12` - `0xFF` | _variable_ | _Reserved_ | Reserved for future standardization. |
 
 use hex_slice::AsHex;
 use serde::Serializer;
 use std::fmt;
+use std::str::FromStr;
 
 fn hex_tuple(name: &str, f: &mut fmt::Formatter<'_>, val: &[u8]) -> fmt::Result {
     write!(f, "{}({:02x})", name, val.plain_hex(false))
 }
 
 /// Represents the UPID with type `0x01`, which the SCTE-35 standard says is deprecated.
-#[derive(serde_derive::Serialize)]
+#[derive(Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct UserDefinedDeprecated(pub Vec<u8>);
 impl fmt::Debug for UserDefinedDeprecated {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -45,25 +48,177 @@ impl fmt::Debug for UserDefinedDeprecated {
     }
 }
 
+/// The reason a string is not a syntactically valid identifier for one of the character-based
+/// UPID schemes (`IsciDeprecated`, `AdID` and `TID`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpidFormatError {
+    /// The identifier does not have the number of characters that the scheme requires
+    Length { expected: usize, actual: usize },
+    /// The character at the given (zero-based) position is not of the kind the scheme requires
+    /// at that position
+    Character { position: usize, found: char },
+}
+impl fmt::Display for UpidFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpidFormatError::Length { expected, actual } => {
+                write!(f, "expected {} characters, but found {}", expected, actual)
+            }
+            UpidFormatError::Character { position, found } => {
+                write!(
+                    f,
+                    "unexpected character {:?} at position {}",
+                    found, position
+                )
+            }
+        }
+    }
+}
+impl std::error::Error for UpidFormatError {}
+
+/// Checks the given identifier against a pattern in which `A` stands for an alphabetic
+/// character, `9` for a digit, and `X` for either
+fn check_format(id: &str, pattern: &str) -> Result<(), UpidFormatError> {
+    let actual = id.chars().count();
+    if actual != pattern.len() {
+        return Err(UpidFormatError::Length {
+            expected: pattern.len(),
+            actual,
+        });
+    }
+    for (position, (found, kind)) in id.chars().zip(pattern.bytes()).enumerate() {
+        let ok = match kind {
+            b'A' => found.is_ascii_alphabetic(),
+            b'9' => found.is_ascii_digit(),
+            _ => found.is_ascii_alphanumeric(),
+        };
+        if !ok {
+            return Err(UpidFormatError::Character { position, found });
+        }
+    }
+    Ok(())
+}
+
 /// _Industry Standard Commercial Identifier_
-#[derive(Debug, serde_derive::Serialize)]
+///
+/// Values parsed from a `segmentation_descriptor()` are not checked, but `from_str()` only
+/// accepts identifiers of 4 alphabetic characters followed by 4 digits, e.g. `ABCD1234`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct IsciDeprecated(pub String);
+impl IsciDeprecated {
+    const FORMAT: &'static str = "AAAA9999";
+
+    /// Checks that the identifier has the form the scheme requires
+    pub fn validate(&self) -> Result<(), UpidFormatError> {
+        check_format(&self.0, Self::FORMAT)
+    }
+}
+impl FromStr for IsciDeprecated {
+    type Err = UpidFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        check_format(s, Self::FORMAT)?;
+        Ok(IsciDeprecated(s.to_string()))
+    }
+}
 
 /// Defined by the _Advertising Digital Identification_ group
-#[derive(Debug, serde_derive::Serialize)]
+///
+/// Values parsed from a `segmentation_descriptor()` are not checked, but `from_str()` only
+/// accepts identifiers of 4 alphabetic characters (the company prefix) followed by 8
+/// alphanumeric characters, e.g. `ABCD0001000H`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct AdID(pub String);
+impl AdID {
+    const FORMAT: &'static str = "AAAAXXXXXXXX";
+
+    /// Checks that the identifier has the form the scheme requires
+    pub fn validate(&self) -> Result<(), UpidFormatError> {
+        check_format(&self.0, Self::FORMAT)
+    }
+}
+impl FromStr for AdID {
+    type Err = UpidFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        check_format(s, Self::FORMAT)?;
+        Ok(AdID(s.to_string()))
+    }
+}
 
 /// Represents the UPID with type `0x05`, which the SCTE-35 standard says is deprecated.
-#[derive(serde_derive::Serialize)]
-pub struct IsanDeprecated(pub Vec<u8>);
+///
+/// Holds the ISO 15706 binary encoding of an ISAN, being the 48-bit root and 16-bit episode.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
+pub struct IsanDeprecated(pub [u8; 8]);
+impl IsanDeprecated {
+    /// The 48-bit root segment, identifying the work
+    pub fn root(&self) -> u64 {
+        isan_root(&self.0)
+    }
+
+    /// The 16-bit episode (or part) segment
+    pub fn episode(&self) -> u16 {
+        u16::from_be_bytes([self.0[6], self.0[7]])
+    }
+
+    /// The standard textual form of the ISAN, with its check character, e.g.
+    /// `0000-0001-2C52-0000-P`
+    pub fn to_canonical(&self) -> String {
+        let digits = format!("{:02X}", self.0.plain_hex(false));
+        format!("{}-{}", dashed(&digits), iso7064_mod_37_36(&digits))
+    }
+}
 impl fmt::Debug for IsanDeprecated {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         hex_tuple("IsanDeprecated", f, &self.0)
     }
 }
 
+/// ISO 15706-2 _versioned ISAN_, in its 12 byte binary encoding (the UPID with type `0x06`),
+/// being the 48-bit root, 16-bit episode and 32-bit version.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
+pub struct Isan(pub [u8; 12]);
+impl Isan {
+    /// The 48-bit root segment, identifying the work
+    pub fn root(&self) -> u64 {
+        isan_root(&self.0)
+    }
+
+    /// The 16-bit episode (or part) segment
+    pub fn episode(&self) -> u16 {
+        u16::from_be_bytes([self.0[6], self.0[7]])
+    }
+
+    /// The 32-bit version segment
+    pub fn version(&self) -> u32 {
+        u32::from_be_bytes([self.0[8], self.0[9], self.0[10], self.0[11]])
+    }
+
+    /// The standard textual form of the versioned ISAN, with both of its check characters, e.g.
+    /// `0000-0001-2C52-0000-P-0000-0000-0`
+    pub fn to_canonical(&self) -> String {
+        let digits = format!("{:02X}", self.0.plain_hex(false));
+        format!(
+            "{}-{}-{}-{}",
+            dashed(&digits[..16]),
+            iso7064_mod_37_36(&digits[..16]),
+            dashed(&digits[16..]),
+            iso7064_mod_37_36(&digits)
+        )
+    }
+}
+fn isan_root(data: &[u8]) -> u64 {
+    data[..6].iter().fold(0, |acc, b| acc << 8 | u64::from(*b))
+}
+impl fmt::Debug for Isan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        hex_tuple("Isan", f, &self.0)
+    }
+}
+
 /// SMPTE ST 330:2011 Unique Material Identifier
-#[derive(serde_derive::Serialize)]
+#[derive(Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct Umid(pub Vec<u8>);
 impl fmt::Debug for Umid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -79,13 +234,32 @@ impl fmt::Debug for Umid {
 }
 
 /// Tribune Media Systems Program identifier
-#[derive(Debug, serde_derive::Serialize)]
+///
+/// Values parsed from a `segmentation_descriptor()` are not checked, but `from_str()` only
+/// accepts identifiers of 2 alphabetic characters followed by 10 digits, e.g. `MV0004146400`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct TID(pub String);
+impl TID {
+    const FORMAT: &'static str = "AA9999999999";
+
+    /// Checks that the identifier has the form the scheme requires
+    pub fn validate(&self) -> Result<(), UpidFormatError> {
+        check_format(&self.0, Self::FORMAT)
+    }
+}
+impl FromStr for TID {
+    type Err = UpidFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        check_format(s, Self::FORMAT)?;
+        Ok(TID(s.to_string()))
+    }
+}
 
 /// AiringID
 ///
 /// (Formerly Turner ID)
-#[derive(PartialEq, serde_derive::Serialize)]
+#[derive(Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct TI(pub Vec<u8>);
 impl fmt::Debug for TI {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -94,43 +268,220 @@ impl fmt::Debug for TI {
 }
 
 /// Cablelabs metadata identifier
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct ADI(pub String);
 
 /// An _Entertainment ID Registry Association_ identifier (compact binary representation)
-#[derive(serde_derive::Serialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct EIDR(pub [u8; 12]);
+impl EIDR {
+    /// The registrant code following the `10.` DOI directory indicator (e.g. `5240` for the
+    /// content records of EIDR), held in the first two bytes of the compact binary encoding
+    pub fn prefix(&self) -> u16 {
+        u16::from_be_bytes([self.0[0], self.0[1]])
+    }
+
+    /// Expands the compact binary encoding into the canonical textual form of the identifier,
+    /// e.g. `10.5240/0E4F-892E-442F-6BD4-15B0-1`, including the trailing ISO 7064 Mod 37,36
+    /// check character.
+    pub fn to_canonical(&self) -> String {
+        let suffix = format!("{:02X}", self.0[2..].plain_hex(false));
+        format!(
+            "10.{}/{}-{}",
+            self.prefix(),
+            dashed(&suffix),
+            iso7064_mod_37_36(&suffix)
+        )
+    }
+}
+
+/// Splits the given hex digits into dash-separated groups of four
+fn dashed(digits: &str) -> String {
+    let groups: Vec<&str> = digits
+        .as_bytes()
+        .chunks(4)
+        .map(|group| std::str::from_utf8(group).unwrap())
+        .collect();
+    groups.join("-")
+}
+
+/// ISO 7064 Mod 37,36 check character over the given upper-case alphanumeric characters, as used
+/// by both EIDR and ISAN
+fn iso7064_mod_37_36(chars: &str) -> char {
+    const ALPHABET: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    const M: u32 = 36;
+    let mut p = M;
+    for c in chars.chars() {
+        let s = match (p + c.to_digit(36).unwrap()) % M {
+            0 => M,
+            s => s,
+        };
+        p = (s * 2) % (M + 1);
+    }
+    ALPHABET[((M + 1 - p) % M) as usize] as char
+}
 impl fmt::Debug for EIDR {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         hex_tuple("EIDR", f, &self.0)
     }
 }
 
-/// `ATSC_content_identifier()` structure
-#[derive(serde_derive::Serialize)]
-pub struct ATSC(pub Vec<u8>);
+/// `ATSC_content_identifier()` structure, per _ATSC A/57B_
+#[derive(Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
+pub struct ATSC {
+    /// The `transport_stream_id` of the Transport Stream in which the content identifier was
+    /// first used
+    pub tsid: u16,
+    /// The hour of the day (UTC, `0`-`23`) after which the `content_id` may be reused, once the
+    /// `unique_for` period has elapsed
+    pub end_of_day: u8,
+    /// The number of days (`1`-`511`) for which the `content_id` will remain unique
+    pub unique_for: u16,
+    pub content_id: Vec<u8>,
+}
 impl fmt::Debug for ATSC {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        hex_tuple("ATSC", f, &self.0)
+        f.debug_struct("ATSC")
+            .field("tsid", &self.tsid)
+            .field("end_of_day", &self.end_of_day)
+            .field("unique_for", &self.unique_for)
+            .field(
+                "content_id",
+                &format_args!("{:02x}", self.content_id.plain_hex(false)),
+            )
+            .finish()
     }
 }
 
 /// _Managed Private UPID_ structure
-#[derive(serde_derive::Serialize)]
+///
+/// The first four bytes hold a SMPTE Registration Authority `format_identifier` which determines
+/// the meaning of the remaining `private_data`.  UPIDs too short to hold a `format_identifier`
+/// are rejected by the parser.
+#[derive(Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct MPU(pub Vec<u8>);
+impl MPU {
+    /// The 32-bit `format_identifier`, or `None` if the UPID is too short to contain one
+    pub fn format_identifier(&self) -> Option<[u8; 4]> {
+        self.0.get(..4).map(|id| [id[0], id[1], id[2], id[3]])
+    }
+
+    /// The bytes following the `format_identifier`
+    pub fn private_data(&self) -> &[u8] {
+        self.0.get(4..).unwrap_or(&[])
+    }
+}
 impl fmt::Debug for MPU {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        hex_tuple("MPU", f, &self.0)
+        match self.format_identifier() {
+            Some(id) if id.iter().all(|b| b.is_ascii_graphic() || *b == b' ') => write!(
+                f,
+                "MPU({}: {:02x})",
+                String::from_utf8_lossy(&id),
+                self.private_data().plain_hex(false)
+            ),
+            _ => hex_tuple("MPU", f, &self.0),
+        }
+    }
+}
+
+/// Human-readable names for some well-known SMPTE Registration Authority `format_identifier`
+/// values.
+pub fn known_format_identifier_name(format_identifier: [u8; 4]) -> Option<&'static str> {
+    Some(match &format_identifier {
+        b"AC-3" => "Dolby AC-3 audio",
+        b"BSSD" => "SMPTE 302M AES3 audio",
+        b"CUEI" => "SCTE-35 splice information",
+        b"GA94" => "ATSC A/53",
+        b"HEVC" => "HEVC video",
+        b"ID3 " => "ID3 timed metadata",
+        b"KLVA" => "SMPTE 336M KLV metadata",
+        b"Opus" => "Opus audio",
+        b"VC-1" => "SMPTE 421M VC-1 video",
+        b"drac" => "Dirac video",
+        _ => return None,
+    })
+}
+
+/// Lookup of names for `format_identifier` values (as found in an `MPU`), holding any names the
+/// application registers, and falling back to `known_format_identifier_name()`.
+#[derive(Debug, Default, Clone)]
+pub struct FormatIdentifierNames {
+    names: std::collections::HashMap<[u8; 4], String>,
+}
+impl FormatIdentifierNames {
+    /// Adds or replaces the name for the given `format_identifier`
+    pub fn register(&mut self, format_identifier: [u8; 4], name: impl Into<String>) {
+        self.names.insert(format_identifier, name.into());
+    }
+
+    pub fn name(&self, format_identifier: [u8; 4]) -> Option<&str> {
+        self.names
+            .get(&format_identifier)
+            .map(|n| n.as_str())
+            .or_else(|| known_format_identifier_name(format_identifier))
     }
 }
 
 /// _Advertising Information_ (SCTE-35 does not specify the format)
-#[derive(Debug, serde_derive::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct ADSInformation(pub Vec<u8>);
 
+/// A _Universally Unique Identifier_, in its 16 byte binary form
+///
+/// When this crate's `uuid` feature is enabled, this converts to and from `uuid::Uuid`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
+pub struct Uuid(pub [u8; 16]);
+impl fmt::Debug for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let b = &self.0;
+        write!(
+            f,
+            "Uuid({:02x}-{:02x}-{:02x}-{:02x}-{:02x})",
+            b[..4].plain_hex(false),
+            b[4..6].plain_hex(false),
+            b[6..8].plain_hex(false),
+            b[8..10].plain_hex(false),
+            b[10..].plain_hex(false)
+        )
+    }
+}
+#[cfg(feature = "uuid")]
+impl From<Uuid> for uuid::Uuid {
+    fn from(u: Uuid) -> uuid::Uuid {
+        uuid::Uuid::from_bytes(u.0)
+    }
+}
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for Uuid {
+    fn from(u: uuid::Uuid) -> Uuid {
+        Uuid(u.into_bytes())
+    }
+}
+
+/// _Subscriber Company Reporting_ parameters
+#[derive(Clone, PartialEq, Eq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
+pub struct SCR(pub Vec<u8>);
+impl fmt::Debug for SCR {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        hex_tuple("SCR", f, &self.0)
+    }
+}
+
 /// Just a wrapper around `url::Url` that adds serde serialisation
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Url(pub url::Url);
+impl<'de> serde::Deserialize<'de> for Url {
+    fn deserialize<D>(deserializer: D) -> Result<Url, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        url::Url::parse(&s)
+            .map(Url)
+            .map_err(serde::de::Error::custom)
+    }
+}
 impl serde::Serialize for Url {
     fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
     where
@@ -145,6 +496,99 @@ mod test {
     use super::*;
     use hex_literal::*;
 
+    #[test]
+    fn mpu_format_identifier() {
+        let mpu = MPU(b"ADFR\x01\x02".to_vec());
+        assert_eq!(mpu.format_identifier(), Some(*b"ADFR"));
+        assert_eq!(mpu.private_data(), &[1, 2]);
+        assert_eq!("MPU(ADFR: 0102)", format!("{:?}", mpu));
+        assert_eq!(MPU(vec![1, 2]).format_identifier(), None);
+
+        let mut names = FormatIdentifierNames::default();
+        assert_eq!(names.name(*b"ADFR"), None);
+        assert_eq!(names.name(*b"CUEI"), Some("SCTE-35 splice information"));
+        names.register(*b"ADFR", "Example registration");
+        assert_eq!(names.name(*b"ADFR"), Some("Example registration"));
+    }
+
+    #[test]
+    fn character_formats() {
+        assert_eq!(
+            "ABCD0001000H".parse::<AdID>(),
+            Ok(AdID("ABCD0001000H".to_string()))
+        );
+        assert_eq!(
+            "ABC10001000H".parse::<AdID>(),
+            Err(UpidFormatError::Character {
+                position: 3,
+                found: '1'
+            })
+        );
+        assert_eq!(
+            "ABCD0001000".parse::<AdID>(),
+            Err(UpidFormatError::Length {
+                expected: 12,
+                actual: 11
+            })
+        );
+        assert!("MV0004146400".parse::<TID>().is_ok());
+        assert_eq!(
+            "MV000414640X".parse::<TID>(),
+            Err(UpidFormatError::Character {
+                position: 11,
+                found: 'X'
+            })
+        );
+        assert!("ABCD1234".parse::<IsciDeprecated>().is_ok());
+        assert!("ABCD123-".parse::<IsciDeprecated>().is_err());
+        // multi-byte characters are counted as single characters
+        assert_eq!(
+            "ABCDÉ001000H".parse::<AdID>(),
+            Err(UpidFormatError::Character {
+                position: 4,
+                found: 'É'
+            })
+        );
+        assert!(TID("0123".to_string()).validate().is_err());
+    }
+
+    #[test]
+    fn eidr_canonical() {
+        let eidr = EIDR(hex!("1478 0E4F892E442F6BD415B0"));
+        assert_eq!(eidr.prefix(), 5240);
+        assert_eq!(eidr.to_canonical(), "10.5240/0E4F-892E-442F-6BD4-15B0-1");
+        assert_eq!(
+            EIDR(hex!("1478 779185342C2390308610")).to_canonical(),
+            "10.5240/7791-8534-2C23-9030-8610-5"
+        );
+    }
+
+    #[test]
+    fn isan_canonical() {
+        let isan = Isan(hex!("000000012C52 0000 00000000"));
+        assert_eq!(isan.root(), 0x1_2c52);
+        assert_eq!(isan.episode(), 0);
+        assert_eq!(isan.version(), 0);
+        assert_eq!(isan.to_canonical(), "0000-0001-2C52-0000-P-0000-0000-0");
+        let deprecated = IsanDeprecated(hex!("000000012C52 0000"));
+        assert_eq!(deprecated.root(), 0x1_2c52);
+        assert_eq!(deprecated.to_canonical(), "0000-0001-2C52-0000-P");
+    }
+
+    #[test]
+    fn uuid_fmt() {
+        let u = Uuid(hex!("f81d4fae7dec11d0a76500a0c91e6bf6"));
+        assert_eq!(
+            "Uuid(f81d4fae-7dec-11d0-a765-00a0c91e6bf6)",
+            format!("{:?}", u)
+        );
+        #[cfg(feature = "uuid")]
+        assert_eq!(
+            uuid::Uuid::from(u).to_string(),
+            "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"
+        );
+    }
+
     #[test]
     fn umid_fmt() {
         assert_eq!(
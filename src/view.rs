@@ -0,0 +1,519 @@
+//! Borrowed views of the descriptors in a section's descriptor loop, exposing their fields over
+//! the original buffer without the heap allocations (for UPIDs, private bytes, DTMF characters
+//! and so on) that producing an owned `SpliceDescriptor` entails.
+//!
+//! Views are obtained from `SpliceDescriptors::views()`, and can be converted into the owned
+//! model with `to_owned()` when the complete descriptor is needed.
+//!
+//! ## Example
+//!
+//! ```
+//! # use scte35_reader::{SegmentationTypeId, SpliceDescriptors};
+//! fn placement_opportunities(descriptors: &SpliceDescriptors<'_>) -> usize {
+//!     descriptors
+//!         .views()
+//!         .filter_map(|view| view.ok()?.segmentation()?.ok())
+//!         .filter(|s| {
+//!             s.segmentation_type_id() == Some(SegmentationTypeId::ProviderPlacementOpportunityStart)
+//!         })
+//!         .count()
+//! }
+//! ```
+
+use crate::{
+    DeliveryRestrictionFlags, DeviceRestrictions, Scte35Diagnostics, SegmentationTypeId,
+    SegmentationUpidType, SpliceDescriptor, SpliceDescriptorErr, SpliceDescriptorIter,
+};
+use std::fmt;
+
+const CUEI: &[u8; 4] = b"CUEI";
+
+/// A single `splice_descriptor()`, borrowing from the buffer holding the descriptor loop.
+///
+/// Only the `splice_descriptor_tag` and `descriptor_length` have been checked on construction;
+/// the rest of the descriptor is read as each accessor is called.
+#[derive(Clone, Copy)]
+pub struct SpliceDescriptorView<'buf> {
+    /// The complete descriptor, including tag and length
+    data: &'buf [u8],
+    diagnostics: &'buf dyn Scte35Diagnostics,
+}
+impl<'buf> SpliceDescriptorView<'buf> {
+    /// Fails if the given buffer does not hold exactly one descriptor, of at least the minimum
+    /// length.
+    pub(crate) fn new(
+        data: &'buf [u8],
+        diagnostics: &'buf dyn Scte35Diagnostics,
+    ) -> Result<SpliceDescriptorView<'buf>, SpliceDescriptorErr> {
+        if data.len() < 6 {
+            return Err(SpliceDescriptorErr::not_enough_data(
+                "splice_descriptor",
+                0,
+                6,
+                data.len(),
+            ));
+        }
+        let descriptor_length = data[1] as usize;
+        if descriptor_length < 4 || descriptor_length + 2 != data.len() {
+            return Err(SpliceDescriptorErr::InvalidDescriptorLength(
+                descriptor_length,
+            ));
+        }
+        Ok(SpliceDescriptorView { data, diagnostics })
+    }
+
+    pub fn splice_descriptor_tag(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// The 32-bit `identifier`, which is `CUEI` for the descriptors defined by _SCTE-35_
+    pub fn identifier(&self) -> [u8; 4] {
+        [self.data[2], self.data[3], self.data[4], self.data[5]]
+    }
+
+    /// The bytes following the `identifier`
+    pub fn payload(&self) -> &'buf [u8] {
+        &self.data[6..]
+    }
+
+    /// The complete descriptor, including the tag and length
+    pub fn as_bytes(&self) -> &'buf [u8] {
+        self.data
+    }
+
+    fn is_cuei(&self, tag: u8) -> bool {
+        self.splice_descriptor_tag() == tag && &self.data[2..6] == CUEI
+    }
+
+    /// For an `avail_descriptor()`, the `provider_avail_id`
+    pub fn provider_avail_id(&self) -> Option<u32> {
+        if !self.is_cuei(0x00) {
+            return None;
+        }
+        let id = self.payload().get(..4)?;
+        Some(u32::from_be_bytes([id[0], id[1], id[2], id[3]]))
+    }
+
+    /// For a `DTMF_descriptor()`, the `DTMF_char` values, or `None` if the descriptor is too
+    /// short to hold the number of characters it declares
+    pub fn dtmf_chars(&self) -> Option<&'buf [u8]> {
+        if !self.is_cuei(0x01) {
+            return None;
+        }
+        let count = usize::from(self.payload().get(1)? >> 5);
+        self.payload().get(2..2 + count)
+    }
+
+    /// As `dtmf_chars()`, but as a string, failing if any is not one of the characters `0`-`9`,
+    /// `*` or `#` (see `SpliceDescriptor::dtmf_str()`)
+    pub fn dtmf_str(&self) -> Option<Result<&'buf str, SpliceDescriptorErr>> {
+        let chars = match self.dtmf_chars() {
+            Some(chars) => chars,
+            // distinguish a truncated DTMF_descriptor() from some other kind of descriptor
+            None if self.is_cuei(0x01) => {
+                return Some(Err(SpliceDescriptorErr::not_enough_data(
+                    "dtmf_descriptor",
+                    0,
+                    self.payload()
+                        .get(1)
+                        .map(|count| 2 + usize::from(count >> 5))
+                        .unwrap_or(2),
+                    self.payload().len(),
+                )))
+            }
+            None => return None,
+        };
+        Some(crate::dtmf_str(chars))
+    }
+
+    /// For a `segmentation_descriptor()`, a view of its fields, failing if the descriptor is
+    /// too short to hold the fields that it declares.  `None` for other descriptors.
+    pub fn segmentation(
+        &self,
+    ) -> Option<Result<SegmentationDescriptorView<'buf>, SpliceDescriptorErr>> {
+        if self.is_cuei(0x02) {
+            Some(SegmentationDescriptorView::new(self.payload()))
+        } else {
+            None
+        }
+    }
+
+    /// Parses the complete descriptor into the owned model
+    pub fn to_owned(self) -> Result<SpliceDescriptor, SpliceDescriptorErr> {
+        SpliceDescriptor::parse(self.data, self.diagnostics)
+    }
+}
+impl fmt::Debug for SpliceDescriptorView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpliceDescriptorView")
+            .field("splice_descriptor_tag", &self.splice_descriptor_tag())
+            .field("identifier", &String::from_utf8_lossy(&self.data[2..6]))
+            .field("payload", &self.payload())
+            .finish()
+    }
+}
+
+/// Iterator over the descriptors of a `SpliceDescriptors`, produced by its `views()` method.
+///
+/// Iteration stops after an error in the descriptor loop itself (where the length of a
+/// descriptor can not be determined); errors within a descriptor only surface when the
+/// relevant accessor of its view is called.
+pub struct SpliceDescriptorViews<'buf> {
+    pub(crate) iter: SpliceDescriptorIter<'buf>,
+}
+impl<'buf> Iterator for SpliceDescriptorViews<'buf> {
+    type Item = Result<SpliceDescriptorView<'buf>, SpliceDescriptorErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let diagnostics = self.iter.diagnostics;
+        let offset = self.iter.offset();
+        self.iter.next_bytes().map(|(_, result)| {
+            result.and_then(|data| {
+                SpliceDescriptorView::new(data, diagnostics)
+                    .map_err(|e| e.in_descriptor(data[0], offset))
+            })
+        })
+    }
+}
+
+/// A `segmentation_descriptor()`, borrowing from the buffer holding the descriptor loop.
+///
+/// The positions of the variable-length fields are found on construction; the fields of a
+/// descriptor with `segmentation_event_cancel_indicator` set are all `None`.
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentationDescriptorView<'buf> {
+    /// The descriptor's bytes following the `identifier`
+    payload: &'buf [u8],
+    detail: Option<Detail>,
+}
+/// Offsets into the payload of the fields following the cancel indicator
+#[derive(Debug, Clone, Copy)]
+struct Detail {
+    components: usize,
+    component_count: usize,
+    duration: Option<usize>,
+    upid: usize,
+    type_id: usize,
+}
+impl<'buf> SegmentationDescriptorView<'buf> {
+    fn new(payload: &'buf [u8]) -> Result<SegmentationDescriptorView<'buf>, SpliceDescriptorErr> {
+        let need = |len: usize, field_name| {
+            if payload.len() < len {
+                Err(SpliceDescriptorErr::not_enough_data(
+                    field_name,
+                    0,
+                    len,
+                    payload.len(),
+                ))
+            } else {
+                Ok(())
+            }
+        };
+        need(4, "segmentation_descriptor.id")?;
+        need(5, "segmentation_descriptor.cancel")?;
+        if payload[4] & 0x80 != 0 {
+            return Ok(SegmentationDescriptorView {
+                payload,
+                detail: None,
+            });
+        }
+        need(6, "segmentation_descriptor.program_segmentation_flag")?;
+        let flags = payload[5];
+        let mut pos = 6;
+        let (components, component_count) = if flags & 0x80 == 0 {
+            need(pos + 1, "segmentation_descriptor.component_count")?;
+            let count = usize::from(payload[pos]);
+            pos += 1 + count * 6;
+            need(pos, "segmentation_descriptor.component")?;
+            (pos - count * 6, count)
+        } else {
+            (pos, 0)
+        };
+        let duration = if flags & 0x40 != 0 {
+            pos += 5;
+            need(pos, "segmentation_descriptor.segmentation_duration")?;
+            Some(pos - 5)
+        } else {
+            None
+        };
+        need(pos + 2, "segmentation_descriptor.segmentation_upid_length")?;
+        let upid = pos;
+        pos += 2 + usize::from(payload[pos + 1]);
+        need(pos, "segmentation_descriptor.segmentation_upid")?;
+        let type_id = pos;
+        need(pos + 3, "segmentation_descriptor.segments_expected")?;
+        if payload.len() > pos + 3 && SegmentationTypeId::from_id(payload[pos]).has_sub_segments() {
+            need(pos + 5, "segmentation_descriptor.sub_segments_expected")?;
+        }
+        Ok(SegmentationDescriptorView {
+            payload,
+            detail: Some(Detail {
+                components,
+                component_count,
+                duration,
+                upid,
+                type_id,
+            }),
+        })
+    }
+
+    pub fn segmentation_event_id(&self) -> u32 {
+        let id = &self.payload[..4];
+        u32::from_be_bytes([id[0], id[1], id[2], id[3]])
+    }
+
+    /// `true` if the `segmentation_event_cancel_indicator` is set
+    pub fn is_cancelled(&self) -> bool {
+        self.detail.is_none()
+    }
+
+    fn flags(&self) -> Option<u8> {
+        self.detail.map(|_| self.payload[5])
+    }
+
+    pub fn program_segmentation_flag(&self) -> Option<bool> {
+        self.flags().map(|f| f & 0x80 != 0)
+    }
+
+    pub fn delivery_restrictions(&self) -> Option<DeliveryRestrictionFlags> {
+        let flags = self.flags()?;
+        Some(if flags & 0x20 != 0 {
+            DeliveryRestrictionFlags::None
+        } else {
+            DeliveryRestrictionFlags::DeliveryRestrictions {
+                web_delivery_allowed_flag: flags & 0x10 != 0,
+                no_regional_blackout_flag: flags & 0x08 != 0,
+                archive_allowed_flag: flags & 0x04 != 0,
+                device_restrictions: match flags & 0x03 {
+                    0 => DeviceRestrictions::RestrictGroup0,
+                    1 => DeviceRestrictions::RestrictGroup1,
+                    2 => DeviceRestrictions::RestrictGroup2,
+                    _ => DeviceRestrictions::None,
+                },
+            }
+        })
+    }
+
+    /// The `component_tag` and `pts_offset` of each component of a component-mode descriptor
+    pub fn components(&self) -> impl Iterator<Item = (u8, u64)> + 'buf {
+        let (start, count) = self
+            .detail
+            .map(|d| (d.components, d.component_count))
+            .unwrap_or((0, 0));
+        self.payload[start..start + count * 6]
+            .chunks_exact(6)
+            .map(|c| {
+                let pts_offset =
+                    c[1..].iter().fold(0, |acc, b| acc << 8 | u64::from(*b)) & 0x1_ffff_ffff;
+                (c[0], pts_offset)
+            })
+    }
+
+    /// The 40-bit `segmentation_duration`, in 90kHz units, if present
+    pub fn segmentation_duration(&self) -> Option<u64> {
+        let pos = self.detail?.duration?;
+        Some(
+            self.payload[pos..pos + 5]
+                .iter()
+                .fold(0, |acc, b| acc << 8 | u64::from(*b)),
+        )
+    }
+
+    pub fn segmentation_upid_type(&self) -> Option<SegmentationUpidType> {
+        let pos = self.detail?.upid;
+        Some(SegmentationUpidType::from_type(self.payload[pos]))
+    }
+
+    /// The raw bytes of the `segmentation_upid()`, whose interpretation depends on
+    /// `segmentation_upid_type()`
+    pub fn segmentation_upid(&self) -> Option<&'buf [u8]> {
+        let pos = self.detail?.upid;
+        let len = usize::from(self.payload[pos + 1]);
+        Some(&self.payload[pos + 2..pos + 2 + len])
+    }
+
+    pub fn segmentation_type_id(&self) -> Option<SegmentationTypeId> {
+        let pos = self.detail?.type_id;
+        Some(SegmentationTypeId::from_id(self.payload[pos]))
+    }
+
+    pub fn segment_num(&self) -> Option<u8> {
+        Some(self.payload[self.detail?.type_id + 1])
+    }
+
+    pub fn segments_expected(&self) -> Option<u8> {
+        Some(self.payload[self.detail?.type_id + 2])
+    }
+
+    /// The `sub_segment_num` and `sub_segments_expected` values, if present.  Only the
+    /// segmentation types for which the spec defines these fields are considered to carry them.
+    pub fn sub_segments(&self) -> Option<(u8, u8)> {
+        let detail = self.detail?;
+        if !self.segmentation_type_id()?.has_sub_segments() {
+            return None;
+        }
+        let pos = detail.type_id + 3;
+        let sub = self.payload.get(pos..pos + 2)?;
+        Some((sub[0], sub[1]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        LogDiagnostics, SegmentationDescriptor, SegmentationTypeId, SegmentationUpidType,
+        SpliceDescriptor, SpliceDescriptorErr, SpliceDescriptors,
+    };
+    use hex_literal::*;
+    use matches::*;
+
+    #[test]
+    fn segmentation() {
+        // from the time_signal() used in the parser's tests, with an Ad-ID UPID and a component
+        // added
+        let data = hex!(
+            "022443554549 00000001 7f 3f 01 05fe00000064 030c 414243443030303130303048 34 01 02 0102"
+            "000843554549 00000007"
+        );
+        let descriptors = SpliceDescriptors {
+            buf: &data[..],
+            diagnostics: &LogDiagnostics,
+        };
+        let views: Vec<_> = descriptors.views().map(|v| v.unwrap()).collect();
+        assert_eq!(views.len(), 2);
+        assert_eq!(views[0].splice_descriptor_tag(), 0x02);
+        assert_eq!(views[0].provider_avail_id(), None);
+        let seg = views[0].segmentation().unwrap().unwrap();
+        assert_eq!(seg.segmentation_event_id(), 1);
+        assert!(!seg.is_cancelled());
+        assert_eq!(seg.program_segmentation_flag(), Some(false));
+        assert_eq!(seg.components().collect::<Vec<_>>(), vec![(5, 100)]);
+        assert_eq!(seg.segmentation_duration(), None);
+        assert_eq!(
+            seg.segmentation_upid_type(),
+            Some(SegmentationUpidType::AdID)
+        );
+        assert_eq!(seg.segmentation_upid(), Some(&b"ABCD0001000H"[..]));
+        assert_eq!(
+            seg.segmentation_type_id(),
+            Some(SegmentationTypeId::ProviderPlacementOpportunityStart)
+        );
+        assert_eq!(seg.segment_num(), Some(1));
+        assert_eq!(seg.segments_expected(), Some(2));
+        assert_eq!(seg.sub_segments(), Some((1, 2)));
+        assert_eq!(views[1].provider_avail_id(), Some(7));
+        assert!(views[1].segmentation().is_none());
+
+        let owned: Vec<_> = descriptors.into_iter().map(|d| d.unwrap()).collect();
+        assert_eq!(views[0].to_owned().unwrap(), owned[0]);
+        assert_eq!(views[1].to_owned().unwrap(), owned[1]);
+    }
+
+    #[test]
+    fn dtmf() {
+        // the second descriptor declares three DTMF_char values, but holds only two
+        let data = hex!("010943554549 64 7f 31322a 010843554549 64 7f 3132");
+        let descriptors = SpliceDescriptors {
+            buf: &data[..],
+            diagnostics: &LogDiagnostics,
+        };
+        let views: Vec<_> = descriptors.views().map(|v| v.unwrap()).collect();
+        assert_eq!(views[0].dtmf_chars(), Some(&b"12*"[..]));
+        assert_eq!(views[0].dtmf_str().unwrap().unwrap(), "12*");
+        assert_eq!(views[1].dtmf_chars(), None);
+        assert_matches!(
+            views[1].dtmf_str(),
+            Some(Err(SpliceDescriptorErr::NotEnoughData {
+                expected: 5,
+                actual: 4,
+                ..
+            }))
+        );
+    }
+
+    #[test]
+    fn cancelled() {
+        let data = hex!("020943554549 0000002a ff");
+        let descriptors = SpliceDescriptors {
+            buf: &data[..],
+            diagnostics: &LogDiagnostics,
+        };
+        let view = descriptors.views().next().unwrap().unwrap();
+        let seg = view.segmentation().unwrap().unwrap();
+        assert_eq!(seg.segmentation_event_id(), 42);
+        assert!(seg.is_cancelled());
+        assert_eq!(seg.segmentation_type_id(), None);
+        assert_eq!(seg.components().count(), 0);
+    }
+
+    #[test]
+    fn truncated() {
+        // the segmentation_upid_length claims more bytes than the descriptor holds
+        let data = hex!("021143554549 00000001 7f bf 03 0c 4142434430");
+        let descriptors = SpliceDescriptors {
+            buf: &data[..],
+            diagnostics: &LogDiagnostics,
+        };
+        let view = descriptors.views().next().unwrap().unwrap();
+        assert_matches!(
+            view.segmentation(),
+            Some(Err(SpliceDescriptorErr::NotEnoughData {
+                field_name: "segmentation_descriptor.segmentation_upid",
+                ..
+            }))
+        );
+    }
+
+    #[test]
+    fn sub_segments() {
+        // a Program Start, which has no sub-segment fields, followed by a Provider Placement
+        // Opportunity Start with a sub_segment_num but no sub_segments_expected
+        let data = hex!(
+            "021143554549 00000001 7f bf 0000 10 01 02 0102"
+            "021043554549 00000002 7f bf 0000 34 01 02 01"
+        );
+        let descriptors = SpliceDescriptors {
+            buf: &data[..],
+            diagnostics: &LogDiagnostics,
+        };
+        let views: Vec<_> = descriptors.views().map(|v| v.unwrap()).collect();
+        let seg = views[0].segmentation().unwrap().unwrap();
+        assert_eq!(seg.segments_expected(), Some(2));
+        assert_eq!(seg.sub_segments(), None);
+        assert_matches!(
+            views[1].segmentation(),
+            Some(Err(SpliceDescriptorErr::NotEnoughData {
+                field_name: "segmentation_descriptor.sub_segments_expected",
+                ..
+            }))
+        );
+        assert!(views[1].to_owned().is_err());
+
+        // the owned parser applies the same rule, whether the trailing data is one byte or two
+        for data in [
+            &hex!("021143554549 00000001 7f bf 0000 10 01 02 0102")[..],
+            &hex!("021043554549 00000001 7f bf 0000 10 01 02 01")[..],
+            &hex!("021143554549 00000001 7f bf 0000 34 01 02 0102")[..],
+        ] {
+            let view = SpliceDescriptors {
+                buf: data,
+                diagnostics: &LogDiagnostics,
+            }
+            .views()
+            .next()
+            .unwrap()
+            .unwrap();
+            let owned = SpliceDescriptor::parse(data, &LogDiagnostics).unwrap();
+            assert_eq!(view.to_owned().unwrap(), owned);
+            let sub_segments = match owned {
+                SpliceDescriptor::SegmentationDescriptor {
+                    descriptor_detail: SegmentationDescriptor::Insert { sub_segments, .. },
+                    ..
+                } => sub_segments.map(|s| (s.sub_segment_num, s.sub_segments_expected)),
+                _ => panic!("expected a segmentation_descriptor()"),
+            };
+            let view = view.segmentation().unwrap().unwrap();
+            assert_eq!(view.sub_segments(), sub_segments);
+        }
+    }
+}
@@ -0,0 +1,567 @@
+//! Reading of the XML representation of `splice_info_section()` defined by the SCTE-35 XML
+//! schema (namespace `http://www.scte.org/schemas/35`), as found in ESAM messages and
+//! DASH `EventStream` elements.
+//!
+//! Available when this crate's `xml` feature is enabled.
+//!
+//! ## Example
+//!
+//! Combined with [`SpliceInfoSection::encode()`](../struct.SpliceInfoSection.html#method.encode),
+//! XML documents can be converted to the binary form,
+//!
+//! ```
+//! # use scte35_reader::SpliceInfoSection;
+//! let section = SpliceInfoSection::from_xml(
+//!     r#"<SpliceInfoSection xmlns="http://www.scte.org/schemas/35" tier="4095">
+//!          <TimeSignal><SpliceTime ptsTime="900000"/></TimeSignal>
+//!        </SpliceInfoSection>"#,
+//! )
+//! .unwrap();
+//! assert_eq!(section.splice_pts(), Some(900_000));
+//! let bytes = section.encode().unwrap();
+//! ```
+
+use crate::{
+    AudioComponent, ComponentSplice, DeliveryRestrictionFlags, DeviceRestrictions,
+    EncryptionAlgorithm, NetworkIndicator, ReturnMode, SapType, ScheduledComponentSplice,
+    ScheduledSplice, ScheduledSpliceMode, SegmentationDescriptor, SegmentationMode,
+    SegmentationModeComponent, SegmentationTypeId, SegmentationUpid, SegmentationUpidType,
+    SpliceCommand, SpliceDescriptor, SpliceDescriptorErr, SpliceDuration, SpliceInfoSection,
+    SpliceInsert, SpliceMode, SpliceScheduleEvent, SpliceTime, SubSegments, Tier, UtcSpliceTime,
+};
+use roxmltree::Node;
+use std::str::FromStr;
+
+/// Problems which prevent an XML document being read as a `SpliceInfoSection`
+#[derive(Debug)]
+pub enum XmlError {
+    /// The text was not well-formed XML
+    Xml(roxmltree::Error),
+    /// The document did not contain an element with the given name, where one was required
+    MissingElement(&'static str),
+    /// The named element did not have the required attribute
+    MissingAttribute {
+        element: String,
+        attribute: &'static str,
+    },
+    /// The named attribute (or element content) had a value that could not be interpreted, or
+    /// was outside the range the spec defines for it
+    InvalidValue {
+        element: String,
+        attribute: &'static str,
+        value: String,
+    },
+    /// The values given for a `SegmentationUpid` were not valid for its type
+    Upid(SpliceDescriptorErr),
+}
+impl std::fmt::Display for XmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            XmlError::Xml(_) => f.write_str("malformed XML"),
+            XmlError::MissingElement(name) => write!(f, "missing {} element", name),
+            XmlError::MissingAttribute { element, attribute } => {
+                write!(f, "{} element lacks the {} attribute", element, attribute)
+            }
+            XmlError::InvalidValue {
+                element,
+                attribute,
+                value,
+            } => write!(
+                f,
+                "invalid value {:?} for {} of {} element",
+                value, attribute, element
+            ),
+            XmlError::Upid(_) => f.write_str("invalid SegmentationUpid"),
+        }
+    }
+}
+impl std::error::Error for XmlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            XmlError::Xml(e) => Some(e),
+            XmlError::Upid(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl SpliceInfoSection {
+    /// Reads a section from the XML representation defined by the SCTE-35 XML schema.
+    ///
+    /// The `SpliceInfoSection` element may be the root of the document or nested within other
+    /// elements (such as an ESAM `Signal`).  Namespaces are not checked.  The values of
+    /// `SegmentationUpid` elements may be given in `hexbinary` (the default) or `text`
+    /// `segmentationUpidFormat`; where a `SegmentationDescriptor` contains more than one, they
+    /// are combined into a `SegmentationUpid::MID`.
+    pub fn from_xml(xml: &str) -> Result<SpliceInfoSection, XmlError> {
+        let doc = roxmltree::Document::parse(xml).map_err(XmlError::Xml)?;
+        let section = doc
+            .descendants()
+            .find(|n| n.tag_name().name() == "SpliceInfoSection")
+            .ok_or(XmlError::MissingElement("SpliceInfoSection"))?;
+        read_section(section)
+    }
+}
+
+fn element_name(node: Node<'_, '_>) -> String {
+    node.tag_name().name().to_string()
+}
+
+pub(crate) fn child<'a, 'i>(node: Node<'a, 'i>, name: &str) -> Option<Node<'a, 'i>> {
+    node.children().find(|n| n.tag_name().name() == name)
+}
+
+pub(crate) fn children<'a, 'i>(
+    node: Node<'a, 'i>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'i>> {
+    node.children().filter(move |n| n.tag_name().name() == name)
+}
+
+pub(crate) fn invalid(node: Node<'_, '_>, attribute: &'static str, value: &str) -> XmlError {
+    XmlError::InvalidValue {
+        element: element_name(node),
+        attribute,
+        value: value.to_string(),
+    }
+}
+
+/// The value of the given attribute, if present
+pub(crate) fn attr<T: FromStr>(
+    node: Node<'_, '_>,
+    attribute: &'static str,
+) -> Result<Option<T>, XmlError> {
+    node.attribute(attribute)
+        .map(|v| v.trim().parse().map_err(|_| invalid(node, attribute, v)))
+        .transpose()
+}
+
+/// The value of the given attribute, which must be present
+pub(crate) fn required<T: FromStr>(
+    node: Node<'_, '_>,
+    attribute: &'static str,
+) -> Result<T, XmlError> {
+    attr(node, attribute)?.ok_or_else(|| XmlError::MissingAttribute {
+        element: element_name(node),
+        attribute,
+    })
+}
+
+/// The value of the given `xsd:boolean` attribute, being `false` if absent
+fn flag(node: Node<'_, '_>, attribute: &'static str) -> Result<bool, XmlError> {
+    match node.attribute(attribute).map(str::trim) {
+        None | Some("false") | Some("0") => Ok(false),
+        Some("true") | Some("1") => Ok(true),
+        Some(v) => Err(invalid(node, attribute, v)),
+    }
+}
+
+fn hex_bytes(node: Node<'_, '_>, attribute: &'static str, text: &str) -> Result<Vec<u8>, XmlError> {
+    let digits: Vec<u32> = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16))
+        .collect::<Option<_>>()
+        .ok_or_else(|| invalid(node, attribute, text))?;
+    let pairs = digits.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(invalid(node, attribute, text));
+    }
+    Ok(pairs.map(|pair| (pair[0] << 4 | pair[1]) as u8).collect())
+}
+
+pub(crate) fn read_section(node: Node<'_, '_>) -> Result<SpliceInfoSection, XmlError> {
+    let tier = attr(node, "tier")?.unwrap_or(0xfff);
+    let encrypted = child(node, "EncryptedPacket");
+    let (encryption_algorithm, cw_index) = match encrypted {
+        Some(e) => (
+            attr(e, "encryptionAlgorithm")?.unwrap_or(0),
+            attr(e, "cwIndex")?.unwrap_or(0),
+        ),
+        None => (0, 0),
+    };
+    let splice_command = node
+        .children()
+        .filter(|n| n.is_element())
+        .find_map(|n| read_command(n).transpose())
+        .ok_or(XmlError::MissingElement("splice command"))??;
+    let splice_descriptors = node
+        .children()
+        .filter(|n| n.is_element())
+        .filter_map(|n| read_descriptor(n).transpose())
+        .collect::<Result<_, _>>()?;
+    Ok(SpliceInfoSection {
+        sap_type: SapType::from_id(attr(node, "sapType")?.unwrap_or(3)),
+        protocol_version: attr(node, "protocolVersion")?.unwrap_or(0),
+        encrypted_packet: encrypted.is_some(),
+        encryption_algorithm: EncryptionAlgorithm::from_id(encryption_algorithm),
+        pts_adjustment: attr(node, "ptsAdjustment")?.unwrap_or(0),
+        cw_index,
+        tier: Tier::new(tier).ok_or_else(|| invalid(node, "tier", &tier.to_string()))?,
+        splice_command,
+        splice_descriptors,
+    })
+}
+
+/// Reads the command, or produces `None` if the given element is not a command
+fn read_command(node: Node<'_, '_>) -> Result<Option<SpliceCommand>, XmlError> {
+    Ok(Some(match node.tag_name().name() {
+        "SpliceNull" => SpliceCommand::SpliceNull {},
+        "SpliceSchedule" => SpliceCommand::SpliceSchedule {
+            events: children(node, "Event")
+                .map(read_schedule_event)
+                .collect::<Result<_, _>>()?,
+        },
+        "SpliceInsert" => read_splice_insert(node)?,
+        "TimeSignal" => SpliceCommand::TimeSignal {
+            splice_time: SpliceTime::Timed(read_splice_time(node)?),
+        },
+        "BandwidthReservation" => SpliceCommand::BandwidthReservation {},
+        "PrivateCommand" => {
+            let identifier: u32 = required(node, "identifier")?;
+            let private_bytes = match child(node, "PrivateBytes") {
+                Some(b) => hex_bytes(b, "PrivateBytes", b.text().unwrap_or(""))?,
+                None => vec![],
+            };
+            SpliceCommand::PrivateCommand {
+                identifier: identifier.to_be_bytes(),
+                private_bytes,
+            }
+        }
+        _ => return Ok(None),
+    }))
+}
+
+/// The `ptsTime` of the `SpliceTime` child of the given element, if any
+fn read_splice_time(node: Node<'_, '_>) -> Result<Option<u64>, XmlError> {
+    match child(node, "SpliceTime") {
+        Some(t) => attr(t, "ptsTime"),
+        None => Ok(None),
+    }
+}
+
+fn read_break_duration(node: Node<'_, '_>) -> Result<Option<SpliceDuration>, XmlError> {
+    child(node, "BreakDuration")
+        .map(|d| {
+            Ok(SpliceDuration {
+                return_mode: if flag(d, "autoReturn")? {
+                    ReturnMode::Automatic
+                } else {
+                    ReturnMode::Manual
+                },
+                duration: required(d, "duration")?,
+            })
+        })
+        .transpose()
+}
+
+fn network_indicator(node: Node<'_, '_>) -> Result<NetworkIndicator, XmlError> {
+    Ok(if flag(node, "outOfNetworkIndicator")? {
+        NetworkIndicator::Out
+    } else {
+        NetworkIndicator::In
+    })
+}
+
+fn read_splice_insert(node: Node<'_, '_>) -> Result<SpliceCommand, XmlError> {
+    let splice_detail = if flag(node, "spliceEventCancelIndicator")? {
+        SpliceInsert::Cancel
+    } else {
+        let immediate = flag(node, "spliceImmediateFlag")?;
+        let time = |n| {
+            Ok(if immediate {
+                SpliceTime::Immediate
+            } else {
+                SpliceTime::Timed(read_splice_time(n)?)
+            })
+        };
+        let splice_mode = match child(node, "Program") {
+            Some(program) => SpliceMode::Program(time(program)?),
+            None => SpliceMode::Components(
+                children(node, "Component")
+                    .map(|c| {
+                        Ok(ComponentSplice {
+                            component_tag: required(c, "componentTag")?,
+                            splice_time: time(c)?,
+                        })
+                    })
+                    .collect::<Result<_, XmlError>>()?,
+            ),
+        };
+        SpliceInsert::Insert {
+            network_indicator: network_indicator(node)?,
+            splice_mode,
+            duration: read_break_duration(node)?,
+            unique_program_id: attr(node, "uniqueProgramId")?.unwrap_or(0),
+            avail_num: attr(node, "availNum")?.unwrap_or(0),
+            avails_expected: attr(node, "availsExpected")?.unwrap_or(0),
+        }
+    };
+    Ok(SpliceCommand::SpliceInsert {
+        splice_event_id: required(node, "spliceEventId")?,
+        reserved: 0x7f,
+        splice_detail,
+    })
+}
+
+fn read_schedule_event(node: Node<'_, '_>) -> Result<SpliceScheduleEvent, XmlError> {
+    let splice_detail = if flag(node, "spliceEventCancelIndicator")? {
+        ScheduledSplice::Cancel
+    } else {
+        let splice_mode = match child(node, "Program") {
+            Some(program) => {
+                ScheduledSpliceMode::Program(UtcSpliceTime(required(program, "utcSpliceTime")?))
+            }
+            None => ScheduledSpliceMode::Components(
+                children(node, "Component")
+                    .map(|c| {
+                        Ok(ScheduledComponentSplice {
+                            component_tag: required(c, "componentTag")?,
+                            utc_splice_time: UtcSpliceTime(required(c, "utcSpliceTime")?),
+                        })
+                    })
+                    .collect::<Result<_, XmlError>>()?,
+            ),
+        };
+        ScheduledSplice::Insert {
+            network_indicator: network_indicator(node)?,
+            splice_mode,
+            duration: read_break_duration(node)?,
+            unique_program_id: attr(node, "uniqueProgramId")?.unwrap_or(0),
+            avail_num: attr(node, "availNum")?.unwrap_or(0),
+            avails_expected: attr(node, "availsExpected")?.unwrap_or(0),
+        }
+    };
+    Ok(SpliceScheduleEvent {
+        splice_event_id: required(node, "spliceEventId")?,
+        splice_detail,
+    })
+}
+
+/// Reads the descriptor, or produces `None` if the given element is not a descriptor
+fn read_descriptor(node: Node<'_, '_>) -> Result<Option<SpliceDescriptor>, XmlError> {
+    Ok(Some(match node.tag_name().name() {
+        "AvailDescriptor" => SpliceDescriptor::AvailDescriptor {
+            provider_avail_id: required(node, "providerAvailId")?,
+        },
+        "DTMFDescriptor" => SpliceDescriptor::DTMFDescriptor {
+            preroll: attr(node, "preroll")?.unwrap_or(0),
+            dtmf_chars: node.attribute("chars").unwrap_or("").as_bytes().to_vec(),
+        },
+        "SegmentationDescriptor" => read_segmentation_descriptor(node)?,
+        "TimeDescriptor" => SpliceDescriptor::TimeDescriptor {
+            tai_seconds: required(node, "taiSeconds")?,
+            tai_nanoseconds: required(node, "taiNs")?,
+            utc_offset: required(node, "utcOffset")?,
+        },
+        "AudioDescriptor" => SpliceDescriptor::AudioDescriptor {
+            components: children(node, "AudioChannel")
+                .map(|c| {
+                    let iso_code = c.attribute("ISOCode").unwrap_or("");
+                    Ok(AudioComponent {
+                        component_tag: required(c, "componentTag")?,
+                        iso_code: iso_code
+                            .as_bytes()
+                            .try_into()
+                            .map_err(|_| invalid(c, "ISOCode", iso_code))?,
+                        bit_stream_mode: attr(c, "BitStreamMode")?.unwrap_or(0),
+                        num_channels: attr(c, "NumChannels")?.unwrap_or(0),
+                        full_srvc_audio: flag(c, "FullSrvcAudio")?,
+                    })
+                })
+                .collect::<Result<_, XmlError>>()?,
+        },
+        _ => return Ok(None),
+    }))
+}
+
+fn read_segmentation_descriptor(node: Node<'_, '_>) -> Result<SpliceDescriptor, XmlError> {
+    let segmentation_event_id = required(node, "segmentationEventId")?;
+    if flag(node, "segmentationEventCancelIndicator")? {
+        return Ok(SpliceDescriptor::SegmentationDescriptor {
+            segmentation_event_id,
+            descriptor_detail: SegmentationDescriptor::Cancel,
+        });
+    }
+    let delivery_restrictions = match child(node, "DeliveryRestrictions") {
+        Some(d) => {
+            let value: u8 = attr(d, "deviceRestrictions")?.unwrap_or(3);
+            DeliveryRestrictionFlags::DeliveryRestrictions {
+                web_delivery_allowed_flag: flag(d, "webDeliveryAllowedFlag")?,
+                no_regional_blackout_flag: flag(d, "noRegionalBlackoutFlag")?,
+                archive_allowed_flag: flag(d, "archiveAllowedFlag")?,
+                device_restrictions: DeviceRestrictions::try_from(value)
+                    .map_err(|_| invalid(d, "deviceRestrictions", &value.to_string()))?,
+            }
+        }
+        None => DeliveryRestrictionFlags::None,
+    };
+    let components: Vec<SegmentationModeComponent> = children(node, "Component")
+        .map(|c| {
+            Ok(SegmentationModeComponent {
+                component_tag: required(c, "componentTag")?,
+                pts_offset: attr(c, "ptsOffset")?.unwrap_or(0),
+            })
+        })
+        .collect::<Result<_, XmlError>>()?;
+    let segmentation_mode = if components.is_empty() {
+        SegmentationMode::Program
+    } else {
+        SegmentationMode::Component { components }
+    };
+    let mut upids = children(node, "SegmentationUpid")
+        .map(read_upid)
+        .collect::<Result<Vec<_>, _>>()?;
+    let segmentation_upid = match upids.len() {
+        0 => SegmentationUpid::None,
+        1 => upids.remove(0),
+        _ => SegmentationUpid::MID(upids),
+    };
+    let segmentation_duration: Option<u64> = attr(node, "segmentationDuration")?;
+    let sub_segments = match (
+        attr(node, "subSegmentNum")?,
+        attr(node, "subSegmentsExpected")?,
+    ) {
+        (Some(sub_segment_num), Some(sub_segments_expected)) => Some(SubSegments {
+            sub_segment_num,
+            sub_segments_expected,
+        }),
+        _ => None,
+    };
+    Ok(SpliceDescriptor::SegmentationDescriptor {
+        segmentation_event_id,
+        descriptor_detail: SegmentationDescriptor::Insert {
+            program_segmentation_flag: matches!(segmentation_mode, SegmentationMode::Program),
+            segmentation_duration_flag: segmentation_duration.is_some(),
+            delivery_not_restricted_flag: matches!(
+                delivery_restrictions,
+                DeliveryRestrictionFlags::None
+            ),
+            delivery_restrictions,
+            segmentation_mode,
+            segmentation_duration,
+            segmentation_upid,
+            segmentation_type_id: SegmentationTypeId::from_id(required(
+                node,
+                "segmentationTypeId",
+            )?),
+            segment_num: attr(node, "segmentNum")?.unwrap_or(0),
+            segments_expected: attr(node, "segmentsExpected")?.unwrap_or(0),
+            sub_segments,
+        },
+    })
+}
+
+fn read_upid(node: Node<'_, '_>) -> Result<SegmentationUpid, XmlError> {
+    let upid_type = SegmentationUpidType::from_type(required(node, "segmentationUpidType")?);
+    let text = node.text().unwrap_or("");
+    let bytes = match node
+        .attribute("segmentationUpidFormat")
+        .unwrap_or("hexbinary")
+    {
+        "hexbinary" => hex_bytes(node, "segmentationUpid", text)?,
+        "text" => text.as_bytes().to_vec(),
+        format => return Err(invalid(node, "segmentationUpidFormat", format)),
+    };
+    if bytes.is_empty() {
+        return Ok(SegmentationUpid::None);
+    }
+    SegmentationUpid::parse_payload(upid_type, bytes).map_err(XmlError::Upid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::*;
+
+    #[test]
+    fn splice_insert() {
+        let section = SpliceInfoSection::from_xml(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <scte35:SpliceInfoSection xmlns:scte35="http://www.scte.org/schemas/35" tier="4095">
+              <scte35:SpliceInsert spliceEventId="1" outOfNetworkIndicator="true"
+                                   uniqueProgramId="1" availNum="1" availsExpected="1">
+                <scte35:Program><scte35:SpliceTime ptsTime="756296448"/></scte35:Program>
+                <scte35:BreakDuration autoReturn="true" duration="19125000"/>
+              </scte35:SpliceInsert>
+            </scte35:SpliceInfoSection>"#,
+        )
+        .unwrap();
+        assert_eq!(section.encode().unwrap(), SPLICE_INSERT);
+    }
+
+    #[test]
+    fn time_signal() {
+        let section = SpliceInfoSection::from_xml(
+            r#"<Signal xmlns="urn:cablelabs:md:xsd:signaling:3.0">
+              <SpliceInfoSection xmlns="http://www.scte.org/schemas/35">
+                <TimeSignal><SpliceTime ptsTime="5791089600"/></TimeSignal>
+                <SegmentationDescriptor segmentationEventId="1" segmentationTypeId="16"
+                                        segmentNum="1" segmentsExpected="1"/>
+              </SpliceInfoSection>
+            </Signal>"#,
+        )
+        .unwrap();
+        assert_eq!(section.encode().unwrap(), TIME_SIGNAL);
+    }
+
+    #[test]
+    fn upids() {
+        let section = SpliceInfoSection::from_xml(
+            r#"<SpliceInfoSection>
+                <TimeSignal><SpliceTime ptsTime="0"/></TimeSignal>
+                <SegmentationDescriptor segmentationEventId="1" segmentationTypeId="52">
+                  <SegmentationUpid segmentationUpidType="3">41424344 30303030 30303031</SegmentationUpid>
+                  <SegmentationUpid segmentationUpidType="9" segmentationUpidFormat="text">SIGNAL:x</SegmentationUpid>
+                </SegmentationDescriptor>
+              </SpliceInfoSection>"#,
+        )
+        .unwrap();
+        match &section.splice_descriptors[0] {
+            SpliceDescriptor::SegmentationDescriptor {
+                descriptor_detail:
+                    SegmentationDescriptor::Insert {
+                        segmentation_upid: SegmentationUpid::MID(upids),
+                        ..
+                    },
+                ..
+            } => {
+                assert!(matches!(&upids[0], SegmentationUpid::AdID(id) if id.0 == "ABCD00000001"));
+                assert!(matches!(&upids[1], SegmentationUpid::ADI(id) if id.0 == "SIGNAL:x"));
+            }
+            d => panic!("unexpected {:?}", d),
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            SpliceInfoSection::from_xml("<SpliceInfoSection>"),
+            Err(XmlError::Xml(_))
+        ));
+        assert!(matches!(
+            SpliceInfoSection::from_xml("<Foo/>"),
+            Err(XmlError::MissingElement("SpliceInfoSection"))
+        ));
+        assert!(matches!(
+            SpliceInfoSection::from_xml("<SpliceInfoSection/>"),
+            Err(XmlError::MissingElement("splice command"))
+        ));
+        assert!(matches!(
+            SpliceInfoSection::from_xml("<SpliceInfoSection><SpliceInsert/></SpliceInfoSection>"),
+            Err(XmlError::MissingAttribute {
+                attribute: "spliceEventId",
+                ..
+            })
+        ));
+        assert!(matches!(
+            SpliceInfoSection::from_xml(
+                r#"<SpliceInfoSection tier="4096"><SpliceNull/></SpliceInfoSection>"#
+            ),
+            Err(XmlError::InvalidValue {
+                attribute: "tier",
+                ..
+            })
+        ));
+    }
+}